@@ -0,0 +1,104 @@
+//! `cargo docsrs`: the docsrs CLI as a cargo subcommand.
+
+use std::path::Path;
+use std::process;
+
+fn main() {
+    let mut args = subcommand_args(std::env::args().skip(1).collect());
+
+    // Like other cargo subcommands, --manifest-path picks the workspace
+    // that versions are resolved against
+    if let Some(manifest_path) = take_manifest_path(&mut args) {
+        let dir = Path::new(&manifest_path)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .unwrap_or(Path::new("."));
+        if let Err(error) = std::env::set_current_dir(dir) {
+            eprintln!("Error: Failed to use manifest {}: {}", manifest_path, error);
+            process::exit(1);
+        }
+    }
+
+    let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
+    match docsrs_core::run_cli_to(&args_refs, &mut std::io::stdout(), true) {
+        Ok(output) => {
+            print!("{}", output);
+            process::exit(0);
+        }
+        Err(error) => {
+            eprintln!("Error: {}", error);
+            process::exit(1);
+        }
+    }
+}
+
+/// The arguments after the program name, without the subcommand name: cargo
+/// runs `cargo-docsrs docsrs <args>`, running the binary directly leaves it
+/// out.
+fn subcommand_args(mut args: Vec<String>) -> Vec<String> {
+    if args.first().is_some_and(|arg| arg == "docsrs") {
+        args.remove(0);
+    }
+    args
+}
+
+/// Remove `--manifest-path <path>` or `--manifest-path=<path>` from `args`,
+/// returning the path.
+fn take_manifest_path(args: &mut Vec<String>) -> Option<String> {
+    let index = args
+        .iter()
+        .position(|arg| arg == "--manifest-path" || arg.starts_with("--manifest-path="))?;
+    let arg = args.remove(index);
+    match arg.strip_prefix("--manifest-path=") {
+        Some(path) => Some(path.to_string()),
+        None if index < args.len() => Some(args.remove(index)),
+        None => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(args: &[&str]) -> Vec<String> {
+        args.iter().map(|arg| arg.to_string()).collect()
+    }
+
+    #[test]
+    fn test_subcommand_name_is_stripped() {
+        assert_eq!(
+            subcommand_args(args(&["docsrs", "serde", "--list"])),
+            ["serde", "--list"]
+        );
+        assert_eq!(subcommand_args(args(&["serde"])), ["serde"]);
+        assert_eq!(
+            subcommand_args(args(&["serde", "docsrs"])),
+            ["serde", "docsrs"]
+        );
+    }
+
+    #[test]
+    fn test_take_manifest_path() {
+        let mut separate = args(&["serde", "--manifest-path", "a/Cargo.toml", "--list"]);
+        assert_eq!(
+            take_manifest_path(&mut separate).as_deref(),
+            Some("a/Cargo.toml")
+        );
+        assert_eq!(separate, ["serde", "--list"]);
+
+        let mut joined = args(&["--manifest-path=a/Cargo.toml", "serde"]);
+        assert_eq!(
+            take_manifest_path(&mut joined).as_deref(),
+            Some("a/Cargo.toml")
+        );
+        assert_eq!(joined, ["serde"]);
+
+        let mut missing_value = args(&["serde", "--manifest-path"]);
+        assert_eq!(take_manifest_path(&mut missing_value), None);
+        assert_eq!(missing_value, ["serde"]);
+
+        let mut absent = args(&["serde"]);
+        assert_eq!(take_manifest_path(&mut absent), None);
+        assert_eq!(absent, ["serde"]);
+    }
+}
@@ -0,0 +1,147 @@
+//! Named shortcuts to items, see `docsrs bookmark`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use directories::ProjectDirs;
+
+use crate::crate_spec::CrateSpec;
+use crate::suggest;
+
+/// Bookmarks by name, each the crate spec of an item.
+pub(crate) struct Bookmarks {
+    path: PathBuf,
+    specs: BTreeMap<String, String>,
+}
+
+/// The bookmarks file, `bookmarks.json` in the config directory.
+pub(crate) fn bookmarks_path() -> Result<PathBuf> {
+    let proj_dirs =
+        ProjectDirs::from("", "", "docsrs").context("Failed to determine config directory")?;
+    Ok(proj_dirs.config_dir().join("bookmarks.json"))
+}
+
+/// The name a bookmark of `spec` gets unless one is given: the item name
+/// without its disambiguator, lowercased, or the crate name.
+pub(crate) fn default_name(spec: &CrateSpec) -> String {
+    let name = match &spec.path_prefix {
+        Some(path) => {
+            let last = path.rsplit("::").next().unwrap_or(path);
+            let last = last.split_once('@').map_or(last, |(_, name)| name);
+            last.trim_end_matches("()").trim_end_matches('!')
+        }
+        None => &spec.name,
+    };
+    name.to_lowercase()
+}
+
+impl Bookmarks {
+    /// The bookmarks saved in the file at `path`, none if it doesn't exist.
+    pub(crate) fn load(path: &Path) -> Result<Self> {
+        let specs = match fs::read_to_string(path) {
+            Ok(contents) => serde_json::from_str(&contents)
+                .with_context(|| format!("Failed to parse bookmarks {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => BTreeMap::new(),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read bookmarks {}", path.display()));
+            }
+        };
+        Ok(Self {
+            path: path.to_path_buf(),
+            specs,
+        })
+    }
+
+    fn save(&self) -> Result<()> {
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        let contents = serde_json::to_string_pretty(&self.specs)?;
+        fs::write(&self.path, contents + "\n")
+            .with_context(|| format!("Failed to write bookmarks {}", self.path.display()))
+    }
+
+    /// Save `spec` as `name`, returning the spec it replaces.
+    pub(crate) fn add(&mut self, name: &str, spec: &CrateSpec) -> Result<Option<String>> {
+        if name.is_empty() || name.contains(char::is_whitespace) {
+            bail!("Invalid bookmark name \"{name}\": use a single word");
+        }
+        let replaced = self.specs.insert(name.to_string(), spec.to_string());
+        self.save()?;
+        Ok(replaced)
+    }
+
+    /// The spec saved as `name`.
+    pub(crate) fn get(&self, name: &str) -> Result<CrateSpec> {
+        match self.specs.get(name) {
+            Some(spec) => CrateSpec::parse(spec),
+            None => bail!("{}", self.not_found(name)),
+        }
+    }
+
+    /// Forget the bookmark `name`, returning its spec.
+    pub(crate) fn remove(&mut self, name: &str) -> Result<String> {
+        let Some(spec) = self.specs.remove(name) else {
+            bail!("{}", self.not_found(name));
+        };
+        self.save()?;
+        Ok(spec)
+    }
+
+    fn not_found(&self, name: &str) -> String {
+        let names = self.specs.keys().map(String::as_str);
+        match suggest::did_you_mean(&suggest::similar(name, names)) {
+            Some(hint) => format!("No bookmark named {name}\n\n{hint}"),
+            None => format!("No bookmark named {name}, see `docsrs bookmark list`"),
+        }
+    }
+
+    /// One line per bookmark, its name followed by its spec.
+    pub(crate) fn render(&self) -> String {
+        let width = self.specs.keys().map(String::len).max().unwrap_or(0);
+        self.specs
+            .iter()
+            .map(|(name, spec)| format!("{name:<width$}  {spec}\n"))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_name_is_item_name() {
+        let name = |spec: &str| default_name(&CrateSpec::parse(spec).unwrap());
+        assert_eq!(name("tokio::sync::mpsc::Sender"), "sender");
+        assert_eq!(name("tokio@1::macro@select"), "select");
+        assert_eq!(name("tokio::spawn()"), "spawn");
+        assert_eq!(name("serde"), "serde");
+    }
+
+    #[test]
+    fn test_bookmarks_persist() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config").join("bookmarks.json");
+        let sender = CrateSpec::parse("tokio::sync::mpsc::Sender").unwrap();
+
+        let mut bookmarks = Bookmarks::load(&path).unwrap();
+        assert_eq!(bookmarks.add("sender", &sender).unwrap(), None);
+
+        let mut bookmarks = Bookmarks::load(&path).unwrap();
+        assert_eq!(
+            bookmarks.get("sender").unwrap().to_string(),
+            sender.to_string()
+        );
+        let err = bookmarks.get("sendr").unwrap_err().to_string();
+        assert!(err.ends_with("did you mean sender?"), "{err}");
+        assert_eq!(bookmarks.render(), "sender  tokio::sync::mpsc::Sender\n");
+
+        bookmarks.remove("sender").unwrap();
+        assert_eq!(Bookmarks::load(&path).unwrap().render(), "");
+    }
+}
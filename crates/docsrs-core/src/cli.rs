@@ -1,7 +1,15 @@
-use clap::{Parser, ValueEnum};
+use std::path::PathBuf;
 
-use crate::color::Color;
+use clap::{Parser, Subcommand, ValueEnum};
+use rustdoc_fmt::CodeFrame;
+
+use crate::color::{Color, ColorDepthChoice};
 use crate::crate_spec::CrateSpec;
+#[cfg(feature = "native")]
+use crate::docfetch::DEFAULT_COMPRESSION_LEVEL;
+/// Nothing is cached without the `native` feature
+#[cfg(not(feature = "native"))]
+const DEFAULT_COMPRESSION_LEVEL: i32 = 0;
 
 /// Install target for `--install-skill`. Determines which directory the
 /// bundled SKILL.md is written to so Claude Code can discover it.
@@ -13,9 +21,204 @@ pub enum SkillScope {
     Project,
 }
 
+/// Framing for code examples, see `--code-frame`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum CodeFrameStyle {
+    /// Indented code only.
+    #[default]
+    None,
+    /// A header line naming the example's language.
+    Label,
+    /// A light border on the left, headed by the example's language.
+    Box,
+}
+
+impl From<CodeFrameStyle> for CodeFrame {
+    fn from(style: CodeFrameStyle) -> Self {
+        match style {
+            CodeFrameStyle::None => CodeFrame::None,
+            CodeFrameStyle::Label => CodeFrame::Label,
+            CodeFrameStyle::Box => CodeFrame::Box,
+        }
+    }
+}
+
+/// When to show doc summaries next to list entries, see `--summaries`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum Summaries {
+    /// Show summaries when stdout is a terminal.
+    #[default]
+    Auto,
+    /// Always show summaries.
+    Always,
+    /// Never show summaries.
+    Never,
+}
+
+impl Summaries {
+    /// Resolve whether summaries are shown, given whether stdout is a terminal.
+    pub fn enabled(self, is_terminal: bool) -> bool {
+        match self {
+            Self::Auto => is_terminal,
+            Self::Always => true,
+            Self::Never => false,
+        }
+    }
+}
+
+/// What `--copy` puts on the clipboard.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum CopyTarget {
+    /// The fully qualified path of the item.
+    Path,
+    /// The signature of the item, on one line and without colors.
+    Signature,
+    /// The first Rust code example in the item's docs.
+    Example,
+}
+
+/// How to group list results, see `--group-by`.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum GroupBy {
+    /// One header per parent module, with items listed by name beneath it.
+    Module,
+}
+
+/// What `--graph` draws.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, ValueEnum)]
+pub enum Graph {
+    /// The module hierarchy, and the modules items are re-exported from.
+    Modules,
+}
+
+/// How `--graph` writes the graph.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum GraphFormat {
+    /// A Mermaid flowchart, rendered by GitHub in markdown files.
+    #[default]
+    Mermaid,
+    /// Graphviz DOT, for `dot -Tsvg`.
+    Dot,
+}
+
+/// How the docs of an item, or the items listed, are written, see
+/// `--format`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, ValueEnum)]
+pub enum OutputFormat {
+    /// Highlighted text for the terminal.
+    #[default]
+    Text,
+    /// A man page in roff, for `man -l -`.
+    Man,
+    /// One JSON object per listed item and line, written as they're found,
+    /// for `jq` or `fzf`.
+    Jsonl,
+}
+
+impl std::str::FromStr for OutputFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        <Self as ValueEnum>::from_str(s, true).map_err(|_| format!("Invalid output format: {}", s))
+    }
+}
+
+/// Commands besides looking up docs.
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Compare the public API of two versions of a crate
+    Diff {
+        /// Crate and old version: crate[@version][::path] (e.g., "serde@1.0.190")
+        #[arg(value_parser = parse_crate_spec)]
+        old: CrateSpec,
+
+        /// Version to compare against (e.g., "1.0.200")
+        new_version: String,
+
+        /// Classify the changes as major, minor or patch and check that the
+        /// version bump is big enough for them.
+        #[arg(long)]
+        semver: bool,
+    },
+    /// Compare the public API of a workspace crate with its latest release
+    DiffLocal {
+        /// Workspace crate: crate[::path] (e.g., "my-crate", "my-crate::config")
+        #[arg(value_parser = parse_crate_spec)]
+        local: CrateSpec,
+
+        /// Classify the changes as major, minor or patch and check that the
+        /// version in Cargo.toml is bumped enough for them.
+        #[arg(long)]
+        semver: bool,
+    },
+    /// Write the docs of a crate as markdown files, one per module and item
+    Export {
+        /// Crate to export: crate[@version] (e.g., "serde@1.0.200")
+        #[arg(value_parser = parse_crate_spec)]
+        spec: CrateSpec,
+
+        /// Directory to write the files to, under a directory named after
+        /// the crate
+        #[arg(long, value_name = "DIR")]
+        out: PathBuf,
+    },
+    /// List recent queries, numbered for re-running them with `docsrs !N`
+    History {
+        /// Forget all recent queries
+        #[arg(long)]
+        clear: bool,
+    },
+    /// Save and recall named shortcuts to items
+    Bookmark {
+        #[command(subcommand)]
+        action: BookmarkAction,
+    },
+    /// Search crates.io for crates, listed as specs to query
+    Search {
+        /// Words to search for (e.g., "async http client")
+        #[arg(required = true)]
+        terms: Vec<String>,
+
+        /// List at most N crates
+        #[arg(long, value_name = "N", default_value_t = 10, value_parser = clap::value_parser!(u16).range(1..=100))]
+        limit: u16,
+    },
+}
+
+/// What `docsrs bookmark` does.
+#[derive(Subcommand, Debug)]
+pub enum BookmarkAction {
+    /// Bookmark an item
+    Add {
+        /// Item to bookmark: crate[@version][::path] (e.g., "tokio::sync::mpsc::Sender")
+        #[arg(value_parser = parse_crate_spec)]
+        spec: CrateSpec,
+
+        /// Name to recall the item by (defaults to the item name, lowercased)
+        #[arg(long)]
+        name: Option<String>,
+    },
+    /// Show the docs of a bookmarked item
+    Show {
+        /// Bookmark name
+        name: String,
+    },
+    /// List bookmarks
+    List,
+    /// Forget a bookmark
+    Remove {
+        /// Bookmark name
+        name: String,
+    },
+}
+
 /// Search for documentation of a symbol in a crate
 #[derive(Parser, Debug)]
-#[command(name = "docsrs")]
+#[command(
+    name = "docsrs",
+    args_conflicts_with_subcommands = true,
+    disable_help_subcommand = true
+)]
 #[command(about = "Search for documentation of a symbol in a crate or list all symbols", long_about = None)]
 #[command(after_help = "\
 EXAMPLES:
@@ -23,6 +226,11 @@ EXAMPLES:
   docsrs tokio::spawn            Specific item
   docsrs serde@1.0::Deserialize  Explicit version
   docsrs tokio task              Search for 'task' in tokio
+  docsrs diff log@0.4.20 0.4.22  API changes between versions
+  docsrs diff-local my-crate     API changes since the last release
+  docsrs search http client      Find crates on crates.io
+  docsrs -- diff                 The crate diff, not the subcommand
+  docsrs '!!'                    Re-run the last query
 ")]
 #[command(after_long_help = "\
 VERSION RESOLUTION:
@@ -44,8 +252,27 @@ EXAMPLES:
   docsrs tokio                   Crate root (version from Cargo.toml)
   docsrs tokio::spawn            Specific item
   docsrs serde@1.0::Deserialize  Explicit version
-  docsrs tokio task              Search for 'task' in tokio")]
+  docsrs tokio task              Search for 'task' in tokio
+  docsrs diff log@0.4.20 0.4.22  API changes between versions
+  docsrs diff-local my-crate     API changes since the last release
+  docsrs -- diff                 The crate diff, not the subcommand
+
+  Subcommand names come before crate names, so crates named like one,
+  e.g. diff or search, are looked up after --.
+
+HISTORY:
+  Queries are remembered in the data directory (or $DOCSRS_HISTORY; set
+  it empty to remember nothing). `docsrs history` lists them, and these
+  arguments re-run one, showing the item it showed:
+    !!    The last query
+    !N    Query N of `docsrs history`
+    !-N   The Nth last query
+
+  Quote them in shells that expand `!`, e.g. docsrs '!!'")]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Crate path: crate[@version][::path] (e.g., "tokio", "serde@1.0", "tokio::task::spawn")
     #[arg(value_parser = parse_crate_spec)]
     pub crate_spec: Option<CrateSpec>,
@@ -57,17 +284,326 @@ pub struct Cli {
     #[arg(long)]
     pub no_cache: bool,
 
+    /// Fetch the docs of the crate a re-export points into (e.g. `std` for
+    /// `pub use std::collections::HashMap`) and show the item itself.
+    #[arg(long)]
+    pub follow_reexports: bool,
+
+    /// Fetch rustdoc JSON from a docs.rs mirror at URL instead of docs.rs,
+    /// as URL/crate/NAME/VERSION/json.
+    #[arg(long, value_name = "URL")]
+    pub mirror: Option<String>,
+
+    /// Look up crates the project doesn't depend on in the registry NAME,
+    /// one of the [registries] in the config, instead of crates.io.
+    #[arg(long, value_name = "NAME")]
+    pub registry: Option<String>,
+
+    /// Fetch the docs docs.rs built for TRIPLE, e.g. x86_64-pc-windows-msvc,
+    /// instead of its default target.
+    #[arg(long, value_name = "TRIPLE")]
+    pub target: Option<String>,
+
+    /// Print how long each phase (download, decompress, parse, process,
+    /// render, ...) took to stderr.
+    #[arg(long)]
+    pub timings: bool,
+
     /// Clear the entire cache directory
     #[arg(long)]
     pub clear_cache: bool,
 
+    /// Keep the docs cache in DIR instead of the user cache directory.
+    #[arg(long, value_name = "DIR")]
+    pub cache_dir: Option<PathBuf>,
+
+    /// Only use cached docs, failing instead of downloading missing ones.
+    #[arg(long, conflicts_with = "no_cache")]
+    pub offline: bool,
+
+    /// Download docs that aren't cached, even when the config file sets
+    /// `offline = true`.
+    #[arg(long, conflicts_with = "offline")]
+    pub online: bool,
+
+    /// Build the docs of a crates.io crate locally from its published
+    /// source, with the nightly toolchain, when docs.rs has none for it.
+    #[arg(long, conflicts_with = "offline")]
+    pub build_fallback: bool,
+
+    /// Print the docs directly instead of through the pager set in the
+    /// config file.
+    #[arg(long)]
+    pub no_pager: bool,
+
+    /// Recompress the cached docs at --compression-level, shrinking caches
+    /// written by earlier versions.
+    #[arg(long, conflicts_with_all = ["crate_spec", "filter", "clear_cache", "warm_cache"])]
+    pub recompress_cache: bool,
+
+    /// zstd level (1-22) downloaded docs are recompressed at before caching,
+    /// or 0 to cache them as downloaded. Higher levels make smaller caches
+    /// but take longer to write.
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        default_value_t = DEFAULT_COMPRESSION_LEVEL,
+        value_parser = clap::value_parser!(i32).range(0..=22)
+    )]
+    pub compression_level: i32,
+
+    /// Search the docs of every direct dependency of the current project
+    /// for items whose path contains FILTER, e.g. to find which one exports
+    /// `Instant`. A disambiguator like `struct@Instant` narrows the kind.
+    #[arg(
+        long,
+        value_name = "FILTER",
+        conflicts_with_all = ["crate_spec", "filter", "tree", "tui", "pick", "open", "copy", "warm_cache"]
+    )]
+    pub workspace: Option<String>,
+
+    /// Download the docs of every dependency of the current project into
+    /// the cache, several crates at a time.
+    #[arg(long, conflicts_with_all = ["crate_spec", "filter", "no_cache", "clear_cache"])]
+    pub warm_cache: bool,
+
+    /// Number of crates --warm-cache fetches at once (defaults to the number
+    /// of CPUs, at most 8).
+    #[arg(
+        long,
+        value_name = "N",
+        requires = "warm_cache",
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub jobs: Option<usize>,
+
     /// When to use colors in output.
     ///
     /// By default, `--color=auto` is active. Using just `--color` without an
-    /// arg is equivalent to `--color=always`.
+    /// arg is equivalent to `--color=always`. Auto respects the `NO_COLOR`,
+    /// `CLICOLOR` and `CLICOLOR_FORCE` environment variables.
     #[arg(long, value_name = "WHEN", default_value = "auto")]
     pub color: Color,
 
+    /// Color palette to emit: auto, truecolor, 256, or 16.
+    ///
+    /// Auto-detection uses `COLORTERM` and `TERM`.
+    #[arg(long, value_name = "DEPTH", default_value = "auto")]
+    pub color_depth: ColorDepthChoice,
+
+    /// Break signatures wider than this many columns across lines
+    /// (defaults to the terminal width; no wrapping when not a terminal).
+    #[arg(long, value_name = "COLUMNS")]
+    pub width: Option<usize>,
+
+    /// Show modules and items as an indented tree instead of docs or a list.
+    #[arg(long)]
+    pub tree: bool,
+
+    /// Browse the crate interactively: searchable items on the left, their
+    /// docs on the right.
+    #[arg(long, requires = "crate_spec", conflicts_with_all = ["filter", "tree"])]
+    pub tui: bool,
+
+    /// When several items match, pick one with an interactive fuzzy search
+    /// and show its docs.
+    #[arg(long, conflicts_with_all = ["tree", "tui"])]
+    pub pick: bool,
+
+    /// Also open the item's docs in the browser: the local HTML docs for
+    /// workspace crates, built with `cargo doc` if needed, else docs.rs.
+    #[arg(long, conflicts_with_all = ["tree", "tui"])]
+    pub open: bool,
+
+    /// Also copy the item's path, signature or first code example to the
+    /// clipboard.
+    #[arg(long, value_name = "WHAT", conflicts_with_all = ["tree", "tui"])]
+    pub copy: Option<CopyTarget>,
+
+    /// Print only the `use` declaration that imports the item, by its
+    /// shortest public path.
+    #[arg(
+        long,
+        requires = "crate_spec",
+        conflicts_with_all = ["tree", "tui", "features_list", "deps", "crate_examples", "check_links", "stats", "what_changed", "graph", "matrix"]
+    )]
+    pub use_stmt: bool,
+
+    /// Print code to start from instead of the docs: for a trait an `impl`
+    /// block with `todo!()` for the required methods, for a function a call
+    /// with a placeholder for each argument.
+    #[arg(
+        long,
+        requires = "crate_spec",
+        conflicts_with_all = ["use_stmt", "tree", "tui", "features_list", "deps", "crate_examples", "check_links", "stats", "what_changed", "graph", "matrix"]
+    )]
+    pub skeleton: bool,
+
+    /// How to write the docs of the item or module: text for the terminal,
+    /// or a man page for `docsrs tokio::select | man -l -`. Searches and
+    /// trees can be written as JSON lines for `jq`, each item as it's found.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "text",
+        conflicts_with_all = ["use_stmt", "skeleton", "tui", "features_list", "deps", "crate_examples", "check_links", "stats", "what_changed", "graph", "matrix"]
+    )]
+    pub format: OutputFormat,
+
+    /// Group list results under headers instead of one flat list.
+    #[arg(long, value_name = "GROUP")]
+    pub group_by: Option<GroupBy>,
+
+    /// Also match the filter against the words of item docs, not just paths.
+    #[arg(long, requires = "filter")]
+    pub full_text: bool,
+
+    /// List the crate's features, and the public items each one unlocks
+    /// where the docs say so.
+    #[arg(
+        long,
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "pick", "open", "copy"]
+    )]
+    pub features_list: bool,
+
+    /// Show the crate's dependency tree from the crates.io index, DEPTH
+    /// levels deep. With --pick, pick a dependency and show its docs.
+    #[arg(
+        long,
+        value_name = "DEPTH",
+        num_args = 0..=1,
+        default_missing_value = "1",
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "features_list", "open", "copy"]
+    )]
+    pub deps: Option<usize>,
+
+    /// List the files in the crate's examples/ directory, or show the
+    /// example NAME (as `cargo run --example` takes it, or a path under
+    /// examples/). With --pick, pick the example to show.
+    #[arg(
+        long,
+        value_name = "NAME",
+        num_args = 0..=1,
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "features_list", "deps", "open", "copy"]
+    )]
+    pub crate_examples: Option<Option<String>>,
+
+    /// Report the intra-doc links in the docs of a crate of the workspace
+    /// that don't resolve to an item, with the item whose docs have them.
+    #[arg(
+        long,
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "pick", "features_list", "deps", "crate_examples", "open", "copy"]
+    )]
+    pub check_links: bool,
+
+    /// Print the size of the crate's public API: the number of items of each
+    /// kind, how many are documented or deprecated, and the `unsafe fn`s.
+    #[arg(
+        long,
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "pick", "features_list", "deps", "crate_examples", "check_links", "open", "copy"]
+    )]
+    pub stats: bool,
+
+    /// List the API changes between the version of the crate viewed before
+    /// and the one docsrs picks now, e.g. after a `cargo update`.
+    #[arg(
+        long,
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "pick", "features_list", "deps", "crate_examples", "check_links", "stats", "open", "copy"]
+    )]
+    pub what_changed: bool,
+
+    /// Print a graph of the crate's structure, in Mermaid or Graphviz DOT,
+    /// see --graph-format.
+    #[arg(
+        long,
+        value_name = "GRAPH",
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "pick", "features_list", "deps", "crate_examples", "check_links", "stats", "what_changed", "open", "copy"]
+    )]
+    pub graph: Option<Graph>,
+
+    /// Format of --graph.
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value = "mermaid",
+        requires = "graph"
+    )]
+    pub graph_format: GraphFormat,
+
+    /// Print which of the commonly implemented traits, like Clone, Debug,
+    /// Send or Serialize, the structs and enums of the module or crate
+    /// implement.
+    #[arg(
+        long,
+        requires = "crate_spec",
+        conflicts_with_all = ["filter", "tree", "tui", "pick", "features_list", "deps", "crate_examples", "check_links", "stats", "what_changed", "graph", "open", "copy"]
+    )]
+    pub matrix: bool,
+
+    /// Read the docs from a rustdoc JSON file, like a CI artifact, instead
+    /// of resolving the crate's version. CRATE_SPEC is then the path of an
+    /// item in it, with or without the crate name. A CRATE_SPEC ending in
+    /// `.json` is read as such a file too.
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["tui", "pick", "open", "copy", "features_list", "deps", "crate_examples", "check_links", "stats", "what_changed", "graph", "matrix", "workspace"]
+    )]
+    pub json_path: Option<PathBuf>,
+
+    /// Only list `const fn`s.
+    #[arg(long = "const", requires = "crate_spec", conflicts_with = "tui")]
+    pub const_: bool,
+
+    /// Only list `async fn`s.
+    #[arg(long = "async", requires = "crate_spec", conflicts_with = "tui")]
+    pub async_: bool,
+
+    /// Only list `unsafe fn`s.
+    #[arg(long = "unsafe", requires = "crate_spec", conflicts_with = "tui")]
+    pub unsafe_: bool,
+
+    /// Show the first sentence of each item's docs next to list results.
+    #[arg(long, value_name = "WHEN", default_value = "auto")]
+    pub summaries: Summaries,
+
+    /// Number the lines of code examples with at least MIN_LINES lines
+    /// (all examples when no value is given).
+    #[arg(
+        long,
+        value_name = "MIN_LINES",
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "1"
+    )]
+    pub line_numbers: Option<usize>,
+
+    /// Frame code examples and label them with their language.
+    #[arg(long, value_name = "STYLE", default_value = "none")]
+    pub code_frame: CodeFrameStyle,
+
+    /// Color signatures with the same syntax highlighter as code examples
+    /// instead of by token kind.
+    #[arg(long)]
+    pub syntect_signatures: bool,
+
+    /// Syntax highlighting theme bundled with docsrs (e.g. "Solarized (dark)").
+    ///
+    /// Defaults to a dark or light theme matching the terminal background.
+    #[arg(long, value_name = "NAME", conflicts_with = "theme_file")]
+    pub theme: Option<String>,
+
+    /// Load a syntect `.tmTheme` file for code highlighting and signature colors
+    #[arg(long, value_name = "PATH")]
+    pub theme_file: Option<PathBuf>,
+
     /// Print the bundled Claude Code SKILL.md to stdout and exit.
     #[arg(
         long,
@@ -1,3 +1,7 @@
+use std::io::IsTerminal;
+
+use rustdoc_fmt::ColorDepth;
+
 /// Controls when to use colors in output.
 #[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
 pub enum Color {
@@ -13,6 +17,50 @@ pub enum Color {
     Always,
 }
 
+impl Color {
+    /// Decide whether to emit colors.
+    ///
+    /// `Auto` follows the CLICOLOR and NO_COLOR conventions, in order of
+    /// priority:
+    /// - `CLICOLOR_FORCE` set and not `0` enables colors
+    /// - `NO_COLOR` set and non-empty disables colors
+    /// - `CLICOLOR=0` disables colors
+    /// - `TERM=dumb` disables colors
+    /// - otherwise colors are used when stdout is a terminal
+    pub fn should_colorize(self) -> bool {
+        match self {
+            Self::Never => false,
+            Self::Always => true,
+            Self::Auto => auto_should_colorize(
+                std::env::var("CLICOLOR_FORCE").ok().as_deref(),
+                std::env::var("NO_COLOR").ok().as_deref(),
+                std::env::var("CLICOLOR").ok().as_deref(),
+                ColorDepth::detect().is_none(),
+                std::io::stdout().is_terminal(),
+            ),
+        }
+    }
+}
+
+fn auto_should_colorize(
+    clicolor_force: Option<&str>,
+    no_color: Option<&str>,
+    clicolor: Option<&str>,
+    dumb_terminal: bool,
+    is_terminal: bool,
+) -> bool {
+    if clicolor_force.is_some_and(|v| !v.is_empty() && v != "0") {
+        return true;
+    }
+    if no_color.is_some_and(|v| !v.is_empty()) {
+        return false;
+    }
+    if clicolor == Some("0") || dumb_terminal {
+        return false;
+    }
+    is_terminal
+}
+
 impl std::str::FromStr for Color {
     type Err = String;
 
@@ -25,3 +73,83 @@ impl std::str::FromStr for Color {
         }
     }
 }
+
+/// Controls how many colors are used in output.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ColorDepthChoice {
+    /// Detect from `COLORTERM` and `TERM`.
+    #[default]
+    Auto,
+
+    /// 24-bit RGB colors.
+    TrueColor,
+
+    /// The xterm 256-color palette.
+    Ansi256,
+
+    /// The 16 basic ANSI colors.
+    Ansi16,
+}
+
+impl ColorDepthChoice {
+    /// The explicitly requested depth, or `None` to keep the detected one.
+    pub fn resolve(self) -> Option<ColorDepth> {
+        match self {
+            Self::Auto => None,
+            Self::TrueColor => Some(ColorDepth::TrueColor),
+            Self::Ansi256 => Some(ColorDepth::Ansi256),
+            Self::Ansi16 => Some(ColorDepth::Ansi16),
+        }
+    }
+}
+
+impl std::str::FromStr for ColorDepthChoice {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "auto" => Ok(Self::Auto),
+            "truecolor" | "24bit" => Ok(Self::TrueColor),
+            "256" => Ok(Self::Ansi256),
+            "16" => Ok(Self::Ansi16),
+            _ => Err(format!("Invalid color depth: {}", s)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auto_clicolor_force_wins() {
+        assert!(auto_should_colorize(
+            Some("1"),
+            Some("1"),
+            Some("0"),
+            false,
+            false
+        ));
+        assert!(!auto_should_colorize(Some("0"), None, None, false, false));
+    }
+
+    #[test]
+    fn test_auto_no_color() {
+        assert!(!auto_should_colorize(None, Some("1"), None, false, true));
+        // An empty NO_COLOR is ignored per no-color.org
+        assert!(auto_should_colorize(None, Some(""), None, false, true));
+    }
+
+    #[test]
+    fn test_auto_clicolor() {
+        assert!(!auto_should_colorize(None, None, Some("0"), false, true));
+        assert!(auto_should_colorize(None, None, Some("1"), false, true));
+        assert!(!auto_should_colorize(None, None, None, false, false));
+    }
+
+    #[test]
+    fn test_auto_dumb_terminal() {
+        assert!(!auto_should_colorize(None, None, None, true, true));
+        assert!(auto_should_colorize(Some("1"), None, None, true, true));
+    }
+}
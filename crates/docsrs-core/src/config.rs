@@ -0,0 +1,570 @@
+//! User preferences from `config.toml` in the config directory, e.g.
+//! `~/.config/docsrs/config.toml`, and project settings from `.docsrs.toml`
+//! next to the project's `Cargo.toml`. The project's settings override the
+//! user's, and command line flags override both.
+
+use std::collections::BTreeMap;
+use std::fmt::{self, Display};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use anyhow::{Context, Result, bail};
+use clap::ArgMatches;
+use clap::parser::ValueSource;
+use directories::ProjectDirs;
+use serde::{Deserialize, Deserializer};
+
+use crate::cli::{Cli, OutputFormat};
+use crate::color::{Color, ColorDepthChoice};
+use crate::source::{Registry, Token};
+
+/// Defaults for the command line flags, and settings without one.
+///
+/// ```toml
+/// color = "always"
+/// theme = "Solarized (dark)"
+/// width = 100
+/// format = "jsonl"
+/// offline = true
+/// pager = "less -R"
+/// target = "x86_64-pc-windows-msvc"
+/// update-check = false
+/// warm = ["regex@1.10"]
+///
+/// [versions]
+/// tokio = "1.38"
+///
+/// [aliases]
+/// tk = "tokio"
+///
+/// [registries.internal]
+/// index = "sparse+https://crates.example.com/index/"
+/// docs = "https://docs.example.com/{name}/{version}/json"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields, rename_all = "kebab-case")]
+pub(crate) struct Config {
+    /// Default for `--color`
+    #[serde(deserialize_with = "parsed")]
+    pub color: Option<Color>,
+    /// Default for `--color-depth`
+    #[serde(deserialize_with = "parsed")]
+    pub color_depth: Option<ColorDepthChoice>,
+    /// Default for `--theme`
+    pub theme: Option<String>,
+    /// Default for `--theme-file`
+    pub theme_file: Option<PathBuf>,
+    /// Default for `--width`
+    pub width: Option<usize>,
+    /// Default for `--format`
+    #[serde(deserialize_with = "parsed")]
+    pub format: Option<OutputFormat>,
+    /// Default for `--cache-dir`
+    pub cache_dir: Option<PathBuf>,
+    /// Default for `--offline`
+    pub offline: Option<bool>,
+    /// Command the docs are piped through when stdout is a terminal, unless
+    /// `--no-pager` is given. Only read from the user's config, as a project
+    /// could otherwise run any command
+    pub pager: Option<String>,
+    /// Default for `--target`
+    pub target: Option<String>,
+    /// Whether to note newer releases of crates whose version comes from
+    /// the lockfile or the cache, on unless set to `false`
+    pub update_check: Option<bool>,
+    /// Versions of crates queried without one, instead of the ones
+    /// `Cargo.toml` resolves
+    pub versions: BTreeMap<String, String>,
+    /// Crates `--warm-cache` fetches besides the project's dependencies, as
+    /// `name` or `name@version`
+    pub warm: Vec<String>,
+    /// Short names for crates, e.g. `tk = "tokio"`
+    pub aliases: BTreeMap<String, String>,
+    /// Registries besides crates.io, by the name `.cargo/config.toml` gives
+    /// them
+    pub registries: BTreeMap<String, RegistryConfig>,
+}
+
+/// A registry besides crates.io, and where the docs of its crates are.
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct RegistryConfig {
+    /// URL of the registry's sparse index, `sparse+https://...`
+    pub index: String,
+    /// URL of the rustdoc JSON of a crate version, with `{name}` and
+    /// `{version}` placeholders
+    pub docs: String,
+    /// `Authorization` header for the index and docs server, e.g.
+    /// `Bearer ...`. Only read from the user's config, and
+    /// `CARGO_REGISTRIES_<NAME>_TOKEN` when it's not set
+    pub token: Option<String>,
+    /// Whether only a project's `.docsrs.toml` defines the registry. Its URLs
+    /// are picked by whoever wrote the project, so no token is sent to them
+    #[serde(skip)]
+    pub from_project: bool,
+}
+
+// By hand, to keep the token out of anything that may be logged
+impl fmt::Debug for RegistryConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RegistryConfig")
+            .field("index", &self.index)
+            .field("docs", &self.docs)
+            .field("token", &self.token.as_ref().map(|_| "<redacted>"))
+            .field("from_project", &self.from_project)
+            .finish()
+    }
+}
+
+/// The variable cargo reads the token of the registry `name` from, e.g.
+/// `CARGO_REGISTRIES_MY_REGISTRY_TOKEN`.
+fn cargo_token_var(name: &str) -> String {
+    format!(
+        "CARGO_REGISTRIES_{}_TOKEN",
+        name.to_uppercase().replace('-', "_")
+    )
+}
+
+/// Deserialize a value from its string form, as given on the command line.
+fn parsed<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: Display,
+{
+    Option::<String>::deserialize(deserializer)?
+        .map(|value| value.parse().map_err(serde::de::Error::custom))
+        .transpose()
+}
+
+/// The config file: `$DOCSRS_CONFIG` when set, or else `config.toml` in
+/// the config directory.
+pub(crate) fn config_path() -> Result<PathBuf> {
+    if let Some(path) = std::env::var_os("DOCSRS_CONFIG") {
+        return Ok(PathBuf::from(path));
+    }
+    let proj_dirs =
+        ProjectDirs::from("", "", "docsrs").context("Failed to determine config directory")?;
+    Ok(proj_dirs.config_dir().join("config.toml"))
+}
+
+/// The project config: `.docsrs.toml` in the nearest directory upward from
+/// the current one that has both it and a `Cargo.toml`, so a workspace can
+/// keep it at its root.
+pub(crate) fn project_config_path() -> Option<PathBuf> {
+    let mut dir = std::env::current_dir().ok()?;
+    loop {
+        let path = dir.join(".docsrs.toml");
+        if path.is_file() && dir.join("Cargo.toml").is_file() {
+            return Some(path);
+        }
+        if !dir.pop() {
+            return None;
+        }
+    }
+}
+
+impl Config {
+    /// Load the user's config file with the project's on top, or the
+    /// defaults when there are none.
+    pub(crate) fn load() -> Result<Self> {
+        let mut config = Self::load_from(&config_path()?)?;
+        if let Some(path) = project_config_path() {
+            let project = Self::load_from(&path)?;
+            if project.pager.is_some() {
+                bail!(
+                    "Invalid config file {}: pager can only be set in {}",
+                    path.display(),
+                    config_path()?.display()
+                );
+            }
+            // Project configs are checked in, where secrets don't belong
+            if let Some(name) = project
+                .registries
+                .iter()
+                .find_map(|(name, registry)| registry.token.is_some().then_some(name))
+            {
+                bail!(
+                    "Invalid config file {}: the token of registry {name} can only be set in {} or ${}",
+                    path.display(),
+                    config_path()?.display(),
+                    cargo_token_var(name)
+                );
+            }
+            config.merge(project);
+        }
+        Ok(config)
+    }
+
+    /// Load the config file at `path`, or the defaults when it doesn't exist.
+    pub(crate) fn load_from(path: &Path) -> Result<Self> {
+        let config: Self = match fs::read_to_string(path) {
+            Ok(contents) => toml::from_str(&contents)
+                .with_context(|| format!("Invalid config file {}", path.display()))?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Self::default()),
+            Err(e) => {
+                return Err(e)
+                    .with_context(|| format!("Failed to read config file {}", path.display()));
+            }
+        };
+        for (alias, name) in &config.aliases {
+            if name.is_empty() || name.contains(['@', ':', '/']) {
+                bail!(
+                    "Invalid config file {}: alias {alias} must stand for a crate name, not \"{name}\"",
+                    path.display()
+                );
+            }
+        }
+        for name in config.registries.keys() {
+            // The name keys the cache entries of the registry's crates
+            let valid = name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+            if name.is_empty() || !valid {
+                bail!(
+                    "Invalid config file {}: registry name \"{name}\" may only have letters, digits, - and _",
+                    path.display()
+                );
+            }
+        }
+        Ok(config)
+    }
+
+    /// Override these settings with the ones `other` sets.
+    fn merge(&mut self, other: Self) {
+        self.color = other.color.or(self.color);
+        self.color_depth = other.color_depth.or(self.color_depth);
+        if other.theme.is_some() || other.theme_file.is_some() {
+            self.theme = other.theme;
+            self.theme_file = other.theme_file;
+        }
+        self.width = other.width.or(self.width);
+        self.format = other.format.or(self.format);
+        self.cache_dir = other.cache_dir.or(self.cache_dir.take());
+        self.offline = other.offline.or(self.offline);
+        self.pager = other.pager.or(self.pager.take());
+        self.target = other.target.or(self.target.take());
+        self.update_check = other.update_check.or(self.update_check);
+        self.versions.extend(other.versions);
+        self.warm.extend(other.warm);
+        self.aliases.extend(other.aliases);
+        // The user's own entry wins, so a project can't send its token elsewhere
+        for (name, mut registry) in other.registries {
+            registry.from_project = true;
+            self.registries.entry(name).or_insert(registry);
+        }
+    }
+
+    /// The registries configured besides crates.io.
+    pub(crate) fn registries(&self) -> Vec<Registry> {
+        self.registries
+            .iter()
+            .map(|(name, registry)| Registry {
+                name: name.clone(),
+                index: registry.index.clone(),
+                docs: registry.docs.clone(),
+                token: (!registry.from_project)
+                    .then(|| {
+                        registry
+                            .token
+                            .clone()
+                            .or_else(|| std::env::var(cargo_token_var(name)).ok())
+                    })
+                    .flatten()
+                    .map(Token::new),
+            })
+            .collect()
+    }
+
+    /// Fill in the flags of `cli` that weren't given on the command line, as
+    /// parsed into `matches`. Fails when a flag given conflicts with a
+    /// setting, as clap only checks the flags.
+    pub(crate) fn apply(&self, cli: &mut Cli, matches: &ArgMatches) -> Result<()> {
+        let given = |id: &str| matches.value_source(id) == Some(ValueSource::CommandLine);
+
+        if let Some(color) = self.color
+            && !given("color")
+        {
+            cli.color = color;
+        }
+        if let Some(color_depth) = self.color_depth
+            && !given("color_depth")
+        {
+            cli.color_depth = color_depth;
+        }
+        // A theme given either way replaces both configured ones
+        if cli.theme.is_none() && cli.theme_file.is_none() {
+            cli.theme = self.theme.clone();
+            cli.theme_file = self.theme_file.clone();
+        }
+        cli.width = cli.width.or(self.width);
+        if let Some(format) = self.format
+            && !given("format")
+        {
+            cli.format = format;
+        }
+        cli.cache_dir = cli.cache_dir.take().or_else(|| self.cache_dir.clone());
+        if self.offline == Some(true) && !cli.offline && !cli.online {
+            for (given, flag) in [
+                (cli.no_cache, "--no-cache"),
+                (cli.build_fallback, "--build-fallback"),
+            ] {
+                if given {
+                    bail!(
+                        "{flag} can't be used with offline = true from the config file, add --online to download"
+                    );
+                }
+            }
+            cli.offline = true;
+        }
+        cli.target = cli.target.take().or_else(|| self.target.clone());
+        Ok(())
+    }
+
+    /// The pager to pipe the docs through, unless `cli` turns it off.
+    pub(crate) fn pager(&self, cli: &Cli) -> Option<&str> {
+        self.pager
+            .as_deref()
+            .filter(|pager| !cli.no_pager && !pager.trim().is_empty())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use clap::{CommandFactory, FromArgMatches};
+
+    use super::*;
+
+    fn cli_with(config: &Config, args: &[&str]) -> Cli {
+        let matches = Cli::command()
+            .try_get_matches_from(std::iter::once("docsrs").chain(args.iter().copied()))
+            .unwrap();
+        let mut cli = Cli::from_arg_matches(&matches).unwrap();
+        config.apply(&mut cli, &matches).unwrap();
+        cli
+    }
+
+    #[test]
+    fn test_config_fills_in_missing_flags() {
+        let config: Config = toml::from_str(
+            r#"
+            color = "never"
+            color-depth = "256"
+            width = 80
+            format = "jsonl"
+            theme = "Solarized (dark)"
+            offline = true
+            "#,
+        )
+        .unwrap();
+
+        let cli = cli_with(&config, &["tokio"]);
+        assert_eq!(cli.color, Color::Never);
+        assert_eq!(cli.color_depth, ColorDepthChoice::Ansi256);
+        assert_eq!(cli.width, Some(80));
+        assert_eq!(cli.format, OutputFormat::Jsonl);
+        assert_eq!(cli.theme.as_deref(), Some("Solarized (dark)"));
+        assert!(cli.offline);
+
+        let cli = cli_with(
+            &config,
+            &[
+                "tokio",
+                "--color",
+                "always",
+                "--width",
+                "120",
+                "--theme-file",
+                "x",
+                "--format",
+                "text",
+            ],
+        );
+        assert_eq!(cli.color, Color::Always);
+        assert_eq!(cli.width, Some(120));
+        assert_eq!(cli.format, OutputFormat::Text);
+        assert_eq!(cli.theme, None);
+    }
+
+    #[test]
+    fn test_online_overrides_offline_from_config() {
+        let config = Config {
+            offline: Some(true),
+            ..Config::default()
+        };
+        assert!(cli_with(&config, &["tokio"]).offline);
+        assert!(!cli_with(&config, &["tokio", "--online"]).offline);
+        assert!(!cli_with(&config, &["tokio", "--online", "--no-cache"]).offline);
+
+        for flag in ["--no-cache", "--build-fallback"] {
+            let matches = Cli::command()
+                .try_get_matches_from(["docsrs", "tokio", flag])
+                .unwrap();
+            let mut cli = Cli::from_arg_matches(&matches).unwrap();
+            let err = config.apply(&mut cli, &matches).unwrap_err();
+            assert!(err.to_string().starts_with(flag), "{err}");
+        }
+    }
+
+    #[test]
+    fn test_config_rejects_unknown_and_invalid_settings() {
+        assert!(toml::from_str::<Config>("colour = \"never\"").is_err());
+        let err = toml::from_str::<Config>("color = \"sometimes\"").unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid color option: sometimes"),
+            "{err}"
+        );
+        let err = toml::from_str::<Config>("format = \"pdf\"").unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid output format: pdf"),
+            "{err}"
+        );
+    }
+
+    #[test]
+    fn test_project_config_overrides_user_config() {
+        let mut config: Config = toml::from_str(
+            r#"
+            width = 80
+            theme = "Solarized (dark)"
+            offline = true
+            warm = ["regex"]
+            versions = { tokio = "1.38", serde = "1.0.200" }
+            "#,
+        )
+        .unwrap();
+        let project: Config = toml::from_str(
+            r#"
+            theme-file = "team.tmTheme"
+            offline = false
+            target = "x86_64-pc-windows-msvc"
+            warm = ["anyhow@1"]
+
+            [versions]
+            tokio = "1.40"
+            "#,
+        )
+        .unwrap();
+        config.merge(project);
+
+        assert_eq!(config.width, Some(80));
+        assert_eq!(config.theme, None);
+        assert_eq!(config.theme_file, Some(PathBuf::from("team.tmTheme")));
+        assert_eq!(config.offline, Some(false));
+        assert_eq!(config.target.as_deref(), Some("x86_64-pc-windows-msvc"));
+        assert_eq!(config.warm, ["regex", "anyhow@1"]);
+        assert_eq!(config.versions["tokio"], "1.40");
+        assert_eq!(config.versions["serde"], "1.0.200");
+
+        let cli = cli_with(&config, &["tokio", "--target", "aarch64-apple-darwin"]);
+        assert_eq!(cli.target.as_deref(), Some("aarch64-apple-darwin"));
+        assert!(!cli.offline);
+    }
+
+    #[test]
+    fn test_registries() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+            [registries.internal]
+            index = "sparse+https://crates.example.com/index/"
+            docs = "https://docs.example.com/{name}/{version}/json"
+            "#,
+        )
+        .unwrap();
+        let registries = Config::load_from(&path).unwrap().registries();
+        assert_eq!(registries.len(), 1);
+        assert_eq!(registries[0].name, "internal");
+        assert_eq!(
+            registries[0].index_url().unwrap(),
+            "https://crates.example.com/index/"
+        );
+        assert_eq!(
+            cargo_token_var("my-registry"),
+            "CARGO_REGISTRIES_MY_REGISTRY_TOKEN"
+        );
+
+        fs::write(
+            &path,
+            r#"
+            [registries.internal]
+            index = "sparse+https://crates.example.com/index/"
+            docs = "https://docs.example.com/{name}/{version}/json"
+            token = "Bearer s3cret"
+            "#,
+        )
+        .unwrap();
+        let config = Config::load_from(&path).unwrap();
+        // Tokens stay out of anything that may be logged
+        assert!(!format!("{config:?}").contains("s3cret"));
+        let registries = config.registries();
+        assert!(registries[0].token.is_some());
+        assert!(!format!("{registries:?}").contains("s3cret"));
+
+        fs::write(
+            &path,
+            "[registries.\"a/b\"]\nindex = \"sparse+https://x/\"\ndocs = \"https://y/\"\n",
+        )
+        .unwrap();
+        let err = Config::load_from(&path).unwrap_err();
+        assert!(err.to_string().contains("registry name"), "{err}");
+    }
+
+    #[test]
+    fn test_project_registries_get_no_token() {
+        let mut config: Config = toml::from_str(
+            r#"
+            [registries.internal]
+            index = "sparse+https://crates.example.com/index/"
+            docs = "https://docs.example.com/{name}/{version}/json"
+            token = "Bearer s3cret"
+            "#,
+        )
+        .unwrap();
+        let project: Config = toml::from_str(
+            r#"
+            [registries.internal]
+            index = "sparse+https://evil.example.com/index/"
+            docs = "https://evil.example.com/{name}/{version}/json"
+
+            [registries.docsrs-test-project]
+            index = "sparse+https://evil.example.com/index/"
+            docs = "https://evil.example.com/{name}/{version}/json"
+            "#,
+        )
+        .unwrap();
+        config.merge(project);
+        // SAFETY: no other test reads or writes this variable
+        unsafe { std::env::set_var("CARGO_REGISTRIES_DOCSRS_TEST_PROJECT_TOKEN", "Bearer x") };
+
+        let registries = config.registries();
+        let project = &registries[0];
+        assert_eq!(project.name, "docsrs-test-project");
+        assert!(project.token.is_none());
+        let internal = &registries[1];
+        assert_eq!(
+            internal.docs,
+            "https://docs.example.com/{name}/{version}/json"
+        );
+        assert!(internal.token.is_some());
+    }
+
+    #[test]
+    fn test_pager_is_turned_off_by_flag() {
+        let config = Config {
+            pager: Some("less -R".to_string()),
+            ..Config::default()
+        };
+        assert_eq!(
+            config.pager(&cli_with(&config, &["tokio"])),
+            Some("less -R")
+        );
+        assert_eq!(
+            config.pager(&cli_with(&config, &["tokio", "--no-pager"])),
+            None
+        );
+    }
+}
@@ -1,6 +1,11 @@
 use anyhow::{Result, bail};
+use std::collections::BTreeMap;
+use std::fmt;
 use std::str::FromStr;
+use std::sync::RwLock;
 
+#[cfg(feature = "native")]
+use crate::list::EntryKind;
 use crate::util::normalize_crate_name;
 
 /// Represents a crate specification with optional version and path prefix
@@ -12,6 +17,11 @@ use crate::util::normalize_crate_name;
 /// - `tokio@1.0` → name="tokio", version=Some("1.0"), path_prefix=None
 /// - `tokio::task` → name="tokio", version=None, path_prefix=Some("task")
 /// - `tokio@1.0::task::spawn` → name="tokio", version=Some("1.0"), path_prefix=Some("task::spawn")
+/// - `tokio::macro@select` → name="tokio", version=None, path_prefix=Some("macro@select")
+///
+/// docs.rs URLs are accepted too, e.g.
+/// `https://docs.rs/tokio/1.38.0/tokio/task/fn.spawn.html` → name="tokio",
+/// version=Some("1.38.0"), path_prefix=Some("task::fn@spawn")
 #[derive(Debug, Clone)]
 pub struct CrateSpec {
     /// Normalized name (hyphens → underscores), used for Rust path matching
@@ -22,10 +32,67 @@ pub struct CrateSpec {
     pub path_prefix: Option<String>,
 }
 
+/// Short names for crates, expanded when a spec is parsed, e.g. `tk` for
+/// `tokio`. Keyed by normalized name.
+static ALIASES: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Expand `aliases`, crate names by alias, in every spec parsed later in
+/// this process.
+pub fn set_crate_aliases(aliases: &BTreeMap<String, String>) {
+    if let Ok(mut current) = ALIASES.write() {
+        *current = aliases
+            .iter()
+            .map(|(alias, name)| (normalize_crate_name(alias), name.clone()))
+            .collect();
+    }
+}
+
+/// The aliases given to [`set_crate_aliases`], by normalized alias.
+pub(crate) fn crate_aliases() -> BTreeMap<String, String> {
+    ALIASES
+        .read()
+        .map(|aliases| aliases.clone())
+        .unwrap_or_default()
+}
+
 impl CrateSpec {
+    /// Parse `input`, replacing a crate name that is an alias set with
+    /// [`set_crate_aliases`] by the crate it stands for.
     pub fn parse(input: &str) -> Result<Self> {
-        // First, split on '@' to separate name from version+path
-        let (name, remainder) = if let Some(at_pos) = input.find('@') {
+        Self::parse_with_aliases(input, &crate_aliases())
+    }
+
+    /// Parse `input`, replacing a crate name among `aliases` (by normalized
+    /// name) with the crate it stands for. The expanded name is resolved like
+    /// any other, so it may itself be a dependency renamed in Cargo.toml.
+    pub(crate) fn parse_with_aliases(
+        input: &str,
+        aliases: &BTreeMap<String, String>,
+    ) -> Result<Self> {
+        let mut spec = Self::parse_raw(input)?;
+        if let Some(name) = aliases.get(&spec.name) {
+            spec.name = normalize_crate_name(name);
+            spec.original_name = name.clone();
+        }
+        Ok(spec)
+    }
+
+    fn parse_raw(input: &str) -> Result<Self> {
+        if let Some(url) = ["https://docs.rs/", "http://docs.rs/", "docs.rs/"]
+            .iter()
+            .find_map(|prefix| input.strip_prefix(prefix))
+        {
+            return Self::parse_docs_rs_url(url);
+        }
+
+        // First, split on '@' to separate name from version+path. An '@'
+        // after the first '::' is a disambiguator in the path instead.
+        let at_pos = input.find('@').filter(|&at_pos| {
+            input
+                .find("::")
+                .is_none_or(|colons_pos| at_pos < colons_pos)
+        });
+        let (name, remainder) = if let Some(at_pos) = at_pos {
             let name = &input[..at_pos];
             let remainder = &input[at_pos + 1..];
             (name, Some(remainder))
@@ -55,6 +122,98 @@ impl CrateSpec {
         Self::build(name, version, path_prefix)
     }
 
+    /// Parse the part of a docs.rs URL after the host: `crate/version`, then
+    /// the page of an item in the crate. The `#method.name` of a member of a
+    /// type or trait is part of the path.
+    fn parse_docs_rs_url(url: &str) -> Result<Self> {
+        let (url, fragment) = match url.split_once('#') {
+            Some((url, fragment)) => (url, Some(fragment)),
+            None => (url, None),
+        };
+        let url = url.split('?').next().unwrap_or_default();
+        let mut segments = url.split('/').filter(|segment| !segment.is_empty());
+        // The crate page: docs.rs/crate/tokio/1.38.0
+        let first = match segments.next() {
+            Some("crate") => segments.next(),
+            first => first,
+        };
+        let Some(name) = first else {
+            bail!("The docs.rs URL doesn't name a crate");
+        };
+        let version = segments.next();
+
+        let member = fragment.and_then(|fragment| {
+            [
+                "method.",
+                "tymethod.",
+                "variant.",
+                "structfield.",
+                "associatedtype.",
+                "associatedconstant.",
+            ]
+            .iter()
+            .find_map(|prefix| fragment.strip_prefix(prefix))
+        });
+
+        // Skip the crate's own module, e.g. `tokio` or `serde_json`
+        let mut path: Vec<&str> = segments.skip(1).collect();
+        let item = match path.pop() {
+            Some("index.html" | "all.html") | None => None,
+            Some(page) => Some(match page.strip_suffix(".html") {
+                // Disambiguators only go on the last segment of a path
+                Some(page) if member.is_none() => Self::docs_rs_page_item(page),
+                Some(page) => page
+                    .split_once('.')
+                    .map_or(page, |(_, name)| name)
+                    .to_string(),
+                None => page.to_string(),
+            }),
+        };
+        let mut path: Vec<String> = path.into_iter().map(str::to_string).collect();
+        path.extend(item);
+        path.extend(member.map(str::to_string));
+
+        let path = path.join("::");
+        Self::build(name, version, Some(&path))
+    }
+
+    /// The item of a docs.rs page file name, like `fn@spawn` for `fn.spawn`.
+    /// Kinds docsrs can't disambiguate by are left out, as in `union.Foo`.
+    fn docs_rs_page_item(page: &str) -> String {
+        let Some((kind, name)) = page.split_once('.') else {
+            return page.to_string();
+        };
+        match kind {
+            "fn" | "struct" | "enum" | "trait" | "macro" | "static" => format!("{kind}@{name}"),
+            "constant" => format!("const@{name}"),
+            "type" => format!("type@{name}"),
+            _ => name.to_string(),
+        }
+    }
+
+    /// The spec of the item at the full `path` in this crate, at the same
+    /// version requirement and disambiguated by `kind`, or of the crate for
+    /// its root module.
+    #[cfg(feature = "native")]
+    pub(crate) fn item(&self, path: &str, kind: Option<EntryKind>) -> Option<Self> {
+        if path == self.name {
+            return Some(Self {
+                path_prefix: None,
+                ..self.clone()
+            });
+        }
+        let within = path.strip_prefix(&format!("{}::", self.name))?;
+        let within = match (kind, within.rsplit_once("::")) {
+            (None, _) => within.to_string(),
+            (Some(kind), Some((parent, name))) => format!("{parent}::{}@{name}", kind.keyword()),
+            (Some(kind), None) => format!("{}@{within}", kind.keyword()),
+        };
+        Some(Self {
+            path_prefix: Some(within),
+            ..self.clone()
+        })
+    }
+
     fn build(name: &str, version: Option<&str>, path_prefix: Option<&str>) -> Result<Self> {
         if name.trim().is_empty() {
             bail!("Crate name cannot be empty");
@@ -83,6 +242,19 @@ impl CrateSpec {
     }
 }
 
+impl fmt::Display for CrateSpec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.original_name)?;
+        if let Some(version) = &self.version {
+            write!(f, "@{version}")?;
+        }
+        if let Some(path) = &self.path_prefix {
+            write!(f, "::{path}")?;
+        }
+        Ok(())
+    }
+}
+
 impl FromStr for CrateSpec {
     type Err = anyhow::Error;
 
@@ -159,6 +331,30 @@ mod tests {
         assert_eq!(spec.path_prefix, Some("task::spawn".to_string()));
     }
 
+    #[test]
+    fn test_display_round_trips() {
+        for input in [
+            "tokio",
+            "serde@^1.0",
+            "tokio@1.0::task::fn@spawn",
+            "my-crate::Foo",
+        ] {
+            assert_eq!(CrateSpec::parse(input).unwrap().to_string(), input);
+        }
+    }
+
+    #[test]
+    fn test_parse_path_with_disambiguator() {
+        let spec = CrateSpec::parse("tokio::macro@select").unwrap();
+        assert_eq!(spec.name, "tokio");
+        assert_eq!(spec.version, None);
+        assert_eq!(spec.path_prefix, Some("macro@select".to_string()));
+
+        let spec = CrateSpec::parse("tokio@1.0::macro@select").unwrap();
+        assert_eq!(spec.version, Some("1.0".to_string()));
+        assert_eq!(spec.path_prefix, Some("macro@select".to_string()));
+    }
+
     #[test]
     fn test_parse_path_with_trailing_colons() {
         let spec = CrateSpec::parse("tokio::task::").unwrap();
@@ -166,6 +362,36 @@ mod tests {
         assert_eq!(spec.path_prefix, Some("task".to_string()));
     }
 
+    #[test]
+    fn test_parse_docs_rs_url() {
+        let spec =
+            CrateSpec::parse("https://docs.rs/tokio/1.38.0/tokio/task/fn.spawn.html").unwrap();
+        assert_eq!(spec.name, "tokio");
+        assert_eq!(spec.version, Some("1.38.0".to_string()));
+        assert_eq!(spec.path_prefix, Some("task::fn@spawn".to_string()));
+
+        let spec = CrateSpec::parse("https://docs.rs/serde-json/latest/serde_json/").unwrap();
+        assert_eq!(spec.original_name, "serde-json");
+        assert_eq!(spec.version, Some("latest".to_string()));
+        assert_eq!(spec.path_prefix, None);
+
+        let spec = CrateSpec::parse("docs.rs/tokio/1/tokio/sync/index.html").unwrap();
+        assert_eq!(spec.path_prefix, Some("sync".to_string()));
+
+        let spec = CrateSpec::parse(
+            "https://docs.rs/tokio/1.38.0/tokio/sync/struct.Mutex.html#method.lock",
+        )
+        .unwrap();
+        assert_eq!(spec.path_prefix, Some("sync::Mutex::lock".to_string()));
+
+        let spec = CrateSpec::parse("https://docs.rs/crate/tokio/1.38.0").unwrap();
+        assert_eq!(spec.version, Some("1.38.0".to_string()));
+        assert_eq!(spec.path_prefix, None);
+
+        let spec = CrateSpec::parse("https://docs.rs/tokio").unwrap();
+        assert_eq!(spec.version, None);
+    }
+
     #[test]
     fn test_parse_empty_name_fails() {
         let result = CrateSpec::parse("");
@@ -229,6 +455,37 @@ mod tests {
         assert_eq!(spec.original_name, "serde_json");
     }
 
+    #[test]
+    fn test_alias_expanded() {
+        let aliases = BTreeMap::from([("tk".to_string(), "tokio".to_string())]);
+        let spec = CrateSpec::parse_with_aliases("tk@1.40::sync::Mutex", &aliases).unwrap();
+        assert_eq!(spec.to_string(), "tokio@1.40::sync::Mutex");
+
+        let aliases = BTreeMap::from([("sj".to_string(), "serde-json".to_string())]);
+        let spec = CrateSpec::parse_with_aliases("sj", &aliases).unwrap();
+        assert_eq!(spec.name, "serde_json");
+        assert_eq!(spec.original_name, "serde-json");
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
+    fn test_item_keeps_version_and_disambiguates() {
+        let spec = CrateSpec::parse("tokio@1.40").unwrap();
+        let item = |path, kind| spec.item(path, kind).map(|item| item.to_string());
+        assert_eq!(
+            item("tokio::task::spawn", Some(EntryKind::Function)).as_deref(),
+            Some("tokio@1.40::task::fn@spawn")
+        );
+        assert_eq!(
+            item("tokio::task", None).as_deref(),
+            Some("tokio@1.40::task")
+        );
+        assert_eq!(
+            item("tokio", Some(EntryKind::Module)).as_deref(),
+            Some("tokio@1.40")
+        );
+    }
+
     #[test]
     fn test_original_name_preserved_with_version() {
         let spec = CrateSpec::parse("sea-orm@1.0").unwrap();
@@ -0,0 +1,431 @@
+//! Lookups in the crates.io API: crate searches, the metadata of
+//! published versions and their READMEs.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use flate2::read::GzDecoder;
+use rustdoc_fmt::Output;
+use serde::Deserialize;
+
+use crate::docfetch::{get_cache_path, is_offline};
+use crate::list::render_rows;
+use crate::result::{CrateInfo, StyleConfig};
+
+/// How long a crates.io request may take, so lookups that only add to the
+/// output don't hold it up.
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A GET request to the crates.io API at `path`, e.g. `/crates`.
+fn get(path: &str) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+    ureq::get(format!("https://crates.io/api/v1{path}"))
+        // crates.io rejects requests that don't say who is asking
+        .header(
+            "User-Agent",
+            "docsrs (https://github.com/human-solutions/mx-docsrs)",
+        )
+        .config()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+}
+
+/// The first page of crates crates.io finds for a query.
+#[derive(Deserialize)]
+pub(crate) struct SearchResults {
+    pub crates: Vec<FoundCrate>,
+    meta: SearchMeta,
+}
+
+#[derive(Deserialize)]
+struct SearchMeta {
+    total: usize,
+}
+
+/// A crate found by a crates.io search.
+#[derive(Deserialize)]
+pub(crate) struct FoundCrate {
+    pub name: String,
+    pub description: Option<String>,
+    max_stable_version: Option<String>,
+    max_version: String,
+}
+
+impl FoundCrate {
+    /// The newest stable version, or the newest one when all are
+    /// pre-releases.
+    fn version(&self) -> &str {
+        self.max_stable_version
+            .as_deref()
+            .unwrap_or(&self.max_version)
+    }
+}
+
+/// The first `per_page` crates crates.io finds for `query`, most relevant
+/// first.
+pub(crate) fn search(query: &str, per_page: usize) -> Result<SearchResults> {
+    let mut response = get("/crates")
+        .query("q", query)
+        .query("per_page", per_page.to_string())
+        .call()
+        .context("Failed to search crates.io")?;
+    serde_json::from_reader(response.body_mut().as_reader())
+        .context("Failed to read the crates.io search results")
+}
+
+/// Names of the crates crates.io finds for `query`.
+pub(crate) fn search_names(query: &str) -> Result<Vec<String>> {
+    let results = search(query, 20)?;
+    Ok(results.crates.into_iter().map(|found| found.name).collect())
+}
+
+/// Render search results as `name@version` specs, ready to query, with
+/// their descriptions in an aligned column.
+pub(crate) fn render_search(query: &str, results: &SearchResults, style: &StyleConfig) -> String {
+    let count = if results.crates.len() < results.meta.total {
+        format!("{} of {}", results.crates.len(), results.meta.total)
+    } else {
+        results.meta.total.to_string()
+    };
+    let description = match results.meta.total {
+        0 => format!("// no crates on crates.io matching \"{query}\""),
+        1 => format!("// 1 crate on crates.io matching \"{query}\""),
+        _ => format!("// {count} crates on crates.io matching \"{query}\""),
+    };
+
+    // Descriptions are often wrapped in Cargo.toml
+    let descriptions: Vec<Option<String>> = results
+        .crates
+        .iter()
+        .map(|found| {
+            let description = found.description.as_deref()?;
+            Some(description.split_whitespace().collect::<Vec<_>>().join(" "))
+        })
+        .collect();
+    let rows = results
+        .crates
+        .iter()
+        .zip(&descriptions)
+        .map(|(found, description)| {
+            let mut spec = Output::new();
+            spec.identifier(found.name.clone())
+                .symbol("@")
+                .identifier(found.version().to_string());
+            (spec, description.as_deref())
+        })
+        .collect();
+    let list = render_rows(rows, "", &style.colorizer, true, style.max_width).join("\n");
+    format!("{}\n\n{list}\n", description.bright_black())
+}
+
+#[derive(Deserialize)]
+struct VersionResponse {
+    version: PublishedVersion,
+}
+
+/// The parts of a published version's `Cargo.toml` crates.io reports.
+#[derive(Deserialize)]
+struct PublishedVersion {
+    description: Option<String>,
+    license: Option<String>,
+    repository: Option<String>,
+    rust_version: Option<String>,
+    edition: Option<String>,
+}
+
+/// Metadata of crate@version as published on crates.io. Published versions
+/// don't change, so the answer is cached next to the docs of the version.
+pub(crate) fn crate_info(crate_name: &str, version: &str) -> Result<CrateInfo> {
+    let cache_path = get_cache_path(crate_name, version)?.with_extension("info.json");
+    if let Ok(json) = fs::read_to_string(&cache_path)
+        && let Ok(info) = parse_crate_info(crate_name, version, &json)
+    {
+        return Ok(info);
+    }
+
+    let mut response = get(&format!("/crates/{crate_name}/{version}")).call()?;
+    let json = response.body_mut().read_to_string()?;
+    let info = parse_crate_info(crate_name, version, &json)?;
+    if let Some(dir) = cache_path.parent() {
+        // Only a later lookup is slower when this fails
+        let _ = fs::create_dir_all(dir).and_then(|()| fs::write(&cache_path, json));
+    }
+    Ok(info)
+}
+
+fn parse_crate_info(crate_name: &str, version: &str, json: &str) -> Result<CrateInfo> {
+    let response: VersionResponse = serde_json::from_str(json)?;
+    let version_info = response.version;
+    Ok(CrateInfo {
+        description: version_info.description,
+        license: version_info.license,
+        repository: version_info.repository,
+        docs_url: Some(format!("https://docs.rs/{crate_name}/{version}")),
+        rust_version: version_info.rust_version,
+        edition: version_info.edition,
+    })
+}
+
+/// The README of crate@version, taken from the `.crate` file crates.io
+/// serves for it. Cached next to the docs of the version.
+pub(crate) fn readme(crate_name: &str, version: &str) -> Result<String> {
+    let cache_path = get_cache_path(crate_name, version)?.with_extension("readme.md");
+    if let Ok(readme) = fs::read_to_string(&cache_path) {
+        return Ok(readme);
+    }
+    if is_offline() {
+        bail!("The README of {crate_name} {version} isn't cached, and docsrs is offline");
+    }
+
+    let readme = readme_in_crate_file(crate_file(crate_name, version)?, crate_name, version)?;
+    if let Some(dir) = cache_path.parent() {
+        // Only a later lookup is slower when this fails
+        let _ = fs::create_dir_all(dir).and_then(|()| fs::write(&cache_path, &readme));
+    }
+    Ok(readme)
+}
+
+/// The `.crate` file crates.io serves for crate@version, a gzipped tarball
+/// of its published source.
+fn crate_file(crate_name: &str, version: &str) -> Result<impl Read> {
+    let url = format!("https://static.crates.io/crates/{crate_name}/{crate_name}-{version}.crate");
+    let response = ureq::get(&url)
+        .config()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .call()
+        .with_context(|| format!("Failed to fetch {url}"))?;
+    Ok(response.into_body().into_reader())
+}
+
+/// Unpack the published source of crate@version into `dir`, returning the
+/// directory of its `Cargo.toml`.
+pub(crate) fn unpack_crate(crate_name: &str, version: &str, dir: &Path) -> Result<PathBuf> {
+    let mut archive = tar::Archive::new(GzDecoder::new(crate_file(crate_name, version)?));
+    archive
+        .unpack(dir)
+        .with_context(|| format!("Failed to unpack {crate_name} {version}"))?;
+    Ok(dir.join(format!("{crate_name}-{version}")))
+}
+
+/// The `.crate` file of crate@version, cached next to its docs.
+pub(crate) fn crate_source(crate_name: &str, version: &str) -> Result<Vec<u8>> {
+    let cache_path = get_cache_path(crate_name, version)?.with_extension("crate");
+    if let Ok(data) = fs::read(&cache_path) {
+        return Ok(data);
+    }
+    if is_offline() {
+        bail!("The source of {crate_name} {version} isn't cached, and docsrs is offline");
+    }
+
+    let mut data = Vec::new();
+    crate_file(crate_name, version)?
+        .read_to_end(&mut data)
+        .with_context(|| format!("Failed to download {crate_name} {version}"))?;
+    if let Some(dir) = cache_path.parent() {
+        // Only a later lookup is slower when this fails
+        let _ = fs::create_dir_all(dir).and_then(|()| fs::write(&cache_path, &data));
+    }
+    Ok(data)
+}
+
+/// The text files in a `.crate` file, by their path in the crate.
+pub(crate) fn crate_files(
+    reader: impl Read,
+    crate_name: &str,
+    version: &str,
+) -> Result<HashMap<PathBuf, String>> {
+    let root = format!("{crate_name}-{version}");
+    let mut archive = tar::Archive::new(GzDecoder::new(reader));
+    let mut files = HashMap::new();
+    for entry in archive
+        .entries()
+        .context("Failed to read the .crate file")?
+    {
+        let mut entry = entry.context("Failed to read the .crate file")?;
+        let Ok(path) = entry.path()?.strip_prefix(&root).map(Path::to_path_buf) else {
+            continue;
+        };
+        let mut contents = String::new();
+        if entry.read_to_string(&mut contents).is_ok() {
+            files.insert(path, contents);
+        }
+    }
+    Ok(files)
+}
+
+/// The README in a `.crate` file: the file the `readme` field of its
+/// `Cargo.toml` names, or `README.md`.
+fn readme_in_crate_file(reader: impl Read, crate_name: &str, version: &str) -> Result<String> {
+    // All of them, as the README may come before the Cargo.toml naming it
+    let mut files = crate_files(reader, crate_name, version)?;
+
+    let manifest: toml::Table = files
+        .get(Path::new("Cargo.toml"))
+        .context("The .crate file has no Cargo.toml")?
+        .parse()
+        .context("Failed to parse the Cargo.toml of the .crate file")?;
+    let readme = manifest
+        .get("package")
+        .and_then(|package| package.get("readme"))
+        .and_then(|readme| readme.as_str())
+        .unwrap_or("README.md");
+    files
+        .remove(Path::new(readme))
+        .with_context(|| format!("{crate_name} {version} has no README"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search_results_prefer_stable_versions() {
+        let results: SearchResults = serde_json::from_str(
+            r#"{
+                "crates": [
+                    {
+                        "name": "reqwest",
+                        "description": "higher level HTTP client library",
+                        "max_stable_version": "0.12.9",
+                        "max_version": "0.12.9"
+                    },
+                    {
+                        "name": "hyper-next",
+                        "description": null,
+                        "max_stable_version": null,
+                        "max_version": "0.1.0-alpha.1"
+                    }
+                ],
+                "meta": {"total": 1523}
+            }"#,
+        )
+        .unwrap();
+        let specs: Vec<String> = results
+            .crates
+            .iter()
+            .map(|found| format!("{}@{}", found.name, found.version()))
+            .collect();
+        assert_eq!(specs, ["reqwest@0.12.9", "hyper-next@0.1.0-alpha.1"]);
+        assert_eq!(results.meta.total, 1523);
+    }
+
+    #[test]
+    fn test_parse_crate_info() {
+        let info = parse_crate_info(
+            "tokio",
+            "1.38.0",
+            r#"{"version": {
+                "num": "1.38.0",
+                "description": "An event-driven, non-blocking I/O platform",
+                "license": "MIT",
+                "repository": "https://github.com/tokio-rs/tokio",
+                "rust_version": "1.63",
+                "edition": "2021"
+            }}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            info.description.as_deref(),
+            Some("An event-driven, non-blocking I/O platform")
+        );
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+        assert_eq!(
+            info.repository.as_deref(),
+            Some("https://github.com/tokio-rs/tokio")
+        );
+        assert_eq!(
+            info.docs_url.as_deref(),
+            Some("https://docs.rs/tokio/1.38.0")
+        );
+        assert_eq!(info.rust_version.as_deref(), Some("1.63"));
+        assert_eq!(info.edition.as_deref(), Some("2021"));
+
+        // Versions published before crates.io recorded them
+        let info = parse_crate_info(
+            "old",
+            "0.1.0",
+            r#"{"version": {"num": "0.1.0", "rust_version": null}}"#,
+        )
+        .unwrap();
+        assert_eq!(info.rust_version, None);
+        assert_eq!(info.edition, None);
+        assert_eq!(info.license, None);
+    }
+
+    #[test]
+    fn test_crate_info_is_read_from_the_cache() {
+        let _globals = crate::lock_test_globals();
+        let cache = tempfile::tempdir().unwrap();
+        let previous = crate::docfetch::replace_cache_dir(Some(cache.path().to_path_buf()));
+        let cache_path = get_cache_path("cached-crate", "1.0.0")
+            .unwrap()
+            .with_extension("info.json");
+        fs::create_dir_all(cache_path.parent().unwrap()).unwrap();
+        fs::write(
+            &cache_path,
+            r#"{"version": {"num": "1.0.0", "license": "MIT", "edition": "2021"}}"#,
+        )
+        .unwrap();
+
+        // A crate that isn't on crates.io, so only the cache can answer
+        let info = crate_info("cached-crate", "1.0.0");
+        crate::docfetch::replace_cache_dir(previous);
+        let info = info.unwrap();
+        assert_eq!(info.license.as_deref(), Some("MIT"));
+        assert_eq!(info.edition.as_deref(), Some("2021"));
+        assert_eq!(
+            info.docs_url.as_deref(),
+            Some("https://docs.rs/cached-crate/1.0.0")
+        );
+    }
+
+    /// A `.crate` file with the given files under `name-version/`.
+    fn crate_file(files: &[(&str, &str)]) -> Vec<u8> {
+        let encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        let mut builder = tar::Builder::new(encoder);
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            builder
+                .append_data(
+                    &mut header,
+                    format!("demo-0.1.0/{path}"),
+                    contents.as_bytes(),
+                )
+                .unwrap();
+        }
+        builder.into_inner().unwrap().finish().unwrap()
+    }
+
+    #[test]
+    fn test_readme_in_crate_file() {
+        // The README comes first, as in files cargo packages
+        let file = crate_file(&[
+            ("docs/intro.md", "# Demo"),
+            ("README.md", "# Not this one"),
+            (
+                "Cargo.toml",
+                "[package]\nname = \"demo\"\nreadme = \"docs/intro.md\"\n",
+            ),
+        ]);
+        let readme = readme_in_crate_file(file.as_slice(), "demo", "0.1.0").unwrap();
+        assert_eq!(readme, "# Demo");
+
+        let file = crate_file(&[
+            ("Cargo.toml", "[package]\nname = \"demo\"\n"),
+            ("README.md", "# Default"),
+        ]);
+        let readme = readme_in_crate_file(file.as_slice(), "demo", "0.1.0").unwrap();
+        assert_eq!(readme, "# Default");
+
+        let file = crate_file(&[("Cargo.toml", "[package]\nname = \"demo\"\n")]);
+        assert!(readme_in_crate_file(file.as_slice(), "demo", "0.1.0").is_err());
+    }
+}
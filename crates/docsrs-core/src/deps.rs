@@ -0,0 +1,343 @@
+//! The dependency tree of a published crate, from the crates.io index, see
+//! `docsrs <crate> --deps`.
+
+use std::collections::HashSet;
+
+use anyhow::{Context, Result, bail};
+use cargo_metadata::semver::VersionReq;
+use colored::Colorize;
+use rustdoc_fmt::Output;
+
+use crate::crate_spec::CrateSpec;
+use crate::list::render_rows;
+use crate::registry_index::{self, IndexDep, IndexEntry, version_req};
+use crate::source::{default_registry, qualified_name, registry_of_source};
+use crate::version_resolver::{VersionResolver, pinned_version};
+use crate::{EventSink, Query, Resolution, StyleConfig};
+
+/// A crate in the dependency tree.
+#[derive(Clone, Debug)]
+pub struct DepNode {
+    /// Name of the crate in its registry
+    pub name: String,
+    /// Registry the crate is from, when it isn't crates.io
+    pub registry: Option<String>,
+    /// Name the parent uses for it, when it's renamed
+    pub alias: Option<String>,
+    /// Version requirement of the parent, `None` for the root
+    pub req: Option<String>,
+    /// Newest published version matching the requirement, `None` when it
+    /// couldn't be found
+    pub version: Option<String>,
+    /// How the parent depends on it, e.g. `optional` or `build`
+    pub notes: Vec<String>,
+    pub children: Vec<DepNode>,
+    /// Set when the crate's dependencies are already shown further up
+    pub repeated: bool,
+}
+
+/// The dependencies of a version of a crate, as published.
+pub struct DepTree {
+    pub resolution: Resolution,
+    pub root: DepNode,
+}
+
+/// The dependency tree of the crate in `args`, `depth` levels deep. Each
+/// dependency is shown at the newest version its requirement allows, which
+/// is what a fresh `cargo update` would pick. Dev-dependencies are left out.
+pub fn dep_tree(args: &Query, depth: usize) -> Result<DepTree> {
+    let mut resolution = Resolution::default();
+    let (registry, version) = root_version(&args.spec, &mut resolution)?;
+    let name = args.spec.original_name.clone();
+    let qualified = qualified_name(registry.as_deref(), &name);
+    let entry = registry_index::newest_matching(&qualified, &version_req(&version)?)?;
+
+    let mut root = DepNode {
+        name,
+        registry,
+        alias: None,
+        req: None,
+        version: Some(entry.vers.clone()),
+        notes: Vec::new(),
+        children: Vec::new(),
+        repeated: false,
+    };
+    let mut seen = HashSet::from([(qualified, entry.vers.clone())]);
+    root.children = children(&root, &entry, depth, &mut seen, &*args.events);
+    resolution.registry = root.registry.clone();
+    Ok(DepTree { resolution, root })
+}
+
+/// Registry and version of the queried crate, resolved like the docs would
+/// be, except that crates of the workspace aren't built.
+fn root_version(spec: &CrateSpec, resolution: &mut Resolution) -> Result<(Option<String>, String)> {
+    if let Some(version) = &spec.version {
+        return Ok((default_registry(), version.clone()));
+    }
+    if let Some(pinned) = pinned_version(&spec.name) {
+        resolution.message = Some(format!("{}@{} (pinned)", spec.original_name, pinned));
+        return Ok((default_registry(), pinned));
+    }
+    if let Ok(resolver) = VersionResolver::new()
+        && let Some(resolved) = resolver.resolve_crate(&spec.name)
+    {
+        if resolved.is_local {
+            bail!(
+                "{} is a crate of the workspace, see `cargo tree -p {}`",
+                resolved.name,
+                resolved.name
+            );
+        }
+        resolution.message = Some(resolved.format_message());
+        let registry = resolver.registry_of(&resolved.name, &resolved.version);
+        return Ok((registry, resolved.version));
+    }
+    resolution.message = Some(format!("{}@latest", spec.original_name));
+    Ok((default_registry(), "latest".to_string()))
+}
+
+/// Dependency nodes of `parent`, published as `entry`, with their own
+/// dependencies down to `depth` levels below it. A crate already in `seen`
+/// is shown once more without its dependencies.
+fn children(
+    parent: &DepNode,
+    entry: &IndexEntry,
+    depth: usize,
+    seen: &mut HashSet<(String, String)>,
+    events: &dyn EventSink,
+) -> Vec<DepNode> {
+    if depth == 0 {
+        return Vec::new();
+    }
+    let mut deps: Vec<&IndexDep> = entry
+        .deps
+        .iter()
+        .filter(|dep| dep.kind.as_deref() != Some("dev"))
+        .collect();
+    deps.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let mut nodes = Vec::new();
+    for dep in deps {
+        let name = dep.crate_name().to_string();
+        // Dependencies are from the parent's registry unless they say
+        // otherwise, and from crates.io when it's no registry set up
+        let registry = match &dep.registry {
+            Some(index) => registry_of_source(index),
+            None => parent.registry.clone(),
+        };
+        let qualified = qualified_name(registry.as_deref(), &name);
+        let resolved = VersionReq::parse(&dep.req)
+            .context("Invalid version requirement")
+            .and_then(|req| registry_index::newest_matching(&qualified, &req));
+        let resolved = match resolved {
+            Ok(resolved) => Some(resolved),
+            Err(e) => {
+                events.on_warning(&format!("{name} {}: {e:#}", dep.req));
+                None
+            }
+        };
+        let mut node = DepNode {
+            alias: (dep.name != name).then(|| dep.name.clone()),
+            name,
+            registry,
+            req: Some(dep.req.clone()),
+            version: resolved.as_ref().map(|entry| entry.vers.clone()),
+            notes: notes(dep),
+            children: Vec::new(),
+            repeated: false,
+        };
+        if let Some(resolved) = resolved {
+            if seen.insert((qualified, resolved.vers.clone())) {
+                node.children = children(&node, &resolved, depth - 1, seen, events);
+            } else {
+                node.repeated = depth > 1
+                    && resolved
+                        .deps
+                        .iter()
+                        .any(|dep| dep.kind.as_deref() != Some("dev"));
+            }
+        }
+        nodes.push(node);
+    }
+    nodes
+}
+
+/// How a crate depends on `dep`, besides its version requirement.
+fn notes(dep: &IndexDep) -> Vec<String> {
+    let mut notes = Vec::new();
+    if dep.optional {
+        notes.push("optional".to_string());
+    }
+    if dep.kind.as_deref() == Some("build") {
+        notes.push("build".to_string());
+    }
+    if let Some(target) = &dep.target {
+        notes.push(target.clone());
+    }
+    notes
+}
+
+impl DepNode {
+    /// What tells crates apart in the tree: their registry, name and version.
+    fn key(&self) -> (&Option<String>, &str, &Option<String>) {
+        (&self.registry, &self.name, &self.version)
+    }
+
+    /// `name@version` to query the crate's docs with, when its version is
+    /// known.
+    pub fn spec(&self) -> Option<String> {
+        let version = self.version.as_ref()?;
+        Some(format!("{}@{version}", self.name))
+    }
+
+    /// `name@version` for the tree, after the tree connectors in `prefix`.
+    fn label(&self, prefix: &str) -> Output {
+        let mut output = Output::new();
+        if !prefix.is_empty() {
+            output.symbol(prefix.to_string());
+        }
+        output.identifier(self.name.clone());
+        if let Some(version) = &self.version {
+            output.symbol("@").identifier(version.clone());
+        }
+        if self.repeated {
+            output.whitespace().symbol("(*)");
+        }
+        output
+    }
+
+    /// The requirement and notes, shown as a comment next to the label.
+    fn comment(&self) -> Option<String> {
+        let req = self.req.as_ref()?;
+        let mut parts = vec![req.clone()];
+        if self.version.is_none() {
+            parts.push("not found".to_string());
+        }
+        if let Some(alias) = &self.alias {
+            parts.push(format!("as {alias}"));
+        }
+        parts.extend(self.notes.iter().cloned());
+        Some(parts.join(", "))
+    }
+
+    /// The dependencies below this crate, every one of them once, in the
+    /// order the tree shows them.
+    pub fn dependencies(&self) -> Vec<&DepNode> {
+        let mut nodes = Vec::new();
+        let mut stack: Vec<&DepNode> = self.children.iter().rev().collect();
+        while let Some(node) = stack.pop() {
+            let is_new = !nodes.iter().any(|seen: &&DepNode| seen.key() == node.key());
+            if is_new {
+                nodes.push(node);
+            }
+            stack.extend(node.children.iter().rev());
+        }
+        nodes
+    }
+}
+
+impl DepTree {
+    /// Render the tree with box-drawing connectors, like `cargo tree`, with
+    /// each dependency's requirement in an aligned comment.
+    pub fn render(&self, style: &StyleConfig) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let dependencies = self.root.dependencies();
+        let mut header = match dependencies.len() {
+            0 => format!("// {} has no dependencies", self.root.name),
+            1 => "// 1 dependency".to_string(),
+            count => format!("// {count} dependencies"),
+        };
+        if dependencies.iter().any(|dep| dep.repeated) {
+            header.push_str(", (*) has its dependencies shown above");
+        }
+        output.push_str(&format!("{}\n\n", header.bright_black()));
+
+        let mut labelled = vec![(self.root.label(""), None)];
+        collect_rows(&self.root, "", &mut labelled);
+        let rows = labelled
+            .iter()
+            .map(|(label, comment)| (label.clone(), comment.as_deref()))
+            .collect();
+        let lines = render_rows(rows, "", &style.colorizer, true, style.max_width);
+        output.push_str(&lines.join("\n"));
+        output.push('\n');
+        output
+    }
+}
+
+/// Labels of the dependencies below `node` with their tree connectors,
+/// and their comments.
+fn collect_rows(node: &DepNode, prefix: &str, rows: &mut Vec<(Output, Option<String>)>) {
+    let count = node.children.len();
+    for (index, child) in node.children.iter().enumerate() {
+        let is_last = index + 1 == count;
+        let connector = if is_last { "└── " } else { "├── " };
+        rows.push((
+            child.label(&format!("{prefix}{connector}")),
+            child.comment(),
+        ));
+        let child_prefix = format!("{prefix}{}", if is_last { "    " } else { "│   " });
+        collect_rows(child, &child_prefix, rows);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(name: &str, req: &str, children: Vec<DepNode>) -> DepNode {
+        DepNode {
+            name: name.to_string(),
+            registry: None,
+            alias: None,
+            req: Some(req.to_string()),
+            version: Some(req.trim_start_matches('^').to_string()),
+            notes: Vec::new(),
+            children,
+            repeated: false,
+        }
+    }
+
+    #[test]
+    fn test_render_tree() {
+        let mut optional = node("serde", "^1.0.100", Vec::new());
+        optional.notes.push("optional".to_string());
+        let root = DepNode {
+            name: "demo".to_string(),
+            registry: None,
+            alias: None,
+            req: None,
+            version: Some("0.3.0".to_string()),
+            notes: Vec::new(),
+            children: vec![node("bytes", "^1.0.0", vec![optional.clone()]), optional],
+            repeated: false,
+        };
+        let tree = DepTree {
+            resolution: Resolution::default(),
+            root,
+        };
+        colored::control::set_override(false);
+        let style = StyleConfig {
+            max_width: Some(80),
+            ..StyleConfig::default()
+        };
+        insta::assert_snapshot!(tree.render(&style), @"
+        // 2 dependencies
+
+        demo@0.3.0
+        ├── bytes@1.0.0        // ^1.0.0
+        │   └── serde@1.0.100  // ^1.0.100, optional
+        └── serde@1.0.100      // ^1.0.100, optional
+        ");
+        let specs: Vec<_> = tree
+            .root
+            .dependencies()
+            .iter()
+            .filter_map(|dep| dep.spec())
+            .collect();
+        assert_eq!(specs, ["bytes@1.0.0", "serde@1.0.100"]);
+    }
+}
@@ -0,0 +1,417 @@
+//! Public API differences between two versions of a crate, see `docsrs diff`.
+
+use std::collections::HashMap;
+use std::mem::{Discriminant, discriminant};
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use jsondoc::JsonDoc;
+use rustdoc_fmt::{Token, tokens_to_string};
+use rustdoc_types::{Id, ItemEnum};
+
+use crate::doc::item_signatures;
+use crate::viewed::{record_viewed, viewed_before};
+use crate::{Query, Resolution, ResolvedDocs, resolve_docs};
+
+/// An item of the public API, with its signature.
+#[derive(Clone, Debug)]
+pub struct ApiItem {
+    pub path: String,
+    /// Id of the item in the docs of its version
+    pub id: Id,
+    /// Id of the item's parent, e.g. the enum of a variant
+    pub parent_id: Option<Id>,
+    pub tokens: Vec<Token>,
+}
+
+/// An item whose signature differs between the versions.
+#[derive(Clone, Debug)]
+pub struct ChangedItem {
+    pub old: ApiItem,
+    pub new: ApiItem,
+}
+
+/// The public API differences between two versions of a crate.
+pub struct ApiDiff {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub old_version: String,
+    pub new_version: String,
+    pub removed: Vec<ApiItem>,
+    pub added: Vec<ApiItem>,
+    pub changed: Vec<ChangedItem>,
+    /// Docs of both versions, to look up the items in
+    pub(crate) old_doc: JsonDoc,
+    pub(crate) new_doc: JsonDoc,
+}
+
+/// Compare the public API of the crate version in `old` to `new_version` of
+/// the same crate. A path in `old` limits the comparison to the items under
+/// it.
+pub fn diff_versions(old: &Query, new_version: &str) -> Result<ApiDiff> {
+    let mut resolution = Resolution::default();
+    let old_doc = resolve_docs(&old.spec, &mut resolution)?.load_doc(old.no_cache, &*old.events)?;
+
+    let mut new_spec = old.spec.clone();
+    new_spec.version = Some(new_version.to_string());
+    let new_doc = resolve_docs(&new_spec, &mut Resolution::default())?
+        .load_doc(old.no_cache, &*old.events)?;
+
+    let old_version = version_of(&old_doc, old.spec.version.as_deref().unwrap_or("local"));
+    let new_version = version_of(&new_doc, new_version);
+    Ok(api_diff(
+        old,
+        resolution,
+        (old_doc, old_version),
+        (new_doc, new_version),
+    ))
+}
+
+/// Compare the latest published version of the workspace crate in `local`
+/// to its docs built from the workspace, i.e. the changes the next release
+/// would make. A path in `local` limits the comparison to the items under
+/// it.
+pub fn diff_local(local: &Query) -> Result<ApiDiff> {
+    if let Some(version) = &local.spec.version {
+        bail!(
+            "diff-local compares the workspace's {} with its latest release, drop the @{version}",
+            local.spec.name
+        );
+    }
+    let mut resolution = Resolution::default();
+    let new_doc =
+        resolve_docs(&local.spec, &mut resolution)?.load_doc(local.no_cache, &*local.events)?;
+    if resolution.local.is_none() {
+        bail!(
+            "diff-local compares crates of the workspace, and {} isn't one",
+            local.spec.original_name
+        );
+    }
+
+    let mut published = local.spec.clone();
+    published.version = Some("latest".to_string());
+    let old_doc = resolve_docs(&published, &mut Resolution::default())?
+        .load_doc(local.no_cache, &*local.events)
+        .with_context(|| {
+            format!(
+                "Failed to load the docs of the latest release of {}",
+                local.spec.name
+            )
+        })?;
+
+    let old_version = version_of(&old_doc, "latest");
+    let new_version = version_of(&new_doc, "local");
+    Ok(api_diff(
+        local,
+        resolution,
+        (old_doc, old_version),
+        (new_doc, new_version),
+    ))
+}
+
+/// Compare the version of the crate in `args` viewed before with the one
+/// docsrs picks for it now, which moved on from it e.g. with a `cargo
+/// update` or a new release. A path in `args` limits the comparison to the
+/// items under it.
+pub fn what_changed(args: &Query) -> Result<ApiDiff> {
+    if let Some(version) = &args.spec.version {
+        bail!(
+            "--what-changed compares the versions docsrs picks for {}, drop the @{version}",
+            args.spec.name
+        );
+    }
+    let mut resolution = Resolution::default();
+    let resolved = resolve_docs(&args.spec, &mut resolution)?;
+    let ResolvedDocs::DocsRs { name, .. } = &resolved else {
+        bail!(
+            "--what-changed compares released versions, and {} is a crate of the workspace",
+            args.spec.original_name
+        );
+    };
+    let name = name.clone();
+    let new_doc = resolved.load_doc(args.no_cache, &*args.events)?;
+    let new_version = new_doc
+        .crate_data()
+        .crate_version
+        .clone()
+        .context("The docs don't say which version of the crate they're for")?;
+
+    record_viewed(&name, &new_version)?;
+    let Some(old_version) = viewed_before(&name, &new_version) else {
+        bail!(
+            "No other version of {} was viewed before {new_version} to compare it with",
+            args.spec.name
+        );
+    };
+    let mut old_spec = args.spec.clone();
+    old_spec.version = Some(old_version.clone());
+    let old_doc = resolve_docs(&old_spec, &mut Resolution::default())?
+        .load_doc(args.no_cache, &*args.events)?;
+
+    let old_version = version_of(&old_doc, &old_version);
+    Ok(api_diff(
+        args,
+        resolution,
+        (old_doc, old_version),
+        (new_doc, new_version),
+    ))
+}
+
+fn version_of(doc: &JsonDoc, fallback: &str) -> String {
+    doc.crate_data()
+        .crate_version
+        .clone()
+        .unwrap_or_else(|| fallback.to_string())
+}
+
+/// The differences between the docs of two versions of the crate of
+/// `query`, limited to the items under its path.
+fn api_diff(
+    query: &Query,
+    resolution: Resolution,
+    (old_doc, old_version): (JsonDoc, String),
+    (new_doc, new_version): (JsonDoc, String),
+) -> ApiDiff {
+    let scope = query
+        .spec
+        .path_prefix
+        .as_ref()
+        .map(|prefix| format!("{}::{}", query.spec.name, prefix));
+    let (removed, added, changed) = diff_docs(&old_doc, &new_doc, scope.as_deref());
+
+    ApiDiff {
+        resolution,
+        crate_name: query.spec.name.clone(),
+        old_version,
+        new_version,
+        removed,
+        added,
+        changed,
+        old_doc,
+        new_doc,
+    }
+}
+
+/// Items only in `old`, only in `new`, and in both with different
+/// signatures, limited to items under the path `scope`.
+fn diff_docs(
+    old: &JsonDoc,
+    new: &JsonDoc,
+    scope: Option<&str>,
+) -> (Vec<ApiItem>, Vec<ApiItem>, Vec<ChangedItem>) {
+    let old_items = api_items(old, scope);
+    let new_items = api_items(new, scope);
+
+    // Items rendered the same in both versions are unchanged, counting
+    // duplicates so an item listed twice that lost one listing shows up
+    let mut new_counts: HashMap<&str, usize> = HashMap::new();
+    for (_, text, _) in &new_items {
+        *new_counts.entry(text).or_default() += 1;
+    }
+    let mut old_counts: HashMap<&str, usize> = HashMap::new();
+    for (_, text, _) in &old_items {
+        *old_counts.entry(text).or_default() += 1;
+    }
+    let only_in = |items: &[(Discriminant<ItemEnum>, String, ApiItem)],
+                   counts: &mut HashMap<&str, usize>| {
+        let mut only = Vec::new();
+        for (kind, text, item) in items {
+            match counts.get_mut(text.as_str()) {
+                Some(count) if *count > 0 => *count -= 1,
+                _ => only.push((*kind, item.clone())),
+            }
+        }
+        only
+    };
+    let removed = only_in(&old_items, &mut new_counts);
+    let mut added = only_in(&new_items, &mut old_counts);
+
+    // An item of the same kind at the same path in both lists changed
+    let mut changed = Vec::new();
+    let mut still_removed = Vec::new();
+    for (kind, old_item) in removed {
+        let counterpart = added
+            .iter()
+            .position(|(new_kind, new_item)| *new_kind == kind && new_item.path == old_item.path);
+        match counterpart {
+            Some(index) => {
+                let (_, new_item) = added.remove(index);
+                changed.push(ChangedItem {
+                    old: old_item,
+                    new: new_item,
+                });
+            }
+            None => still_removed.push(old_item),
+        }
+    }
+    let mut added: Vec<ApiItem> = added.into_iter().map(|(_, item)| item).collect();
+
+    let text = |item: &ApiItem| tokens_to_string(&item.tokens);
+    still_removed.sort_by_cached_key(|item| (item.path.clone(), text(item)));
+    added.sort_by_cached_key(|item| (item.path.clone(), text(item)));
+    changed.sort_by_cached_key(|change| (change.old.path.clone(), text(&change.old)));
+    (still_removed, added, changed)
+}
+
+/// The items of `doc` under `scope`, with their kind and rendered text.
+fn api_items(doc: &JsonDoc, scope: Option<&str>) -> Vec<(Discriminant<ItemEnum>, String, ApiItem)> {
+    item_signatures(doc)
+        .into_iter()
+        .filter_map(|(item, tokens)| {
+            let path = item.path_string();
+            if let Some(scope) = scope
+                && path != scope
+                && !path.starts_with(&format!("{scope}::"))
+            {
+                return None;
+            }
+            let text = tokens_to_string(&tokens);
+            let api_item = ApiItem {
+                path,
+                id: item.id(),
+                parent_id: item.parent_id(),
+                tokens,
+            };
+            Some((discriminant(&item.item().inner), text, api_item))
+        })
+        .collect()
+}
+
+impl ApiDiff {
+    /// Whether the versions have the same public API.
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty() && self.changed.is_empty()
+    }
+
+    /// Render the differences as `-` and `+` lines, highlighting the tokens
+    /// that differ in changed signatures.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let summary = if self.is_empty() {
+            "no public API changes".to_string()
+        } else {
+            format!(
+                "{} removed, {} added, {} changed",
+                self.removed.len(),
+                self.added.len(),
+                self.changed.len()
+            )
+        };
+        let header = format!(
+            "// {} {} -> {}: {}",
+            self.crate_name, self.old_version, self.new_version, summary
+        );
+        output.push_str(&format!("{}\n", header.bright_black()));
+
+        if !self.removed.is_empty() {
+            output.push_str(&format!("\n{}\n", "// removed".bright_black()));
+            for item in &self.removed {
+                let line = format!("-{}", tokens_to_string(&item.tokens));
+                output.push_str(&format!("{}\n", line.red()));
+            }
+        }
+        if !self.added.is_empty() {
+            output.push_str(&format!("\n{}\n", "// added".bright_black()));
+            for item in &self.added {
+                let line = format!("+{}", tokens_to_string(&item.tokens));
+                output.push_str(&format!("{}\n", line.green()));
+            }
+        }
+        if !self.changed.is_empty() {
+            output.push_str(&format!("\n{}\n", "// changed".bright_black()));
+            for change in &self.changed {
+                let (old_changed, new_changed) =
+                    changed_tokens(&change.old.tokens, &change.new.tokens);
+                output.push_str(&format!(
+                    "{}{}\n",
+                    "-".red(),
+                    highlight(&change.old.tokens, &old_changed, |text| text.red())
+                ));
+                output.push_str(&format!(
+                    "{}{}\n",
+                    "+".green(),
+                    highlight(&change.new.tokens, &new_changed, |text| text.green())
+                ));
+            }
+        }
+        output
+    }
+}
+
+/// Color `tokens`, emphasizing those flagged in `changed`.
+fn highlight(
+    tokens: &[Token],
+    changed: &[bool],
+    color: impl Fn(&str) -> colored::ColoredString,
+) -> String {
+    tokens
+        .iter()
+        .zip(changed)
+        .map(|(token, &changed)| {
+            let text = color(token.text());
+            if changed {
+                text.bold().underline().to_string()
+            } else {
+                text.to_string()
+            }
+        })
+        .collect()
+}
+
+/// Flag the tokens of `old` and `new` that aren't part of their longest
+/// common subsequence, i.e. the tokens removed and added by the change.
+fn changed_tokens(old: &[Token], new: &[Token]) -> (Vec<bool>, Vec<bool>) {
+    // lengths[i][j] is the LCS length of old[i..] and new[j..]
+    let mut lengths = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lengths[i][j] = if old[i] == new[j] {
+                lengths[i + 1][j + 1] + 1
+            } else {
+                lengths[i + 1][j].max(lengths[i][j + 1])
+            };
+        }
+    }
+
+    let mut old_changed = vec![true; old.len()];
+    let mut new_changed = vec![true; new.len()];
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            old_changed[i] = false;
+            new_changed[j] = false;
+            i += 1;
+            j += 1;
+        } else if lengths[i + 1][j] >= lengths[i][j + 1] {
+            i += 1;
+        } else {
+            j += 1;
+        }
+    }
+    (old_changed, new_changed)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_changed_tokens_flags_replaced_type() {
+        let old = [
+            Token::Identifier("x".into()),
+            Token::Symbol(": ".into()),
+            Token::Primitive("u32".into()),
+        ];
+        let new = [
+            Token::Identifier("x".into()),
+            Token::Symbol(": ".into()),
+            Token::Primitive("u64".into()),
+        ];
+        let (old_changed, new_changed) = changed_tokens(&old, &new);
+        assert_eq!(old_changed, [false, false, true]);
+        assert_eq!(new_changed, [false, false, true]);
+    }
+}
@@ -0,0 +1,139 @@
+use rustdoc_types::{
+    Crate, Function, GenericArg, GenericArgs, GenericBound, GenericParamDefKind, Generics,
+    ItemEnum, Trait, Type, WherePredicate,
+};
+
+/// Whether `dyn Trait` can be used for `trait_`, as a comment line such as
+/// `// dyn compatible: no (fn new has no self receiver)`.
+///
+/// The answer comes from rustdoc; the reasons are worked out from the items
+/// of the trait the way rustc does, so they point at what to change.
+pub(super) fn dyn_compat_line(krate: &Crate, trait_: &Trait) -> String {
+    if trait_.is_dyn_compatible {
+        return "// dyn compatible: yes".to_string();
+    }
+    let mut reasons = dyn_incompat_reasons(krate, trait_);
+    if reasons.is_empty() && !trait_.bounds.is_empty() {
+        // Supertraits from other crates, such as `Clone`, aren't in the docs
+        reasons.push("a supertrait isn't dyn compatible".to_string());
+    }
+    if reasons.is_empty() {
+        "// dyn compatible: no".to_string()
+    } else {
+        format!("// dyn compatible: no ({})", reasons.join(", "))
+    }
+}
+
+/// Why `trait_` can't be used as `dyn Trait`, one reason per offending item.
+fn dyn_incompat_reasons(krate: &Crate, trait_: &Trait) -> Vec<String> {
+    let mut reasons = Vec::new();
+
+    if trait_.bounds.iter().any(is_sized_bound) || requires_sized_self(&trait_.generics) {
+        reasons.push("requires Self: Sized".to_string());
+    }
+    for bound in &trait_.bounds {
+        if let GenericBound::TraitBound { trait_: path, .. } = bound
+            && let Some(item) = krate.index.get(&path.id)
+            && let ItemEnum::Trait(supertrait) = &item.inner
+            && !supertrait.is_dyn_compatible
+        {
+            let name = item.name.as_deref().unwrap_or("unknown");
+            reasons.push(format!("supertrait {name} isn't dyn compatible"));
+        }
+    }
+
+    for item in trait_.items.iter().filter_map(|id| krate.index.get(id)) {
+        let name = item.name.as_deref().unwrap_or("unknown");
+        match &item.inner {
+            ItemEnum::AssocConst { .. } => reasons.push(format!("has associated const {name}")),
+            ItemEnum::AssocType { generics, .. } if !generics.params.is_empty() => {
+                reasons.push(format!("type {name} is generic"));
+            }
+            ItemEnum::Function(function) => {
+                if let Some(reason) = method_reason(function) {
+                    reasons.push(format!("fn {name} {reason}"));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    reasons
+}
+
+/// Why the trait method `function` keeps its trait from being dyn
+/// compatible. Methods with `where Self: Sized` aren't callable on
+/// `dyn Trait`, so they never do.
+fn method_reason(function: &Function) -> Option<&'static str> {
+    if requires_sized_self(&function.generics) {
+        return None;
+    }
+    let mut inputs = function.sig.inputs.iter();
+    if inputs.next().is_none_or(|(name, _)| name != "self") {
+        return Some("has no self receiver");
+    }
+    let is_generic = function.generics.params.iter().any(|param| {
+        matches!(
+            param.kind,
+            GenericParamDefKind::Type { .. } | GenericParamDefKind::Const { .. }
+        )
+    });
+    if is_generic {
+        return Some("is generic");
+    }
+    if function.header.is_async || function.sig.output.as_ref().is_some_and(is_impl_trait) {
+        return Some("returns impl Trait");
+    }
+    if inputs.any(|(_, type_)| mentions_self(type_))
+        || function.sig.output.as_ref().is_some_and(mentions_self)
+    {
+        return Some("takes or returns Self");
+    }
+    None
+}
+
+/// Whether `generics` has a `where Self: Sized` bound.
+fn requires_sized_self(generics: &Generics) -> bool {
+    generics.where_predicates.iter().any(|predicate| {
+        matches!(
+            predicate,
+            WherePredicate::BoundPredicate { type_: Type::Generic(name), bounds, .. }
+                if name == "Self" && bounds.iter().any(is_sized_bound)
+        )
+    })
+}
+
+/// Whether `bound` is `Sized`, and not `?Sized`.
+fn is_sized_bound(bound: &GenericBound) -> bool {
+    matches!(
+        bound,
+        GenericBound::TraitBound { trait_, modifier: rustdoc_types::TraitBoundModifier::None, .. }
+            if trait_.path.rsplit("::").next() == Some("Sized")
+    )
+}
+
+fn is_impl_trait(type_: &Type) -> bool {
+    matches!(type_, Type::ImplTrait(_))
+}
+
+/// Whether `type_` names `Self` itself. Projections such as `Self::Item`
+/// are fine in dyn compatible traits.
+fn mentions_self(type_: &Type) -> bool {
+    match type_ {
+        Type::Generic(name) => name == "Self",
+        Type::ResolvedPath(path) => match path.args.as_deref() {
+            Some(GenericArgs::AngleBracketed { args, .. }) => args.iter().any(|arg| match arg {
+                GenericArg::Type(type_) => mentions_self(type_),
+                _ => false,
+            }),
+            Some(GenericArgs::Parenthesized { inputs, output }) => {
+                inputs.iter().any(mentions_self) || output.as_ref().is_some_and(mentions_self)
+            }
+            _ => false,
+        },
+        Type::Tuple(types) => types.iter().any(mentions_self),
+        Type::Slice(type_) | Type::Array { type_, .. } => mentions_self(type_),
+        Type::BorrowedRef { type_, .. } | Type::RawPointer { type_, .. } => mentions_self(type_),
+        _ => false,
+    }
+}
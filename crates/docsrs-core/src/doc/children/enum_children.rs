@@ -1,22 +1,23 @@
 use anyhow::Result;
-use jsondoc::ImplKind;
 use rustdoc_fmt::{Colorizer, Output};
-use rustdoc_types::{Crate, ItemEnum, Variant};
+use rustdoc_types::{Crate, Id, ItemEnum, Variant};
 
-use super::{first_doc_line, write_body_block, write_comment_section, write_trait_impls};
+use super::{
+    collect_impls, first_doc_line, write_blanket_impls, write_body_block, write_comment_section,
+    write_deref_methods, write_trait_impls,
+};
 use crate::doc::render::RenderingContext;
 
 /// Format child items for an enum (variants, methods and trait implementations)
 pub(crate) fn format_enum_children(
     krate: &Crate,
+    id: &Id,
     enum_: &rustdoc_types::Enum,
     output: &mut String,
     context: &RenderingContext,
 ) -> Result<()> {
-    let colorizer = Colorizer::get();
+    let colorizer = context.colorizer;
     let mut variants: Vec<(Option<String>, String)> = Vec::new();
-    let mut methods: Vec<(Option<String>, String)> = Vec::new();
-    let mut trait_impls = Vec::new();
 
     // Process enum variants
     for variant_id in &enum_.variants {
@@ -25,50 +26,12 @@ pub(crate) fn format_enum_children(
         {
             let variant_str =
                 format_variant(variant_item.name.as_deref(), variant, colorizer, context);
-            let doc = first_doc_line(&variant_item.docs);
+            let doc = first_doc_line(variant_item, context);
             variants.push((doc, variant_str));
         }
     }
 
-    // Process each impl block
-    for impl_id in &enum_.impls {
-        if let Some(impl_item) = krate.index.get(impl_id)
-            && let ItemEnum::Impl(impl_) = &impl_item.inner
-        {
-            // Check if this impl should be included (filter out auto-traits and blanket impls)
-            let impl_kind = ImplKind::from(impl_item, impl_);
-            if !impl_kind.is_active() {
-                continue;
-            }
-
-            if impl_.trait_.is_some() {
-                // This is a trait implementation
-                let impl_tokens = context.render_impl(impl_, &[], false);
-                let impl_str = colorizer.tokens(&impl_tokens.into_tokens());
-                trait_impls.push(impl_str);
-            } else {
-                // This is an inherent impl - extract methods
-                for item_id in &impl_.items {
-                    if let Some(item) = krate.index.get(item_id) {
-                        // Only include functions (methods)
-                        if let ItemEnum::Function(func) = &item.inner {
-                            let mut name_output = Output::new();
-                            name_output.function(item.name.as_deref().unwrap_or("unknown"));
-                            let method_output = context.render_function(
-                                name_output,
-                                &func.sig,
-                                &func.generics,
-                                &func.header,
-                            );
-                            let method_str = colorizer.tokens(&method_output.into_tokens());
-                            let doc = first_doc_line(&item.docs);
-                            methods.push((doc, method_str));
-                        }
-                    }
-                }
-            }
-        }
-    }
+    let (methods, trait_impls) = collect_impls(id, context);
 
     // Output: variants in { } block, methods and trait impls after
     if !variants.is_empty() {
@@ -79,7 +42,9 @@ pub(crate) fn format_enum_children(
     }
 
     write_comment_section(output, "Methods", &methods);
+    write_deref_methods(output, id, context);
     write_trait_impls(output, &trait_impls);
+    write_blanket_impls(output, id, context);
 
     Ok(())
 }
@@ -92,7 +57,7 @@ fn format_variant(
     context: &RenderingContext,
 ) -> String {
     let mut variant_output = Output::new();
-    variant_output.function(name.unwrap_or("unknown"));
+    variant_output.function(name.unwrap_or("unknown").to_string());
 
     // Handle different variant kinds
     match &variant.kind {
@@ -128,7 +93,8 @@ fn format_variant(
                         variant_output.symbol(",");
                         variant_output.whitespace();
                     }
-                    variant_output.identifier(field_item.name.as_deref().unwrap_or("_"));
+                    variant_output
+                        .identifier(field_item.name.clone().unwrap_or_else(|| "_".to_string()));
                     variant_output.symbol(":");
                     variant_output.whitespace();
                     variant_output.extend(context.render_type(field_type));
@@ -1,19 +1,219 @@
+mod dyn_compat;
 mod enum_children;
 mod module_children;
+mod sealed;
 mod struct_children;
 mod trait_children;
 
 pub(crate) use enum_children::format_enum_children;
 pub(crate) use module_children::format_module_children;
+#[cfg(feature = "native")]
+pub(crate) use sealed::sealed_line;
 pub(crate) use struct_children::format_struct_children;
 pub(crate) use trait_children::format_trait_children;
 
-/// Extract the first line of a doc comment, if present.
-fn first_doc_line(docs: &Option<String>) -> Option<String> {
-    docs.as_ref()
+use std::collections::HashSet;
+
+use jsondoc::ImplKind;
+use rustdoc_fmt::{Output, tokens_to_string};
+use rustdoc_types::{GenericBound, Id, Impl, Item, ItemEnum, Type};
+
+use crate::doc::link_resolver::RustdocLinkResolver;
+use crate::doc::render::RenderingContext;
+
+/// Signatures of items with the first line of their docs, as listed in a
+/// section.
+type Section = Vec<(Option<String>, String)>;
+
+/// Collect the inherent methods and trait impls of a struct or enum, skipping
+/// blanket and auto trait impls. Impls of a trait that is a supertrait of
+/// another one implemented are marked as such.
+fn collect_impls(id: &Id, context: &RenderingContext) -> (Section, Vec<String>) {
+    let mut methods = Section::new();
+    let mut trait_impls = Vec::new();
+    let implemented: Vec<&Id> = context
+        .doc
+        .impls_for(id)
+        .into_iter()
+        .filter_map(|impl_ref| impl_ref.impl_.trait_.as_ref().map(|trait_| &trait_.id))
+        .collect();
+
+    for impl_ref in context.doc.impls_for(id) {
+        if !impl_ref.kind.is_active() {
+            continue;
+        }
+
+        if let Some(trait_) = &impl_ref.impl_.trait_ {
+            // This is a trait implementation
+            let mut impl_tokens = context.render_impl(impl_ref.impl_, &[], false);
+            impl_tokens.extend(Output::new().symbol_elided_body());
+            let mut impl_str = context.format_signature(&impl_tokens.into_tokens(), 0);
+            let subtraits = subtraits_among(&trait_.id, &implemented, context);
+            if !subtraits.is_empty() {
+                impl_str.push_str(&format!("  // supertrait of {}", subtraits.join(", ")));
+            }
+            trait_impls.push(impl_str);
+        } else {
+            // This is an inherent impl - extract methods
+            methods.extend(render_methods(&impl_ref.impl_.items, context, false));
+        }
+    }
+
+    (methods, trait_impls)
+}
+
+/// Collect the blanket impls of this crate that cover the type with `id`,
+/// such as `impl<T: Display> ToString for T`, rendered as declared. Blanket
+/// impls from other crates, like `impl<T> From<T> for T`, apply to nearly
+/// every type and are left out; only the local ones have a span.
+fn collect_blanket_impls(id: &Id, context: &RenderingContext) -> Vec<String> {
+    context
+        .doc
+        .impls_for(id)
+        .into_iter()
+        .filter(|impl_ref| impl_ref.kind == ImplKind::Blanket && impl_ref.item.span.is_some())
+        .filter_map(|impl_ref| {
+            let declared = Impl {
+                for_: impl_ref.impl_.blanket_impl.clone()?,
+                ..impl_ref.impl_.clone()
+            };
+            let mut impl_tokens = context.render_impl(&declared, &[], false);
+            impl_tokens.extend(Output::new().symbol_elided_body());
+            Some(context.format_signature(&impl_tokens.into_tokens(), 0))
+        })
+        .collect()
+}
+
+/// Names of the traits among `traits` that have the trait with `id` as a
+/// supertrait. Only traits defined in the crate have their bounds at hand.
+fn subtraits_among(id: &Id, traits: &[&Id], context: &RenderingContext) -> Vec<String> {
+    traits
+        .iter()
+        .filter_map(|trait_id| context.crate_.index.get(*trait_id))
+        .filter(|item| {
+            match &item.inner {
+            ItemEnum::Trait(trait_) => trait_.bounds.iter().any(|bound| {
+                matches!(bound, GenericBound::TraitBound { trait_, .. } if trait_.id == *id)
+            }),
+            _ => false,
+        }
+        })
+        .filter_map(|item| item.name.clone())
+        .collect()
+}
+
+/// Render the functions among `item_ids`, only those taking `self` when
+/// `receivers_only`.
+fn render_methods(item_ids: &[Id], context: &RenderingContext, receivers_only: bool) -> Section {
+    let mut methods = Section::new();
+    for item_id in item_ids {
+        // Only include functions (methods)
+        if let Some(item) = context.crate_.index.get(item_id)
+            && let ItemEnum::Function(func) = &item.inner
+            && (!receivers_only
+                || func
+                    .sig
+                    .inputs
+                    .first()
+                    .is_some_and(|(name, _)| name == "self"))
+        {
+            let mut name_output = Output::new();
+            name_output.function(item.name.clone().unwrap_or_else(|| "unknown".to_string()));
+            let method_output =
+                context.render_function(name_output, &func.sig, &func.generics, &func.header);
+            let method_str = context.format_signature(&method_output.into_tokens(), 0);
+            let doc = first_doc_line(item, context);
+            methods.push((doc, method_str));
+        }
+    }
+    methods
+}
+
+/// Collect the methods reachable through `Deref` from the type with `id`,
+/// following the chain of `Deref` impls like docs.rs does: a `Methods from
+/// Deref<Target = T>` heading for each target, with its methods that take
+/// `self`. The methods of targets from other crates aren't in the docs, so
+/// their section points to them instead.
+fn collect_deref_methods(id: &Id, context: &RenderingContext) -> Vec<(String, Section)> {
+    let mut sections = Vec::new();
+    let mut visited = HashSet::from([*id]);
+    let mut current = *id;
+
+    while let Some(target) = deref_target(&current, context) {
+        let target_name = tokens_to_string(&context.render_type(target).into_tokens());
+        let heading = format!("Methods from Deref<Target = {target_name}>");
+        let target_id = match target {
+            Type::ResolvedPath(path) if context.crate_.index.contains_key(&path.id) => path.id,
+            _ => {
+                let note = format!("// see the docs of {target_name}");
+                sections.push((heading, vec![(None, note)]));
+                break;
+            }
+        };
+        if !visited.insert(target_id) {
+            break;
+        }
+
+        let methods: Section = context
+            .doc
+            .impls_for(&target_id)
+            .iter()
+            .filter(|impl_ref| impl_ref.kind.is_active() && impl_ref.impl_.trait_.is_none())
+            .flat_map(|impl_ref| render_methods(&impl_ref.impl_.items, context, true))
+            .collect();
+        sections.push((heading, methods));
+        current = target_id;
+    }
+
+    sections
+}
+
+/// The `Target` of the `Deref` impl of the type with `id`, if it has one.
+fn deref_target<'c>(id: &Id, context: &RenderingContext<'c>) -> Option<&'c Type> {
+    context.doc.impls_for(id).into_iter().find_map(|impl_ref| {
+        let trait_ = impl_ref.impl_.trait_.as_ref()?;
+        if trait_.path.rsplit("::").next() != Some("Deref") {
+            return None;
+        }
+        impl_ref
+            .impl_
+            .items
+            .iter()
+            .filter_map(|item_id| context.crate_.index.get(item_id))
+            .find_map(|item| match &item.inner {
+                ItemEnum::AssocType {
+                    type_: Some(type_), ..
+                } if item.name.as_deref() == Some("Target") => Some(type_),
+                _ => None,
+            })
+    })
+}
+
+/// Extract the first line of the doc comment of `item`, if present, with its
+/// intra-doc links resolved.
+fn first_doc_line(item: &Item, context: &RenderingContext) -> Option<String> {
+    let line = item
+        .docs
+        .as_ref()
         .and_then(|d| d.lines().next())
-        .filter(|line| !line.is_empty())
-        .map(|line| line.to_string())
+        .filter(|line| !line.is_empty())?;
+    let resolver = RustdocLinkResolver {
+        item_links: &item.links,
+        krate: context.crate_,
+        id_to_items: &context.id_to_items,
+        page_url: None,
+    };
+    Some(resolver.resolve_shortcut_links(line))
+}
+
+/// Open the `{ }` body of the signature in `output`, on a line of its own
+/// after a wrapped where clause as rustfmt puts it.
+fn open_body(output: &mut String) {
+    output.push_str(if output.ends_with(',') {
+        "\n{\n"
+    } else {
+        " {\n"
+    });
 }
 
 /// Write items inside a `{ }` body block with given trailing punctuation.
@@ -24,7 +224,7 @@ fn write_body_block(output: &mut String, items: &[(Option<String>, String)], tra
     if items.is_empty() {
         return;
     }
-    output.push_str(" {\n");
+    open_body(output);
     for (doc, signature) in items {
         if let Some(doc_line) = doc {
             output.push_str("    /// ");
@@ -63,16 +263,37 @@ fn write_comment_section(output: &mut String, heading: &str, items: &[(Option<St
     }
 }
 
+/// Write the methods reachable through `Deref`, a section per target.
+fn write_deref_methods(output: &mut String, id: &Id, context: &RenderingContext) {
+    for (heading, methods) in collect_deref_methods(id, context) {
+        write_comment_section(output, &heading, &methods);
+    }
+}
+
 /// Write a trait implementations section with `impl ... { .. }` lines.
 fn write_trait_impls(output: &mut String, impls: &[String]) {
+    write_impls(output, "Trait Implementations", impls);
+}
+
+/// Write the blanket impls of this crate that cover the type with `id`.
+fn write_blanket_impls(output: &mut String, id: &Id, context: &RenderingContext) {
+    write_impls(
+        output,
+        "Blanket Implementations",
+        &collect_blanket_impls(id, context),
+    );
+}
+
+/// Write a section of `impl ... { .. }` lines, as collected, under `heading`.
+fn write_impls(output: &mut String, heading: &str, impls: &[String]) {
     if impls.is_empty() {
         return;
     }
     output.push('\n');
-    output.push_str(&format_block_header("Trait Implementations"));
+    output.push_str(&format_block_header(heading));
     output.push('\n');
-    for trait_impl in impls {
-        output.push_str(trait_impl);
-        output.push_str(" { .. }\n");
+    for impl_line in impls {
+        output.push_str(impl_line);
+        output.push('\n');
     }
 }
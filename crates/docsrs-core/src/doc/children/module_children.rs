@@ -1,41 +1,36 @@
 use anyhow::Result;
-use rustdoc_fmt::Colorizer;
-use rustdoc_types::{Crate, Item, ItemEnum};
+use rustdoc_types::Id;
 
 use crate::doc::render::RenderingContext;
 use crate::list::ListItem;
 
 /// Format child items for a module
+///
+/// Children are the processed items whose logical parent is the module, so
+/// re-exports are listed under the name they're exported as and glob
+/// re-exports (`pub use inner::*`) list every item they bring in.
 pub(crate) fn format_module_children(
-    krate: &Crate,
-    module: &rustdoc_types::Module,
+    module_id: &Id,
     output: &mut String,
     context: &RenderingContext,
 ) -> Result<()> {
-    let colorizer = Colorizer::get();
-    let mut items: Vec<ListItem> = Vec::new();
+    let colorizer = context.colorizer;
+    let mut items: Vec<ListItem> = context
+        .id_to_items
+        .values()
+        .flatten()
+        .filter(|item| item.parent_id() == Some(*module_id) && !item.is_hidden())
+        .filter_map(|item| ListItem::from_jsondoc_item(item))
+        .collect();
 
-    for item_id in &module.items {
-        // Resolve Use items to their targets, since id_to_items
-        // is keyed by the target's ID (Use items are inlined during processing)
-        let lookup_id = match krate.index.get(item_id) {
-            Some(Item {
-                inner: ItemEnum::Use(use_),
-                ..
-            }) => use_.id.as_ref().unwrap_or(item_id),
-            _ => item_id,
-        };
-
-        if let Some(jsondoc_items) = context.id_to_items.get(lookup_id)
-            && let Some(jsondoc_item) = jsondoc_items.first()
-            && let Some(list_item) = ListItem::from_jsondoc_item(jsondoc_item)
-        {
-            items.push(list_item);
-        }
-    }
-
-    // Sort items by path for consistent output
-    items.sort_by(|a, b| a.path.cmp(&b.path));
+    // Sort items by name for consistent output. A module reachable through
+    // several paths yields each child once per path, so keep only the first.
+    items.sort_by(|a, b| {
+        child_key(a)
+            .cmp(&child_key(b))
+            .then_with(|| a.path.cmp(&b.path))
+    });
+    items.dedup_by(|a, b| child_key(a) == child_key(b));
 
     // Output all items using module-relative rendering: "pub TYPE Name"
     if !items.is_empty() {
@@ -48,3 +43,10 @@ pub(crate) fn format_module_children(
 
     Ok(())
 }
+
+/// Name and kind of a module child, as shown in the listing.
+fn child_key(item: &ListItem) -> Option<(&str, &'static str)> {
+    item.segments()
+        .last()
+        .map(|(name, kind)| (name.as_str(), kind.keyword()))
+}
@@ -0,0 +1,97 @@
+use rustdoc_types::{
+    Attribute, GenericArg, GenericArgs, GenericBound, Id, ItemEnum, Path, Trait, Type,
+};
+
+use crate::doc::render::RenderingContext;
+
+/// A comment line saying `trait_` is sealed, when it follows one of the
+/// common patterns that keep other crates from implementing it: a private
+/// supertrait, a hidden required method, or a required method taking a
+/// private type.
+///
+/// Hidden methods are only in the docs when they were built with
+/// `--document-hidden-items`, so that pattern goes unnoticed otherwise.
+pub(crate) fn sealed_line(trait_: &Trait, context: &RenderingContext) -> Option<String> {
+    let reason = sealing_reason(trait_, context)?;
+    Some(format!(
+        "// sealed: cannot be implemented outside this crate ({reason})"
+    ))
+}
+
+fn sealing_reason(trait_: &Trait, context: &RenderingContext) -> Option<String> {
+    for bound in &trait_.bounds {
+        if let GenericBound::TraitBound { trait_: path, .. } = bound
+            && is_private(&path.id, context)
+        {
+            return Some(format!("private supertrait {}", path.path));
+        }
+    }
+
+    for item in trait_
+        .items
+        .iter()
+        .filter_map(|id| context.crate_.index.get(id))
+    {
+        let ItemEnum::Function(function) = &item.inner else {
+            continue;
+        };
+        if function.has_body {
+            continue;
+        }
+        let name = item.name.as_deref().unwrap_or("unknown");
+        let hidden = item
+            .attrs
+            .iter()
+            .any(|attr| matches!(attr, Attribute::Other(attr) if attr.contains("doc(hidden)")));
+        if hidden {
+            return Some(format!("hidden required method {name}"));
+        }
+        let private_type = function
+            .sig
+            .inputs
+            .iter()
+            .find_map(|(_, type_)| private_path(type_, context));
+        if let Some(path) = private_type {
+            return Some(format!("method {name} takes private type {}", path.path));
+        }
+    }
+
+    None
+}
+
+/// Whether `id` is an item of this crate that isn't public, such as a
+/// trait in a private module.
+fn is_private(id: &Id, context: &RenderingContext) -> bool {
+    context
+        .crate_
+        .paths
+        .get(id)
+        .is_some_and(|summary| summary.crate_id == 0)
+        && !context.id_to_items.contains_key(id)
+}
+
+/// The first path in `type_` to a private item of this crate.
+fn private_path<'t>(type_: &'t Type, context: &RenderingContext) -> Option<&'t Path> {
+    match type_ {
+        Type::ResolvedPath(path) => {
+            if is_private(&path.id, context) {
+                return Some(path);
+            }
+            match path.args.as_deref() {
+                Some(GenericArgs::AngleBracketed { args, .. }) => {
+                    args.iter().find_map(|arg| match arg {
+                        GenericArg::Type(type_) => private_path(type_, context),
+                        _ => None,
+                    })
+                }
+                _ => None,
+            }
+        }
+        Type::Tuple(types) => types.iter().find_map(|type_| private_path(type_, context)),
+        Type::Slice(type_)
+        | Type::Array { type_, .. }
+        | Type::BorrowedRef { type_, .. }
+        | Type::RawPointer { type_, .. } => private_path(type_, context),
+        _ => None,
+    }
+}
@@ -1,32 +1,44 @@
+use std::collections::{HashSet, VecDeque};
+
 use anyhow::Result;
-use rustdoc_fmt::{Colorizer, Output};
-use rustdoc_types::{Crate, ItemEnum};
+use rustdoc_fmt::Output;
+use rustdoc_types::{Crate, GenericBound, Id, ItemEnum, Path, Trait, Type};
 
-use super::first_doc_line;
+use super::dyn_compat::dyn_compat_line;
+use super::sealed::sealed_line;
+use super::{Section, first_doc_line, open_body, write_comment_section};
 use crate::doc::render::RenderingContext;
 
 /// Format child items for a trait (associated types, consts, methods)
 ///
-/// All items are rendered inside a single `{ }` block.
+/// All items are rendered inside a single `{ }` block, like rustdoc does:
+/// associated types and consts first, then the required methods implementors
+/// must write, then the provided ones with a default body. Whether the trait
+/// is dyn compatible and sealed follows, and its supertrait hierarchy when it
+/// goes deeper than the signature shows.
 pub(crate) fn format_trait_children(
     krate: &Crate,
-    trait_: &rustdoc_types::Trait,
+    id: &Id,
+    trait_: &Trait,
     output: &mut String,
     context: &RenderingContext,
 ) -> Result<()> {
-    let colorizer = Colorizer::get();
-    let mut body_items: Vec<(Option<String>, String)> = Vec::new();
+    let colorizer = context.colorizer;
+    let mut body_items = Section::new();
+    let mut required = Section::new();
+    let mut provided = Section::new();
 
     // Process trait items in order: types, consts, then methods
     for item_id in &trait_.items {
         if let Some(item) = krate.index.get(item_id) {
-            let doc = first_doc_line(&item.docs);
+            let doc = first_doc_line(item, context);
             match &item.inner {
                 ItemEnum::AssocType { type_, .. } => {
                     let mut type_output = Output::new();
                     type_output.keyword("type");
                     type_output.whitespace();
-                    type_output.function(item.name.as_deref().unwrap_or("unknown"));
+                    type_output
+                        .function(item.name.clone().unwrap_or_else(|| "unknown".to_string()));
 
                     if let Some(default_type) = type_ {
                         type_output.whitespace();
@@ -43,7 +55,8 @@ pub(crate) fn format_trait_children(
                     let mut const_output = Output::new();
                     const_output.keyword("const");
                     const_output.whitespace();
-                    const_output.function(item.name.as_deref().unwrap_or("unknown"));
+                    const_output
+                        .function(item.name.clone().unwrap_or_else(|| "unknown".to_string()));
                     const_output.symbol(":");
                     const_output.whitespace();
                     const_output.extend(context.render_type(type_));
@@ -52,7 +65,7 @@ pub(crate) fn format_trait_children(
                         const_output.whitespace();
                         const_output.symbol("=");
                         const_output.whitespace();
-                        const_output.symbol(val);
+                        const_output.symbol(val.clone());
                     }
 
                     let const_str = colorizer.tokens(&const_output.into_tokens());
@@ -62,17 +75,21 @@ pub(crate) fn format_trait_children(
                 ItemEnum::Function(func) => {
                     // Trait methods don't use `pub` qualifier
                     let mut name_output = Output::new();
-                    name_output.function(item.name.as_deref().unwrap_or("unknown"));
-                    let method_output =
+                    name_output
+                        .function(item.name.clone().unwrap_or_else(|| "unknown".to_string()));
+                    let mut method_output =
                         context.render_method(name_output, &func.sig, &func.generics, &func.header);
-                    let method_str = colorizer.tokens(&method_output.into_tokens());
 
                     if func.has_body {
                         // Provided method: has default impl
-                        body_items.push((doc, format!("{method_str} {{ .. }}")));
+                        method_output.extend(Output::new().symbol_elided_body());
+                        let method_str = context.format_signature(&method_output.into_tokens(), 4);
+                        provided.push((doc, method_str));
                     } else {
                         // Required method: ends with ";"
-                        body_items.push((doc, format!("{method_str};")));
+                        method_output.symbol(";");
+                        let method_str = context.format_signature(&method_output.into_tokens(), 4);
+                        required.push((doc, method_str));
                     }
                 }
                 _ => {}
@@ -80,10 +97,53 @@ pub(crate) fn format_trait_children(
         }
     }
 
-    // Write the body block (items already have their own terminators)
-    if !body_items.is_empty() {
-        output.push_str(" {\n");
-        for (doc, signature) in &body_items {
+    // Write the body block (items already have their own terminators), the
+    // groups apart and the methods under a `// Required methods` comment
+    let groups = [
+        (None, body_items),
+        (Some("Required"), required),
+        (Some("Provided"), provided),
+    ];
+    let groups: Vec<_> = groups
+        .into_iter()
+        .filter(|(_, items)| !items.is_empty())
+        .collect();
+    if groups.is_empty() {
+        output.push('\n');
+    } else {
+        write_trait_body(output, &groups);
+    }
+    output.push('\n');
+    output.push_str(&dyn_compat_line(krate, trait_));
+    output.push('\n');
+    if let Some(sealed) = sealed_line(trait_, context) {
+        output.push_str(&sealed);
+        output.push('\n');
+    }
+
+    let hierarchy: Vec<_> = supertrait_hierarchy(krate, id, trait_, context)
+        .into_iter()
+        .map(|line| (None, line))
+        .collect();
+    if hierarchy.len() > 1 {
+        write_comment_section(output, "Supertraits", &hierarchy);
+    }
+
+    Ok(())
+}
+
+/// Write the `{ }` body of a trait, its groups of items apart.
+fn write_trait_body(output: &mut String, groups: &[(Option<&str>, Section)]) {
+    open_body(output);
+    for (index, (heading, items)) in groups.iter().enumerate() {
+        if index > 0 {
+            output.push('\n');
+        }
+        if let Some(heading) = heading {
+            let plural = if items.len() == 1 { "" } else { "s" };
+            output.push_str(&format!("    // {heading} method{plural}\n"));
+        }
+        for (doc, signature) in items {
             if let Some(doc_line) = doc {
                 output.push_str("    /// ");
                 output.push_str(doc_line);
@@ -93,10 +153,46 @@ pub(crate) fn format_trait_children(
             output.push_str(signature);
             output.push('\n');
         }
-        output.push_str("}\n");
-    } else {
-        output.push('\n');
     }
+    output.push_str("}\n");
+}
 
-    Ok(())
+/// The supertrait hierarchy of the trait with `id`, a line per trait with
+/// supertraits such as `Copy: Clone`, starting with the trait itself.
+/// Supertraits are followed as long as they are defined in `krate`.
+fn supertrait_hierarchy(
+    krate: &Crate,
+    id: &Id,
+    trait_: &Trait,
+    context: &RenderingContext,
+) -> Vec<String> {
+    let root = Path {
+        path: String::new(),
+        id: *id,
+        args: None,
+    };
+    let mut lines = Vec::new();
+    let mut visited = HashSet::from([*id]);
+    let mut queue = VecDeque::from([(root, trait_)]);
+
+    while let Some((path, trait_)) = queue.pop_front() {
+        if trait_.bounds.is_empty() {
+            continue;
+        }
+        let mut line = context.render_type(&Type::ResolvedPath(path));
+        line.extend(context.render_generic_bounds_with_colon(&trait_.bounds));
+        lines.push(context.colorizer.tokens(&line.into_tokens()));
+
+        for bound in &trait_.bounds {
+            if let GenericBound::TraitBound { trait_: path, .. } = bound
+                && let Some(ItemEnum::Trait(supertrait)) =
+                    krate.index.get(&path.id).map(|item| &item.inner)
+                && visited.insert(path.id)
+            {
+                queue.push_back((path.clone(), supertrait));
+            }
+        }
+    }
+
+    lines
 }
@@ -1,7 +1,9 @@
 use anyhow::Result;
-use rustdoc_fmt::{Colorizer, format_markdown};
+use rustdoc_fmt::format_markdown_with;
 use rustdoc_types::{Crate, ItemEnum};
 
+use crate::urls;
+
 use super::children::{
     format_enum_children, format_module_children, format_struct_children, format_trait_children,
 };
@@ -11,61 +13,68 @@ use super::render::RenderingContext;
 
 /// Format documentation for a single PublicItem
 pub fn format_doc(krate: &Crate, item: &PublicItem, context: &RenderingContext) -> Result<String> {
-    let colorizer = Colorizer::get();
+    let colorizer = context.colorizer;
     let mut output = String::new();
 
-    // Build the signature string (without trailing newline yet)
-    let signature = colorizer.tokens(&item.tokens);
-
-    // Try to get the full Item from the crate index to access documentation
-    if let Some(full_item) = krate.index.get(&item._id) {
-        // 1. Format docs with "/// " prefix on each line (above signature)
-        if let Some(docs) = &full_item.docs {
-            let resolver = RustdocLinkResolver {
-                item_links: &full_item.links,
-                krate,
-                id_to_items: &context.id_to_items,
-            };
-            let formatted_docs = format_markdown(docs, &resolver);
-            for line in formatted_docs.lines() {
-                if line.is_empty() {
-                    output.push_str("///\n");
-                } else {
-                    output.push_str("/// ");
-                    output.push_str(line);
-                    output.push('\n');
-                }
+    // 1. Format docs with "/// " prefix on each line (above signature)
+    if let Some(full_item) = krate.index.get(&item._id)
+        && let Some(docs) = &full_item.docs
+    {
+        let resolver = RustdocLinkResolver {
+            item_links: &full_item.links,
+            krate,
+            id_to_items: &context.id_to_items,
+            page_url: urls::item_url(context.doc, &item._id),
+        };
+        let formatted_docs = format_markdown_with(docs, &resolver, colorizer);
+        for line in formatted_docs.lines() {
+            if line.is_empty() {
+                output.push_str("///\n");
+            } else {
+                output.push_str("/// ");
+                output.push_str(line);
+                output.push('\n');
             }
         }
+    }
 
-        // 2. Signature
-        output.push_str(&signature);
+    // 2. Signature, and the item's children
+    output.push_str(&format_declaration(krate, item, context)?);
+    Ok(output)
+}
 
-        // 3. Format child items based on item type
-        // Children that produce body blocks (struct/enum/trait) append " { ... }\n"
-        // Others just append "\n"
-        match &full_item.inner {
-            ItemEnum::Struct(struct_) => {
-                format_struct_children(krate, struct_, &mut output, context)?;
-            }
-            ItemEnum::Enum(enum_) => {
-                format_enum_children(krate, enum_, &mut output, context)?;
-            }
-            ItemEnum::Trait(trait_) => {
-                format_trait_children(krate, trait_, &mut output, context)?;
-            }
-            ItemEnum::Module(module) => {
-                output.push('\n');
-                format_module_children(krate, module, &mut output, context)?;
-            }
-            _ => {
-                output.push('\n');
-            }
-        }
-    } else {
-        output.push_str(&signature);
+/// Format the signature of a PublicItem with its child items, like the
+/// fields of a struct or the items of a module, without its docs
+pub fn format_declaration(
+    krate: &Crate,
+    item: &PublicItem,
+    context: &RenderingContext,
+) -> Result<String> {
+    let mut output = context.format_signature(&item.tokens, 0);
+    let Some(full_item) = krate.index.get(&item._id) else {
         output.push('\n');
-    }
+        return Ok(output);
+    };
 
+    // Children that produce body blocks (struct/enum/trait) append " { ... }\n"
+    // Others just append "\n"
+    match &full_item.inner {
+        ItemEnum::Struct(struct_) => {
+            format_struct_children(krate, &full_item.id, struct_, &mut output, context)?;
+        }
+        ItemEnum::Enum(enum_) => {
+            format_enum_children(krate, &full_item.id, enum_, &mut output, context)?;
+        }
+        ItemEnum::Trait(trait_) => {
+            format_trait_children(krate, &full_item.id, trait_, &mut output, context)?;
+        }
+        ItemEnum::Module(_) => {
+            output.push('\n');
+            format_module_children(&full_item.id, &mut output, context)?;
+        }
+        _ => {
+            output.push('\n');
+        }
+    }
     Ok(output)
 }
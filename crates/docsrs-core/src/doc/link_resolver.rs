@@ -3,9 +3,11 @@
 use std::{cmp::Ordering, collections::HashMap};
 
 use jsondoc::JsonDocItem;
-use rustdoc_fmt::LinkResolver;
+use rustdoc_fmt::{Link, LinkResolution, LinkResolver};
 use rustdoc_types::{Crate, Id};
 
+use crate::urls;
+
 /// A link resolver that uses rustdoc data to resolve intra-doc links.
 pub struct RustdocLinkResolver<'a> {
     /// Links from the current item's documentation
@@ -14,39 +16,92 @@ pub struct RustdocLinkResolver<'a> {
     pub krate: &'a Crate,
     /// Mapping from IDs to public items
     pub id_to_items: &'a HashMap<&'a Id, Vec<&'a JsonDocItem<'a>>>,
+    /// URL of the HTML page of the docs, for relative links like
+    /// `../struct.Foo.html`
+    pub page_url: Option<String>,
 }
 
 impl<'a> RustdocLinkResolver<'a> {
     /// Resolves a single link to its fully qualified path.
     ///
-    /// External URLs are formatted as "text (url)".
+    /// External URLs are shown after the link text.
     /// Intra-doc links are resolved via `Item.links` and `id_to_items`.
-    fn resolve_single_link(&self, link_text: &str, dest_url: &str) -> String {
-        // External URLs - format as "text (url)"
-        if dest_url.starts_with("http://") || dest_url.starts_with("https://") {
-            return format!("{link_text} ({dest_url})");
+    fn resolve_single_link(&self, link: &Link<'_>) -> LinkResolution {
+        // External URLs - shown as "text (url)"
+        if link.is_external() {
+            return LinkResolution::text(link.text).with_url(link.dest);
         }
 
         // Try to resolve via Item.links
         // Strip backticks for lookup - rustdoc normalizes these
-        let lookup_key = link_text.trim_matches('`');
+        let lookup_key = link.text.trim_matches('`');
 
         if let Some(resolved_id) = self.item_links.get(lookup_key)
-            && let Some(fqn_path) = self.id_to_public_path(resolved_id)
+            && let Some(resolved) = self.resolved_path(resolved_id)
+        {
+            return resolved;
+        }
+
+        // Also try the dest as a key (for inline links like [text](Type::method))
+        let dest_key = link.dest.trim_end_matches("()"); // Strip method parens
+        if let Some(resolved_id) = self
+            .item_links
+            .get(link.dest)
+            .or_else(|| self.item_links.get(dest_key))
+            && let Some(resolved) = self.resolved_path(resolved_id)
         {
-            return fqn_path;
+            return resolved;
         }
 
-        // Also try the dest_url as a key (for inline links like [text](Type::method))
-        let dest_key = dest_url.trim_end_matches("()"); // Strip method parens
-        if let Some(resolved_id) = self.item_links.get(dest_key)
-            && let Some(fqn_path) = self.id_to_public_path(resolved_id)
+        // Relative links to HTML docs, resolved against the docs' own page
+        if let Some(page_url) = &self.page_url
+            && urls::is_relative_doc_link(link.dest)
         {
-            return fqn_path;
+            return LinkResolution::text(link.text).with_url(urls::join(page_url, link.dest));
         }
 
-        // Unresolvable - return original link text
-        link_text.to_string()
+        // Unresolvable - return the link text, without a disambiguator
+        LinkResolution::text(link.display_text())
+    }
+
+    /// The fully qualified path of `id`, with the URL of its HTML docs when
+    /// it's from another crate.
+    fn resolved_path(&self, id: &Id) -> Option<LinkResolution> {
+        let path = self.id_to_public_path(id)?;
+        let resolution = LinkResolution::text(path.clone()).with_item_path(path);
+        Some(match urls::external_item_url(self.krate, id) {
+            Some(url) => resolution.with_url(url),
+            None => resolution,
+        })
+    }
+
+    /// Replace the shortcut intra-doc links in `text`, like ``[`Vec`]``, with
+    /// the paths they resolve to, for docs shown as plain text. Links with a
+    /// destination and ones that don't resolve are left as they are.
+    pub(crate) fn resolve_shortcut_links(&self, text: &str) -> String {
+        let mut result = String::with_capacity(text.len());
+        let mut rest = text;
+        while let Some(open) = rest.find('[') {
+            let Some(close) = rest[open..].find(']').map(|i| open + i) else {
+                break;
+            };
+            let label = &rest[open + 1..close];
+            let after = &rest[close + 1..];
+            let path = (!after.starts_with(['(', '[']))
+                .then(|| self.item_links.get(label))
+                .flatten()
+                .and_then(|id| self.id_to_public_path(id));
+            result.push_str(&rest[..open]);
+            match path {
+                // Keep the code style of [`Vec`]
+                Some(path) if label.starts_with('`') => result.push_str(&format!("`{path}`")),
+                Some(path) => result.push_str(&path),
+                None => result.push_str(&rest[open..=close]),
+            }
+            rest = after;
+        }
+        result.push_str(rest);
+        result
     }
 
     /// Convert an Id to a fully qualified public path string.
@@ -115,8 +170,12 @@ impl<'a> RustdocLinkResolver<'a> {
 }
 
 impl LinkResolver for RustdocLinkResolver<'_> {
-    fn resolve_link(&self, link_text: &str, dest_url: &str) -> String {
-        self.resolve_single_link(link_text, dest_url)
+    fn resolve(&self, link: &Link<'_>) -> LinkResolution {
+        self.resolve_single_link(link)
+    }
+
+    fn is_intra_doc_link(&self, reference: &str) -> bool {
+        self.item_links.contains_key(reference)
     }
 }
 
@@ -147,6 +206,7 @@ mod tests {
             item_links: &HashMap::new(),
             krate: &krate,
             id_to_items: &HashMap::new(),
+            page_url: None,
         };
         let result = resolver.resolve_link("docs", "https://docs.rs/tokio");
         assert_eq!(result, "docs (https://docs.rs/tokio)");
@@ -159,8 +219,121 @@ mod tests {
             item_links: &HashMap::new(),
             krate: &krate,
             id_to_items: &HashMap::new(),
+            page_url: None,
         };
         let result = resolver.resolve_link("Unknown", "Unknown");
         assert_eq!(result, "Unknown");
+        let result = resolver.resolve_link("method@Unknown::len", "`method@Unknown::len`");
+        assert_eq!(result, "Unknown::len");
+    }
+
+    #[test]
+    fn test_shortcut_intra_doc_links() {
+        let mut krate = empty_crate();
+        krate.paths.insert(
+            Id(1),
+            rustdoc_types::ItemSummary {
+                crate_id: 0,
+                path: vec!["demo".into(), "config".into(), "Options".into()],
+                kind: rustdoc_types::ItemKind::Struct,
+            },
+        );
+        let item_links = HashMap::from([("`Options`".to_string(), Id(1))]);
+        let resolver = RustdocLinkResolver {
+            item_links: &item_links,
+            krate: &krate,
+            id_to_items: &HashMap::new(),
+            page_url: None,
+        };
+
+        let resolution = resolver.resolve(&Link::new("Options", "`Options`"));
+        assert_eq!(
+            resolution.item_path.as_deref(),
+            Some("demo::config::Options")
+        );
+
+        colored::control::set_override(false);
+        let formatted = rustdoc_fmt::format_markdown(
+            "Returns [`Options`], unlike [`Missing`] or [1].",
+            &resolver,
+        );
+        assert_eq!(
+            formatted.trim(),
+            "Returns demo::config::Options, unlike [`Missing`] or [1]."
+        );
+        assert_eq!(
+            resolver.resolve_shortcut_links("Returns [`Options`], unlike [`Options`](x) or [1]."),
+            "Returns `demo::config::Options`, unlike [`Options`](x) or [1]."
+        );
+    }
+
+    #[test]
+    fn test_links_to_other_crates_show_their_url() {
+        let mut krate = empty_crate();
+        krate.paths.insert(
+            Id(1),
+            rustdoc_types::ItemSummary {
+                crate_id: 1,
+                path: vec!["core".into(), "option".into(), "Option".into()],
+                kind: rustdoc_types::ItemKind::Enum,
+            },
+        );
+        krate.paths.insert(
+            Id(2),
+            rustdoc_types::ItemSummary {
+                crate_id: 0,
+                path: vec!["demo".into(), "Options".into()],
+                kind: rustdoc_types::ItemKind::Struct,
+            },
+        );
+        let item_links = HashMap::from([
+            ("`Option`".to_string(), Id(1)),
+            ("`Options`".to_string(), Id(2)),
+        ]);
+        let resolver = RustdocLinkResolver {
+            item_links: &item_links,
+            krate: &krate,
+            id_to_items: &HashMap::new(),
+            page_url: None,
+        };
+
+        colored::control::set_override(false);
+        let format = |markdown| rustdoc_fmt::format_markdown(markdown, &resolver);
+        // Resolved to another crate: its path and the URL of its docs
+        assert_eq!(
+            format("Returns [`Option`].").trim(),
+            "Returns core::option::Option (https://doc.rust-lang.org/stable/core/option/enum.Option.html)."
+        );
+        // Resolved to this crate: only its path
+        assert_eq!(format("Takes [`Options`].").trim(), "Takes demo::Options.");
+        // Unresolved: only the link text
+        assert_eq!(
+            format("Unlike [`Missing`](Missing).").trim(),
+            "Unlike Missing."
+        );
+        // External: the link text and its URL
+        assert_eq!(
+            format("See [the guide](https://tokio.rs/guide).").trim(),
+            "See the guide (https://tokio.rs/guide)."
+        );
+    }
+
+    #[test]
+    fn test_relative_links() {
+        let krate = empty_crate();
+        let resolver = RustdocLinkResolver {
+            item_links: &HashMap::new(),
+            krate: &krate,
+            id_to_items: &HashMap::new(),
+            page_url: Some("https://docs.rs/demo/0.1.0/demo/sync/struct.Mutex.html".to_string()),
+        };
+        assert_eq!(
+            resolver.resolve_link("Guard", "../guard/struct.Guard.html"),
+            "Guard (https://docs.rs/demo/0.1.0/demo/guard/struct.Guard.html)"
+        );
+        assert_eq!(
+            resolver.resolve_link("lock", "#method.lock"),
+            "lock (https://docs.rs/demo/0.1.0/demo/sync/struct.Mutex.html#method.lock)"
+        );
     }
 }
@@ -1,29 +1,44 @@
 use anyhow::Result;
 use jsondoc::JsonDoc;
-use rustdoc_types::Id;
+#[cfg(feature = "native")]
+use jsondoc::JsonDocItem;
+#[cfg(feature = "native")]
+use rustdoc_fmt::Token;
+use rustdoc_fmt::{Colorizer, tokens_to_string};
+use rustdoc_types::{Id, Item, ItemEnum};
 
 mod children;
 mod doc_formatter;
 mod link_resolver;
 mod public_item;
 mod render;
+#[cfg(feature = "native")]
+mod skeleton;
 
+#[cfg(feature = "native")]
+use doc_formatter::format_declaration;
 use doc_formatter::format_doc;
 use public_item::PublicItem;
 use render::RenderingContext;
 
-pub fn signature_for_id(doc: &JsonDoc, id: &Id) -> Result<String> {
+pub fn signature_for_id(
+    doc: &JsonDoc,
+    id: &Id,
+    colorizer: &Colorizer,
+    max_width: Option<usize>,
+) -> Result<String> {
     // Find the item with the matching id
     let item = doc
-        .items()
-        .iter()
-        .find(|item| item.id() == *id)
+        .item_by_id(id)
         .ok_or_else(|| anyhow::anyhow!("Item with id {:?} not found", id))?;
 
     // Create rendering context
     let context = RenderingContext {
         crate_: doc.crate_data(),
+        doc,
         id_to_items: doc.id_to_items(),
+        colorizer,
+        max_width,
     };
 
     // Convert to PublicItem
@@ -32,3 +47,162 @@ pub fn signature_for_id(doc: &JsonDoc, id: &Id) -> Result<String> {
     // Format the documentation
     format_doc(doc.crate_data(), &public_item, &context)
 }
+
+/// The signature of the item with `id` on one line, without colors.
+pub fn plain_signature(doc: &JsonDoc, id: &Id) -> Result<String> {
+    let item = doc
+        .item_by_id(id)
+        .ok_or_else(|| anyhow::anyhow!("Item with id {:?} not found", id))?;
+
+    let context = RenderingContext {
+        crate_: doc.crate_data(),
+        doc,
+        id_to_items: doc.id_to_items(),
+        colorizer: Colorizer::get(),
+        max_width: None,
+    };
+
+    Ok(tokens_to_string(&context.token_stream(item).into_tokens()))
+}
+
+/// The signature of the item with `id` with its fields, variants, methods
+/// and impls, as its docs show it but without its doc comment or colors.
+#[cfg(feature = "native")]
+pub(crate) fn plain_declaration(
+    doc: &JsonDoc,
+    id: &Id,
+    max_width: Option<usize>,
+) -> Result<String> {
+    let item = doc
+        .item_by_id(id)
+        .ok_or_else(|| anyhow::anyhow!("Item with id {:?} not found", id))?;
+
+    let context = RenderingContext {
+        crate_: doc.crate_data(),
+        doc,
+        id_to_items: doc.id_to_items(),
+        colorizer: Colorizer::get(),
+        max_width,
+    };
+    let public_item = PublicItem::from_jsondoc_item(&context, item);
+    let declaration = format_declaration(doc.crate_data(), &public_item, &context)?;
+    Ok(rustdoc_fmt::strip_ansi(&declaration))
+}
+
+/// Every item of `doc`, including methods and impls, with its signature.
+#[cfg(feature = "native")]
+pub(crate) fn item_signatures(doc: &JsonDoc) -> Vec<(&JsonDocItem<'_>, Vec<Token>)> {
+    let context = RenderingContext {
+        crate_: doc.crate_data(),
+        doc,
+        id_to_items: doc.id_to_items(),
+        colorizer: Colorizer::get(),
+        max_width: None,
+    };
+
+    doc.items()
+        .iter()
+        .map(|item| (item, context.token_stream(item).into_tokens()))
+        .collect()
+}
+
+/// The shortest of the public paths the item with `id` can be imported at
+/// with `use`, `preferred` among those as short. None for the crate root
+/// and items that can't be imported, like methods.
+pub(crate) fn import_path(doc: &JsonDoc, id: &Id, preferred: &str) -> Option<String> {
+    doc.items_by_id(id)
+        .filter(|item| !item.is_hidden() && item.parent().is_some())
+        .filter(|item| {
+            item.ancestors()
+                .all(|ancestor| is_importable_from(ancestor.item.item))
+        })
+        .map(|item| item.path_string())
+        .min_by_key(|path| {
+            let depth = path.matches("::").count();
+            (depth, *path != preferred, path.len(), path.clone())
+        })
+}
+
+/// Whether items can be imported from `parent`: modules, and enums for
+/// their variants.
+fn is_importable_from(parent: &Item) -> bool {
+    matches!(parent.inner, ItemEnum::Module(_) | ItemEnum::Enum(_))
+}
+
+/// Code to start from for the item with `id`: an `impl` block for a trait
+/// and a call for a function or method, after the `use` declaration they
+/// need. `use_path` is the path the item is imported by, when it's known.
+/// None for other kinds of items.
+#[cfg(feature = "native")]
+pub(crate) fn skeleton(
+    doc: &JsonDoc,
+    id: &Id,
+    use_path: Option<&str>,
+    colorizer: &Colorizer,
+    max_width: Option<usize>,
+) -> Result<Option<String>> {
+    let item = doc
+        .item_by_id(id)
+        .ok_or_else(|| anyhow::anyhow!("Item with id {:?} not found", id))?;
+
+    let context = RenderingContext {
+        crate_: doc.crate_data(),
+        doc,
+        id_to_items: doc.id_to_items(),
+        colorizer,
+        max_width,
+    };
+    let name = item.item().name.clone().unwrap_or_default();
+    let imported_name = |path: &str| path.rsplit("::").next().unwrap_or(path).to_string();
+
+    let (use_path, code) = match &item.item().inner {
+        ItemEnum::Trait(trait_) => {
+            let name = use_path.map(imported_name).unwrap_or(name);
+            let code = skeleton::impl_skeleton(&context, &name, trait_);
+            (use_path.map(str::to_string), code)
+        }
+        ItemEnum::Function(function) => {
+            // Methods are called on their type, or on an implementor of
+            // their trait
+            let parent = item
+                .ancestors()
+                .map(|ancestor| ancestor.item.item)
+                .find(|ancestor| !matches!(ancestor.inner, ItemEnum::Impl(_)));
+            match parent {
+                Some(parent) if !is_importable_from(parent) => {
+                    let path = import_path(doc, &parent.id, "");
+                    let parent_name = match &path {
+                        Some(path) => imported_name(path),
+                        None => parent.name.clone().unwrap_or_default(),
+                    };
+                    let receiver = match parent.inner {
+                        ItemEnum::Trait(_) => skeleton::IMPLEMENTOR.to_string(),
+                        _ => parent_name.clone(),
+                    };
+                    let callee = format!("{parent_name}::{name}");
+                    let code =
+                        skeleton::call_skeleton(&context, &callee, Some(&receiver), function);
+                    (path, code)
+                }
+                _ => {
+                    let callee = use_path.map(imported_name).unwrap_or(name);
+                    let code = skeleton::call_skeleton(&context, &callee, None, function);
+                    (use_path.map(str::to_string), code)
+                }
+            }
+        }
+        _ => return Ok(None),
+    };
+    let mut output = String::new();
+    if let Some(path) = use_path {
+        let mut line = rustdoc_fmt::Output::new();
+        line.keyword("use")
+            .whitespace()
+            .identifier(path)
+            .symbol(";");
+        output.push_str(&colorizer.tokens(&line.into_tokens()));
+        output.push_str("\n\n");
+    }
+    output.push_str(&code);
+    Ok(Some(output))
+}
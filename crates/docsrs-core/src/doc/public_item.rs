@@ -1,6 +1,7 @@
 use jsondoc::JsonDocItem;
 use rustdoc_fmt::{Token, tokens_to_string};
 use rustdoc_types::Id;
+use serde::Serialize;
 use std::fmt::Display;
 use std::hash::Hash;
 
@@ -8,15 +9,19 @@ use crate::doc::render::RenderingContext;
 
 /// Represent a public item of an analyzed crate, i.e. an item that forms part
 /// of the public API of a crate.
-#[derive(Clone)]
+///
+/// Serializes as `{"tokens": [...], "parent_id": 7, "id": 42}`.
+#[derive(Clone, Serialize)]
 pub struct PublicItem {
     /// The rendered item as a stream of [`Token`]s
     pub(crate) tokens: Vec<Token>,
 
     /// The [`Id`] of this item's logical parent (if any)
+    #[serde(rename = "parent_id")]
     pub(crate) _parent_id: Option<Id>,
 
     /// The [`Id`] to which this public item corresponds
+    #[serde(rename = "id")]
     pub(crate) _id: Id,
 }
 
@@ -58,3 +63,35 @@ impl Display for PublicItem {
         write!(f, "{}", tokens_to_string(&self.tokens))
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use jsondoc::JsonDoc;
+    use rustdoc_fmt::Colorizer;
+
+    #[test]
+    fn test_serializes_rendered_tokens() {
+        let json = std::fs::read(crate::test_docs("test_items.json")).unwrap();
+        let krate = crate::json::parse_rustdoc_json(json).unwrap();
+        let doc = JsonDoc::from(krate);
+        let id = doc.find_item_by_path("test_items::builder").unwrap();
+        let colorizer = Colorizer::with_default_theme(false);
+        let context = RenderingContext {
+            crate_: doc.crate_data(),
+            doc: &doc,
+            id_to_items: doc.id_to_items(),
+            colorizer: &colorizer,
+            max_width: None,
+        };
+        let item = PublicItem::from_jsondoc_item(&context, doc.item_by_id(&id).unwrap());
+
+        let json = serde_json::to_value(&item).unwrap();
+        assert_eq!(json["id"], serde_json::to_value(id).unwrap());
+        assert_eq!(
+            json["tokens"][0],
+            serde_json::json!({"kind": "qualifier", "text": "pub"})
+        );
+        assert_eq!(json["tokens"], serde_json::to_value(&item.tokens).unwrap());
+    }
+}
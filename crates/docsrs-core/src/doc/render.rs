@@ -1,5 +1,5 @@
-use jsondoc::{JsonDocItem, NameableItem, PathComponent};
-use rustdoc_fmt::Output;
+use jsondoc::{JsonDoc, JsonDocItem, NameableItem, PathComponent};
+use rustdoc_fmt::{Colorizer, Output, Token, wrap_signature};
 use std::{borrow::Cow, cmp::Ordering, collections::HashMap};
 
 use rustdoc_types::{
@@ -17,11 +17,31 @@ pub struct RenderingContext<'c> {
     /// The original and unmodified rustdoc JSON, in deserialized form.
     pub crate_: &'c Crate,
 
+    /// The processed items, for queries such as the impls of a type.
+    pub doc: &'c JsonDoc,
+
     /// Given a rustdoc JSON ID, keeps track of what public items that have this Id.
     pub id_to_items: HashMap<&'c Id, Vec<&'c JsonDocItem<'c>>>,
+
+    /// Styling used when turning rendered tokens into terminal output.
+    pub colorizer: &'c Colorizer,
+
+    /// Signatures wider than this are broken across lines.
+    pub max_width: Option<usize>,
 }
 
 impl<'c> RenderingContext<'c> {
+    /// Colorize a rendered signature that starts at column `indent`, breaking
+    /// it across lines if it doesn't fit in `max_width`.
+    pub fn format_signature(&self, tokens: &[Token], indent: usize) -> String {
+        match self.max_width {
+            Some(max_width) => self
+                .colorizer
+                .tokens(&wrap_signature(tokens, max_width, indent)),
+            None => self.colorizer.tokens(tokens),
+        }
+    }
+
     pub fn token_stream(&self, public_item: &JsonDocItem<'c>) -> Output {
         let item = public_item.item();
         let item_path = public_item.path();
@@ -106,7 +126,7 @@ impl<'c> RenderingContext<'c> {
                     VariantKind::Plain => {
                         if let Some(discriminant) = &inner.discriminant {
                             output.extend(Output::new().symbol_equals());
-                            output.identifier(&discriminant.value);
+                            output.identifier(discriminant.value.clone());
                         }
                     }
                     VariantKind::Tuple(fields) => {
@@ -181,7 +201,7 @@ impl<'c> RenderingContext<'c> {
             ItemEnum::ProcMacro(inner) => {
                 let mut output = self.render_simple(&["proc", "macro"], item_path);
                 output.pop(); // Remove name of macro to possibly wrap it in `#[]`
-                let name = item.name.as_deref().unwrap_or("");
+                let name = item.name.clone().unwrap_or_default();
                 match inner.kind {
                     MacroKind::Bang => {
                         output.identifier(name).symbol("!()");
@@ -197,7 +217,10 @@ impl<'c> RenderingContext<'c> {
             }
             ItemEnum::Primitive(primitive) => {
                 let mut output = Output::new().qualifier_pub();
-                output.kind("type").whitespace().primitive(&primitive.name);
+                output
+                    .kind("type")
+                    .whitespace()
+                    .primitive(primitive.name.clone());
                 output
             }
         };
@@ -233,7 +256,7 @@ impl<'c> RenderingContext<'c> {
     fn render_simple(&self, tags: &[&str], path: &[PathComponent]) -> Output {
         let mut output = Output::new().qualifier_pub();
         for tag in tags {
-            output.kind(*tag).whitespace();
+            output.kind(tag.to_string()).whitespace();
         }
         output.extend(self.render_path(path));
         output
@@ -352,12 +375,12 @@ impl<'c> RenderingContext<'c> {
             Type::DynTrait(dyn_trait) => self.render_dyn_trait(dyn_trait),
             Type::Generic(name) => {
                 let mut output = Output::new();
-                output.generic(name);
+                output.generic(name.clone());
                 output
             }
             Type::Primitive(name) => {
                 let mut output = Output::new();
-                output.primitive(name);
+                output.primitive(name.to_string());
                 output
             }
             Type::FunctionPointer(ptr) => self.render_function_pointer(ptr),
@@ -424,7 +447,7 @@ impl<'c> RenderingContext<'c> {
 
         if let Some(lt) = &dyn_trait.lifetime {
             output.extend(Output::new().symbol_plus());
-            output.lifetime(lt);
+            output.lifetime(lt.clone());
         }
 
         if more_than_one {
@@ -468,15 +491,15 @@ impl<'c> RenderingContext<'c> {
         } else {
             Output::new()
         };
-        if header.is_unsafe {
-            output.qualifier("unsafe").whitespace();
-        };
         if header.is_const {
             output.qualifier("const").whitespace();
         };
         if header.is_async {
             output.qualifier("async").whitespace();
         };
+        if header.is_unsafe {
+            output.qualifier("unsafe").whitespace();
+        };
         if header.abi != Abi::Rust {
             let abi_str = match &header.abi {
                 Abi::C { .. } => "c",
@@ -490,7 +513,7 @@ impl<'c> RenderingContext<'c> {
                 Abi::Other(text) => text,
                 Abi::Rust => unreachable!(),
             };
-            output.qualifier(abi_str).whitespace();
+            output.qualifier(abi_str.to_string()).whitespace();
         }
 
         output.kind("fn").whitespace();
@@ -524,7 +547,7 @@ impl<'c> RenderingContext<'c> {
                     let mut output = Output::new();
                     let ignore_name = name.is_empty() || (name == "_" && !include_underscores);
                     if !ignore_name {
-                        output.identifier(name).symbol(":").whitespace();
+                        output.identifier(name.to_string()).symbol(":").whitespace();
                     }
                     output.extend(self.render_type(ty));
                     output
@@ -556,7 +579,7 @@ impl<'c> RenderingContext<'c> {
                         let mut output = Output::new();
                         output.symbol("&");
                         if let Some(lt) = lifetime {
-                            output.lifetime(lt).whitespace();
+                            output.lifetime(lt.clone()).whitespace();
                         }
                         if *is_mutable {
                             output.keyword("mut").whitespace();
@@ -657,9 +680,9 @@ impl<'c> RenderingContext<'c> {
         let len = path.len();
         for (index, part) in path.into_iter().enumerate() {
             if index == len - 1 {
-                output.type_(part.as_ref());
+                output.type_(part.as_ref().to_string());
             } else {
-                output.identifier(part.as_ref());
+                output.identifier(part.as_ref().to_string());
             }
             output.symbol("::");
         }
@@ -712,7 +735,11 @@ impl<'c> RenderingContext<'c> {
         let mut output = Output::new();
         output.symbol("[");
         output.extend(self.render_type(type_));
-        output.symbol(";").whitespace().primitive(len).symbol("]");
+        output
+            .symbol(";")
+            .whitespace()
+            .primitive(len.to_string())
+            .symbol("]");
         output
     }
 
@@ -775,7 +802,7 @@ impl<'c> RenderingContext<'c> {
         let mut output = Output::new();
         output.symbol("&");
         if let Some(lt) = lifetime {
-            output.lifetime(lt).whitespace();
+            output.lifetime(lt.to_string()).whitespace();
         }
         if is_mutable {
             output.keyword("mut").whitespace();
@@ -802,7 +829,7 @@ impl<'c> RenderingContext<'c> {
                 }
             }
         }
-        output.symbol("::").identifier(name);
+        output.symbol("::").identifier(name.to_string());
         output
     }
 
@@ -888,7 +915,7 @@ impl<'c> RenderingContext<'c> {
         match arg {
             GenericArg::Lifetime(name) => {
                 let mut output = Output::new();
-                output.lifetime(name);
+                output.lifetime(name.clone());
                 output
             }
             GenericArg::Type(ty) => self.render_type(ty),
@@ -903,7 +930,7 @@ impl<'c> RenderingContext<'c> {
 
     fn render_assoc_item_constraint(&self, constraints: &AssocItemConstraint) -> Output {
         let mut output = Output::new();
-        output.identifier(&constraints.name);
+        output.identifier(constraints.name.clone());
         if let Some(generic_args) = &constraints.args {
             output.extend(self.render_generic_args(generic_args));
         }
@@ -925,12 +952,12 @@ impl<'c> RenderingContext<'c> {
             output.extend(self.render_type(type_));
         } else if let Some(value) = &constant.value {
             if constant.is_literal {
-                output.primitive(value);
+                output.primitive(value.clone());
             } else {
-                output.identifier(value);
+                output.identifier(value.clone());
             }
         } else {
-            output.identifier(&constant.expr);
+            output.identifier(constant.expr.clone());
         }
         output
     }
@@ -942,7 +969,7 @@ impl<'c> RenderingContext<'c> {
         output
     }
 
-    fn render_generic_param_defs(&self, params: &[GenericParamDef]) -> Output {
+    pub fn render_generic_param_defs(&self, params: &[GenericParamDef]) -> Output {
         let params_without_synthetics: Vec<_> = params
             .iter()
             .filter(|p| {
@@ -972,7 +999,7 @@ impl<'c> RenderingContext<'c> {
         let mut output = Output::new();
         match &generic_param_def.kind {
             GenericParamDefKind::Lifetime { outlives } => {
-                output.lifetime(&generic_param_def.name);
+                output.lifetime(generic_param_def.name.clone());
                 if !outlives.is_empty() {
                     output.extend(Output::new().symbol_colon());
                     output.extend(self.render_sequence(
@@ -982,23 +1009,33 @@ impl<'c> RenderingContext<'c> {
                         outlives,
                         |s| {
                             let mut out = Output::new();
-                            out.lifetime(s);
+                            out.lifetime(s.clone());
                             out
                         },
                     ));
                 }
             }
-            GenericParamDefKind::Type { bounds, .. } => {
-                output.generic(&generic_param_def.name);
+            GenericParamDefKind::Type {
+                bounds, default, ..
+            } => {
+                output.generic(generic_param_def.name.clone());
                 output.extend(self.render_generic_bounds_with_colon(bounds));
+                if let Some(default) = default {
+                    output.extend(Output::new().symbol_equals());
+                    output.extend(self.render_type(default));
+                }
             }
-            GenericParamDefKind::Const { type_, .. } => {
+            GenericParamDefKind::Const { type_, default } => {
                 output
                     .qualifier("const")
                     .whitespace()
-                    .identifier(&generic_param_def.name);
+                    .identifier(generic_param_def.name.clone());
                 output.extend(Output::new().symbol_colon());
                 output.extend(self.render_type(type_));
+                if let Some(default) = default {
+                    output.extend(Output::new().symbol_equals());
+                    output.identifier(default.clone());
+                }
             }
         }
         output
@@ -1034,7 +1071,7 @@ impl<'c> RenderingContext<'c> {
                 output.extend(self.render_generic_bounds_with_colon(bounds));
             }
             WherePredicate::LifetimePredicate { lifetime, outlives } => {
-                output.lifetime(lifetime);
+                output.lifetime(lifetime.clone());
                 output.extend(self.render_sequence_if_not_empty(
                     Output::new().symbol_colon(),
                     Output::new(),
@@ -1042,7 +1079,7 @@ impl<'c> RenderingContext<'c> {
                     outlives,
                     |s| {
                         let mut out = Output::new();
-                        out.lifetime(s);
+                        out.lifetime(s.clone());
                         out
                     },
                 ));
@@ -1056,7 +1093,7 @@ impl<'c> RenderingContext<'c> {
         output
     }
 
-    fn render_generic_bounds_with_colon(&self, bounds: &[GenericBound]) -> Output {
+    pub fn render_generic_bounds_with_colon(&self, bounds: &[GenericBound]) -> Output {
         let mut output = Output::new();
         if !bounds.is_empty() {
             output.extend(Output::new().symbol_colon());
@@ -1091,7 +1128,7 @@ impl<'c> RenderingContext<'c> {
                     }
                     GenericBound::Outlives(id) => {
                         let mut output = Output::new();
-                        output.lifetime(id);
+                        output.lifetime(id.clone());
                         output
                     }
                     GenericBound::Use(args) => {
@@ -1101,10 +1138,10 @@ impl<'c> RenderingContext<'c> {
                         for i in 0..args.len() {
                             match &args[i] {
                                 rustdoc_types::PreciseCapturingArg::Lifetime(lifetime) => {
-                                    output.lifetime(lifetime);
+                                    output.lifetime(lifetime.clone());
                                 }
                                 rustdoc_types::PreciseCapturingArg::Param(param) => {
-                                    output.generic(param);
+                                    output.generic(param.clone());
                                 }
                             }
 
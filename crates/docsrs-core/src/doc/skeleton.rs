@@ -0,0 +1,224 @@
+//! Code to start from when using an item, see `docsrs <item> --skeleton`:
+//! an impl block for a trait, a call for a function.
+
+use rustdoc_fmt::Output;
+use rustdoc_types::{Function, GenericParamDefKind, Item, ItemEnum, Trait, Type};
+
+use super::children::sealed_line;
+use super::render::RenderingContext;
+
+/// Type the trait skeletons implement the trait for, and the receiver of
+/// trait methods.
+pub(super) const IMPLEMENTOR: &str = "MyType";
+
+/// An `impl` block of the trait `name` for [`IMPLEMENTOR`], with the
+/// associated types and consts without defaults and the required methods,
+/// their bodies `todo!()`.
+pub(super) fn impl_skeleton(context: &RenderingContext, name: &str, trait_: &Trait) -> String {
+    let colorizer = context.colorizer;
+    let mut output = String::new();
+    if let Some(sealed) = sealed_line(trait_, context) {
+        output.push_str(&format!("{sealed}\n"));
+    }
+
+    // Parameters with defaults are left to them
+    let params: Vec<_> = trait_
+        .generics
+        .params
+        .iter()
+        .filter(|param| match &param.kind {
+            GenericParamDefKind::Lifetime { .. } => true,
+            GenericParamDefKind::Type { default, .. } => default.is_none(),
+            GenericParamDefKind::Const { default, .. } => default.is_none(),
+        })
+        .cloned()
+        .collect();
+    let mut header = Output::new();
+    if trait_.is_unsafe {
+        header.qualifier("unsafe").whitespace();
+    }
+    header.keyword("impl");
+    header.extend(context.render_generic_param_defs(&params));
+    header.whitespace().type_(name.to_string());
+    if !params.is_empty() {
+        let args: Vec<&str> = params.iter().map(|param| param.name.as_str()).collect();
+        header.symbol(format!("<{}>", args.join(", ")));
+    }
+    header
+        .whitespace()
+        .keyword("for")
+        .whitespace()
+        .type_(IMPLEMENTOR)
+        .whitespace()
+        .symbol("{");
+    output.push_str(&colorizer.tokens(&header.into_tokens()));
+    output.push('\n');
+
+    let items: Vec<&Item> = trait_
+        .items
+        .iter()
+        .filter_map(|id| context.crate_.index.get(id))
+        .collect();
+    let mut associated = Vec::new();
+    let mut methods = Vec::new();
+    for item in items {
+        let name = item.name.clone().unwrap_or_else(|| "unknown".to_string());
+        match &item.inner {
+            ItemEnum::AssocType { type_: None, .. } => {
+                let mut line = Output::new();
+                line.keyword("type").whitespace().type_(name);
+                line.whitespace()
+                    .symbol("=")
+                    .whitespace()
+                    .symbol("/* ... */;");
+                associated.push(colorizer.tokens(&line.into_tokens()));
+            }
+            ItemEnum::AssocConst { type_, value: None } => {
+                let mut line = Output::new();
+                line.keyword("const").whitespace().identifier(name);
+                line.extend(Output::new().symbol_colon());
+                line.extend(context.render_type(type_));
+                line.whitespace().symbol("=").whitespace();
+                line.function("todo!").symbol("();");
+                associated.push(colorizer.tokens(&line.into_tokens()));
+            }
+            ItemEnum::Function(function) if !function.has_body => {
+                let mut name_output = Output::new();
+                name_output.function(name);
+                let mut signature = context.render_method(
+                    name_output,
+                    &function.sig,
+                    &function.generics,
+                    &function.header,
+                );
+                signature.whitespace().symbol("{");
+                let signature = context.format_signature(&signature.into_tokens(), 4);
+                methods.push(format!("{signature}\n        todo!()\n    }}"));
+            }
+            _ => {}
+        }
+    }
+
+    let mut groups: Vec<String> = Vec::new();
+    if !associated.is_empty() {
+        groups.push(
+            associated
+                .iter()
+                .map(|line| format!("    {line}\n"))
+                .collect(),
+        );
+    }
+    groups.extend(methods.iter().map(|method| format!("    {method}\n")));
+    output.push_str(&groups.join("\n"));
+    output.push_str("}\n");
+    output
+}
+
+/// A call of `function` with a `todo!()` binding for each argument. `callee`
+/// is the path it's called by, e.g. `Text::new`, or for methods `receiver`
+/// the type of the value it's called on.
+pub(super) fn call_skeleton(
+    context: &RenderingContext,
+    callee: &str,
+    receiver: Option<&str>,
+    function: &Function,
+) -> String {
+    let colorizer = context.colorizer;
+    let mut lines = Vec::new();
+    let mut args = Vec::new();
+    let mut receiver_variable = None;
+    for (index, (name, ty)) in function.sig.inputs.iter().enumerate() {
+        let mut binding = Output::new();
+        binding.keyword("let").whitespace();
+        if name == "self"
+            && let Some(receiver) = receiver
+        {
+            if matches!(
+                ty,
+                Type::BorrowedRef {
+                    is_mutable: true,
+                    ..
+                }
+            ) {
+                binding.keyword("mut").whitespace();
+            }
+            let variable = snake_case(receiver);
+            binding.identifier(variable.clone());
+            binding.extend(Output::new().symbol_colon());
+            binding.type_(receiver.to_string());
+            receiver_variable = Some(variable);
+        } else {
+            let variable = if is_identifier(name) {
+                name.clone()
+            } else {
+                format!("arg{index}")
+            };
+            binding.identifier(variable.clone());
+            binding.extend(Output::new().symbol_colon());
+            binding.extend(context.render_type(ty));
+            args.push(variable);
+        }
+        binding.whitespace().symbol("=").whitespace();
+        binding.function("todo!").symbol("();");
+        lines.push(colorizer.tokens(&binding.into_tokens()));
+    }
+
+    let mut call = Output::new();
+    match receiver_variable {
+        Some(variable) => {
+            let method = callee.rsplit("::").next().unwrap_or(callee);
+            call.identifier(variable)
+                .symbol(".")
+                .function(method.to_string());
+        }
+        None => {
+            call.function(callee.to_string());
+        }
+    }
+    call.symbol(format!("({})", args.join(", ")));
+    if function.header.is_async {
+        call.symbol(".").keyword("await");
+    }
+    let mut line = Output::new();
+    if function.sig.output.is_some() {
+        line.keyword("let")
+            .whitespace()
+            .identifier("result")
+            .whitespace()
+            .symbol("=")
+            .whitespace();
+    }
+    if function.header.is_unsafe {
+        line.keyword("unsafe").whitespace().symbol("{").whitespace();
+        line.extend(call);
+        line.whitespace().symbol("}");
+    } else {
+        line.extend(call);
+    }
+    line.symbol(";");
+    lines.push(colorizer.tokens(&line.into_tokens()));
+    lines.iter().map(|line| format!("{line}\n")).collect()
+}
+
+/// Whether `name` can be used as a variable name, unlike patterns such as
+/// `(a, b)` or `_`.
+fn is_identifier(name: &str) -> bool {
+    name != "_"
+        && name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// The variable name for a value of the type `name`, `default_hasher` for
+/// `DefaultHasher`.
+fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut previous_lower = false;
+    for c in name.chars() {
+        if c.is_uppercase() && previous_lower {
+            snake.push('_');
+        }
+        previous_lower = c.is_lowercase() || c.is_ascii_digit();
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
@@ -1,11 +1,26 @@
+use crate::crates_io;
+use crate::events::EventSink;
+use crate::json::parse_rustdoc_json;
+use crate::registry_index;
+use crate::search_index::SearchIndex;
+use crate::source::{DocSource, DocsNotFound, doc_source_for, split_qualified_name, target};
+use crate::suggest;
+#[cfg(not(feature = "simd-json"))]
+use crate::timings::TimedReader;
+use crate::timings::{self, Phase};
 use crate::util::alternate_crate_name;
 use anyhow::{Context, Result, bail};
 use directories::ProjectDirs;
 use rustdoc_types::Crate;
-use std::fs;
+use std::fs::{self, File};
+#[cfg(not(feature = "simd-json"))]
+use std::io::BufReader;
 use std::io::Read;
 use std::path::{Path, PathBuf};
 use std::process::Command;
+use std::sync::RwLock;
+use std::sync::atomic::{AtomicBool, AtomicI32, Ordering};
+use std::time::Instant;
 
 /// Result of building local documentation
 pub enum BuildLocalDocsResult {
@@ -20,11 +35,9 @@ pub enum BuildLocalDocsResult {
 /// Runs `cargo +nightly doc -p {crate_name} --no-deps` and loads the resulting JSON.
 /// If the build fails but cached docs exist, returns those with a warning.
 pub fn build_local_docs(crate_name: &str, doc_path: &Path) -> Result<BuildLocalDocsResult> {
-    // Run cargo +nightly doc
-    let output = Command::new("cargo")
-        .args(["+nightly", "doc", "-p", crate_name, "--no-deps"])
-        .env("RUSTDOCFLAGS", "-Z unstable-options --output-format=json")
-        .output();
+    let output = timings::time(Phase::Build, || {
+        cargo_doc_json().args(["-p", crate_name]).output()
+    });
 
     match output {
         Ok(output) if output.status.success() => {
@@ -67,6 +80,128 @@ pub fn build_local_docs(crate_name: &str, doc_path: &Path) -> Result<BuildLocalD
     }
 }
 
+/// `cargo +nightly doc --no-deps`, writing rustdoc JSON instead of HTML.
+fn cargo_doc_json() -> Command {
+    let mut command = Command::new("cargo");
+    command
+        .args(["+nightly", "doc", "--no-deps"])
+        .env("RUSTDOCFLAGS", "-Z unstable-options --output-format=json");
+    command
+}
+
+static BUILD_FALLBACK: AtomicBool = AtomicBool::new(false);
+
+/// Build the docs of crates.io crates docs.rs has none for from their
+/// published source, instead of failing.
+pub fn set_build_fallback(enabled: bool) {
+    BUILD_FALLBACK.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether docs docs.rs has none for are built from their published source.
+pub(crate) fn build_fallback() -> bool {
+    BUILD_FALLBACK.load(Ordering::Relaxed)
+}
+
+/// Build the rustdoc JSON of crate@version from the source published on
+/// crates.io, and cache it under `cache_version` like downloaded docs.
+///
+/// The crate is unpacked and built in a temporary directory, which is
+/// removed once the build succeeds and kept, named in the error, when it
+/// fails.
+fn build_published_docs(
+    crate_name: &str,
+    version: &str,
+    cache_version: &str,
+    use_cache: bool,
+    events: &dyn EventSink,
+) -> Result<Crate> {
+    events.on_warning(&format!(
+        "docs.rs has no docs for {crate_name}@{version}, building them from its published source..."
+    ));
+    let build_dir = std::env::temp_dir().join(format!(
+        "docsrs-build-{crate_name}-{version}-{}",
+        std::process::id()
+    ));
+    let json_data = build_kept_on_failure(&build_dir, |build_dir| {
+        build_in(build_dir, crate_name, version)
+    })?;
+
+    if use_cache {
+        let compressed = zstd::encode_all(&json_data[..], compression_level().max(1))?;
+        let cache_path = write_cache(crate_name, cache_version, &compressed)?;
+        events.on_cache_saved(&cache_path);
+    }
+    timings::time(Phase::Parse, || parse_rustdoc_json(json_data))
+        .context("Failed to parse the built rustdoc JSON")
+}
+
+/// Run `build` in `build_dir`, removing the directory once it succeeds.
+/// The files are only left there when the build fails, to look into why,
+/// and the error names the directory.
+fn build_kept_on_failure(
+    build_dir: &Path,
+    build: impl FnOnce(&Path) -> Result<Vec<u8>>,
+) -> Result<Vec<u8>> {
+    let json_data = build(build_dir)
+        .with_context(|| format!("Failed to build the docs in {}", build_dir.display()))?;
+    let _ = fs::remove_dir_all(build_dir);
+    Ok(json_data)
+}
+
+/// Unpack crate@version in `build_dir` and build its rustdoc JSON there,
+/// returning the JSON.
+fn build_in(build_dir: &Path, crate_name: &str, version: &str) -> Result<Vec<u8>> {
+    let manifest_dir = crates_io::unpack_crate(crate_name, version, build_dir)?;
+    let target_dir = build_dir.join("target");
+    let output = timings::time(Phase::Build, || {
+        cargo_doc_json()
+            .arg("--lib")
+            .current_dir(&manifest_dir)
+            .env("CARGO_TARGET_DIR", &target_dir)
+            .output()
+    })
+    .context("Failed to run cargo")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if is_nightly_missing(&stderr) {
+            bail!(
+                "Nightly toolchain required to build documentation.\n\
+                 Install with: rustup toolchain install nightly"
+            );
+        }
+        bail!("{}", extract_error_summary(&stderr));
+    }
+
+    // The library may be named differently than the package, and it's the
+    // only one documented
+    let json_path = fs::read_dir(target_dir.join("doc"))?
+        .filter_map(|entry| Some(entry.ok()?.path()))
+        .find(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == "json")
+        })
+        .context("cargo doc wrote no rustdoc JSON")?;
+    fs::read(&json_path).with_context(|| format!("Failed to read {}", json_path.display()))
+}
+
+/// Build the HTML docs of the local package `package` into `target/doc`,
+/// with `cargo doc -p {package} --no-deps`.
+pub fn build_local_html(package: &str) -> Result<()> {
+    let output = timings::time(Phase::Build, || {
+        Command::new("cargo")
+            .args(["doc", "-p", package, "--no-deps"])
+            .output()
+    })
+    .context("Failed to run cargo")?;
+    if !output.status.success() {
+        bail!(
+            "Failed to build HTML documentation:\n{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+    }
+    Ok(())
+}
+
 /// Check if stderr indicates missing nightly toolchain
 fn is_nightly_missing(stderr: &str) -> bool {
     stderr.contains("toolchain 'nightly'")
@@ -91,79 +226,280 @@ fn extract_error_summary(stderr: &str) -> String {
     "compilation error".to_string()
 }
 
-/// Load documentation from a local rustdoc JSON file
+/// Load documentation from a local rustdoc JSON file, either as rustdoc
+/// wrote it or compressed with zstd, like the docs docs.rs serves
 pub fn load_local_docs(path: &Path) -> Result<Crate> {
-    let json_data = fs::read_to_string(path)
+    let json_data = fs::read(path)
         .with_context(|| format!("Failed to read local rustdoc JSON at {}", path.display()))?;
 
-    let krate: Crate =
-        serde_json::from_str(&json_data).context("Failed to parse local rustdoc JSON")?;
+    if json_data.starts_with(&ZSTD_MAGIC) {
+        return parse_compressed_rustdoc_json(&json_data[..])
+            .context("Failed to parse local rustdoc JSON");
+    }
+    timings::time(Phase::Parse, || parse_rustdoc_json(json_data))
+        .context("Failed to parse local rustdoc JSON")
+}
 
-    Ok(krate)
+/// The first bytes of every zstd frame
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// Load the rustdoc JSON of a standard library crate (`std`, `core`, ...)
+/// shipped by the nightly `rust-docs-json` component.
+pub fn load_sysroot_docs(crate_name: &str) -> Result<Crate> {
+    let output = Command::new("rustc")
+        .args(["+nightly", "--print", "sysroot"])
+        .output()
+        .context("Failed to run rustc")?;
+    if !output.status.success() {
+        bail!("Nightly toolchain required for standard library documentation");
+    }
+    let sysroot = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    let path = Path::new(&sysroot)
+        .join("share/doc/rust/json")
+        .join(format!("{crate_name}.json"));
+    if !path.exists() {
+        bail!(
+            "No rustdoc JSON for {crate_name}.\n\
+             Install it with: rustup component add --toolchain nightly rust-docs-json"
+        );
+    }
+    load_local_docs(&path)
 }
 
 /// Fetch and search documentation from docs.rs
 /// Returns the search results and the parsed crate data.
 /// If the fetch fails and the crate name contains `_` or `-`, retries with the swapped form.
-pub fn fetch_docs(crate_name: &str, version: &str, use_cache: bool) -> Result<Crate> {
-    match fetch_docs_inner(crate_name, version, use_cache) {
-        Ok(krate) => Ok(krate),
-        Err(original_err) => {
-            if is_http_404(&original_err) {
-                if let Some(alt_name) = alternate_crate_name(crate_name) {
-                    eprintln!(
-                        "Fetch failed for '{}', retrying with '{}'...",
-                        crate_name, alt_name
-                    );
-                    if let Ok(krate) = fetch_docs_inner(&alt_name, version, use_cache) {
-                        return Ok(krate);
-                    }
-                }
-                bail!(
-                    "Crate '{}@{}' not found on docs.rs. Check the crate name and version.",
-                    crate_name,
-                    version
-                );
-            }
-            Err(original_err)
+///
+/// Downloads, cache hits and parsing are reported to `events`.
+pub fn fetch_docs(
+    crate_name: &str,
+    version: &str,
+    use_cache: bool,
+    events: &dyn EventSink,
+) -> Result<Crate> {
+    fetch_docs_inner(crate_name, version, use_cache, events)
+        .or_else(|err| retry_not_found(crate_name, version, use_cache, events, err))
+}
+
+/// Recover from the first fetch of crate@version failing with `err`: docs
+/// that weren't found are retried under the alternate crate name, then
+/// built from the published source with `--build-fallback`. Other errors
+/// are returned as they are.
+pub(crate) fn retry_not_found(
+    crate_name: &str,
+    version: &str,
+    use_cache: bool,
+    events: &dyn EventSink,
+    err: anyhow::Error,
+) -> Result<Crate> {
+    if !is_not_found(&err) {
+        return Err(err);
+    }
+    if let Some(alt_name) = alternate_crate_name(crate_name) {
+        events.on_warning(&format!(
+            "Fetch failed for '{}', retrying with '{}'...",
+            crate_name, alt_name
+        ));
+        if let Ok(krate) = fetch_docs_inner(&alt_name, version, use_cache, events) {
+            return Ok(krate);
         }
     }
+    let (source, name) = doc_source_for(crate_name)?;
+    let hint = suggest::did_you_mean(&source.similar_crates(name))
+        .map(|hint| format!("\n\n{hint}"))
+        .unwrap_or_default();
+    let host = match split_qualified_name(crate_name) {
+        (Some(registry), _) => format!("the docs server of registry {registry}"),
+        (None, _) => "docs.rs".to_string(),
+    };
+    let mut message = format!(
+        "Crate '{}@{}' not found on {}. Check the crate name and version.",
+        name, version, host
+    );
+    // docs.rs has no docs for versions it failed to build, which
+    // can still be built from the source on crates.io
+    let published = published_version(crate_name, version);
+    if let Some(krate) = fall_back_to_build(&mut message, published, |published| {
+        build_published_docs(name, published, version, use_cache, events)
+    }) {
+        return Ok(krate);
+    }
+    bail!("{}{}", message, hint);
 }
 
-fn fetch_docs_inner(crate_name: &str, version: &str, use_cache: bool) -> Result<Crate> {
-    // Try to load from cache first
-    let compressed_data = if use_cache {
-        match load_from_cache(crate_name, version) {
-            Ok(data) => data,
-            Err(_) => {
-                // Cache miss, download
-                download_and_cache(crate_name, version)?
-            }
+/// Build the docs of a crate docs.rs has none for with `build`, given the
+/// `published` version the request matches, with `--build-fallback`.
+/// Without it, or when the build fails, `message` says why.
+fn fall_back_to_build(
+    message: &mut String,
+    published: Option<String>,
+    build: impl FnOnce(&str) -> Result<Crate>,
+) -> Option<Crate> {
+    let published = published?;
+    if !build_fallback() {
+        message.push_str(
+            "\n\nIt is published on crates.io, run again with --build-fallback to build its docs locally.",
+        );
+        return None;
+    }
+    match build(&published) {
+        Ok(krate) => Some(krate),
+        Err(e) => {
+            message.push_str(&format!(
+                "\n\nBuilding it from its published source failed: {e:#}"
+            ));
+            None
         }
-    } else {
-        // Skip cache, download directly
-        download_rustdoc_json(crate_name, version)?
-    };
+    }
+}
+
+fn fetch_docs_inner(
+    crate_name: &str,
+    version: &str,
+    use_cache: bool,
+    events: &dyn EventSink,
+) -> Result<Crate> {
+    if let Some(krate) = load_cached_docs(crate_name, version, use_cache, events)? {
+        return Ok(krate);
+    }
+    let compressed_data = download_rustdoc_json(crate_name, version, events)?;
+    parse_downloaded_docs(crate_name, version, use_cache, &compressed_data, events)
+}
+
+/// Parse the cached docs of crate@version, streaming straight from the
+/// file. `None` on a cache miss, or when `use_cache` is off, unless docsrs
+/// is offline, which fails as there is nothing to download them with.
+pub(crate) fn load_cached_docs(
+    crate_name: &str,
+    version: &str,
+    use_cache: bool,
+    events: &dyn EventSink,
+) -> Result<Option<Crate>> {
+    if use_cache && let Ok(mut file) = open_cache(crate_name, version) {
+        events.on_cache_hit(crate_name, version);
+        return parse_docs(crate_name, version, &mut file, events).map(Some);
+    }
 
-    // Decompress with zstd
-    let decompressed_data =
-        zstd::decode_all(&compressed_data[..]).context("Failed to decompress zstd data")?;
+    if is_offline() {
+        bail!("Docs for '{crate_name}@{version}' aren't cached, and docsrs is offline");
+    }
+    Ok(None)
+}
 
-    // Parse rustdoc JSON
-    let krate: Crate =
-        serde_json::from_slice(&decompressed_data).context("Failed to parse rustdoc JSON")?;
+/// Parse downloaded docs of crate@version, saving them to the cache first
+/// when `use_cache` is on.
+pub(crate) fn parse_downloaded_docs(
+    crate_name: &str,
+    version: &str,
+    use_cache: bool,
+    compressed_data: &[u8],
+    events: &dyn EventSink,
+) -> Result<Crate> {
+    // Save to cache (ignore errors)
+    if use_cache && let Err(e) = save_to_cache(crate_name, version, compressed_data, events) {
+        events.on_warning(&format!("Failed to cache data: {}", e));
+    }
+    parse_docs(crate_name, version, &mut &compressed_data[..], events)
+}
 
+fn parse_docs(
+    crate_name: &str,
+    version: &str,
+    compressed: &mut dyn Read,
+    events: &dyn EventSink,
+) -> Result<Crate> {
+    let start = Instant::now();
+    let krate =
+        parse_compressed_rustdoc_json(compressed).context("Failed to parse rustdoc JSON")?;
+    events.on_parse_done(crate_name, version, start.elapsed());
     Ok(krate)
 }
 
-/// Check if an error is an HTTP 404 from ureq
-fn is_http_404(err: &anyhow::Error) -> bool {
-    err.downcast_ref::<ureq::Error>()
-        .is_some_and(|e| matches!(e, ureq::Error::StatusCode(404)))
+/// The newest version of the crates.io crate `crate_name` that `version`
+/// allows, if there is one and it could be built.
+fn published_version(crate_name: &str, version: &str) -> Option<String> {
+    if is_offline() || split_qualified_name(crate_name).0.is_some() {
+        return None;
+    }
+    let req = registry_index::version_req(version).ok()?;
+    Some(registry_index::newest_matching(crate_name, &req).ok()?.vers)
+}
+
+/// Decompress and parse zstd-compressed rustdoc JSON
+///
+/// The JSON is decoded as it is parsed, so the decompressed document (often
+/// several times the size of the parsed crate) is never held in memory at
+/// once. The simd-json parser needs the whole document, so with that feature
+/// the decompressed bytes are still collected first.
+#[cfg(not(feature = "simd-json"))]
+fn parse_compressed_rustdoc_json(compressed: impl Read) -> Result<Crate> {
+    let start = Instant::now();
+    let mut decoder =
+        TimedReader::new(zstd::Decoder::new(compressed).context("Failed to decompress zstd data")?);
+    let krate = serde_json::from_reader(BufReader::new(&mut decoder)).map_err(|err| {
+        match err.io_error_kind() {
+            Some(_) => anyhow::Error::new(err).context("Failed to decompress zstd data"),
+            None => err.into(),
+        }
+    });
+    timings::record(Phase::Decompress, decoder.elapsed);
+    timings::record(
+        Phase::Parse,
+        start.elapsed().saturating_sub(decoder.elapsed),
+    );
+    krate
+}
+
+/// Decompress and parse zstd-compressed rustdoc JSON
+///
+/// simd-json parses the whole document in place, so it's decoded into one
+/// buffer first.
+#[cfg(feature = "simd-json")]
+fn parse_compressed_rustdoc_json(compressed: impl Read) -> Result<Crate> {
+    let json_data = timings::time(Phase::Decompress, || {
+        let mut json_data = Vec::new();
+        zstd::Decoder::new(compressed)
+            .and_then(|mut decoder| decoder.read_to_end(&mut json_data))
+            .context("Failed to decompress zstd data")?;
+        anyhow::Ok(json_data)
+    })?;
+    timings::time(Phase::Parse, || parse_rustdoc_json(json_data))
+}
+
+/// Check if an error is the source not having the docs, e.g. an HTTP 404
+fn is_not_found(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<DocsNotFound>().is_some()
+}
+
+static CACHE_DIR: RwLock<Option<PathBuf>> = RwLock::new(None);
+
+/// Keep the cache in `dir` instead of the user cache directory, for every
+/// later query in this process.
+pub fn set_cache_dir(dir: impl Into<PathBuf>) {
+    if let Ok(mut current) = CACHE_DIR.write() {
+        *current = Some(dir.into());
+    }
+}
+
+/// The directory set with [`set_cache_dir`], if any.
+pub(crate) fn cache_dir_override() -> Option<PathBuf> {
+    CACHE_DIR.read().ok().and_then(|dir| dir.clone())
+}
+
+/// Keep the cache in `dir`, or the user cache directory when it's `None`,
+/// returning the directory set before.
+pub(crate) fn replace_cache_dir(dir: Option<PathBuf>) -> Option<PathBuf> {
+    match CACHE_DIR.write() {
+        Ok(mut current) => std::mem::replace(&mut *current, dir),
+        Err(_) => None,
+    }
 }
 
 /// Get the cache directory path for rustdoc JSON files
 fn get_cache_dir() -> Result<PathBuf> {
+    if let Some(dir) = cache_dir_override() {
+        return Ok(dir);
+    }
     let proj_dirs =
         ProjectDirs::from("", "", "docsrs").context("Failed to determine cache directory")?;
     Ok(proj_dirs.cache_dir().to_path_buf())
@@ -214,11 +550,25 @@ fn validate_path_component(value: &str, component_name: &str) -> Result<()> {
 
 /// Get the cache file path for a specific crate and version.
 /// Validates inputs and ensures the resulting path stays within the cache directory.
-fn get_cache_path(crate_name: &str, version: &str) -> Result<PathBuf> {
+/// Docs of another target than the default one are kept as `{version}@{target}.zst`.
+pub(crate) fn get_cache_path(crate_name: &str, version: &str) -> Result<PathBuf> {
     // Validate inputs
-    validate_path_component(crate_name, "crate name")?;
     validate_path_component(version, "version")?;
+    let file_name = match target() {
+        Some(target) => {
+            validate_path_component(&target, "target")?;
+            format!("{version}@{target}.zst")
+        }
+        None => format!("{version}.zst"),
+    };
+    get_crate_cache_file(crate_name, &file_name)
+}
 
+/// Get the path of `file_name` in the cache directory of a crate, such as
+/// its registry index entry. Validates the crate name and ensures the
+/// resulting path stays within the cache directory.
+pub(crate) fn get_crate_cache_file(crate_name: &str, file_name: &str) -> Result<PathBuf> {
+    validate_path_component(crate_name, "crate name")?;
     let cache_dir = get_cache_dir()?;
 
     // Verify the path stays within the cache directory.
@@ -230,9 +580,7 @@ fn get_cache_path(crate_name: &str, version: &str) -> Result<PathBuf> {
 
     // For the cache path, we need to build it from the canonical cache dir
     // since the file may not exist yet
-    let safe_cache_path = canonical_cache_dir
-        .join(crate_name)
-        .join(format!("{}.zst", version));
+    let safe_cache_path = canonical_cache_dir.join(crate_name).join(file_name);
 
     // Double-check that no path traversal occurred by verifying the path starts with cache_dir
     if !safe_cache_path.starts_with(&canonical_cache_dir) {
@@ -242,14 +590,27 @@ fn get_cache_path(crate_name: &str, version: &str) -> Result<PathBuf> {
     Ok(safe_cache_path)
 }
 
-/// Load compressed rustdoc JSON from cache
-fn load_from_cache(crate_name: &str, version: &str) -> Result<Vec<u8>> {
+/// Open the cached compressed rustdoc JSON
+fn open_cache(crate_name: &str, version: &str) -> Result<File> {
     let cache_path = get_cache_path(crate_name, version)?;
-    fs::read(&cache_path).context("Cache miss")
+    File::open(&cache_path).context("Cache miss")
 }
 
 /// Save compressed rustdoc JSON to cache
-fn save_to_cache(crate_name: &str, version: &str, data: &[u8]) -> Result<()> {
+fn save_to_cache(
+    crate_name: &str,
+    version: &str,
+    data: &[u8],
+    events: &dyn EventSink,
+) -> Result<()> {
+    let cache_path = write_cache(crate_name, version, data)?;
+    events.on_cache_saved(&cache_path);
+    Ok(())
+}
+
+/// Write compressed rustdoc JSON to its cache file, returning the file's path.
+/// The data is recompressed at the configured level first.
+fn write_cache(crate_name: &str, version: &str, data: &[u8]) -> Result<PathBuf> {
     let cache_path = get_cache_path(crate_name, version)?;
 
     // Create parent directory if it doesn't exist
@@ -257,42 +618,165 @@ fn save_to_cache(crate_name: &str, version: &str, data: &[u8]) -> Result<()> {
         fs::create_dir_all(parent)?;
     }
 
-    fs::write(&cache_path, data).context("Failed to save to cache")?;
-    eprintln!("Saved to cache: {}", cache_path.display());
-    Ok(())
+    // Data that doesn't decompress is cached as is, to fail the same way later
+    let recompressed = recompress(data, compression_level()).ok().flatten();
+    fs::write(&cache_path, recompressed.as_deref().unwrap_or(data))
+        .context("Failed to save to cache")?;
+    Ok(cache_path)
 }
 
-/// Download rustdoc JSON from docs.rs
-fn download_rustdoc_json(crate_name: &str, version: &str) -> Result<Vec<u8>> {
-    eprintln!("Fetching rustdoc JSON from docs.rs...");
+/// zstd level cached docs are recompressed at when none is configured.
+///
+/// docs.rs serves rustdoc JSON compressed at a low level; level 12 makes
+/// cache entries about 30% smaller while keeping recompression fast.
+///
+/// Entries are compressed on their own, without a zstd dictionary trained
+/// across cached docs: a shared dictionary would make every entry unreadable
+/// without it, and entries copied in from elsewhere couldn't be read as is.
+pub const DEFAULT_COMPRESSION_LEVEL: i32 = 12;
+
+/// Configured zstd level for cached docs, 0 to cache docs as downloaded.
+static COMPRESSION_LEVEL: AtomicI32 = AtomicI32::new(DEFAULT_COMPRESSION_LEVEL);
+
+/// Set the zstd level cached docs are recompressed at (1-22), or 0 to cache
+/// them as downloaded.
+pub fn set_compression_level(level: i32) {
+    COMPRESSION_LEVEL.store(level, Ordering::Relaxed);
+}
 
-    let url = format!("https://docs.rs/crate/{}/{}/json", crate_name, version);
-    eprintln!("URL: {}", url);
+pub(crate) fn compression_level() -> i32 {
+    COMPRESSION_LEVEL.load(Ordering::Relaxed)
+}
 
-    let mut response = ureq::get(&url).call()?;
+static OFFLINE: AtomicBool = AtomicBool::new(false);
 
-    let mut compressed_data = Vec::new();
-    response
-        .body_mut()
-        .as_reader()
-        .read_to_end(&mut compressed_data)?;
-    eprintln!("Downloaded {} bytes (compressed)", compressed_data.len());
+/// Only use cached docs, failing instead of downloading missing ones.
+pub fn set_offline(offline: bool) {
+    OFFLINE.store(offline, Ordering::Relaxed);
+}
 
-    Ok(compressed_data)
+/// Whether docsrs only uses what's cached, and makes no requests.
+pub(crate) fn is_offline() -> bool {
+    OFFLINE.load(Ordering::Relaxed)
 }
 
-/// Download and cache rustdoc JSON
-fn download_and_cache(crate_name: &str, version: &str) -> Result<Vec<u8>> {
-    let compressed_data = download_rustdoc_json(crate_name, version)?;
+/// Recompress zstd data at `level`, or `None` if `level` is 0.
+fn recompress(data: &[u8], level: i32) -> Result<Option<Vec<u8>>> {
+    if level == 0 {
+        return Ok(None);
+    }
+    let decoder = zstd::Decoder::new(data)?;
+    Ok(Some(zstd::encode_all(decoder, level)?))
+}
 
-    // Save to cache (ignore errors)
-    if let Err(e) = save_to_cache(crate_name, version, &compressed_data) {
-        eprintln!("Warning: Failed to cache data: {}", e);
+/// Sizes before and after recompressing the cache.
+pub struct RecompressSummary {
+    /// Number of cached docs
+    pub entries: usize,
+    /// Number of cached docs that got smaller and were replaced
+    pub recompressed: usize,
+    /// Total size of the cached docs before, in bytes
+    pub size_before: u64,
+    /// Total size of the cached docs after, in bytes
+    pub size_after: u64,
+}
+
+/// Recompress every cached doc at the configured level, keeping the result
+/// where it is smaller. Lets caches written before recompression, or at
+/// another level, catch up.
+pub fn recompress_cache() -> Result<RecompressSummary> {
+    recompress_dir(&get_cache_dir()?, compression_level())
+}
+
+/// Recompress the docs cached in `cache_dir` at `level`. The search indexes
+/// saved with them are stamped with the rewritten files, so they're still
+/// used.
+fn recompress_dir(cache_dir: &Path, level: i32) -> Result<RecompressSummary> {
+    let mut summary = RecompressSummary {
+        entries: 0,
+        recompressed: 0,
+        size_before: 0,
+        size_after: 0,
+    };
+    let Ok(crate_dirs) = fs::read_dir(cache_dir) else {
+        return Ok(summary);
+    };
+
+    for crate_dir in crate_dirs.flatten() {
+        let Ok(entries) = fs::read_dir(crate_dir.path()) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.extension().is_none_or(|ext| ext != "zst") {
+                continue;
+            }
+            let data =
+                fs::read(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+            summary.entries += 1;
+            summary.size_before += data.len() as u64;
+
+            match recompress(&data, level) {
+                Ok(Some(recompressed)) if recompressed.len() < data.len() => {
+                    let index = SearchIndex::load(&path);
+                    fs::write(&path, &recompressed)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    if let Some(mut index) = index {
+                        index.save(&path)?;
+                    }
+                    summary.recompressed += 1;
+                    summary.size_after += recompressed.len() as u64;
+                }
+                _ => summary.size_after += data.len() as u64,
+            }
+        }
     }
 
+    Ok(summary)
+}
+
+/// Download the rustdoc JSON of crate@version into the cache, unless it is
+/// cached already. Nothing is printed, so downloads can run in parallel.
+///
+/// Returns whether the docs were downloaded.
+pub(crate) fn cache_docs(crate_name: &str, version: &str, events: &dyn EventSink) -> Result<bool> {
+    if open_cache(crate_name, version).is_ok() {
+        return Ok(false);
+    }
+    let (source, name) = doc_source_for(crate_name)?;
+    let compressed_data = request_rustdoc_json(&*source, name, version, events)?;
+    write_cache(crate_name, version, &compressed_data)?;
+    Ok(true)
+}
+
+/// Download rustdoc JSON from the configured source, docs.rs by default, or
+/// from the docs server of the crate's registry
+fn download_rustdoc_json(
+    crate_name: &str,
+    version: &str,
+    events: &dyn EventSink,
+) -> Result<Vec<u8>> {
+    let (source, name) = doc_source_for(crate_name)?;
+    events.on_download_start(name, version, &source.location(name, version));
+
+    let compressed_data = request_rustdoc_json(&*source, name, version, events)?;
+    events.on_download_done(compressed_data.len() as u64);
+
     Ok(compressed_data)
 }
 
+/// Fetch the compressed rustdoc JSON of crate@version from `source`
+fn request_rustdoc_json(
+    source: &dyn DocSource,
+    crate_name: &str,
+    version: &str,
+    events: &dyn EventSink,
+) -> Result<Vec<u8>> {
+    timings::time(Phase::Download, || {
+        source.fetch(crate_name, version, events)
+    })
+}
+
 /// Clear the entire cache directory
 pub fn clear_cache() -> Result<()> {
     let cache_dir = get_cache_dir()?;
@@ -388,6 +872,7 @@ mod tests {
 
     #[test]
     fn test_get_cache_path_valid() {
+        let _globals = crate::lock_test_globals();
         let result = get_cache_path("serde", "1.0.0");
         assert!(result.is_ok());
         let path = result.unwrap();
@@ -397,6 +882,7 @@ mod tests {
 
     #[test]
     fn test_get_cache_path_path_traversal_rejected() {
+        let _globals = crate::lock_test_globals();
         // Attempt path traversal in crate name
         let result = get_cache_path("../../../etc", "passwd");
         assert!(result.is_err());
@@ -491,4 +977,135 @@ error: could not compile `my-crate` due to previous error
         let stderr = "warning: unused variable";
         assert!(!is_nightly_missing(stderr));
     }
+
+    #[test]
+    fn test_load_local_docs_compressed_matches_uncompressed() {
+        let json = crate::test_docs("test_items.json");
+        let dir = tempfile::tempdir().unwrap();
+        let compressed = dir.path().join("test_items.json.zst");
+        let json_data = fs::read(&json).unwrap();
+        fs::write(&compressed, zstd::encode_all(&json_data[..], 3).unwrap()).unwrap();
+
+        let krate = load_local_docs(&compressed).unwrap();
+        assert_eq!(krate, load_local_docs(&json).unwrap());
+        assert_eq!(krate.index[&krate.root].name.as_deref(), Some("test_items"));
+    }
+
+    #[test]
+    fn test_fall_back_to_build_only_with_build_fallback() {
+        let _globals = crate::lock_test_globals();
+        let docs = || load_local_docs(&crate::test_docs("test_items.json"));
+
+        // Crates that aren't published can't be built
+        set_build_fallback(true);
+        let mut message = String::new();
+        assert!(fall_back_to_build(&mut message, None, |_| docs()).is_none());
+        assert!(message.is_empty());
+
+        // Without --build-fallback the message points at it
+        set_build_fallback(false);
+        let mut message = String::new();
+        let krate = fall_back_to_build(&mut message, Some("1.2.3".into()), |_| {
+            panic!("built without --build-fallback")
+        });
+        assert!(krate.is_none());
+        assert!(
+            message.contains("run again with --build-fallback"),
+            "{message}"
+        );
+
+        // With it the published version is built
+        set_build_fallback(true);
+        let mut message = String::new();
+        let krate = fall_back_to_build(&mut message, Some("1.2.3".into()), |version| {
+            assert_eq!(version, "1.2.3");
+            docs()
+        });
+        assert!(krate.is_some());
+        assert!(message.is_empty());
+
+        // A failed build is explained
+        let mut message = String::new();
+        let krate = fall_back_to_build(&mut message, Some("1.2.3".into()), |_| {
+            bail!("error[E0425]: cannot find value `x`")
+        });
+        set_build_fallback(false);
+        assert!(krate.is_none());
+        assert!(
+            message.ends_with(
+                "Building it from its published source failed: error[E0425]: cannot find value `x`"
+            ),
+            "{message}"
+        );
+    }
+
+    #[test]
+    fn test_failed_build_keeps_its_dir() {
+        let dir = tempfile::tempdir().unwrap();
+        let build_dir = dir.path().join("build");
+        let unpack = |build_dir: &Path| fs::create_dir_all(build_dir.join("src"));
+
+        let err = build_kept_on_failure(&build_dir, |build_dir| {
+            unpack(build_dir)?;
+            bail!("cargo doc failed")
+        })
+        .unwrap_err();
+        assert!(build_dir.join("src").exists());
+        assert_eq!(
+            format!("{err:#}"),
+            format!(
+                "Failed to build the docs in {}: cargo doc failed",
+                build_dir.display()
+            )
+        );
+
+        let json = build_kept_on_failure(&build_dir, |build_dir| {
+            unpack(build_dir)?;
+            Ok(b"{}".to_vec())
+        })
+        .unwrap();
+        assert_eq!(json, b"{}");
+        assert!(!build_dir.exists());
+    }
+
+    #[test]
+    fn test_parse_compressed_rustdoc_json_corrupt() {
+        let mut data = ZSTD_MAGIC.to_vec();
+        data.extend_from_slice(b"not a zstd frame");
+        let err = parse_compressed_rustdoc_json(&data[..]).unwrap_err();
+        assert!(err.to_string().contains("Failed to decompress"), "{err}");
+    }
+
+    #[test]
+    fn test_recompress_dir() {
+        let json = crate::test_docs("test_items.json");
+        let json_data = fs::read(json).unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        fs::create_dir(dir.path().join("test_items")).unwrap();
+        let docs = dir.path().join("test_items/0.1.0.zst");
+        let fast = zstd::encode_all(&json_data[..], 1).unwrap();
+        fs::write(&docs, &fast).unwrap();
+        let krate = parse_compressed_rustdoc_json(&fast[..]).unwrap();
+        let mut index = SearchIndex::build(&jsondoc::JsonDoc::from(krate.clone()));
+        index.save(&docs).unwrap();
+
+        let summary = recompress_dir(dir.path(), 19).unwrap();
+        assert_eq!(summary.entries, 1);
+        assert_eq!(summary.recompressed, 1);
+        assert_eq!(summary.size_before, fast.len() as u64);
+
+        let recompressed = fs::read(&docs).unwrap();
+        assert_eq!(summary.size_after, recompressed.len() as u64);
+        assert_eq!(recompressed, zstd::encode_all(&json_data[..], 19).unwrap());
+        assert_eq!(
+            parse_compressed_rustdoc_json(&recompressed[..]).unwrap(),
+            krate
+        );
+        assert!(SearchIndex::load(&docs).is_some());
+
+        // Already as small as it gets at this level
+        let summary = recompress_dir(dir.path(), 19).unwrap();
+        assert_eq!(summary.recompressed, 0);
+        assert_eq!(summary.size_after, summary.size_before);
+    }
 }
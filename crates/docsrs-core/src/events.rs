@@ -0,0 +1,123 @@
+//! Progress events reported while fetching docs, so hosts can show them
+//! their own way instead of the library printing.
+
+use std::path::Path;
+use std::time::Duration;
+
+/// Receives progress events while docs are fetched and parsed.
+///
+/// Every method does nothing by default, so sinks only implement the events
+/// they show.
+pub trait EventSink: Send + Sync {
+    /// Downloading the docs of `crate_name@version` from `url` started.
+    fn on_download_start(&self, crate_name: &str, version: &str, url: &str) {
+        let _ = (crate_name, version, url);
+    }
+
+    /// `downloaded` bytes of the compressed docs arrived, out of `total`
+    /// when the server sent the size.
+    fn on_download_progress(&self, downloaded: u64, total: Option<u64>) {
+        let _ = (downloaded, total);
+    }
+
+    /// The download finished with `bytes` of compressed docs.
+    fn on_download_done(&self, bytes: u64) {
+        let _ = bytes;
+    }
+
+    /// The docs of `crate_name@version` were found in the cache.
+    fn on_cache_hit(&self, crate_name: &str, version: &str) {
+        let _ = (crate_name, version);
+    }
+
+    /// Downloaded docs were saved to the cache at `path`.
+    fn on_cache_saved(&self, path: &Path) {
+        let _ = path;
+    }
+
+    /// The docs of `crate_name@version` were decompressed and parsed in
+    /// `elapsed`.
+    fn on_parse_done(&self, crate_name: &str, version: &str, elapsed: Duration) {
+        let _ = (crate_name, version, elapsed);
+    }
+
+    /// The `done`th of `total` crates whose docs are cached at once, as by
+    /// `--warm-cache`, finished as `status`: `downloaded`, `cached` or
+    /// `failed`.
+    fn on_crate_done(
+        &self,
+        done: usize,
+        total: usize,
+        crate_name: &str,
+        version: &str,
+        status: &str,
+    ) {
+        let _ = (done, total, crate_name, version, status);
+    }
+
+    /// The `what` of the item at `path`, e.g. its `signature`, was put on
+    /// the clipboard.
+    fn on_copied(&self, what: &str, path: &str) {
+        let _ = (what, path);
+    }
+
+    /// History references like `!!` were replaced with the arguments of the
+    /// query they refer to, `args`, which is run again.
+    fn on_history_expanded(&self, args: &[String]) {
+        let _ = args;
+    }
+
+    /// Something went wrong that didn't stop the query, such as failing to
+    /// write the cache.
+    fn on_warning(&self, message: &str) {
+        let _ = message;
+    }
+}
+
+/// Ignores every event.
+pub struct NoEvents;
+
+impl EventSink for NoEvents {}
+
+/// Prints downloads, cache writes, crates cached at once, clipboard copies,
+/// queries re-run from the history and warnings to stderr, as the CLI does.
+pub struct StderrEvents;
+
+impl EventSink for StderrEvents {
+    fn on_download_start(&self, _crate_name: &str, _version: &str, url: &str) {
+        eprintln!("Fetching rustdoc JSON...");
+        eprintln!("URL: {}", url);
+    }
+
+    fn on_download_done(&self, bytes: u64) {
+        eprintln!("Downloaded {} bytes (compressed)", bytes);
+    }
+
+    fn on_cache_saved(&self, path: &Path) {
+        eprintln!("Saved to cache: {}", path.display());
+    }
+
+    fn on_crate_done(
+        &self,
+        done: usize,
+        total: usize,
+        crate_name: &str,
+        version: &str,
+        status: &str,
+    ) {
+        eprintln!("[{done}/{total}] {crate_name}@{version} {status}");
+    }
+
+    fn on_copied(&self, what: &str, path: &str) {
+        eprintln!("Copied the {what} of {path} to the clipboard");
+    }
+
+    fn on_history_expanded(&self, args: &[String]) {
+        // Echoed like a shell does
+        eprintln!("docsrs {}", args.join(" "));
+    }
+
+    fn on_warning(&self, message: &str) {
+        eprintln!("Warning: {}", message);
+    }
+}
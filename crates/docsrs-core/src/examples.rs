@@ -0,0 +1,298 @@
+//! The files in a crate's `examples/` directory, see `docsrs <crate>
+//! --crate-examples`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Result, bail};
+use cargo_metadata::semver::Version;
+use colored::Colorize;
+use rustdoc_fmt::Output;
+
+use crate::crates_io;
+use crate::list::render_rows;
+use crate::registry_index::{self, version_req};
+use crate::source::default_registry;
+use crate::suggest;
+use crate::version_resolver::{VersionResolver, pinned_version};
+use crate::{Query, Resolution, StyleConfig};
+
+/// A file under `examples/`.
+#[derive(Clone, Debug)]
+pub struct Example {
+    /// Path relative to `examples/`, with `/` separators
+    pub path: String,
+    pub source: String,
+}
+
+/// The examples of a version of a crate.
+pub struct CrateExamples {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub version: String,
+    /// By path
+    pub examples: Vec<Example>,
+}
+
+/// The examples of the crate in `args`: read from its sources for crates
+/// in the project, else from the `.crate` file published on crates.io.
+pub fn crate_examples(args: &Query) -> Result<CrateExamples> {
+    let spec = &args.spec;
+    let mut resolution = Resolution::default();
+
+    if spec.version.is_none()
+        && pinned_version(&spec.name).is_none()
+        && let Ok(resolver) = VersionResolver::new()
+        && let Some(resolved) = resolver.resolve_crate(&spec.name)
+        && let Some(dir) = resolver.package_dir(&resolved.name, &resolved.version)
+    {
+        resolution.message = Some(resolved.format_message());
+        let mut examples = Vec::new();
+        read_examples(&dir.join("examples"), "", &mut examples);
+        return Ok(CrateExamples::new(
+            resolution,
+            resolved.name,
+            resolved.version,
+            examples,
+        ));
+    }
+
+    if let Some(registry) = default_registry() {
+        bail!(
+            "Examples of crates from registry {registry} are only read from the project's dependencies"
+        );
+    }
+    let version = match (&spec.version, pinned_version(&spec.name)) {
+        (Some(version), _) => version.clone(),
+        (None, Some(pinned)) => {
+            resolution.message = Some(format!("{}@{} (pinned)", spec.original_name, pinned));
+            pinned
+        }
+        (None, None) => "latest".to_string(),
+    };
+    // The .crate file is only found by its exact version
+    let version = match Version::parse(&version) {
+        Ok(_) => version,
+        Err(_) => {
+            let entry =
+                registry_index::newest_matching(&spec.original_name, &version_req(&version)?)?;
+            if resolution.message.is_none() {
+                resolution.message = Some(format!("{}@{}", spec.original_name, entry.vers));
+            }
+            entry.vers
+        }
+    };
+
+    let data = crates_io::crate_source(&spec.original_name, &version)?;
+    let files = crates_io::crate_files(&data[..], &spec.original_name, &version)?;
+    let examples = files
+        .into_iter()
+        .filter_map(|(path, source)| {
+            let path = path.strip_prefix("examples").ok()?;
+            let path = path.to_str()?.replace('\\', "/");
+            Some(Example { path, source })
+        })
+        .collect();
+    Ok(CrateExamples::new(
+        resolution,
+        spec.original_name.clone(),
+        version,
+        examples,
+    ))
+}
+
+/// Add the text files under `dir` to `examples`, with paths starting with
+/// `prefix`.
+fn read_examples(dir: &Path, prefix: &str, examples: &mut Vec<Example>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path: PathBuf = entry.path();
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if path.is_dir() {
+            read_examples(&path, &format!("{prefix}{name}/"), examples);
+        } else if let Ok(source) = fs::read_to_string(&path) {
+            examples.push(Example {
+                path: format!("{prefix}{name}"),
+                source,
+            });
+        }
+    }
+}
+
+impl Example {
+    /// Name of the example `cargo run --example` takes, for the files it
+    /// finds on its own: `examples/{name}.rs` and `examples/{name}/main.rs`.
+    pub fn name(&self) -> Option<&str> {
+        self.path
+            .strip_suffix("/main.rs")
+            .or_else(|| self.path.strip_suffix(".rs"))
+            .filter(|name| !name.contains('/'))
+    }
+
+    /// The path, with the example name in it highlighted.
+    pub(crate) fn label(&self) -> Output {
+        let mut output = Output::new();
+        match self.name() {
+            Some(name) => output
+                .identifier(name.to_string())
+                .symbol(self.path[name.len()..].to_string()),
+            None => output.symbol(self.path.clone()),
+        };
+        output
+    }
+
+    /// The first line of the file's `//!` docs.
+    fn summary(&self) -> Option<&str> {
+        self.source
+            .lines()
+            .map(str::trim)
+            .find(|line| !line.is_empty() && !line.starts_with("#!"))?
+            .strip_prefix("//!")
+            .map(str::trim)
+            .filter(|summary| !summary.is_empty())
+    }
+
+    /// The file, highlighted like code examples in docs.
+    pub fn render(&self, style: &StyleConfig) -> String {
+        let language = match self.path.rsplit_once('.') {
+            Some((_, "rs")) => "rust",
+            Some((_, extension)) => extension,
+            None => "text",
+        };
+        let header = format!("// examples/{}", self.path).bright_black();
+        format!(
+            "{header}\n\n{}",
+            style.colorizer.code_block(&self.source, language)
+        )
+    }
+}
+
+impl CrateExamples {
+    fn new(
+        resolution: Resolution,
+        crate_name: String,
+        version: String,
+        mut examples: Vec<Example>,
+    ) -> Self {
+        examples.sort_by(|a, b| a.path.cmp(&b.path));
+        CrateExamples {
+            resolution,
+            crate_name,
+            version,
+            examples,
+        }
+    }
+
+    /// The example named `name`, as `cargo run --example` takes it, or the
+    /// file at that path under `examples/`.
+    pub fn find(&self, name: &str) -> Result<&Example> {
+        let path = name.strip_prefix("examples/").unwrap_or(name);
+        if let Some(example) = self
+            .examples
+            .iter()
+            .find(|example| example.path == path || example.name() == Some(path))
+        {
+            return Ok(example);
+        }
+        let names = self
+            .examples
+            .iter()
+            .map(|example| example.name().unwrap_or(&example.path));
+        let hint = suggest::did_you_mean(&suggest::similar(path, names))
+            .map(|hint| format!(", {hint}"))
+            .unwrap_or_default();
+        bail!(
+            "{}@{} has no example {name}{hint}",
+            self.crate_name,
+            self.version
+        )
+    }
+
+    /// The files, with the example names `cargo run --example` takes and
+    /// the first line of their docs.
+    pub fn render(&self, style: &StyleConfig) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let header = match self.examples.len() {
+            0 => format!("// {}@{} has no examples", self.crate_name, self.version),
+            1 => format!(
+                "// 1 file in examples/ of {}@{}",
+                self.crate_name, self.version
+            ),
+            count => format!(
+                "// {count} files in examples/ of {}@{}",
+                self.crate_name, self.version
+            ),
+        };
+        output.push_str(&format!("{}\n", header.bright_black()));
+        if self.examples.is_empty() {
+            return output;
+        }
+        output.push('\n');
+
+        let rows = self
+            .examples
+            .iter()
+            .map(|example| (example.label(), example.summary()))
+            .collect();
+        let lines = render_rows(rows, "", &style.colorizer, true, style.max_width);
+        output.push_str(&lines.join("\n"));
+        output.push('\n');
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn example(path: &str, source: &str) -> Example {
+        Example {
+            path: path.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_render_list() {
+        let examples = CrateExamples::new(
+            Resolution::default(),
+            "demo".to_string(),
+            "0.3.0".to_string(),
+            vec![
+                example("server/main.rs", "//! A small HTTP server.\nfn main() {}\n"),
+                example("server/routes.rs", "pub fn index() {}\n"),
+                example(
+                    "client.rs",
+                    "#![allow(unused)]\n//! Fetch a page.\nfn main() {}\n",
+                ),
+            ],
+        );
+        colored::control::set_override(false);
+        let style = StyleConfig {
+            max_width: Some(80),
+            ..StyleConfig::default()
+        };
+        insta::assert_snapshot!(examples.render(&style), @"
+        // 3 files in examples/ of demo@0.3.0
+
+        client.rs         // Fetch a page.
+        server/main.rs    // A small HTTP server.
+        server/routes.rs
+        ");
+
+        assert_eq!(examples.find("server").unwrap().path, "server/main.rs");
+        assert_eq!(
+            examples.find("examples/server/routes.rs").unwrap().path,
+            "server/routes.rs"
+        );
+        assert_eq!(
+            examples.find("clint").unwrap_err().to_string(),
+            "demo@0.3.0 has no example clint, did you mean client?"
+        );
+    }
+}
@@ -0,0 +1,204 @@
+//! Writing the docs of a crate as a directory of markdown files, see
+//! `docsrs export`.
+
+use std::collections::{BTreeMap, HashMap};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use jsondoc::JsonDoc;
+use rustdoc_fmt::{DocLinkKind, rewrite_links};
+use rustdoc_types::Id;
+
+use crate::doc::plain_declaration;
+use crate::list::{EntryKind, ListItem, list_items};
+use crate::{Query, Resolution, resolve_docs, urls};
+
+/// Width signatures are wrapped at in code blocks.
+const MAX_WIDTH: usize = 100;
+
+/// The markdown files written for a version of a crate.
+pub struct Export {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub version: String,
+    /// Directory the files were written to
+    pub dir: PathBuf,
+    /// Number of files written, one per module and item
+    pub pages: usize,
+}
+
+/// Write the docs of the crate in `args` to `out`, one markdown file per
+/// module and item laid out like rustdoc's HTML pages, e.g.
+/// `tokio/sync/struct.Mutex.md`. Intra-doc links to the pages are
+/// rewritten to relative paths, and those to other crates to their docs.
+pub fn export_docs(args: &Query, out: &Path) -> Result<Export> {
+    let mut resolution = Resolution::default();
+    let doc = resolve_docs(&args.spec, &mut resolution)?.load_doc(args.no_cache, &*args.events)?;
+    let krate = doc.crate_data();
+    let version = krate
+        .crate_version
+        .clone()
+        .context("The docs don't say which version of the crate they're for")?;
+
+    // An item re-exported at several paths has the page of its definition
+    let items = list_items(&doc);
+    let mut pages: BTreeMap<String, &ListItem> = BTreeMap::new();
+    for item in &items {
+        if let Some(page) = page_path(&doc, &item.id) {
+            pages.entry(page).or_insert(item);
+        }
+    }
+    let page_of: HashMap<Id, String> = pages
+        .iter()
+        .map(|(page, item)| (item.id, page.clone()))
+        .collect();
+
+    for (page, item) in &pages {
+        let markdown = render_page(&doc, item, page, &items, &pages, &page_of)?;
+        let path = out.join(page);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create {}", parent.display()))?;
+        }
+        fs::write(&path, markdown)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+
+    Ok(Export {
+        resolution,
+        crate_name: args.spec.name.clone(),
+        version,
+        dir: out.join(&args.spec.name),
+        pages: pages.len(),
+    })
+}
+
+/// The file of the page documenting `id`, relative to the output directory.
+fn page_path(doc: &JsonDoc, id: &Id) -> Option<String> {
+    let html = urls::html_path(doc, id)?;
+    let page = html.split('#').next()?;
+    Some(format!("{}.md", page.strip_suffix(".html")?))
+}
+
+/// The markdown of the page of `item`: its declaration and docs, and for
+/// modules a list of their items.
+fn render_page(
+    doc: &JsonDoc,
+    item: &ListItem,
+    page: &str,
+    items: &[ListItem],
+    pages: &BTreeMap<String, &ListItem>,
+    page_of: &HashMap<Id, String>,
+) -> Result<String> {
+    let krate = doc.crate_data();
+    let mut markdown = format!("# {} {}\n\n", item.kind.keyword(), item.path);
+
+    if item.kind != EntryKind::Module {
+        let declaration = plain_declaration(doc, &item.id, Some(MAX_WIDTH))?;
+        markdown.push_str(&format!("```rust\n{}\n```\n\n", declaration.trim_end()));
+    }
+
+    if let Some(data) = krate.index.get(&item.id)
+        && let Some(docs) = &data.docs
+    {
+        let docs = rewrite_links(docs, |link| {
+            if link.kind != DocLinkKind::IntraDoc {
+                return None;
+            }
+            let id = data.links.get(&link.target)?;
+            match page_path(doc, id).filter(|target| pages.contains_key(target)) {
+                Some(target) => Some(relative_path(page, &target)),
+                None => urls::external_item_url(krate, id),
+            }
+        });
+        markdown.push_str(docs.trim_end());
+        markdown.push_str("\n\n");
+    }
+
+    if item.kind == EntryKind::Module {
+        let prefix = format!("{}::", item.path);
+        let mut children: Vec<&ListItem> = items
+            .iter()
+            .filter(|child| {
+                child
+                    .path
+                    .strip_prefix(&prefix)
+                    .is_some_and(|name| !name.contains("::"))
+            })
+            .collect();
+        children.sort_by(|a, b| (a.kind, &a.path).cmp(&(b.kind, &b.path)));
+        if !children.is_empty() {
+            markdown.push_str("## Items\n\n");
+        }
+        for child in children {
+            let name = &child.path[prefix.len()..];
+            let entry = match page_of.get(&child.id) {
+                Some(target) => format!(
+                    "[`{} {name}`]({})",
+                    child.kind.keyword(),
+                    relative_path(page, target)
+                ),
+                None => format!("`{} {name}`", child.kind.keyword()),
+            };
+            match &child.summary {
+                Some(summary) => markdown.push_str(&format!("- {entry}: {summary}\n")),
+                None => markdown.push_str(&format!("- {entry}\n")),
+            }
+        }
+    }
+    Ok(format!("{}\n", markdown.trim_end()))
+}
+
+/// The path of the file `to` relative to the directory of the file `from`,
+/// both relative to the output directory.
+fn relative_path(from: &str, to: &str) -> String {
+    let from_dir: Vec<&str> = from.split('/').collect();
+    let from_dir = &from_dir[..from_dir.len() - 1];
+    let to: Vec<&str> = to.split('/').collect();
+    let common = from_dir.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    let mut segments = vec![".."; from_dir.len() - common];
+    segments.extend(&to[common..]);
+    segments.join("/")
+}
+
+impl Export {
+    /// Render a line saying how many files were written where.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let header = format!(
+            "// wrote {} pages of {} {} to {}",
+            self.pages,
+            self.crate_name,
+            self.version,
+            self.dir.display()
+        );
+        output.push_str(&format!("{}\n", header.bright_black()));
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_relative_path() {
+        assert_eq!(
+            relative_path("tokio/sync/struct.Mutex.md", "tokio/sync/index.md"),
+            "index.md"
+        );
+        assert_eq!(
+            relative_path("tokio/sync/struct.Mutex.md", "tokio/task/fn.spawn.md"),
+            "../task/fn.spawn.md"
+        );
+        assert_eq!(
+            relative_path("tokio/index.md", "tokio/sync/struct.Mutex.md"),
+            "sync/struct.Mutex.md"
+        );
+    }
+}
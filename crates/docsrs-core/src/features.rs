@@ -0,0 +1,221 @@
+//! The features of a crate and the public items each one unlocks, see
+//! `docsrs <crate> --features-list`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use rustdoc_types::Attribute;
+
+use crate::list::{ListItem, list_items, render_rows};
+use crate::registry_index;
+use crate::source::qualified_name;
+use crate::version_resolver::VersionResolver;
+use crate::{Query, Resolution, StyleConfig, resolve_docs};
+
+/// A feature of a crate.
+#[derive(Clone)]
+pub struct Feature {
+    pub name: String,
+    /// Features and dependencies it enables, as in `Cargo.toml`
+    pub enables: Vec<String>,
+    /// Public items only compiled with the feature, as far as their `cfg`
+    /// attributes in the docs say
+    pub items: Vec<ListItem>,
+}
+
+/// The features of a version of a crate.
+pub struct FeatureList {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub version: String,
+    /// The `default` feature first, then the others by name
+    pub features: Vec<Feature>,
+}
+
+/// List the features of the crate in `args`, from its `Cargo.toml` for
+/// crates of the workspace and from the crates.io index for others.
+pub fn list_features(args: &Query) -> Result<FeatureList> {
+    let mut resolution = Resolution::default();
+    let doc = resolve_docs(&args.spec, &mut resolution)?.load_doc(args.no_cache, &*args.events)?;
+    let version = doc
+        .crate_data()
+        .crate_version
+        .clone()
+        .context("The docs don't say which version of the crate they're for")?;
+
+    let declared = match &resolution.local {
+        Some(local) => VersionResolver::new()?
+            .package_features(&local.package, &version)
+            .with_context(|| format!("{} isn't in the workspace", local.package))?,
+        None => {
+            let name = qualified_name(resolution.registry.as_deref(), &args.spec.original_name);
+            registry_index::entry(&name, &version)?.features()
+        }
+    };
+
+    let mut features: BTreeMap<String, Feature> = declared
+        .into_iter()
+        .map(|(name, enables)| {
+            let feature = Feature {
+                name: name.clone(),
+                enables,
+                items: Vec::new(),
+            };
+            (name, feature)
+        })
+        .collect();
+    for item in list_items(&doc) {
+        let Some(attrs) = doc.crate_data().index.get(&item.id).map(|item| &item.attrs) else {
+            continue;
+        };
+        for name in required_features(attrs) {
+            features
+                .entry(name.clone())
+                .or_insert_with(|| Feature {
+                    name,
+                    enables: Vec::new(),
+                    items: Vec::new(),
+                })
+                .items
+                .push(item.clone());
+        }
+    }
+
+    let mut features: Vec<Feature> = features.into_values().collect();
+    features.sort_by_key(|feature| feature.name != "default");
+    for feature in &mut features {
+        feature.items.sort_by(|a, b| a.path.cmp(&b.path));
+    }
+    Ok(FeatureList {
+        resolution,
+        crate_name: args.spec.name.clone(),
+        version,
+        features,
+    })
+}
+
+/// Features the `cfg` attributes of an item require, leaving out the ones
+/// it requires to be off, as in `not(feature = "std")`.
+fn required_features(attrs: &[Attribute]) -> BTreeSet<String> {
+    attrs
+        .iter()
+        .filter_map(|attr| match attr {
+            // `#[cfg(...)]` and `#[doc(cfg(...))]`, or `CfgTrace` as rustc
+            // prints them
+            Attribute::Other(attr) if attr.contains("cfg") || attr.contains("Cfg") => Some(attr),
+            _ => None,
+        })
+        .flat_map(|attr| features_in(attr))
+        .collect()
+}
+
+/// The features named in a `cfg` attribute, either in source form, as
+/// `feature = "std"`, or as rustc prints it, as `name: "feature", value:
+/// Some("std")`.
+fn features_in(cfg: &str) -> Vec<String> {
+    const PATTERNS: [&str; 2] = ["feature = \"", "name: \"feature\", value: Some(\""];
+
+    let mut features = Vec::new();
+    let mut depth = 0;
+    // Depths inside a `not(...)`
+    let mut negated: Vec<usize> = Vec::new();
+    let mut rest = cfg;
+    while let Some(c) = rest.chars().next() {
+        if let Some(pattern) = PATTERNS.iter().find(|pattern| rest.starts_with(**pattern)) {
+            let value = &rest[pattern.len()..];
+            let Some(end) = value.find('"') else {
+                break;
+            };
+            if negated.is_empty() {
+                features.push(value[..end].to_string());
+            }
+            rest = &value[end + 1..];
+            continue;
+        }
+        if rest.starts_with("not(") || rest.starts_with("Not(") {
+            negated.push(depth + 1);
+        }
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                if negated.last() == Some(&depth) {
+                    negated.pop();
+                }
+                depth = depth.saturating_sub(1);
+            }
+            _ => {}
+        }
+        rest = &rest[c.len_utf8()..];
+    }
+    features
+}
+
+impl FeatureList {
+    /// Render the features as in `Cargo.toml`, each followed by the items it
+    /// unlocks.
+    pub fn render(&self, style: &StyleConfig) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let header = match self.features.len() {
+            0 => format!("// {} {} has no features", self.crate_name, self.version),
+            1 => format!("// 1 feature of {} {}", self.crate_name, self.version),
+            count => format!(
+                "// {count} features of {} {}",
+                self.crate_name, self.version
+            ),
+        };
+        output.push_str(&format!("{}\n", header.bright_black()));
+
+        for feature in &self.features {
+            let enables: Vec<String> = feature
+                .enables
+                .iter()
+                .map(|enabled| format!("{enabled:?}"))
+                .collect();
+            output.push_str(&format!("\n{} = [{}]", feature.name, enables.join(", ")));
+            let rows = feature
+                .items
+                .iter()
+                .map(|item| (item.as_output(), item.summary.as_deref()))
+                .collect();
+            for line in render_rows(
+                rows,
+                "    ",
+                &style.colorizer,
+                style.summaries,
+                style.max_width,
+            ) {
+                output.push_str(&format!("\n{line}"));
+            }
+        }
+        output.push('\n');
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_features_in_source_form() {
+        assert_eq!(features_in(r#"#[doc(cfg(feature = "std"))]"#), ["std"]);
+        assert_eq!(
+            features_in(r#"#[cfg(any(feature = "rt", feature = "net"))]"#),
+            ["rt", "net"]
+        );
+        assert_eq!(
+            features_in(r#"#[cfg(all(feature = "rt", not(feature = "std")))]"#),
+            ["rt"]
+        );
+    }
+
+    #[test]
+    fn test_features_in_printed_form() {
+        let cfg = r#"#[attr = CfgTrace([All([NameValue { name: "feature", value: Some("a"), span: src/lib.rs:13:11: 13:24 (#0) }, Not(NameValue { name: "feature", value: Some("b"), span: src/lib.rs:13:30: 13:43 (#0) }, src/lib.rs:13:29: 13:44 (#0))], src/lib.rs:13:10: 13:45 (#0))])]"#;
+        assert_eq!(features_in(cfg), ["a"]);
+    }
+}
@@ -0,0 +1,220 @@
+//! Graphs of the structure of a crate, see `docsrs <crate> --graph modules`.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use anyhow::{Context, Result};
+use rustdoc_types::{Crate, Id, ItemEnum, ItemKind};
+
+use crate::cli::GraphFormat;
+use crate::list::{EntryKind, list_items};
+use crate::{Query, Resolution, resolve_docs};
+
+/// Most item names an edge of re-exports is labelled with.
+const MAX_LABEL_NAMES: usize = 4;
+
+/// The modules of a crate, with the modules it re-exports items from.
+pub struct ModuleGraph {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub version: String,
+    /// Paths of the public modules, the crate root first
+    pub modules: Vec<String>,
+    /// Paths of the private modules and those of other crates that items
+    /// are re-exported from
+    pub sources: Vec<String>,
+    /// Parent and child module
+    pub children: Vec<(String, String)>,
+    /// Module with `pub use`s, module the items are defined in, and the
+    /// items, `*` for glob imports
+    pub reexports: Vec<(String, String, Vec<String>)>,
+}
+
+/// The module graph of the crate in `args`: the public modules and their
+/// children, and the `pub use`s of the modules.
+pub fn module_graph(args: &Query) -> Result<ModuleGraph> {
+    let mut resolution = Resolution::default();
+    let doc = resolve_docs(&args.spec, &mut resolution)?.load_doc(args.no_cache, &*args.events)?;
+    let krate = doc.crate_data();
+    let version = krate
+        .crate_version
+        .clone()
+        .context("The docs don't say which version of the crate they're for")?;
+
+    let public: BTreeMap<String, Id> = list_items(&doc)
+        .into_iter()
+        .filter(|item| item.kind == EntryKind::Module)
+        .map(|item| (item.path, item.id))
+        .collect();
+
+    let mut children = Vec::new();
+    let mut reexports: BTreeMap<(String, String), Vec<String>> = BTreeMap::new();
+    for (path, id) in &public {
+        if let Some((parent, _)) = path.rsplit_once("::")
+            && public.contains_key(parent)
+        {
+            children.push((parent.to_string(), path.clone()));
+        }
+        for (source, name) in module_reexports(krate, id) {
+            if source != *path {
+                reexports
+                    .entry((path.clone(), source))
+                    .or_default()
+                    .push(name);
+            }
+        }
+    }
+
+    let sources: BTreeSet<String> = reexports
+        .keys()
+        .map(|(_, source)| source.clone())
+        .filter(|source| !public.contains_key(source))
+        .collect();
+    Ok(ModuleGraph {
+        resolution,
+        crate_name: args.spec.name.clone(),
+        version,
+        // The crate root sorts first, being a prefix of the other paths
+        modules: public.into_keys().collect(),
+        sources: sources.into_iter().collect(),
+        children,
+        reexports: reexports
+            .into_iter()
+            .map(|((module, source), names)| (module, source, names))
+            .collect(),
+    })
+}
+
+/// The `pub use`s of the module with `id`, as the module the item is
+/// defined in and the name it's re-exported as.
+fn module_reexports(krate: &Crate, id: &Id) -> Vec<(String, String)> {
+    let Some(ItemEnum::Module(module)) = krate.index.get(id).map(|item| &item.inner) else {
+        return Vec::new();
+    };
+    module
+        .items
+        .iter()
+        .filter_map(|item_id| match &krate.index.get(item_id)?.inner {
+            ItemEnum::Use(use_) => Some(use_),
+            _ => None,
+        })
+        .filter_map(|use_| {
+            let summary = krate.paths.get(use_.id.as_ref()?)?;
+            let (name, parents) = summary.path.split_last()?;
+            if !use_.is_glob {
+                return Some((parents.join("::"), use_.name.clone()));
+            }
+            // `pub use module::*` re-exports from the module, and
+            // `pub use Enum::*` the variants of the enum
+            Some(match summary.kind {
+                ItemKind::Module => (summary.path.join("::"), "*".to_string()),
+                _ => (parents.join("::"), format!("{name}::*")),
+            })
+        })
+        .collect()
+}
+
+impl ModuleGraph {
+    /// Render the graph as Graphviz DOT or a Mermaid flowchart, with the
+    /// modules that items are re-exported from dashed.
+    pub fn render(&self, format: GraphFormat) -> String {
+        match format {
+            GraphFormat::Mermaid => self.render_mermaid(),
+            GraphFormat::Dot => self.render_dot(),
+        }
+    }
+
+    fn header(&self) -> Vec<String> {
+        let mut header: Vec<String> = self.resolution.message.iter().cloned().collect();
+        header.push(format!(
+            "modules of {} {}, and where their re-exports come from (dashed)",
+            self.crate_name, self.version
+        ));
+        header
+    }
+
+    fn render_dot(&self) -> String {
+        let mut output = String::new();
+        for line in self.header() {
+            output.push_str(&format!("// {line}\n"));
+        }
+        output.push_str(&format!("digraph \"{}\" {{\n", self.crate_name));
+        output.push_str("    rankdir=LR;\n");
+        output.push_str("    node [shape=box, fontname=\"monospace\"];\n");
+        for module in &self.modules {
+            output.push_str(&format!("    \"{module}\";\n"));
+        }
+        for source in &self.sources {
+            output.push_str(&format!("    \"{source}\" [style=dashed];\n"));
+        }
+        for (parent, child) in &self.children {
+            output.push_str(&format!("    \"{parent}\" -> \"{child}\";\n"));
+        }
+        for (module, source, names) in &self.reexports {
+            output.push_str(&format!(
+                "    \"{module}\" -> \"{source}\" [style=dashed, label=\"{}\"];\n",
+                label(names)
+            ));
+        }
+        output.push_str("}\n");
+        output
+    }
+
+    fn render_mermaid(&self) -> String {
+        let mut output = String::new();
+        for line in self.header() {
+            output.push_str(&format!("%% {line}\n"));
+        }
+        output.push_str("flowchart LR\n");
+        // Mermaid ids can't have `::` in them, so nodes are numbered
+        let ids: BTreeMap<&str, String> = self
+            .modules
+            .iter()
+            .chain(&self.sources)
+            .enumerate()
+            .map(|(i, path)| (path.as_str(), format!("m{i}")))
+            .collect();
+        for path in self.modules.iter().chain(&self.sources) {
+            output.push_str(&format!("    {}[\"{path}\"]\n", ids[path.as_str()]));
+        }
+        for (parent, child) in &self.children {
+            output.push_str(&format!(
+                "    {} --> {}\n",
+                ids[parent.as_str()],
+                ids[child.as_str()]
+            ));
+        }
+        for (module, source, names) in &self.reexports {
+            output.push_str(&format!(
+                "    {} -.->|\"{}\"| {}\n",
+                ids[module.as_str()],
+                label(names),
+                ids[source.as_str()]
+            ));
+        }
+        if !self.sources.is_empty() {
+            let sources: Vec<&str> = self
+                .sources
+                .iter()
+                .map(|source| ids[source.as_str()].as_str())
+                .collect();
+            output.push_str("    classDef source stroke-dasharray: 5 5\n");
+            output.push_str(&format!("    class {} source\n", sources.join(",")));
+        }
+        output
+    }
+}
+
+/// The names of re-exported items, the first few of them when there are
+/// many.
+fn label(names: &[String]) -> String {
+    let mut label = names
+        .iter()
+        .take(MAX_LABEL_NAMES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if names.len() > MAX_LABEL_NAMES {
+        label.push_str(&format!(" +{} more", names.len() - MAX_LABEL_NAMES));
+    }
+    label
+}
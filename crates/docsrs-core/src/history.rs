@@ -0,0 +1,235 @@
+//! Recent queries, see `docsrs history` and `docsrs !!`.
+
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use directories::ProjectDirs;
+use serde::{Deserialize, Serialize};
+
+use crate::query::Query;
+use crate::result::QueryResult;
+
+/// Most queries kept, older ones are dropped.
+const MAX_ENTRIES: usize = 100;
+
+/// A query that was answered, and the item it showed.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub(crate) struct HistoryEntry {
+    /// The crate spec and filter, as given
+    pub query: Vec<String>,
+    /// Spec of the item whose docs were shown, when the query doesn't lead
+    /// to it by itself: a search, or an item picked with `--pick`
+    pub item: Option<String>,
+}
+
+impl HistoryEntry {
+    /// The entry for `args` answered with `result`, which the user picked
+    /// among the matches of `args` when `picked`.
+    pub(crate) fn new(args: &Query, result: &QueryResult, picked: bool) -> Self {
+        let item = match result {
+            QueryResult::ItemDoc(item) if args.lists_items() || picked => {
+                let spec = args.spec.item(&item.path, item.kind);
+                spec.map(|spec| spec.to_string())
+            }
+            _ => None,
+        };
+        Self {
+            item,
+            ..Self::listed(args)
+        }
+    }
+
+    /// The entry for `args` answered with the items it lists.
+    pub(crate) fn listed(args: &Query) -> Self {
+        Self {
+            query: std::iter::once(args.spec.to_string())
+                .chain(args.filter.clone())
+                .collect(),
+            item: None,
+        }
+    }
+
+    /// Arguments re-running the entry: the item it showed, or else the query.
+    pub(crate) fn args(&self) -> Vec<String> {
+        match &self.item {
+            Some(item) => vec![item.clone()],
+            None => self.query.clone(),
+        }
+    }
+}
+
+/// The history file: `$DOCSRS_HISTORY` when set, none when it's empty, and
+/// otherwise `history.jsonl` in the data directory.
+pub(crate) fn history_path() -> Result<Option<PathBuf>> {
+    if let Some(path) = std::env::var_os("DOCSRS_HISTORY") {
+        return Ok((!path.is_empty()).then(|| PathBuf::from(path)));
+    }
+    let proj_dirs =
+        ProjectDirs::from("", "", "docsrs").context("Failed to determine data directory")?;
+    Ok(Some(proj_dirs.data_dir().join("history.jsonl")))
+}
+
+/// The entries in the history file at `path`, oldest first. Lines that
+/// don't parse, e.g. written by another version, are skipped.
+pub(crate) fn load(path: &Path) -> Result<Vec<HistoryEntry>> {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => {
+            return Err(e).with_context(|| format!("Failed to read history {}", path.display()));
+        }
+    };
+    Ok(contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// Append `entry` to the history file at `path`, unless it repeats the last
+/// entry, keeping the newest [`MAX_ENTRIES`].
+pub(crate) fn record(path: &Path, entry: HistoryEntry) -> Result<()> {
+    let mut entries = load(path)?;
+    if entries.last() == Some(&entry) {
+        return Ok(());
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .with_context(|| format!("Failed to create {}", parent.display()))?;
+    }
+
+    let line = serde_json::to_string(&entry)?;
+    if entries.len() < MAX_ENTRIES {
+        let mut file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .with_context(|| format!("Failed to open history {}", path.display()))?;
+        writeln!(file, "{line}")?;
+        return Ok(());
+    }
+
+    entries.push(entry);
+    let mut contents = String::new();
+    for entry in &entries[entries.len() - MAX_ENTRIES..] {
+        contents.push_str(&serde_json::to_string(entry)?);
+        contents.push('\n');
+    }
+    fs::write(path, contents).with_context(|| format!("Failed to write history {}", path.display()))
+}
+
+/// Forget every query by removing the history file at `path`.
+pub(crate) fn clear(path: &Path) -> Result<()> {
+    match fs::remove_file(path) {
+        Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+            Err(e).with_context(|| format!("Failed to clear history {}", path.display()))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// The entry a `!!`, `!N` or `!-N` argument refers to: the last query, query
+/// `N` as numbered by `docsrs history`, or the `N`th last query. None when
+/// `arg` isn't a history reference.
+pub(crate) fn lookup<'a>(
+    arg: &str,
+    entries: &'a [HistoryEntry],
+) -> Option<Result<&'a HistoryEntry>> {
+    let reference = arg.strip_prefix('!')?;
+    let index = if reference == "!" {
+        entries.len().checked_sub(1)
+    } else if let Some(back) = reference.strip_prefix('-') {
+        let back: usize = back.parse().ok()?;
+        entries.len().checked_sub(back).filter(|_| back > 0)
+    } else {
+        let number: usize = reference.parse().ok()?;
+        number.checked_sub(1)
+    };
+    Some(match index.and_then(|index| entries.get(index)) {
+        Some(entry) => Ok(entry),
+        None if entries.is_empty() => Err(anyhow::anyhow!("No queries in history yet")),
+        None => Err(anyhow::anyhow!(
+            "No query {arg} in history, see `docsrs history`"
+        )),
+    })
+}
+
+/// Replace the history references among `args` with the arguments of the
+/// entries they refer to. None when there are no references.
+pub(crate) fn expand(args: &[&str], entries: &[HistoryEntry]) -> Result<Option<Vec<String>>> {
+    let mut expanded = Vec::new();
+    let mut found = false;
+    for &arg in args {
+        match lookup(arg, entries) {
+            Some(entry) => {
+                expanded.extend(entry?.args());
+                found = true;
+            }
+            None => expanded.push(arg.to_string()),
+        }
+    }
+    Ok(found.then_some(expanded))
+}
+
+/// Render `entries` numbered for `!N`, oldest first, with the item each
+/// search showed.
+pub(crate) fn render(entries: &[HistoryEntry]) -> String {
+    let width = entries.len().to_string().len();
+    let mut output = String::new();
+    for (number, entry) in entries.iter().enumerate() {
+        let query = entry.query.join(" ");
+        output.push_str(&format!("{:>width$}  {}", number + 1, query));
+        if let Some(item) = &entry.item {
+            output.push_str(&format!("  -> {item}"));
+        }
+        output.push('\n');
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(query: &[&str], item: Option<&str>) -> HistoryEntry {
+        HistoryEntry {
+            query: query.iter().map(|arg| arg.to_string()).collect(),
+            item: item.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn test_expand_replaces_references() {
+        let entries = [
+            entry(&["tokio", "spawn"], Some("tokio::task::fn@spawn")),
+            entry(&["serde", "Deser"], None),
+        ];
+        let args = |args: &[&str]| expand(args, &entries).unwrap();
+        assert_eq!(
+            args(&["!!", "--tree"]),
+            Some(vec!["serde".into(), "Deser".into(), "--tree".into()])
+        );
+        assert_eq!(args(&["!1"]), Some(vec!["tokio::task::fn@spawn".into()]));
+        assert_eq!(args(&["!-2"]), args(&["!1"]));
+        assert_eq!(args(&["tokio"]), None);
+        assert!(expand(&["!3"], &entries).is_err());
+        assert!(expand(&["!!"], &[]).is_err());
+    }
+
+    #[test]
+    fn test_record_skips_repeats_and_keeps_newest() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("history.jsonl");
+        record(&path, entry(&["tokio"], Some("tokio"))).unwrap();
+        record(&path, entry(&["tokio"], Some("tokio"))).unwrap();
+        assert_eq!(load(&path).unwrap().len(), 1);
+
+        for n in 0..MAX_ENTRIES {
+            record(&path, entry(&[&format!("crate{n}")], None)).unwrap();
+        }
+        let entries = load(&path).unwrap();
+        assert_eq!(entries.len(), MAX_ENTRIES);
+        assert_eq!(entries[0], entry(&["crate0"], None));
+    }
+}
@@ -0,0 +1,42 @@
+//! Parsing rustdoc JSON, shared by every way of loading docs.
+
+use anyhow::Result;
+use rustdoc_types::Crate;
+
+/// Parse rustdoc JSON bytes into a [`Crate`]
+///
+/// Parsing works on the raw bytes, skipping the separate UTF-8 validation
+/// pass a `&str` would need. With the `simd-json` feature the bytes are
+/// parsed in place by simd-json, which is faster on large documents on
+/// CPUs with AVX2/NEON.
+pub fn parse_rustdoc_json(json_data: Vec<u8>) -> Result<Crate> {
+    #[cfg(feature = "simd-json")]
+    let krate = simd_json::serde::from_slice(&mut { json_data })?;
+    #[cfg(not(feature = "simd-json"))]
+    let krate = serde_json::from_slice(&json_data)?;
+    Ok(krate)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_rustdoc_json() {
+        let json = std::fs::read(crate::test_docs("test_items.json")).unwrap();
+        let expected: Crate = serde_json::from_str(std::str::from_utf8(&json).unwrap()).unwrap();
+
+        // Whichever parser is enabled reads the docs as serde_json does
+        let krate = parse_rustdoc_json(json).unwrap();
+        assert_eq!(krate, expected);
+        assert_eq!(krate.index[&krate.root].name.as_deref(), Some("test_items"));
+    }
+
+    #[test]
+    fn test_parse_rustdoc_json_rejects_invalid_json() {
+        assert!(parse_rustdoc_json(b"{\"root\": 0,".to_vec()).is_err());
+        assert!(parse_rustdoc_json(b"{}".to_vec()).is_err());
+        // Invalid UTF-8 is caught without a separate validation pass
+        assert!(parse_rustdoc_json(b"{\"root\": \"\xff\"}".to_vec()).is_err());
+    }
+}
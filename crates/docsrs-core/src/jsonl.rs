@@ -0,0 +1,105 @@
+//! Listed items as JSON lines, see `docsrs <crate> <filter> --format jsonl`.
+
+use std::io::{ErrorKind, Write};
+
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::crate_spec::CrateSpec;
+use crate::list::{EntryKind, ListItem, Qualifiers, doc_summary};
+use crate::result::ItemDoc;
+
+/// An item, written as one line of JSON.
+#[derive(Serialize)]
+struct Line<'a> {
+    /// Crate the item is in as `name@version`
+    #[serde(rename = "crate", skip_serializing_if = "Option::is_none")]
+    krate: Option<String>,
+    path: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<&'static str>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    qualifiers: Vec<&'static str>,
+    summary: Option<&'a str>,
+    /// Spec that looks up the item again, e.g. for its docs after picking it
+    #[serde(skip_serializing_if = "Option::is_none")]
+    handle: Option<String>,
+}
+
+impl<'a> Line<'a> {
+    /// The line of the item at `path` in the crate of `krate`, whose spec
+    /// has the version the handles are pinned to. Docs read from a file
+    /// have no crate to look their items up in again.
+    fn new(krate: Option<&CrateSpec>, path: &'a str, kind: Option<EntryKind>) -> Self {
+        Self {
+            krate: krate.map(ToString::to_string),
+            path,
+            kind: kind.map(EntryKind::keyword),
+            qualifiers: Vec::new(),
+            summary: None,
+            handle: krate
+                .and_then(|krate| krate.item(path, kind))
+                .map(|item| item.to_string()),
+        }
+    }
+}
+
+/// Write a line for each of `items` of the crate `krate` to `stream`, each
+/// flushed so that whatever reads it can start on them.
+pub(crate) fn write_items(
+    stream: &mut dyn Write,
+    krate: Option<&CrateSpec>,
+    items: &[ListItem],
+) -> Result<()> {
+    for item in items {
+        let line = Line {
+            qualifiers: keywords(item.qualifiers),
+            summary: item.summary.as_deref(),
+            ..Line::new(krate, &item.path, Some(item.kind))
+        };
+        write_line(stream, &line)?;
+    }
+    Ok(())
+}
+
+/// Write the line of the only item a search found, or that a path names.
+pub(crate) fn write_item(
+    stream: &mut dyn Write,
+    krate: Option<&CrateSpec>,
+    item: &ItemDoc,
+) -> Result<()> {
+    let summary = item.docs.as_deref().and_then(doc_summary);
+    let qualifiers = match item.doc.crate_data().index.get(&item.id) {
+        Some(found) => Qualifiers::from_item_enum(&found.inner),
+        None => Qualifiers::default(),
+    };
+    let line = Line {
+        qualifiers: keywords(qualifiers),
+        summary: summary.as_deref(),
+        ..Line::new(krate, &item.path, item.kind)
+    };
+    write_line(stream, &line)
+}
+
+/// Write `line` and flush it. A reader that stopped reading, like `head`,
+/// has all it wants, so that isn't an error.
+fn write_line(stream: &mut dyn Write, line: &Line) -> Result<()> {
+    let written =
+        writeln!(stream, "{}", serde_json::to_string(line)?).and_then(|()| stream.flush());
+    match written {
+        Err(e) if e.kind() != ErrorKind::BrokenPipe => Err(e.into()),
+        _ => Ok(()),
+    }
+}
+
+/// The keywords of the qualifiers set in `qualifiers`.
+fn keywords(qualifiers: Qualifiers) -> Vec<&'static str> {
+    [
+        (qualifiers.is_const, "const"),
+        (qualifiers.is_async, "async"),
+        (qualifiers.is_unsafe, "unsafe"),
+    ]
+    .into_iter()
+    .filter_map(|(set, keyword)| set.then_some(keyword))
+    .collect()
+}
@@ -1,147 +1,1663 @@
+#[cfg(feature = "native")]
+mod bookmarks;
 pub mod cli;
 mod color;
+#[cfg(feature = "native")]
+mod config;
 mod crate_spec;
+#[cfg(feature = "native")]
+mod crates_io;
+#[cfg(feature = "native")]
+pub mod deps;
+#[cfg(feature = "native")]
+pub mod diff;
 mod doc;
+#[cfg(feature = "native")]
 mod docfetch;
+mod events;
+#[cfg(feature = "native")]
+pub mod examples;
+#[cfg(feature = "native")]
+pub mod export;
+#[cfg(feature = "native")]
+pub mod features;
+#[cfg(feature = "native")]
+pub mod graph;
+#[cfg(feature = "native")]
+mod history;
+mod json;
+#[cfg(feature = "native")]
+mod jsonl;
+#[cfg(feature = "native")]
+pub mod link_check;
 mod list;
+#[cfg(feature = "native")]
+mod man;
+#[cfg(feature = "native")]
+pub mod matrix;
+#[cfg(feature = "async")]
+pub mod nonblocking;
+mod query;
+mod reexport;
+#[cfg(feature = "native")]
+mod registry_index;
+mod result;
+mod search_index;
+#[cfg(feature = "native")]
+pub mod semver;
+#[cfg(feature = "native")]
 pub mod skill;
+#[cfg(feature = "native")]
+mod source;
+#[cfg(feature = "native")]
+pub mod stats;
+mod suggest;
+mod timings;
+#[cfg(feature = "tui")]
+mod tui;
+mod urls;
 mod util;
+#[cfg(feature = "native")]
 mod version_resolver;
+#[cfg(feature = "native")]
+mod viewed;
+#[cfg(feature = "native")]
+mod warm;
+#[cfg(feature = "native")]
+pub mod workspace;
 
-use clap::Parser;
+use std::collections::BTreeSet;
+#[cfg(feature = "native")]
+use std::sync::Arc;
+#[cfg(feature = "native")]
+use std::time::Instant;
+
+use anyhow::bail;
+#[cfg(feature = "native")]
+use bookmarks::Bookmarks;
+#[cfg(feature = "native")]
+use cargo_metadata::semver::Version;
+#[cfg(feature = "native")]
+use clap::{CommandFactory, FromArgMatches};
+#[cfg(feature = "native")]
 use cli::Cli;
-use colored::Colorize;
-use docfetch::{BuildLocalDocsResult, build_local_docs, clear_cache, fetch_docs};
+#[cfg(feature = "native")]
+use config::Config;
+#[cfg(feature = "native")]
+use docfetch::{
+    BuildLocalDocsResult, build_local_docs, build_local_html, clear_cache, load_local_docs,
+    recompress_cache,
+};
+#[cfg(feature = "native")]
+use history::HistoryEntry;
 use jsondoc::JsonDoc;
-use version_resolver::VersionResolver;
+use rustdoc_types::{Crate, Id};
+#[cfg(feature = "native")]
+use source::{default_registry, qualified_name, split_qualified_name};
+#[cfg(feature = "native")]
+use version_resolver::{VersionResolver, pinned_version};
+
+use crate::crate_spec::CrateSpec;
+use crate::reexport::ExternalReexport;
+use crate::search_index::SearchIndex;
+use crate::timings::Phase;
+#[cfg(feature = "native")]
+use crate::util::{
+    copy_to_clipboard, format_size, normalize_crate_name, open_in_browser, run_pager,
+};
+#[cfg(feature = "native")]
+use crate::warm::{WarmOutcome, warm_cache};
+
+use crate::list::list_items;
 
-use crate::list::{EntryKind, ListItem, list_items};
+pub use crate::crate_spec::set_crate_aliases;
+#[cfg(feature = "native")]
+pub use crate::docfetch::fetch_docs;
+pub use crate::events::{EventSink, NoEvents, StderrEvents};
+pub use crate::json::parse_rustdoc_json;
+pub use crate::list::{EntryKind, ListItem, Qualifiers};
+pub use crate::query::Query;
+pub use crate::result::{
+    CrateInfo, ItemDoc, LocalCrate, MatchKind, MatchList, QueryResult, Reexport, Resolution,
+    StyleConfig,
+};
+#[cfg(feature = "native")]
+pub use crate::source::{
+    DirSource, DocSource, DocsNotFound, HttpSource, Registry, Token, set_default_registry,
+    set_doc_source, set_registries, set_target,
+};
+pub use rustdoc_fmt::{DocLink, DocLinkKind};
 
 /// Run the CLI with the given arguments and return the output as a string.
 ///
+/// The settings its flags and the config file give, like `--mirror` or
+/// `offline = true`, only apply to this call, which waits for other calls to
+/// finish.
+///
 /// # Arguments
 /// * `args` - Command line arguments (excluding program name)
 ///
 /// # Returns
 /// * `Ok(String)` - Successful output (stdout)
 /// * `Err(String)` - Error message (stderr)
+#[cfg(feature = "native")]
 pub fn run_cli(args: &[&str]) -> Result<String, String> {
-    match run_cli_impl(args) {
-        Ok(output) => Ok(output),
-        Err(e) => {
-            // Format the full error chain so root causes aren't lost
-            let mut msg = e.to_string();
-            for cause in e.chain().skip(1) {
-                let cause_str = cause.to_string();
-                if !msg.contains(&cause_str) {
-                    msg.push_str(&format!(": {}", cause_str));
-                }
-            }
-            Err(msg)
+    let mut streamed = Vec::new();
+    let output = run_cli_to(args, &mut streamed, false)?;
+    Ok(String::from_utf8_lossy(&streamed).into_owned() + &output)
+}
+
+/// Like [`run_cli`], but output written bit by bit as it's found, the lines
+/// of `--format jsonl`, goes to `stream` instead of the returned string.
+/// Queries are remembered for `docsrs history`, and arguments like `!!`
+/// re-run them, when `record_history`, which only the command line tools
+/// ask for, leaving the history to the queries the user typed.
+#[cfg(feature = "native")]
+pub fn run_cli_to(
+    args: &[&str],
+    stream: &mut dyn std::io::Write,
+    record_history: bool,
+) -> Result<String, String> {
+    run_cli_impl(args, stream, record_history).map_err(|e| error_message(&e))
+}
+
+/// The message of `e` with its causes, as [`run_cli`] reports errors, so
+/// root causes aren't lost.
+pub fn error_message(e: &anyhow::Error) -> String {
+    let mut msg = e.to_string();
+    for cause in e.chain().skip(1) {
+        let cause_str = cause.to_string();
+        if !msg.contains(&cause_str) {
+            msg.push_str(&format!(": {}", cause_str));
         }
     }
+    msg
 }
 
-fn run_cli_impl(args: &[&str]) -> anyhow::Result<String> {
-    let mut output = String::new();
+/// Held while a [`run_cli`] call runs, as they share process-wide settings.
+#[cfg(feature = "native")]
+static CLI_RUNNING: std::sync::Mutex<()> = std::sync::Mutex::new(());
 
-    // Parse arguments using the Cli::try_parse_from method
-    let parsed_args =
-        match Cli::try_parse_from(std::iter::once("docsrs").chain(args.iter().copied())) {
-            Ok(args) => args,
-            Err(e) => {
-                // Handle --help and --version as successful outputs
-                if e.kind() == clap::error::ErrorKind::DisplayHelp
-                    || e.kind() == clap::error::ErrorKind::DisplayVersion
-                {
-                    return Ok(e.to_string());
-                }
-                return Err(e.into());
+/// The process-wide settings a [`run_cli`] call takes from its flags and the
+/// config file, put back as they were before it, e.g. set by
+/// [`set_doc_source`], when this is dropped.
+#[cfg(feature = "native")]
+struct CliSettings {
+    colorize: bool,
+    aliases: std::collections::BTreeMap<String, String>,
+    pinned_versions: std::collections::BTreeMap<String, String>,
+    compression_level: i32,
+    offline: bool,
+    build_fallback: bool,
+    update_check: bool,
+    cache_dir: Option<std::path::PathBuf>,
+    doc_source: Option<Arc<dyn DocSource>>,
+    target: Option<String>,
+    registries: Vec<Registry>,
+    default_registry: Option<String>,
+    _running: std::sync::MutexGuard<'static, ()>,
+}
+
+#[cfg(feature = "native")]
+impl CliSettings {
+    /// Wait for other calls to finish, then remember the settings.
+    fn save() -> Self {
+        let running = CLI_RUNNING
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner);
+        Self {
+            colorize: colored::control::SHOULD_COLORIZE.should_colorize(),
+            aliases: crate_spec::crate_aliases(),
+            pinned_versions: version_resolver::pinned_versions(),
+            compression_level: docfetch::compression_level(),
+            offline: docfetch::is_offline(),
+            build_fallback: docfetch::build_fallback(),
+            update_check: registry_index::update_check(),
+            cache_dir: docfetch::cache_dir_override(),
+            doc_source: source::doc_source_override(),
+            target: source::target(),
+            registries: source::registries(),
+            default_registry: default_registry(),
+            _running: running,
+        }
+    }
+}
+
+#[cfg(feature = "native")]
+impl Drop for CliSettings {
+    fn drop(&mut self) {
+        colored::control::set_override(self.colorize);
+        set_crate_aliases(&self.aliases);
+        version_resolver::set_pinned_versions(&self.pinned_versions);
+        docfetch::set_compression_level(self.compression_level);
+        docfetch::set_offline(self.offline);
+        docfetch::set_build_fallback(self.build_fallback);
+        registry_index::set_update_check(self.update_check);
+        docfetch::replace_cache_dir(self.cache_dir.take());
+        source::replace_doc_source(self.doc_source.take());
+        set_target(self.target.take());
+        set_registries(std::mem::take(&mut self.registries));
+        set_default_registry(self.default_registry.take());
+    }
+}
+
+#[cfg(feature = "native")]
+fn run_cli_impl(
+    args: &[&str],
+    stream: &mut dyn std::io::Write,
+    record_history: bool,
+) -> anyhow::Result<String> {
+    let started = Instant::now();
+    let _settings = CliSettings::save();
+
+    // Replace `!!` and `!N` with the queries they refer to, only for the
+    // command line tools recording the history, where `!!` can't be a crate
+    let expanded = if record_history {
+        expand_history(args)?
+    } else {
+        None
+    };
+    if let Some(expanded) = &expanded {
+        StderrEvents.on_history_expanded(expanded);
+    }
+    let args: Vec<&str> = match &expanded {
+        Some(expanded) => expanded.iter().map(String::as_str).collect(),
+        None => args.to_vec(),
+    };
+
+    // The config comes first, as crate aliases are expanded while parsing
+    let config = Config::load()?;
+    set_crate_aliases(&config.aliases);
+
+    // Parse the arguments, keeping the matches to tell which flags were given
+    let matches = match Cli::command()
+        .try_get_matches_from(std::iter::once("docsrs").chain(args.iter().copied()))
+    {
+        Ok(matches) => matches,
+        Err(e) => {
+            // Handle --help and --version as successful outputs
+            if e.kind() == clap::error::ErrorKind::DisplayHelp
+                || e.kind() == clap::error::ErrorKind::DisplayVersion
+            {
+                return Ok(e.to_string());
             }
-        };
+            return Err(e.into());
+        }
+    };
+    let mut parsed_args = Cli::from_arg_matches(&matches)?;
+
+    // Flags that weren't given default to the config file
+    config.apply(&mut parsed_args, &matches)?;
+    let pager = config.pager(&parsed_args);
+
+    timings::reset(parsed_args.timings);
 
-    // Apply global color override based on --color flag
-    match parsed_args.color {
-        color::Color::Never => colored::control::set_override(false),
-        color::Color::Always => colored::control::set_override(true),
-        color::Color::Auto => {} // colored handles auto-detection
+    // Resolve --color (and CLICOLOR/NO_COLOR for auto) once, so every styling
+    // path, including syntax highlighting, follows the same decision
+    colored::control::set_override(parsed_args.color.should_colorize());
+
+    // Build the colorizer up front so a bad theme path fails before any download
+    let style = timings::time(Phase::Setup, || StyleConfig::from_cli(&parsed_args))?;
+
+    apply_settings(&parsed_args, &config)?;
+
+    let output = run_command(parsed_args, &config, &style, stream, record_history)?;
+
+    // Report the timings before the pager takes over the terminal
+    if let Some(report) = timings::finish(started.elapsed()) {
+        eprint!("{}", report);
+    }
+    match output {
+        Output::Plain(output) => Ok(output),
+        Output::Paged(output) => page(output, pager),
+    }
+}
+
+/// Set the process-wide settings `args` and `config` give, for a call
+/// holding [`CliSettings`].
+#[cfg(feature = "native")]
+fn apply_settings(args: &Cli, config: &Config) -> anyhow::Result<()> {
+    docfetch::set_compression_level(args.compression_level);
+    docfetch::set_offline(args.offline);
+    docfetch::set_build_fallback(args.build_fallback);
+    registry_index::set_update_check(config.update_check.unwrap_or(true));
+    if let Some(cache_dir) = &args.cache_dir {
+        docfetch::set_cache_dir(cache_dir);
+    }
+    if let Some(mirror) = &args.mirror {
+        let mut source = HttpSource::new(mirror.as_str());
+        if let Ok(token) = std::env::var("DOCSRS_MIRROR_TOKEN") {
+            source = source.with_token(Token::new(token));
+        }
+        set_doc_source(Arc::new(source));
+    }
+    set_target(args.target.clone());
+    set_registries(config.registries());
+    if let Some(registry) = &args.registry {
+        // Fail before anything is resolved against a registry that isn't set up
+        source::registry(registry)?;
     }
+    set_default_registry(args.registry.clone());
+    version_resolver::set_pinned_versions(&config.versions);
+    Ok(())
+}
+
+/// Look up `spec`, searching for `filter` under it, as `docsrs -- SPEC
+/// FILTER` does: with the aliases, versions, registries and other settings
+/// of the config file, but never running a subcommand. The docs are
+/// rendered without colors, for hosts like the MCP server passing them on
+/// as text.
+#[cfg(feature = "native")]
+pub fn lookup(spec: &str, filter: Option<&str>) -> anyhow::Result<String> {
+    let _settings = CliSettings::save();
+    let config = Config::load()?;
+    set_crate_aliases(&config.aliases);
+    let args = ["docsrs", "--", spec].into_iter().chain(filter);
+    let matches = Cli::command().try_get_matches_from(args)?;
+    let mut args = Cli::from_arg_matches(&matches)?;
+    config.apply(&mut args, &matches)?;
+    colored::control::set_override(false);
+    let style = StyleConfig::from_cli(&args)?;
+    apply_settings(&args, &config)?;
+
+    let result = query(&Query::from_cli(&args)?.events(Arc::new(NoEvents)))?;
+    result.render(&style)
+}
+
+/// The output of a command, printed once the timings are reported.
+#[cfg(feature = "native")]
+enum Output {
+    /// Printed as is, e.g. for scripts or other tools to read
+    Plain(String),
+    /// Shown in the pager, if there is one and stdout is a terminal
+    Paged(String),
+}
 
+/// Run the subcommand, or the mode given by the flags, that `args` ask for.
+#[cfg(feature = "native")]
+fn run_command(
+    mut args: Cli,
+    config: &Config,
+    style: &StyleConfig,
+    stream: &mut dyn std::io::Write,
+    record_history: bool,
+) -> anyhow::Result<Output> {
     // Handle --clear-cache flag
-    if parsed_args.clear_cache {
+    if args.clear_cache {
         clear_cache()?;
-        output.push_str("Cache cleared successfully\n");
-        return Ok(output);
+        return Ok(Output::Plain("Cache cleared successfully\n".to_string()));
+    }
+
+    // Handle --recompress-cache: shrink entries cached at a lower level
+    if args.recompress_cache {
+        let summary = recompress_cache()?;
+        return Ok(Output::Plain(format!(
+            "Recompressed {} of {} cached docs ({} -> {})\n",
+            summary.recompressed,
+            summary.entries,
+            format_size(summary.size_before),
+            format_size(summary.size_after)
+        )));
+    }
+
+    // Handle --warm-cache: fetch the docs of every dependency of the project,
+    // and the extra crates the config lists
+    if args.warm_cache {
+        return warm(&args, config).map(Output::Plain);
     }
 
     // Handle --print-skill: emit the bundled SKILL.md and exit.
-    if parsed_args.print_skill {
-        return Ok(skill::SKILL_MD.to_string());
+    if args.print_skill {
+        return Ok(Output::Plain(skill::SKILL_MD.to_string()));
     }
 
     // Handle --install-skill: write the bundled SKILL.md into Claude Code's
     // discovery path for the requested scope.
-    if parsed_args.install_skill {
-        let target_dir = skill::resolve_scope_dir(parsed_args.scope)?;
-        let outcome = skill::install_skill(&target_dir, parsed_args.force)?;
-        let target_file = target_dir.join("SKILL.md");
-        match outcome {
-            skill::InstallOutcome::Written => {
-                output.push_str(&format!("Installed {}\n", target_file.display()));
+    if args.install_skill {
+        return install_skill(&args).map(Output::Plain);
+    }
+
+    if let Some(command) = &args.command {
+        return run_subcommand(command, &args, style);
+    }
+
+    // Handle --workspace: search the docs of every dependency at once
+    if let Some(filter) = &args.workspace {
+        return search_workspace(filter, &args, style, stream);
+    }
+
+    // Handle --features-list: the crate's features and what they unlock
+    if args.features_list {
+        let list = features::list_features(&Query::from_cli(&args)?)?;
+        return Ok(Output::Paged(list.render(style)));
+    }
+
+    // Handle --check-links: the intra-doc links of a workspace crate that
+    // don't resolve
+    if args.check_links {
+        let check = link_check::check_links(&Query::from_cli(&args)?)?;
+        return Ok(Output::Paged(check.render()));
+    }
+
+    // Handle --stats: counts of the items of the crate's public API
+    if args.stats {
+        let stats = stats::api_stats(&Query::from_cli(&args)?)?;
+        return Ok(Output::Paged(stats.render()));
+    }
+
+    // Handle --what-changed: the API changes since the version viewed before
+    if args.what_changed {
+        let diff = diff::what_changed(&Query::from_cli(&args)?)?;
+        return Ok(Output::Paged(diff.render()));
+    }
+
+    // Handle --graph: the structure of the crate as a graph
+    if let Some(cli::Graph::Modules) = args.graph {
+        let graph = graph::module_graph(&Query::from_cli(&args)?)?;
+        return Ok(Output::Paged(graph.render(args.graph_format)));
+    }
+
+    // Handle --matrix: the common traits the types of a module implement
+    if args.matrix {
+        let matrix = matrix::trait_matrix(&Query::from_cli(&args)?)?;
+        return Ok(Output::Paged(matrix.render()));
+    }
+
+    // Handle --crate-examples: the files in the crate's examples/, or one of them
+    if let Some(name) = &args.crate_examples {
+        return crate_examples(name.as_deref(), &args, style);
+    }
+
+    // Handle --deps: the crate's dependency tree, or the docs of one of them
+    if let Some(depth) = args.deps {
+        return dependencies(depth, &args, style);
+    }
+
+    // Handle --tui: browse the crate until the user quits
+    if args.tui {
+        #[cfg(not(feature = "tui"))]
+        bail!("docsrs was built without the tui feature");
+        #[cfg(feature = "tui")]
+        return browse(&Query::from_cli(&args)?, style).map(|()| Output::Plain(String::new()));
+    }
+
+    // Handle rustdoc JSON files, given with --json-path or as CRATE_SPEC
+    if let Some(path) = json_file(&args) {
+        let krate = load_local_docs(&path)?;
+        args.crate_spec = Some(spec_in_json_file(
+            &krate,
+            args.crate_spec.as_ref(),
+            args.json_path.is_none(),
+        )?);
+        let result = query_crate(krate, &Query::from_cli(&args)?)?;
+        // The file isn't a crate that handles could name
+        return item_output(&result, &args, None, style, stream);
+    }
+
+    query_docs(&args, style, stream, record_history)
+}
+
+/// Run one of the subcommands, like `diff` or `bookmark`.
+#[cfg(feature = "native")]
+fn run_subcommand(
+    command: &cli::Command,
+    args: &Cli,
+    style: &StyleConfig,
+) -> anyhow::Result<Output> {
+    match command {
+        // Compare the public API of two versions
+        cli::Command::Diff {
+            old,
+            new_version,
+            semver,
+        } => {
+            let diff = diff::diff_versions(&Query::for_spec(args, old.clone()), new_version)?;
+            Ok(Output::Plain(render_diff(&diff, *semver)))
+        }
+        // Compare a workspace crate with its latest release
+        cli::Command::DiffLocal { local, semver } => {
+            let diff = diff::diff_local(&Query::for_spec(args, local.clone()))?;
+            Ok(Output::Plain(render_diff(&diff, *semver)))
+        }
+        // Write the crate's docs as markdown files
+        cli::Command::Export { spec, out } => {
+            let export = export::export_docs(&Query::for_spec(args, spec.clone()), out)?;
+            Ok(Output::Plain(export.render()))
+        }
+        // List or forget recent queries
+        cli::Command::History { clear } => {
+            let Some(path) = history::history_path()? else {
+                return Ok(Output::Plain(String::new()));
+            };
+            if *clear {
+                history::clear(&path)?;
+                return Ok(Output::Plain("History cleared\n".to_string()));
             }
-            skill::InstallOutcome::AlreadyUpToDate => {
-                output.push_str(&format!("Already up to date: {}\n", target_file.display()));
+            Ok(Output::Plain(history::render(&history::load(&path)?)))
+        }
+        // Save, recall or forget shortcuts to items
+        cli::Command::Bookmark { action } => bookmark(action, args, style),
+        // Find crates on crates.io
+        cli::Command::Search { terms, limit } => {
+            if docfetch::is_offline() {
+                bail!("Searching crates.io needs the network, and docsrs is offline");
             }
-            skill::InstallOutcome::Differs => {
-                return Err(anyhow::anyhow!(
-                    "Refusing to overwrite existing file with different content: {}\n\
-                     Pass --force to overwrite.",
-                    target_file.display()
-                ));
+            let terms = terms.join(" ");
+            let results = crates_io::search(&terms, usize::from(*limit))?;
+            Ok(Output::Paged(crates_io::render_search(
+                &terms, &results, style,
+            )))
+        }
+    }
+}
+
+/// The API changes in `diff`, or with `semver` the version bump they need.
+#[cfg(feature = "native")]
+fn render_diff(diff: &diff::ApiDiff, semver: bool) -> String {
+    if semver {
+        return semver::check_semver(diff).render();
+    }
+    diff.render()
+}
+
+/// Fetch the docs of the project's dependencies and the crates the config
+/// lists to warm, for --warm-cache.
+#[cfg(feature = "native")]
+fn warm(args: &Cli, config: &Config) -> anyhow::Result<String> {
+    let resolver = VersionResolver::new()?;
+    let mut crates: Vec<(String, String)> = resolver
+        .direct_dependencies()
+        .into_iter()
+        .map(|resolved| {
+            let version = pinned_version(&resolved.name).unwrap_or(resolved.version);
+            (resolved.name, version)
+        })
+        .collect();
+    for extra in &config.warm {
+        let spec = CrateSpec::parse(extra)?;
+        let version = spec
+            .version
+            .or_else(|| pinned_version(&spec.name))
+            .unwrap_or_else(|| "latest".to_string());
+        crates.push((spec.original_name, version));
+    }
+    crates.sort();
+    crates.dedup();
+    let outcomes = warm_cache(&crates, args.jobs, &StderrEvents);
+
+    let downloaded = outcomes
+        .iter()
+        .filter(|outcome| matches!(outcome, WarmOutcome::Downloaded))
+        .count();
+    let failures: Vec<_> = crates
+        .iter()
+        .zip(&outcomes)
+        .filter_map(|((name, version), outcome)| match outcome {
+            WarmOutcome::Failed(e) => Some(format!("  {name}@{version}: {e:#}\n")),
+            _ => None,
+        })
+        .collect();
+    let mut output = format!(
+        "Cached docs for {} of {} crates ({} downloaded)\n",
+        crates.len() - failures.len(),
+        crates.len(),
+        downloaded
+    );
+    if !failures.is_empty() {
+        output.push_str("Failed:\n");
+        output.push_str(&failures.concat());
+    }
+    Ok(output)
+}
+
+/// Write the bundled SKILL.md for --install-skill, noting a copy in the
+/// other scope.
+#[cfg(feature = "native")]
+fn install_skill(args: &Cli) -> anyhow::Result<String> {
+    let target_dir = skill::resolve_scope_dir(args.scope)?;
+    let outcome = skill::install_skill(&target_dir, args.force)?;
+    let target_file = target_dir.join("SKILL.md");
+    let mut output = match outcome {
+        skill::InstallOutcome::Written => format!("Installed {}\n", target_file.display()),
+        skill::InstallOutcome::AlreadyUpToDate => {
+            format!("Already up to date: {}\n", target_file.display())
+        }
+        skill::InstallOutcome::Differs => {
+            return Err(anyhow::anyhow!(
+                "Refusing to overwrite existing file with different content: {}\n\
+                 Pass --force to overwrite.",
+                target_file.display()
+            ));
+        }
+    };
+    if let Some(other) = skill::other_scope_skill(args.scope) {
+        // Claude Code precedence: personal (user) overrides project.
+        let note = match args.scope {
+            cli::SkillScope::User => format!(
+                "Note: a project-scope SKILL.md also exists at {}; your new user-scope copy takes precedence (personal > project).",
+                other.display()
+            ),
+            cli::SkillScope::Project => format!(
+                "Note: a user-scope SKILL.md also exists at {} and takes precedence over the project-scope copy you just installed (personal > project).",
+                other.display()
+            ),
+        };
+        output.push_str(&note);
+        output.push('\n');
+    }
+    Ok(output)
+}
+
+/// Save, show, list or remove a bookmark, for `docsrs bookmark`.
+#[cfg(feature = "native")]
+fn bookmark(
+    action: &cli::BookmarkAction,
+    args: &Cli,
+    style: &StyleConfig,
+) -> anyhow::Result<Output> {
+    let mut bookmarks = Bookmarks::load(&bookmarks::bookmarks_path()?)?;
+    let output = match action {
+        cli::BookmarkAction::Add { spec, name } => {
+            // Look the item up first, so typos fail now instead of on show
+            if let QueryResult::MatchList(_) = query(&Query::for_spec(args, spec.clone()))? {
+                bail!("{spec} is ambiguous, add a disambiguator such as struct@ to bookmark it");
+            }
+            let name = name
+                .clone()
+                .unwrap_or_else(|| bookmarks::default_name(spec));
+            match bookmarks.add(&name, spec)? {
+                Some(replaced) if replaced != spec.to_string() => {
+                    format!("Bookmarked {spec} as {name}, replacing {replaced}\n")
+                }
+                _ => format!("Bookmarked {spec} as {name}\n"),
+            }
+        }
+        cli::BookmarkAction::Show { name } => {
+            let result = query(&Query::for_spec(args, bookmarks.get(name)?))?;
+            return Ok(Output::Paged(result.render(style)?));
+        }
+        cli::BookmarkAction::List => bookmarks.render(),
+        cli::BookmarkAction::Remove { name } => {
+            let spec = bookmarks.remove(name)?;
+            format!("Removed bookmark {name} ({spec})\n")
+        }
+    };
+    Ok(Output::Plain(output))
+}
+
+/// Search the docs of every dependency of the project for `filter`, for
+/// --workspace. With --format jsonl each crate's matches are written to
+/// `stream` as soon as it's searched.
+#[cfg(feature = "native")]
+fn search_workspace(
+    filter: &str,
+    args: &Cli,
+    style: &StyleConfig,
+    stream: &mut dyn std::io::Write,
+) -> anyhow::Result<Output> {
+    let jsonl = args.format == cli::OutputFormat::Jsonl;
+    let search = workspace::search_workspace(filter, args.no_cache, &StderrEvents, |found| {
+        if jsonl {
+            let krate = CrateSpec::parse(&format!("{}@{}", found.name, found.version))?;
+            jsonl::write_items(stream, Some(&krate), &found.items)?;
+        }
+        Ok(())
+    })?;
+    if jsonl {
+        for (spec, error) in &search.failed {
+            let error = error.lines().next().unwrap_or_default();
+            StderrEvents.on_warning(&format!("Couldn't search {spec}: {error}"));
+        }
+        return Ok(Output::Plain(String::new()));
+    }
+    Ok(Output::Paged(search.render(style)))
+}
+
+/// The files in the crate's examples/, or the one called `name`, or picked
+/// with --pick, for --crate-examples.
+#[cfg(feature = "native")]
+fn crate_examples(name: Option<&str>, args: &Cli, style: &StyleConfig) -> anyhow::Result<Output> {
+    let examples = examples::crate_examples(&Query::from_cli(args)?)?;
+    let picked = if args.pick && name.is_none() {
+        #[cfg(not(feature = "tui"))]
+        bail!("docsrs was built without the tui feature");
+        #[cfg(feature = "tui")]
+        {
+            let choices = examples
+                .examples
+                .iter()
+                .map(|example| (example.path.clone(), example.label()))
+                .collect();
+            match tui::pick_from(choices, style)? {
+                Some(picked) => Some(&examples.examples[picked]),
+                None => return Ok(Output::Plain(String::new())),
             }
         }
-        if let Some(other) = skill::other_scope_skill(parsed_args.scope) {
-            // Claude Code precedence: personal (user) overrides project.
-            let note = match parsed_args.scope {
-                cli::SkillScope::User => format!(
-                    "Note: a project-scope SKILL.md also exists at {}; your new user-scope copy takes precedence (personal > project).",
-                    other.display()
-                ),
-                cli::SkillScope::Project => format!(
-                    "Note: a user-scope SKILL.md also exists at {} and takes precedence over the project-scope copy you just installed (personal > project).",
-                    other.display()
-                ),
+    } else {
+        name.map(|name| examples.find(name)).transpose()?
+    };
+    Ok(Output::Paged(match picked {
+        Some(example) => example.render(style),
+        None => examples.render(style),
+    }))
+}
+
+/// The crate's dependency tree `depth` levels deep, or with --pick the docs
+/// of the dependency picked from it, for --deps.
+#[cfg(feature = "native")]
+fn dependencies(depth: usize, args: &Cli, style: &StyleConfig) -> anyhow::Result<Output> {
+    let tree = deps::dep_tree(&Query::from_cli(args)?, depth)?;
+    if args.pick {
+        #[cfg(not(feature = "tui"))]
+        bail!("docsrs was built without the tui feature");
+        #[cfg(feature = "tui")]
+        {
+            let (deps, specs): (Vec<_>, Vec<_>) = tree
+                .root
+                .dependencies()
+                .into_iter()
+                .filter_map(|dep| Some((dep, dep.spec()?)))
+                .unzip();
+            let Some(picked) = tui::pick_spec(&specs, style)? else {
+                return Ok(Output::Plain(String::new()));
             };
-            output.push_str(&note);
-            output.push('\n');
+            // The dependency may be from another registry than the crate
+            set_default_registry(deps[picked].registry.clone());
+            let result = query(&Query::for_spec(args, CrateSpec::parse(&specs[picked])?))?;
+            return Ok(Output::Paged(result.render(style)?));
         }
-        return Ok(output);
     }
+    Ok(Output::Paged(tree.render(style)))
+}
 
-    // Require crate_spec if not clearing cache
-    let crate_spec = parsed_args
-        .crate_spec
-        .ok_or_else(|| anyhow::anyhow!("Missing required argument: CRATE_SPEC"))?;
+/// Query the docs the arguments name, letting the user pick one of several
+/// matches with --pick, and show them in the form the flags ask for.
+#[cfg(feature = "native")]
+fn query_docs(
+    parsed_args: &Cli,
+    style: &StyleConfig,
+    stream: &mut dyn std::io::Write,
+    record_history: bool,
+) -> anyhow::Result<Output> {
+    let args = Query::from_cli(parsed_args)?;
 
-    // Filter is optional - if not provided, we'll list all items
-    let filter = parsed_args.filter;
-    let path_prefix = crate_spec.path_prefix.clone();
+    // Handle --format jsonl: listed items are written as they're found. When
+    // none are, the query is answered as usual, e.g. for a path like
+    // `Value::get` naming a method, which isn't listed
+    let streams = parsed_args.format == cli::OutputFormat::Jsonl
+        && args.lists_items()
+        && !(parsed_args.pick || parsed_args.open || parsed_args.use_stmt || parsed_args.skeleton)
+        && parsed_args.copy.is_none();
+    if streams && stream_list(&args, stream)? > 0 {
+        if record_history {
+            record_query(&args, HistoryEntry::listed(&args));
+        }
+        return Ok(Output::Plain(String::new()));
+    }
 
-    // Resolve the crate version and load documentation
-    let krate = if let Some(explicit_version) = crate_spec.version.clone() {
+    let result = query(&args)?;
+
+    // Handle --pick: show the docs of the match the user picks
+    let picking =
+        parsed_args.pick && matches!(&result, QueryResult::MatchList(list) if list.items.len() > 1);
+    #[cfg(not(feature = "tui"))]
+    if picking {
+        bail!("docsrs was built without the tui feature");
+    }
+    #[cfg(feature = "tui")]
+    let result = match result {
+        QueryResult::MatchList(list) if picking => match tui::pick(&list.items, style)? {
+            Some(picked) => query(&args.for_item(&list.items[picked]))?,
+            None => return Ok(Output::Plain(String::new())),
+        },
+        result => result,
+    };
+
+    if record_history {
+        record_query(&args, HistoryEntry::new(&args, &result, picking));
+    }
+
+    // Handle --open: show the item in the browser as well
+    if parsed_args.open {
+        open_docs(&result, &*args.events)?;
+    }
+
+    // Handle --copy: put part of the item on the clipboard
+    if let Some(target) = parsed_args.copy {
+        copy_item(&result, target, &*args.events)?;
+    }
+
+    item_output(&result, parsed_args, Some(&args.spec), style, stream)
+}
+
+/// Remember the query `args` as `entry` for `docsrs history` and `docsrs !!`.
+#[cfg(feature = "native")]
+fn record_query(args: &Query, entry: HistoryEntry) {
+    let recorded = history::history_path()
+        .and_then(|path| path.map_or(Ok(()), |path| history::record(&path, entry)));
+    if let Err(e) = recorded {
+        args.events
+            .on_warning(&format!("Failed to record history: {e:#}"));
+    }
+}
+
+/// Write the items a tree or search of `args` lists to `stream` as they're
+/// found, for --format jsonl, returning how many were written.
+///
+/// Items are written in the order of the crate: each one under the path
+/// whose path contains the filter or, with --full-text, whose name or docs
+/// have its words. Without a cached index of the crate, they're written
+/// while the index is built.
+#[cfg(feature = "native")]
+fn stream_list(args: &Query, stream: &mut dyn std::io::Write) -> anyhow::Result<usize> {
+    let spec = &args.spec;
+    let mut resolution = Resolution::default();
+    let resolved = resolve_docs(spec, &mut resolution)?;
+    let filter = args.filter.as_deref().unwrap_or_default();
+    let full_text = args.full_text && !args.tree;
+    let listed = |item: &ListItem| {
+        args.kind.is_none_or(|kind| item.kind == kind)
+            && item.qualifiers.contains(args.qualifiers)
+            && spec
+                .path_prefix
+                .as_deref()
+                .is_none_or(|prefix| has_path_prefix(item, &spec.name, prefix))
+    };
+    let mut written = 0;
+
+    let cache_key = resolved
+        .cache_key(args.no_cache)
+        .map(|(name, version)| (name.to_string(), version.to_string()));
+    let index = cache_key.as_ref().and_then(|(name, version)| {
+        timings::time(Phase::Index, || SearchIndex::load_cached(name, version))
+    });
+    if let Some(index) = index {
+        let krate = pinned_crate(spec, &resolution, index.version.clone());
+        let doc_matches = match full_text {
+            true => index.matching_docs(filter),
+            false => BTreeSet::new(),
+        };
+        for item in index.items() {
+            if listed(item) && (item.path.contains(filter) || doc_matches.contains(&item.id)) {
+                jsonl::write_items(stream, Some(&krate), std::slice::from_ref(item))?;
+                written += 1;
+            }
+        }
+        return Ok(written);
+    }
+
+    let doc = resolved.load_doc(args.no_cache, &*args.events)?;
+    let krate = pinned_crate(spec, &resolution, doc.crate_data().crate_version.clone());
+    let mut failed = None;
+    let mut index = timings::time(Phase::Index, || {
+        SearchIndex::build_with(&doc, |item, words| {
+            let found = listed(item)
+                && (item.path.contains(filter)
+                    || full_text && search_index::has_words(words, filter));
+            if found && failed.is_none() {
+                match jsonl::write_items(stream, Some(&krate), std::slice::from_ref(item)) {
+                    Ok(()) => written += 1,
+                    Err(e) => failed = Some(e),
+                }
+            }
+        })
+    });
+    if let Some(e) = failed {
+        return Err(e);
+    }
+    cache_index(&mut index, cache_key.as_ref(), &*args.events);
+    Ok(written)
+}
+
+/// Save `index` with the cached docs of the crate@version of `cache_key`,
+/// warning `events` when that fails.
+#[cfg(feature = "native")]
+fn cache_index(
+    index: &mut SearchIndex,
+    cache_key: Option<&(String, String)>,
+    events: &dyn EventSink,
+) {
+    if let Some((name, version)) = cache_key
+        && let Err(e) = index.save_cached(name, version)
+    {
+        events.on_warning(&format!("Failed to cache search index: {}", e));
+    }
+}
+
+/// The output for `result` in the form the flags ask for: its docs, its
+/// `use` line, code to start from, a man page or JSON lines with handles
+/// into the crate of `spec`.
+#[cfg(feature = "native")]
+fn item_output(
+    result: &QueryResult,
+    args: &Cli,
+    spec: Option<&CrateSpec>,
+    style: &StyleConfig,
+    stream: &mut dyn std::io::Write,
+) -> anyhow::Result<Output> {
+    // Handle --use-stmt: only the line importing the item
+    if args.use_stmt {
+        return use_statement(result).map(Output::Plain);
+    }
+
+    // Handle --skeleton: code to start from instead of the docs
+    if args.skeleton {
+        return skeleton(result, style).map(Output::Paged);
+    }
+
+    // Handle --format: a man page to pipe to `man -l -`, or a JSON line per
+    // item for jq
+    match args.format {
+        cli::OutputFormat::Text => Ok(Output::Paged(result.render(style)?)),
+        cli::OutputFormat::Man => man_page(result).map(Output::Plain),
+        cli::OutputFormat::Jsonl => write_jsonl(result, spec, stream).map(Output::Plain),
+    }
+}
+
+/// The `use` declaration importing the item in `result`, for --use-stmt.
+#[cfg(feature = "native")]
+fn use_statement(result: &QueryResult) -> anyhow::Result<String> {
+    let item = match result {
+        QueryResult::ItemDoc(item) => item,
+        QueryResult::CrateOverview(item) => bail!("{} is the crate root", item.path),
+        QueryResult::MatchList(list) => bail!(
+            "{} items match, pick one with --pick or a disambiguator like struct@",
+            list.items.len()
+        ),
+    };
+    let Some(statement) = item.use_statement() else {
+        bail!("{} can't be imported with `use`", item.path);
+    };
+    Ok(format!("{statement}\n"))
+}
+
+/// Code to start from for the trait or function in `result`, for
+/// --skeleton.
+#[cfg(feature = "native")]
+fn skeleton(result: &QueryResult, style: &StyleConfig) -> anyhow::Result<String> {
+    let item = match result {
+        QueryResult::ItemDoc(item) => item,
+        QueryResult::CrateOverview(item) => bail!("{} is the crate root", item.path),
+        QueryResult::MatchList(list) => bail!(
+            "{} items match, pick one with --pick or a disambiguator like trait@",
+            list.items.len()
+        ),
+    };
+    let Some(skeleton) = item.skeleton(style)? else {
+        let kind = item.kind.map(|kind| format!("{} ", kind.keyword()));
+        bail!(
+            "--skeleton needs a trait or a function, not {}{}",
+            kind.unwrap_or_default(),
+            item.path
+        );
+    };
+    Ok(skeleton)
+}
+
+/// The man page of the item or module in `result`, for --format man.
+#[cfg(feature = "native")]
+fn man_page(result: &QueryResult) -> anyhow::Result<String> {
+    match result {
+        QueryResult::ItemDoc(item) | QueryResult::CrateOverview(item) => man::man_page(item),
+        QueryResult::MatchList(list) => bail!(
+            "{} items match, a man page needs one: pick it with --pick or a longer path",
+            list.items.len()
+        ),
+    }
+}
+
+/// Write a JSON line for each item listed in `result` to `stream`, for
+/// --format jsonl, with a handle to look it up by in the crate of `spec`.
+#[cfg(feature = "native")]
+fn write_jsonl(
+    result: &QueryResult,
+    spec: Option<&CrateSpec>,
+    stream: &mut dyn std::io::Write,
+) -> anyhow::Result<String> {
+    match result {
+        QueryResult::MatchList(list) => {
+            let krate = spec.map(|spec| pinned_crate(spec, &list.resolution, list.version.clone()));
+            jsonl::write_items(stream, krate.as_ref(), &list.items)?;
+        }
+        QueryResult::ItemDoc(item) => {
+            let version = item.doc.crate_data().crate_version.clone();
+            let krate = spec.map(|spec| pinned_crate(spec, &item.resolution, version));
+            jsonl::write_item(stream, krate.as_ref(), item)?;
+        }
+        QueryResult::CrateOverview(item) => bail!(
+            "--format jsonl writes listed items, give a filter or --tree to list those of {}",
+            item.path
+        ),
+    }
+    Ok(String::new())
+}
+
+/// The crate of `spec` at `version`, the version its docs are of, so that
+/// handles keep naming the same items once newer versions are out. Crates
+/// of the workspace keep their spec, as a version would look them up on
+/// docs.rs.
+#[cfg(feature = "native")]
+fn pinned_crate(spec: &CrateSpec, resolution: &Resolution, version: Option<String>) -> CrateSpec {
+    let version = match &resolution.local {
+        Some(_) => spec.version.clone(),
+        None => version.or_else(|| spec.version.clone()),
+    };
+    CrateSpec {
+        version,
+        path_prefix: None,
+        ..spec.clone()
+    }
+}
+
+/// Show `output` through `pager` when there is one and stdout is a
+/// terminal, leaving nothing else to print, or else return it as is.
+#[cfg(feature = "native")]
+fn page(output: String, pager: Option<&str>) -> anyhow::Result<String> {
+    use std::io::IsTerminal;
+
+    match pager {
+        Some(pager) if std::io::stdout().is_terminal() => {
+            run_pager(pager, &output)?;
+            Ok(String::new())
+        }
+        _ => Ok(output),
+    }
+}
+
+/// Open the HTML docs of the item in `result` in the browser: the local docs
+/// of workspace crates, built first if missing, or else docs.rs.
+#[cfg(feature = "native")]
+fn open_docs(result: &QueryResult, events: &dyn EventSink) -> anyhow::Result<()> {
+    let item = match result {
+        QueryResult::ItemDoc(item) | QueryResult::CrateOverview(item) => item,
+        QueryResult::MatchList(list) => {
+            events.on_warning(&format!(
+                "Not opening docs: {} items match, pick one with --pick",
+                list.items.len()
+            ));
+            return Ok(());
+        }
+    };
+    let no_page = || anyhow::anyhow!("{} has no HTML docs page", item.path);
+    let target = match (&item.resolution.local, &item.reexport) {
+        (Some(local), None) => {
+            let html_path = item.html_path().ok_or_else(no_page)?;
+            let (page, anchor) = match html_path.split_once('#') {
+                Some((page, anchor)) => (page, format!("#{anchor}")),
+                None => (html_path.as_str(), String::new()),
+            };
+            let page = local.doc_dir.join(page);
+            if !page.exists() {
+                build_local_html(&local.package)?;
+            }
+            format!("file://{}{}", page.display(), anchor)
+        }
+        _ => item.docs_url().ok_or_else(no_page)?,
+    };
+    open_in_browser(&target)
+}
+
+/// Copy the path, signature or first code example of the item in `result`
+/// to the clipboard.
+#[cfg(feature = "native")]
+fn copy_item(
+    result: &QueryResult,
+    target: cli::CopyTarget,
+    events: &dyn EventSink,
+) -> anyhow::Result<()> {
+    let item = match result {
+        QueryResult::ItemDoc(item) | QueryResult::CrateOverview(item) => item,
+        QueryResult::MatchList(list) => {
+            events.on_warning(&format!(
+                "Nothing copied: {} items match, pick one with --pick",
+                list.items.len()
+            ));
+            return Ok(());
+        }
+    };
+    let (text, what) = match target {
+        cli::CopyTarget::Path => (Some(item.path.clone()), "path"),
+        cli::CopyTarget::Signature => (Some(item.signature.clone()), "signature"),
+        cli::CopyTarget::Example => (item.first_example(), "first example"),
+    };
+    let Some(text) = text else {
+        events.on_warning(&format!(
+            "Nothing copied: the docs of {} have no Rust code example",
+            item.path
+        ));
+        return Ok(());
+    };
+    copy_to_clipboard(&text)?;
+    events.on_copied(what, &item.path);
+    Ok(())
+}
+
+/// `args` with the history references among them replaced by the queries
+/// they refer to, or None when there are none.
+#[cfg(feature = "native")]
+fn expand_history(args: &[&str]) -> anyhow::Result<Option<Vec<String>>> {
+    if !args.iter().any(|arg| arg.starts_with('!')) {
+        return Ok(None);
+    }
+    let entries = match history::history_path()? {
+        Some(path) => history::load(&path)?,
+        None => Vec::new(),
+    };
+    history::expand(args, &entries)
+}
+
+/// Answer a docs query: the docs of an item, a list of items or the crate
+/// root docs, depending on the path, filter and tree mode of `args`.
+#[cfg(feature = "native")]
+pub fn query(args: &Query) -> anyhow::Result<QueryResult> {
+    let crate_spec = &args.spec;
+
+    // Resolve the crate version
+    let mut resolution = Resolution::default();
+    let resolved = resolve_docs(crate_spec, &mut resolution)?;
+    // Docs of a version the query didn't ask for, from the lockfile or
+    // cached as `latest`, may be behind the newest release
+    let unasked = match &resolved {
+        ResolvedDocs::DocsRs { name, version } if crate_spec.version.is_none() => {
+            Some((name.clone(), version.clone()))
+        }
+        _ => None,
+    };
+    if let Some((name, version)) = &unasked
+        && version != "latest"
+    {
+        note_newer_version(&mut resolution, name, version);
+        note_viewed(&mut resolution, name, version, args);
+    }
+    // `latest` is only known once the docs are loaded
+    let note_latest = |resolution: &mut Resolution, doc: &JsonDoc| {
+        if let Some((name, version)) = &unasked
+            && version == "latest"
+            && let Some(loaded) = &doc.crate_data().crate_version
+        {
+            note_newer_version(resolution, name, loaded);
+            note_viewed(resolution, name, loaded, args);
+        }
+    };
+
+    if args.lists_items() {
+        // Trees and searches are built from the search index, which is saved
+        // with cached docs so later lists don't need to parse the crate
+        let cache_key = resolved.cache_key(args.no_cache);
+        let index = cache_key.and_then(|(name, version)| {
+            timings::time(Phase::Index, || SearchIndex::load_cached(name, version))
+        });
+        let view = index
+            .as_ref()
+            .map(|index| list_view(index, crate_spec, args));
+        let may_name_impl_item = view
+            .as_ref()
+            .is_some_and(|view| impl_item_query(view, args).is_some());
+        if !may_name_impl_item && let Some(ListView::Matches(kind, items)) = view {
+            let version = index.and_then(|index| index.version);
+            return Ok(QueryResult::MatchList(MatchList::new(
+                resolution, kind, items, version, args.limit,
+            )));
+        }
+
+        // No cached index yet, or a single match to show the docs of
+        let cache_key = cache_key.map(|(name, version)| (name.to_string(), version.to_string()));
+        let doc = resolved.load_doc(args.no_cache, &*args.events)?;
+        note_latest(&mut resolution, &doc);
+        let view = match view {
+            Some(view) => view,
+            None => {
+                let index = timings::time(Phase::Index, || {
+                    let mut index = SearchIndex::build(&doc);
+                    cache_index(&mut index, cache_key.as_ref(), &*args.events);
+                    index
+                });
+                list_view(&index, crate_spec, args)
+            }
+        };
+        if let Some(item_args) = impl_item_query(&view, args)
+            && has_item(&doc, &item_args)
+        {
+            return item_result(resolution, doc, &item_args);
+        }
+        return list_result(resolution, view, doc, args.limit);
+    }
+
+    let doc = match crate_spec.path_prefix.as_deref() {
+        Some(prefix) => {
+            let (_, path) = split_disambiguator(prefix)?;
+            let path = format!("{}::{}", crate_spec.name, path);
+            resolved.load_doc_at(&path, args.no_cache, &*args.events)?
+        }
+        None => resolved.load_doc(args.no_cache, &*args.events)?,
+    };
+    note_latest(&mut resolution, &doc);
+    let mut result = item_result(resolution, doc, args)?;
+    if let QueryResult::CrateOverview(overview) = &mut result
+        && overview.resolution.crate_info.is_none()
+        && overview.resolution.registry.is_none()
+        && !docfetch::is_offline()
+    {
+        // Not in the project, so ask crates.io about the version the docs
+        // are of; the header is left out when that fails
+        let version = overview.doc.crate_data().crate_version.clone();
+        overview.resolution.crate_info = version.and_then(|version| {
+            timings::time(Phase::Resolve, || {
+                crates_io::crate_info(&crate_spec.original_name, &version).ok()
+            })
+        });
+    }
+    if let QueryResult::CrateOverview(overview) = &mut result
+        && !overview.has_docs()
+        && let Some(version) = overview.doc.crate_data().crate_version.clone()
+    {
+        // Many crates only document themselves in their README
+        let name = match &overview.resolution.local {
+            Some(local) => local.package.clone(),
+            None => crate_spec.original_name.clone(),
+        };
+        let published = overview.resolution.registry.is_none();
+        overview.readme = timings::time(Phase::Resolve, || readme(&name, &version, published));
+    }
+    Ok(result)
+}
+
+/// Note in `resolution` when the crate `name` has a newer release than
+/// `version`, the version its docs are of.
+#[cfg(feature = "native")]
+fn note_newer_version(resolution: &mut Resolution, name: &str, version: &str) {
+    let newer = timings::time(Phase::Resolve, || {
+        registry_index::newer_version(name, version)
+    });
+    if let Some(newer) = newer {
+        let (_, crate_name) = split_qualified_name(name);
+        resolution.notice = Some(format!(
+            "note: {crate_name} {newer} is available (you're viewing {version})"
+        ));
+    }
+}
+
+/// Remember `version` as the version of the crate `name` viewed last, and
+/// note in `resolution` when the one viewed before was another.
+#[cfg(feature = "native")]
+fn note_viewed(resolution: &mut Resolution, name: &str, version: &str, args: &Query) {
+    if args.no_cache {
+        return;
+    }
+    match viewed::record_viewed(name, version) {
+        Ok(previous) => resolution.previous_version = previous,
+        Err(e) => args
+            .events
+            .on_warning(&format!("Failed to remember the version viewed: {}", e)),
+    }
+}
+
+/// The README of crate@version: the file in the project, or in the
+/// registry sources of a dependency, else the one published on crates.io
+/// for `published` crates.
+#[cfg(feature = "native")]
+fn readme(name: &str, version: &str, published: bool) -> Option<String> {
+    let in_project = VersionResolver::new()
+        .ok()
+        .and_then(|resolver| resolver.readme(name, version));
+    in_project.or_else(|| {
+        published
+            .then(|| crates_io::readme(name, version).ok())
+            .flatten()
+    })
+}
+
+/// Open the interactive browser on the crate of `args`, at its path.
+#[cfg(feature = "tui")]
+fn browse(args: &Query, style: &StyleConfig) -> anyhow::Result<()> {
+    let doc = resolve_docs(&args.spec, &mut Resolution::default())?
+        .load_doc(args.no_cache, &*args.events)?;
+    let start = match &args.spec.path_prefix {
+        Some(prefix) => format!("{}::{}", args.spec.name, prefix),
+        None => args.spec.name.clone(),
+    };
+    tui::browse(&doc, &start, style)
+}
+
+/// Answer a docs query from the docs in `krate`, whatever the version in
+/// `args`. Nothing is resolved, fetched or cached, so this works without the
+/// `native` feature, e.g. to render uploaded rustdoc JSON in a browser.
+pub fn query_crate(krate: Crate, args: &Query) -> anyhow::Result<QueryResult> {
+    let doc = timings::time(Phase::Process, || JsonDoc::from(krate));
+    if args.lists_items() {
+        let index = timings::time(Phase::Index, || SearchIndex::build(&doc));
+        let view = list_view(&index, &args.spec, args);
+        if let Some(item_args) = impl_item_query(&view, args)
+            && has_item(&doc, &item_args)
+        {
+            return item_result(Resolution::default(), doc, &item_args);
+        }
+        return list_result(Resolution::default(), view, doc, args.limit);
+    }
+    item_result(Resolution::default(), doc, args)
+}
+
+/// The query for the item a search for a path like `Value::get` names, when
+/// no listed item matches it: methods and other items of impls aren't
+/// listed, but can be looked up by path.
+fn impl_item_query(view: &ListView, args: &Query) -> Option<Query> {
+    let ListView::Matches(
+        MatchKind::Search {
+            filter,
+            matched: false,
+        },
+        _,
+    ) = view
+    else {
+        return None;
+    };
+    if !filter.contains("::") || args.tree {
+        return None;
+    }
+    let path = match &args.spec.path_prefix {
+        Some(prefix) => format!("{prefix}::{filter}"),
+        None => filter.clone(),
+    };
+    let mut query = args.clone().path(path);
+    query.filter = None;
+    query.qualifiers = Qualifiers::default();
+    Some(query)
+}
+
+/// Whether `doc` has an item at the path of `args`.
+fn has_item(doc: &JsonDoc, args: &Query) -> bool {
+    let Some(prefix) = &args.spec.path_prefix else {
+        return false;
+    };
+    split_disambiguator(prefix).is_ok_and(|(_, path)| {
+        !doc.find_items_by_path(&format!("{}::{path}", args.spec.name))
+            .is_empty()
+    })
+}
+
+/// The result for a tree or search in `doc`: the listed items, or the docs
+/// of the only item matched.
+fn list_result(
+    resolution: Resolution,
+    view: ListView,
+    doc: JsonDoc,
+    limit: Option<usize>,
+) -> anyhow::Result<QueryResult> {
+    Ok(match view {
+        ListView::Matches(kind, items) => {
+            let version = doc.crate_data().crate_version.clone();
+            QueryResult::MatchList(MatchList::new(resolution, kind, items, version, limit))
+        }
+        ListView::Single(item) => QueryResult::ItemDoc(ItemDoc::new(
+            resolution,
+            item.path,
+            Some(item.kind),
+            item.id,
+            None,
+            doc,
+        )?),
+    })
+}
+
+/// The result for the path in `args`: the item at the path, the candidates
+/// when several items share it, or the crate root without a path.
+fn item_result(resolution: Resolution, doc: JsonDoc, args: &Query) -> anyhow::Result<QueryResult> {
+    let crate_spec = &args.spec;
+    let Some(prefix) = crate_spec.path_prefix.as_deref() else {
+        // No path, no filter: show crate root doc
+        let id = doc.crate_root_id();
+        return Ok(QueryResult::CrateOverview(ItemDoc::new(
+            resolution,
+            crate_spec.name.clone(),
+            Some(EntryKind::Module),
+            id,
+            None,
+            doc,
+        )?));
+    };
+
+    // Pure navigation: show doc for exact path
+    let (kind, prefix) = split_disambiguator(prefix)?;
+    let kind = kind.or(args.kind);
+    let full_path = format!("{}::{}", crate_spec.name, prefix);
+    let kind_of = |doc: &JsonDoc, id: &Id| {
+        doc.crate_data()
+            .index
+            .get(id)
+            .and_then(|item| EntryKind::from_item_enum(&item.inner))
+    };
+    let ids: Vec<Id> = doc
+        .find_items_by_path(&full_path)
+        .into_iter()
+        .filter(|id| kind.is_none() || kind_of(&doc, id) == kind)
+        .collect();
+
+    match ids.as_slice() {
+        [] => {
+            let items = list_items(&doc);
+            let paths = items
+                .iter()
+                .filter(|item| kind.is_none_or(|kind| item.kind == kind))
+                .map(|item| item.path.as_str());
+            let hint = suggest::did_you_mean(&suggest::similar(&full_path, paths))
+                .map(|hint| format!("\n\n{hint}"))
+                .unwrap_or_default();
+            match kind {
+                Some(kind) => bail!("No {} found at {}{}", kind.keyword(), full_path, hint),
+                None => bail!("No item found at {}{}", full_path, hint),
+            }
+        }
+        [id] => {
+            let item = match ExternalReexport::find(&doc, id) {
+                #[cfg(feature = "native")]
+                Some(reexport) if args.follow_reexports => {
+                    // Render the item from the docs of the crate defining it
+                    let target_doc = reexport.load_docs(args.no_cache, &*args.events)?;
+                    let target_id = reexport.find_target(&target_doc)?;
+                    ItemDoc::new(
+                        resolution,
+                        full_path,
+                        kind_of(&target_doc, &target_id),
+                        target_id,
+                        Some(Reexport::Followed {
+                            source: reexport.source,
+                        }),
+                        target_doc,
+                    )?
+                }
+                reexport => ItemDoc::new(
+                    resolution,
+                    full_path,
+                    kind_of(&doc, id),
+                    *id,
+                    reexport.map(|reexport| Reexport::NotFollowed {
+                        crate_name: reexport.crate_name,
+                    }),
+                    doc,
+                )?,
+            };
+            Ok(QueryResult::ItemDoc(item))
+        }
+        _ => {
+            // Several items share the path, e.g. a struct and a macro: list
+            // them with the disambiguated spec for each
+            let (parent, name) = match prefix.rsplit_once("::") {
+                Some((parent, name)) => (format!("{parent}::"), name),
+                None => (String::new(), prefix.as_str()),
+            };
+            let mut items: Vec<ListItem> = Vec::new();
+            let mut specs = Vec::new();
+            for item in list_items(&doc) {
+                if item.path == full_path
+                    && ids.contains(&item.id)
+                    && !items.iter().any(|c| c.id == item.id)
+                {
+                    specs.push(format!(
+                        "{}::{}{}@{}",
+                        crate_spec.original_name,
+                        parent,
+                        item.kind.keyword(),
+                        name
+                    ));
+                    items.push(item);
+                }
+            }
+            let kind = MatchKind::Ambiguous {
+                path: full_path,
+                specs,
+            };
+            let version = doc.crate_data().crate_version.clone();
+            Ok(QueryResult::MatchList(MatchList::new(
+                resolution, kind, items, version, None,
+            )))
+        }
+    }
+}
+
+/// The rustdoc JSON file to read the docs from: the one given with
+/// `--json-path`, or a CRATE_SPEC naming a `.json` file.
+#[cfg(feature = "native")]
+fn json_file(args: &Cli) -> Option<std::path::PathBuf> {
+    if let Some(path) = &args.json_path {
+        return Some(path.clone());
+    }
+    let spec = args.crate_spec.as_ref()?;
+    let file = &spec.original_name;
+    (spec.version.is_none() && (file.ends_with(".json") || file.ends_with(".json.zst")))
+        .then(|| file.into())
+}
+
+/// The spec to query the docs in `krate` with: the crate's own name, and
+/// the path of `spec` in it. When `spec` is the file itself, only its path
+/// is kept, as in `target/doc/foo.json::Bar`; otherwise its crate name is
+/// optional, so `Bar` and `foo::Bar` are the same item.
+#[cfg(feature = "native")]
+fn spec_in_json_file(
+    krate: &Crate,
+    spec: Option<&CrateSpec>,
+    spec_is_file: bool,
+) -> anyhow::Result<CrateSpec> {
+    let name = krate
+        .index
+        .get(&krate.root)
+        .and_then(|root| root.name.clone())
+        .ok_or_else(|| anyhow::anyhow!("The rustdoc JSON has no crate root"))?;
+    let path = match spec {
+        Some(spec) if spec_is_file || spec.name == normalize_crate_name(&name) => {
+            spec.path_prefix.clone()
+        }
+        Some(spec) => Some(match &spec.path_prefix {
+            Some(path) => format!("{}::{path}", spec.original_name),
+            None => spec.original_name.clone(),
+        }),
+        None => None,
+    };
+    CrateSpec::parse(&match path {
+        Some(path) => format!("{name}::{path}"),
+        None => name,
+    })
+}
+
+/// Where the docs of a crate come from, once its version is resolved.
+#[cfg(feature = "native")]
+enum ResolvedDocs {
+    /// Docs built from a local crate
+    Local(Crate),
+    /// Docs fetched from docs.rs, or the cache
+    DocsRs { name: String, version: String },
+}
+
+#[cfg(feature = "native")]
+impl ResolvedDocs {
+    /// Crate name and version the docs are cached under, if the cache is used.
+    fn cache_key(&self, no_cache: bool) -> Option<(&str, &str)> {
+        match self {
+            ResolvedDocs::DocsRs { name, version } if !no_cache => Some((name, version)),
+            _ => None,
+        }
+    }
+
+    fn load(self, no_cache: bool, events: &dyn EventSink) -> anyhow::Result<Crate> {
+        match self {
+            ResolvedDocs::Local(krate) => Ok(krate),
+            ResolvedDocs::DocsRs { name, version } => {
+                fetch_docs(&name, &version, !no_cache, events)
+            }
+        }
+    }
+
+    /// Load the crate and process it into a [`JsonDoc`].
+    fn load_doc(self, no_cache: bool, events: &dyn EventSink) -> anyhow::Result<JsonDoc> {
+        let krate = self.load(no_cache, events)?;
+        Ok(timings::time(Phase::Process, || JsonDoc::from(krate)))
+    }
+
+    /// Load the crate and process only the items along `path` into a
+    /// [`JsonDoc`]. Without an item at `path` the whole crate is processed,
+    /// so that similar paths can be suggested.
+    fn load_doc_at(
+        self,
+        path: &str,
+        no_cache: bool,
+        events: &dyn EventSink,
+    ) -> anyhow::Result<JsonDoc> {
+        let krate = self.load(no_cache, events)?;
+        Ok(timings::time(Phase::Process, || {
+            let doc = JsonDoc::for_path(krate, path);
+            if doc.find_items_by_path(path).is_empty() {
+                JsonDoc::from(doc.into_crate())
+            } else {
+                doc
+            }
+        }))
+    }
+}
+
+/// Resolve the version of the crate in `crate_spec` and load its rustdoc JSON,
+/// from a local build, the cache or docs.rs. Resolution messages are added
+/// to `resolution`.
+#[cfg(feature = "native")]
+fn load_crate(
+    crate_spec: &CrateSpec,
+    no_cache: bool,
+    resolution: &mut Resolution,
+    events: &dyn EventSink,
+) -> anyhow::Result<Crate> {
+    resolve_docs(crate_spec, resolution)?.load(no_cache, events)
+}
+
+/// Resolve the version of the crate in `crate_spec`, building the docs of a
+/// local crate. Resolution messages are added to `resolution`.
+#[cfg(feature = "native")]
+fn resolve_docs(
+    crate_spec: &CrateSpec,
+    resolution: &mut Resolution,
+) -> anyhow::Result<ResolvedDocs> {
+    // Crates outside the project come from --registry, or crates.io
+    let outside_name = qualified_name(default_registry().as_deref(), &crate_spec.original_name);
+    let mut resolved = if let Some(explicit_version) = crate_spec.version.clone() {
         // User provided explicit version - skip resolution, just fetch
-        let use_cache = !parsed_args.no_cache;
-        fetch_docs(&crate_spec.original_name, &explicit_version, use_cache)?
+        ResolvedDocs::DocsRs {
+            name: outside_name,
+            version: explicit_version,
+        }
+    } else if let Some(pinned) = pinned_version(&crate_spec.name) {
+        // Pinned in the config, e.g. the project's .docsrs.toml
+        resolution.message = Some(format!("{}@{} (pinned)", crate_spec.original_name, pinned));
+        ResolvedDocs::DocsRs {
+            name: outside_name,
+            version: pinned,
+        }
     } else {
         // Try to resolve from Cargo.toml
-        match VersionResolver::new() {
+        match timings::time(Phase::Resolve, VersionResolver::new) {
             Ok(resolver) => {
-                if let Some(resolved) = resolver.resolve_crate(&crate_spec.name) {
+                let resolved =
+                    timings::time(Phase::Resolve, || resolver.resolve_crate(&crate_spec.name));
+                if let Some(resolved) = resolved {
                     // Print resolution message as a comment
-                    output.push_str(&format!(
-                        "{}\n\n",
-                        format!("// {}", resolved.format_message()).bright_black()
-                    ));
+                    resolution.message = Some(resolved.format_message());
+                    resolution.crate_info = resolver.crate_info(&resolved.name, &resolved.version);
 
                     if resolved.is_local {
                         // Build and load local docs
@@ -154,127 +1670,170 @@ fn run_cli_impl(args: &[&str]) -> anyhow::Result<String> {
                                 )
                             })?;
 
+                        resolution.local = doc_path.parent().map(|doc_dir| LocalCrate {
+                            package: resolved.name.clone(),
+                            doc_dir: doc_dir.to_path_buf(),
+                        });
                         match build_local_docs(&resolved.name, &doc_path)? {
-                            BuildLocalDocsResult::Success(krate) => krate,
+                            BuildLocalDocsResult::Success(krate) => ResolvedDocs::Local(krate),
                             BuildLocalDocsResult::CachedWithWarning { krate, warning } => {
-                                output.push_str(&format!("Warning: {}\n", warning));
-                                krate
+                                resolution.warnings.push(warning);
+                                ResolvedDocs::Local(krate)
                             }
                         }
                     } else {
-                        // External dependency - fetch from docs.rs
-                        let use_cache = !parsed_args.no_cache;
-                        fetch_docs(&resolved.name, &resolved.version, use_cache)?
+                        // External dependency - fetch from docs.rs, or the
+                        // docs server of its registry
+                        let registry = resolver.registry_of(&resolved.name, &resolved.version);
+                        ResolvedDocs::DocsRs {
+                            name: qualified_name(registry.as_deref(), &resolved.name),
+                            version: resolved.version,
+                        }
                     }
                 } else {
                     // Not found in project, use latest
-                    output.push_str(&format!(
-                        "{}\n\n",
-                        format!("// {}@latest", crate_spec.original_name).bright_black()
-                    ));
-                    let use_cache = !parsed_args.no_cache;
-                    fetch_docs(&crate_spec.original_name, "latest", use_cache)?
+                    resolution.message = Some(format!("{}@latest", crate_spec.original_name));
+                    ResolvedDocs::DocsRs {
+                        name: outside_name,
+                        version: "latest".to_string(),
+                    }
                 }
             }
             Err(_) => {
                 // No Cargo.toml found, default to latest
-                output.push_str(&format!(
-                    "{}\n\n",
-                    format!("// {}@latest", crate_spec.original_name).bright_black()
-                ));
-                let use_cache = !parsed_args.no_cache;
-                fetch_docs(&crate_spec.original_name, "latest", use_cache)?
+                resolution.message = Some(format!("{}@latest", crate_spec.original_name));
+                ResolvedDocs::DocsRs {
+                    name: outside_name,
+                    version: "latest".to_string(),
+                }
             }
         }
     };
 
-    let doc = JsonDoc::from(krate);
-
-    // Determine the output based on path and filter
-    let (description, result) = match (path_prefix.as_deref(), filter.as_deref()) {
-        // Pure navigation: show doc for exact path
-        (Some(prefix), None) => {
-            let full_path = format!("{}::{}", crate_spec.name, prefix);
-            let id = doc
-                .find_item_by_path(&full_path)
-                .ok_or_else(|| anyhow::anyhow!("No item found at {}", full_path))?;
-            let kind_str = doc
-                .crate_data()
-                .index
-                .get(&id)
-                .and_then(|item| EntryKind::from_item_enum(&item.inner))
-                .map(|k| format!("{} ", k.keyword()))
-                .unwrap_or_default();
-            let desc = format!("// found {}{}", kind_str, full_path);
-            (desc, doc::signature_for_id(&doc, &id)?)
+    // Docs servers of private registries may only know full versions, so
+    // requirements and `latest` are resolved in the registry's index
+    if let ResolvedDocs::DocsRs { name, version } = &mut resolved
+        && let (Some(registry), crate_name) = split_qualified_name(name)
+    {
+        resolution.registry = Some(registry.to_string());
+        if Version::parse(version).is_err() {
+            let entry =
+                registry_index::newest_matching(name, &registry_index::version_req(version)?)?;
+            resolution.message = Some(format!(
+                "{crate_name}@{} (newest in {registry})",
+                entry.vers
+            ));
+            *version = entry.vers;
         }
-        // Search mode: filter items and show list or single doc
-        (path_prefix, Some(filter)) => {
-            let mut list = list_items(&doc);
-
-            // Filter by path prefix if provided
-            if let Some(prefix) = path_prefix {
-                filter_by_path_prefix(&mut list, &crate_spec.name, prefix);
-            }
+    }
+    Ok(resolved)
+}
 
-            // Track whether filter narrows the list
-            let pre_filter_count = list.len();
-            filter_list(&mut list, filter);
-            let filter_matched = list.len() < pre_filter_count;
+/// Split a rustdoc-style disambiguator off the last segment of a path, as in
+/// `sync::struct@Mutex`, `select!` or `spawn()`.
+fn split_disambiguator(path: &str) -> anyhow::Result<(Option<EntryKind>, String)> {
+    let (parent, last) = match path.rsplit_once("::") {
+        Some((parent, last)) => (Some(parent), last),
+        None => (None, path),
+    };
+    let (kind, name) = if let Some((disambiguator, name)) = last.split_once('@') {
+        let kind = EntryKind::from_disambiguator(disambiguator).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown disambiguator \"{}\". Use one of: mod, struct, enum, trait, fn, const, static, type, macro",
+                disambiguator
+            )
+        })?;
+        (Some(kind), name)
+    } else if let Some(name) = last.strip_suffix('!') {
+        (Some(EntryKind::Macro), name)
+    } else if let Some(name) = last.strip_suffix("()") {
+        (Some(EntryKind::Function), name)
+    } else {
+        (None, last)
+    };
+    let path = match parent {
+        Some(parent) => format!("{parent}::{name}"),
+        None => name.to_string(),
+    };
+    Ok((kind, path))
+}
 
-            list.sort_by(|item1, item2| item1.path.cmp(&item2.path));
+/// A tree or search result from the search index.
+enum ListView {
+    /// The listed items
+    Matches(MatchKind, Vec<ListItem>),
+    /// The only item the search matched, whose docs are shown instead
+    Single(ListItem),
+}
 
-            if list.len() == 1 {
-                let desc = format!("// found {} {}", list[0].kind.keyword(), list[0].path);
-                (desc, doc::signature_for_id(&doc, &list[0].id)?)
-            } else {
-                let colorizer = rustdoc_fmt::Colorizer::get();
+/// Find the tree or search result for `args` in the items in `index`.
+fn list_view(index: &SearchIndex, crate_spec: &CrateSpec, args: &Query) -> ListView {
+    let path_prefix = crate_spec.path_prefix.as_deref();
+    let filter = args.filter.as_deref();
+    let mut list = index.items().to_vec();
+    if let Some(kind) = args.kind {
+        list.retain(|item| item.kind == kind);
+    }
+    if !args.qualifiers.is_empty() {
+        list.retain(|item| item.qualifiers.contains(args.qualifiers));
+    }
 
-                let desc = if filter_matched {
-                    format!("// {} items matching \"{}\"", list.len(), filter)
-                } else {
-                    format!(
-                        "// no matches for \"{}\" \u{2014} showing all {} items",
-                        filter,
-                        list.len()
-                    )
-                };
-
-                let items: Vec<String> = list
-                    .iter()
-                    .map(|entry| colorizer.tokens(&entry.as_output().into_tokens()))
-                    .collect();
-
-                (desc, items.join("\n"))
-            }
+    if args.tree {
+        // Tree overview of everything under the path, optionally filtered
+        if let Some(prefix) = path_prefix {
+            filter_by_path_prefix(&mut list, &crate_spec.name, prefix);
         }
-        // No path, no filter: show crate root doc
-        (None, None) => {
-            let id = doc.crate_root_id();
-            let desc = format!("// showing mod {} (crate root)", crate_spec.name);
-            (desc, doc::signature_for_id(&doc, &id)?)
+        if let Some(filter) = filter {
+            filter_list(&mut list, filter);
         }
-    };
+        let root = match path_prefix {
+            Some(prefix) => format!("{}::{}", crate_spec.name, prefix),
+            None => crate_spec.name.clone(),
+        };
+        return ListView::Matches(MatchKind::Tree { root }, list);
+    }
 
-    // Build final output: version line + description line + content
-    let description_line = format!("{}", description.bright_black());
-    if output.is_empty() {
-        Ok(format!("{}\n\n{}", description_line, result))
+    // Search mode: filter items and show list or single doc
+    let filter = filter.unwrap_or_default();
+
+    // Filter by path prefix if provided
+    if let Some(prefix) = path_prefix {
+        filter_by_path_prefix(&mut list, &crate_spec.name, prefix);
+    }
+
+    // Track whether filter narrows the list
+    let pre_filter_count = list.len();
+    if args.full_text {
+        filter_full_text(&mut list, filter, &index.matching_docs(filter));
     } else {
-        // output ends with \n\n; replace trailing \n with description + \n\n
-        let trimmed = output.trim_end_matches('\n');
-        Ok(format!("{}\n{}\n\n{}", trimmed, description_line, result))
+        filter_list(&mut list, filter);
+    }
+    let matched = list.len() < pre_filter_count;
+
+    list.sort_by(|item1, item2| item1.path.cmp(&item2.path));
+
+    if list.len() == 1 {
+        return ListView::Single(list.remove(0));
     }
+
+    let kind = MatchKind::Search {
+        filter: filter.to_string(),
+        matched,
+    };
+    ListView::Matches(kind, list)
 }
 
 /// Filter items by path prefix.
 /// Keeps items where path starts with `{crate_name}::{prefix}` (matching all descendants).
 fn filter_by_path_prefix(list: &mut Vec<ListItem>, crate_name: &str, prefix: &str) {
+    list.retain(|item| has_path_prefix(item, crate_name, prefix));
+}
+
+/// Whether `item` is at `{crate_name}::{prefix}` or under it.
+fn has_path_prefix(item: &ListItem, crate_name: &str, prefix: &str) -> bool {
     let full_prefix = format!("{crate_name}::{prefix}");
-    list.retain(|item| {
-        // Match exact prefix or prefix followed by ::
-        item.path == full_prefix || item.path.starts_with(&format!("{full_prefix}::"))
-    });
+    // Match exact prefix or prefix followed by ::
+    item.path == full_prefix || item.path.starts_with(&format!("{full_prefix}::"))
 }
 
 fn filter_list(list: &mut Vec<ListItem>, filter: &str) {
@@ -301,3 +1860,42 @@ fn filter_list(list: &mut Vec<ListItem>, filter: &str) {
         *list = matching_sub;
     }
 }
+
+/// Keep items whose path contains `filter` or that are in `doc_matches`,
+/// leaving the list as is when nothing matches.
+fn filter_full_text(list: &mut Vec<ListItem>, filter: &str, doc_matches: &BTreeSet<Id>) {
+    let matching: Vec<_> = list
+        .iter()
+        .filter(|item| item.path.contains(filter) || doc_matches.contains(&item.id))
+        .cloned()
+        .collect();
+
+    if !matching.is_empty() {
+        *list = matching;
+    }
+}
+
+/// Held by unit tests using the process-wide doc source or cache
+/// directory, so none runs while another points them elsewhere.
+#[cfg(all(test, feature = "native"))]
+pub(crate) fn lock_test_globals() -> std::sync::MutexGuard<'static, ()> {
+    static GLOBALS: std::sync::Mutex<()> = std::sync::Mutex::new(());
+    GLOBALS
+        .lock()
+        .unwrap_or_else(std::sync::PoisonError::into_inner)
+}
+
+/// Path of the rustdoc JSON `file` of a test crate, written to `target/doc`
+/// by `scripts/generate-test-docs.sh`.
+#[cfg(test)]
+pub(crate) fn test_docs(file: &str) -> std::path::PathBuf {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../target/doc")
+        .join(file);
+    assert!(
+        path.exists(),
+        "{} is missing, run scripts/generate-test-docs.sh first",
+        path.display()
+    );
+    path
+}
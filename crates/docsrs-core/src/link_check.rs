@@ -0,0 +1,121 @@
+//! Intra-doc links of a workspace crate that don't resolve, see `docsrs
+//! <crate> --check-links`.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use rustdoc_fmt::{DocLink, DocLinkKind, collect_links};
+use rustdoc_types::{Crate, Id};
+
+use crate::{Query, Resolution, resolve_docs};
+
+/// An intra-doc link that doesn't resolve.
+#[derive(Clone, Debug)]
+pub struct BrokenLink {
+    /// Path of the item whose docs have the link
+    pub item_path: String,
+    pub link: DocLink,
+}
+
+/// The broken intra-doc links of a version of a crate.
+pub struct LinkCheck {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub version: String,
+    /// In the order of the items, then of the links in their docs
+    pub broken: Vec<BrokenLink>,
+}
+
+/// Check the intra-doc links in the docs of the crate in `args`, which must
+/// be a crate of the workspace, against the items rustdoc resolved them to.
+pub fn check_links(args: &Query) -> Result<LinkCheck> {
+    let mut resolution = Resolution::default();
+    let doc = resolve_docs(&args.spec, &mut resolution)?.load_doc(args.no_cache, &*args.events)?;
+    if resolution.local.is_none() {
+        bail!(
+            "--check-links checks crates of the workspace, and {} isn't one",
+            args.spec.original_name
+        );
+    }
+    let krate = doc.crate_data();
+    let version = krate
+        .crate_version
+        .clone()
+        .context("The docs don't say which version of the crate they're for")?;
+
+    let mut checked = HashSet::new();
+    let mut broken = Vec::new();
+    for item in doc.items() {
+        let data = item.item();
+        if data.crate_id != 0 || !checked.insert(data.id) {
+            continue;
+        }
+        let Some(docs) = &data.docs else {
+            continue;
+        };
+        broken.extend(
+            collect_links(docs)
+                .into_iter()
+                .filter(|link| link.kind == DocLinkKind::IntraDoc)
+                .filter(|link| !resolves(krate, &data.links, &link.target))
+                .map(|link| BrokenLink {
+                    item_path: item.path_string(),
+                    link,
+                }),
+        );
+    }
+    Ok(LinkCheck {
+        resolution,
+        crate_name: args.spec.name.clone(),
+        version,
+        broken,
+    })
+}
+
+/// Whether rustdoc resolved `target` to an item it knows, in this crate or
+/// another one.
+fn resolves(krate: &Crate, links: &HashMap<String, Id>, target: &str) -> bool {
+    links
+        .get(target)
+        .is_some_and(|id| krate.index.contains_key(id) || krate.paths.contains_key(id))
+}
+
+impl LinkCheck {
+    /// Render one line per broken link: the item, the link text, and what it
+    /// points to when that isn't the text.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let header = match self.broken.len() {
+            0 => format!(
+                "// no broken intra-doc links in {} {}",
+                self.crate_name, self.version
+            ),
+            1 => format!(
+                "// 1 broken intra-doc link in {} {}",
+                self.crate_name, self.version
+            ),
+            count => format!(
+                "// {count} broken intra-doc links in {} {}",
+                self.crate_name, self.version
+            ),
+        };
+        output.push_str(&format!("{}\n", header.bright_black()));
+        if !self.broken.is_empty() {
+            output.push('\n');
+        }
+
+        for broken in &self.broken {
+            let DocLink { text, target, .. } = &broken.link;
+            output.push_str(&format!("{}: [{text}]", broken.item_path));
+            if target.trim_matches('`') != text {
+                output.push_str(&format!("({target})"));
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
@@ -1,8 +1,9 @@
 use jsondoc::JsonDocItem;
-use rustdoc_fmt::Output;
+use rustdoc_fmt::{Link, Output};
 use rustdoc_types::{Id, ItemEnum};
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum EntryKind {
     Module,
     Struct,
@@ -42,7 +43,24 @@ impl EntryKind {
         })
     }
 
-    pub(crate) fn keyword(self) -> &'static str {
+    /// Parse a rustdoc-style disambiguator, the `struct` in `struct@Foo`.
+    pub(crate) fn from_disambiguator(disambiguator: &str) -> Option<Self> {
+        Some(match disambiguator {
+            "mod" | "module" => EntryKind::Module,
+            "struct" => EntryKind::Struct,
+            "enum" => EntryKind::Enum,
+            "trait" => EntryKind::Trait,
+            "fn" | "function" | "method" => EntryKind::Function,
+            "const" | "constant" => EntryKind::Constant,
+            "static" => EntryKind::Static,
+            "type" | "tyalias" => EntryKind::TypeAlias,
+            "macro" => EntryKind::Macro,
+            _ => return None,
+        })
+    }
+
+    /// Rust keyword introducing the item, e.g. `fn`.
+    pub fn keyword(self) -> &'static str {
         match self {
             EntryKind::Module => "mod",
             EntryKind::Struct => "struct",
@@ -57,14 +75,52 @@ impl EntryKind {
     }
 }
 
+/// The `const`, `async` and `unsafe` qualifiers of a function, all unset
+/// for other items.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Qualifiers {
+    pub is_const: bool,
+    pub is_async: bool,
+    pub is_unsafe: bool,
+}
+
+impl Qualifiers {
+    pub(crate) fn from_item_enum(item: &ItemEnum) -> Self {
+        match item {
+            ItemEnum::Function(function) => Self {
+                is_const: function.header.is_const,
+                is_async: function.header.is_async,
+                is_unsafe: function.header.is_unsafe,
+            },
+            _ => Self::default(),
+        }
+    }
+
+    /// Whether no qualifier is set.
+    pub fn is_empty(self) -> bool {
+        self == Self::default()
+    }
+
+    /// Whether every qualifier set in `required` is also set here.
+    pub fn contains(self, required: Self) -> bool {
+        (self.is_const || !required.is_const)
+            && (self.is_async || !required.is_async)
+            && (self.is_unsafe || !required.is_unsafe)
+    }
+}
+
 /// Represent a public item of an analyzed crate, i.e. an item that forms part
 /// of the public API of a crate.
-#[derive(Clone)]
+#[derive(Clone, Serialize, Deserialize)]
 pub struct ListItem {
     module: Vec<(String, EntryKind)>,
     pub path: String,
-    pub(crate) kind: EntryKind,
+    pub kind: EntryKind,
     pub id: Id,
+    /// Qualifiers of a function
+    pub qualifiers: Qualifiers,
+    /// First sentence of the item's docs
+    pub summary: Option<String>,
 }
 
 impl ListItem {
@@ -97,9 +153,16 @@ impl ListItem {
             path,
             kind,
             id: item.id(),
+            qualifiers: Qualifiers::from_item_enum(&item.item().inner),
+            summary: item.item().docs.as_deref().and_then(doc_summary),
         })
     }
 
+    /// Path segments with the kind of item each one names.
+    pub(crate) fn segments(&self) -> &[(String, EntryKind)] {
+        &self.module
+    }
+
     /// Full-path output for search results: `fn crate::path::name`
     pub fn as_output(&self) -> Output {
         let mut out = Output::new();
@@ -120,6 +183,33 @@ impl ListItem {
         out
     }
 
+    /// Output of the item's parent, e.g. `mod crate::path`, or `None` for the
+    /// crate root.
+    pub(crate) fn parent_output(&self) -> Option<Output> {
+        let (_, parents) = self.module.split_last()?;
+        let (_, parent_kind) = parents.last()?;
+
+        let mut out = Output::new();
+        out.kind(parent_kind.keyword()).whitespace();
+        for (i, (seg, seg_kind)) in parents.iter().enumerate() {
+            if i > 0 {
+                out.symbol("::");
+            }
+            Self::color_path_segment(&mut out, seg, seg_kind);
+        }
+        Some(out)
+    }
+
+    /// Name-only output without visibility: `struct Name`
+    pub(crate) fn as_name_output(&self) -> Output {
+        let mut out = Output::new();
+        out.kind(self.kind.keyword()).whitespace();
+        if let Some((name, _)) = self.module.last() {
+            Self::color_last_segment(&mut out, name, self.kind);
+        }
+        out
+    }
+
     /// Module-relative output for child listings: `pub struct Name`
     pub fn as_module_child(&self) -> Output {
         let mut out = Output::new();
@@ -135,22 +225,22 @@ impl ListItem {
         out
     }
 
-    fn color_last_segment(out: &mut Output, seg: &str, kind: EntryKind) {
+    pub(crate) fn color_last_segment(out: &mut Output, seg: &str, kind: EntryKind) {
         match kind {
             EntryKind::Macro => {
-                out.identifier(seg).symbol("!");
+                out.identifier(seg.to_string()).symbol("!");
             }
             EntryKind::Constant | EntryKind::Static => {
-                out.symbol(seg);
+                out.symbol(seg.to_string());
             }
             EntryKind::Enum | EntryKind::Struct | EntryKind::Trait | EntryKind::TypeAlias => {
-                out.type_(seg);
+                out.type_(seg.to_string());
             }
             EntryKind::Function => {
-                out.function(seg);
+                out.function(seg.to_string());
             }
             _ => {
-                out.identifier(seg);
+                out.identifier(seg.to_string());
             }
         };
     }
@@ -158,20 +248,138 @@ impl ListItem {
     fn color_path_segment(out: &mut Output, seg: &str, seg_kind: &EntryKind) {
         match seg_kind {
             EntryKind::Enum | EntryKind::Struct | EntryKind::Trait | EntryKind::TypeAlias => {
-                out.type_(seg);
+                out.type_(seg.to_string());
             }
             EntryKind::Function => {
-                out.function(seg);
+                out.function(seg.to_string());
             }
             EntryKind::Macro => {
-                out.identifier(seg).symbol("!");
+                out.identifier(seg.to_string()).symbol("!");
             }
             EntryKind::Constant | EntryKind::Static => {
-                out.symbol(seg);
+                out.symbol(seg.to_string());
             }
             _ => {
-                out.identifier(seg);
+                out.identifier(seg.to_string());
             }
         };
     }
 }
+
+/// Extract the first sentence of a doc comment as plain, single-line text.
+///
+/// Only the first paragraph is considered. Markdown links are reduced to their
+/// text, and the sentence ends at the first period followed by whitespace.
+pub(crate) fn doc_summary(docs: &str) -> Option<String> {
+    let paragraph = docs
+        .lines()
+        .map(str::trim)
+        .skip_while(|line| line.is_empty())
+        .take_while(|line| !line.is_empty())
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    let text = strip_markdown_links(&paragraph);
+    let sentence = match text.find(". ") {
+        Some(end) => &text[..=end],
+        None => text.as_str(),
+    };
+    let sentence = sentence.trim();
+    (!sentence.is_empty()).then(|| sentence.to_string())
+}
+
+/// Replace `[text](url)` and `[text][ref]` with `text`, and `[text]` with `text`.
+/// Disambiguators are left out of links like ``[`fn@spawn`]``.
+fn strip_markdown_links(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(open) = rest.find('[') {
+        let Some(close) = rest[open..].find(']').map(|i| open + i) else {
+            break;
+        };
+        result.push_str(&rest[..open]);
+        let label = &rest[open + 1..close];
+        rest = &rest[close + 1..];
+        // Skip an inline destination, which may have parentheses in it like
+        // `super::builder()`, or a reference label
+        let end = match rest.chars().next() {
+            Some('(') => closing_paren(rest),
+            Some('[') => rest.find(']'),
+            _ => None,
+        };
+        let mut dest = label;
+        if let Some(end) = end {
+            dest = &rest[1..end];
+            rest = &rest[end + 1..];
+        }
+        let name = label.trim_matches('`');
+        result.push_str(&label.replacen(name, Link::new(name, dest).display_text(), 1));
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Index of the `)` closing the `(` that `text` starts with.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    for (i, c) in text.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_doc_summary_first_sentence() {
+        assert_eq!(
+            doc_summary("Spawns a task. The task runs\nin the background.").as_deref(),
+            Some("Spawns a task.")
+        );
+    }
+
+    #[test]
+    fn test_doc_summary_first_paragraph_only() {
+        assert_eq!(
+            doc_summary("\nA channel\nfor messages\n\nMore details.").as_deref(),
+            Some("A channel for messages")
+        );
+    }
+
+    #[test]
+    fn test_doc_summary_strips_links() {
+        assert_eq!(
+            doc_summary("Like [`Vec`](std::vec::Vec) but [growable][grow].").as_deref(),
+            Some("Like `Vec` but growable.")
+        );
+        assert_eq!(
+            doc_summary("See [`Mutex`] for details").as_deref(),
+            Some("See `Mutex` for details")
+        );
+        assert_eq!(
+            doc_summary("Like [`fn@spawn`] and [`struct@Task`](Task)").as_deref(),
+            Some("Like `spawn` and `struct@Task`")
+        );
+        assert_eq!(
+            doc_summary("Options for the [`builder()`](super::builder())").as_deref(),
+            Some("Options for the `builder()`")
+        );
+    }
+
+    #[test]
+    fn test_doc_summary_empty() {
+        assert_eq!(doc_summary(""), None);
+        assert_eq!(doc_summary("\n\n"), None);
+    }
+}
@@ -1,13 +1,156 @@
-pub(crate) use crate::list::list_item::EntryKind;
+pub use crate::list::list_item::EntryKind;
 pub use crate::list::list_item::ListItem;
+pub use crate::list::list_item::Qualifiers;
+use std::collections::BTreeMap;
+
+use colored::Colorize;
 use jsondoc::JsonDoc;
+use rustdoc_fmt::{Colorizer, Output, display_width, tokens_to_string, truncate_to_width};
 
 mod list_item;
+mod tree;
+
+#[cfg(feature = "native")]
+pub(crate) use list_item::doc_summary;
+pub(crate) use tree::render_tree;
+
+/// Total width of list output when the terminal width is unknown.
+const DEFAULT_LIST_WIDTH: usize = 100;
+
+/// Summaries are never truncated below this many columns.
+const MIN_SUMMARY_WIDTH: usize = 20;
 
 /// Extract public API from a crate.
 pub(crate) fn list_items(doc: &JsonDoc) -> Vec<ListItem> {
-    doc.items()
-        .iter()
+    doc.iter_items()
         .filter_map(ListItem::from_jsondoc_item)
         .collect()
 }
+
+/// Render list entries one per line, optionally followed by an aligned
+/// `// summary` column truncated to fit `max_width`.
+pub(crate) fn render_list(
+    list: &[ListItem],
+    colorizer: &Colorizer,
+    summaries: bool,
+    max_width: Option<usize>,
+) -> String {
+    let rows: Vec<(Output, Option<&str>)> = list
+        .iter()
+        .map(|entry| (entry.as_output(), entry.summary.as_deref()))
+        .collect();
+    render_rows(rows, "", colorizer, summaries, max_width).join("\n")
+}
+
+/// Render list entries under a header for each parent module, with entries
+/// shown by name only. Groups are ordered by module path and summaries are
+/// aligned across all groups.
+pub(crate) fn render_grouped(
+    list: &[ListItem],
+    colorizer: &Colorizer,
+    summaries: bool,
+    max_width: Option<usize>,
+) -> String {
+    let mut groups: BTreeMap<String, (Option<Output>, Vec<&ListItem>)> = BTreeMap::new();
+    for item in list {
+        let segments = item.segments();
+        let key = segments[..segments.len().saturating_sub(1)]
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>()
+            .join("::");
+        groups
+            .entry(key)
+            .or_insert_with(|| (item.parent_output(), Vec::new()))
+            .1
+            .push(item);
+    }
+
+    let rows: Vec<(Output, Option<&str>)> = groups
+        .values()
+        .flat_map(|(_, items)| items.iter())
+        .map(|item| (item.as_name_output(), item.summary.as_deref()))
+        .collect();
+    let mut lines = render_rows(rows, GROUP_INDENT, colorizer, summaries, max_width).into_iter();
+
+    groups
+        .into_values()
+        .map(|(parent, items)| {
+            let mut group = Vec::with_capacity(items.len() + 1);
+            if let Some(parent) = parent {
+                group.push(colorizer.tokens(&parent.into_tokens()));
+            }
+            group.extend(lines.by_ref().take(items.len()));
+            group.join("\n")
+        })
+        .collect::<Vec<_>>()
+        .join("\n\n")
+}
+
+/// Entries under a module header are indented by this much.
+const GROUP_INDENT: &str = "    ";
+
+/// Render items that share a path, each followed by an aligned `// hint`
+/// showing how to select it.
+pub(crate) fn render_candidates(
+    candidates: &[(ListItem, String)],
+    colorizer: &Colorizer,
+    max_width: Option<usize>,
+) -> String {
+    let rows: Vec<(Output, Option<&str>)> = candidates
+        .iter()
+        .map(|(entry, hint)| (entry.as_output(), Some(hint.as_str())))
+        .collect();
+    render_rows(rows, "", colorizer, true, max_width).join("\n")
+}
+
+/// Colorize rows and append their summaries in a column wide enough for the
+/// widest row, returning one line per row.
+pub(crate) fn render_rows(
+    rows: Vec<(Output, Option<&str>)>,
+    indent: &str,
+    colorizer: &Colorizer,
+    summaries: bool,
+    max_width: Option<usize>,
+) -> Vec<String> {
+    let entries: Vec<(String, usize, Option<&str>)> = rows
+        .into_iter()
+        .map(|(output, summary)| {
+            let tokens = output.into_tokens();
+            let width = indent.len() + display_width(&tokens_to_string(&tokens));
+            (
+                format!("{indent}{}", colorizer.tokens(&tokens)),
+                width,
+                summary,
+            )
+        })
+        .collect();
+
+    if !summaries {
+        return entries.into_iter().map(|(line, _, _)| line).collect();
+    }
+
+    let total_width = max_width.unwrap_or(DEFAULT_LIST_WIDTH);
+    // Keep a single very long path from pushing every summary off screen
+    let column = entries
+        .iter()
+        .map(|(_, width, _)| *width)
+        .max()
+        .unwrap_or(0)
+        .min(total_width * 3 / 5);
+    let summary_width = total_width
+        .saturating_sub(column + "  // ".len())
+        .max(MIN_SUMMARY_WIDTH);
+
+    entries
+        .into_iter()
+        .map(|(line, width, summary)| match summary {
+            Some(summary) => {
+                let padding = " ".repeat(column.saturating_sub(width));
+                let comment = format!("// {}", truncate_to_width(summary, summary_width));
+                format!("{line}{padding}  {}", comment.bright_black())
+            }
+            None => line,
+        })
+        .collect()
+}
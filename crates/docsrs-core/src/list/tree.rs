@@ -0,0 +1,66 @@
+use std::collections::BTreeMap;
+
+use rustdoc_fmt::{Colorizer, Output};
+
+use super::{EntryKind, ListItem};
+
+/// A node in the module tree, keyed by `(name, kind keyword)` so that items
+/// sharing a name (e.g. a struct and a macro) stay distinct.
+#[derive(Default)]
+struct TreeNode {
+    children: BTreeMap<(String, &'static str), (EntryKind, TreeNode)>,
+}
+
+impl TreeNode {
+    fn insert(&mut self, segments: &[(String, EntryKind)]) {
+        if let Some(((name, kind), rest)) = segments.split_first() {
+            let (_, child) = self
+                .children
+                .entry((name.clone(), kind.keyword()))
+                .or_insert_with(|| (*kind, TreeNode::default()));
+            child.insert(rest);
+        }
+    }
+}
+
+/// Render items as an indented tree with box-drawing connectors, like
+/// `cargo tree`. Every item's ancestors are included even if they aren't
+/// themselves in the list.
+pub(crate) fn render_tree(list: &[ListItem], colorizer: &Colorizer) -> String {
+    let mut root = TreeNode::default();
+    for item in list {
+        root.insert(item.segments());
+    }
+
+    let mut output = String::new();
+    for ((name, _), (kind, node)) in &root.children {
+        output.push_str(&colorizer.tokens(&node_label(name, *kind).into_tokens()));
+        output.push('\n');
+        write_children(&mut output, node, "", colorizer);
+    }
+    output.truncate(output.trim_end().len());
+    output
+}
+
+fn write_children(output: &mut String, node: &TreeNode, prefix: &str, colorizer: &Colorizer) {
+    let count = node.children.len();
+    for (index, ((name, _), (kind, child))) in node.children.iter().enumerate() {
+        let is_last = index + 1 == count;
+        let connector = if is_last { "└── " } else { "├── " };
+        output.push_str(prefix);
+        output.push_str(connector);
+        output.push_str(&colorizer.tokens(&node_label(name, *kind).into_tokens()));
+        output.push('\n');
+
+        let child_prefix = format!("{prefix}{}", if is_last { "    " } else { "│   " });
+        write_children(output, child, &child_prefix, colorizer);
+    }
+}
+
+/// `kind name` label for a tree node.
+fn node_label(name: &str, kind: EntryKind) -> Output {
+    let mut out = Output::new();
+    out.kind(kind.keyword()).whitespace();
+    ListItem::color_last_segment(&mut out, name, kind);
+    out
+}
@@ -0,0 +1,70 @@
+//! Man pages of items and modules, see `docsrs <item> --format man`.
+
+use anyhow::Result;
+use rustdoc_fmt::{escape_roff, markdown_to_roff};
+
+use crate::doc::plain_declaration;
+use crate::list::doc_summary;
+use crate::result::ItemDoc;
+
+/// Width the synopsis is wrapped at, that of a terminal `man` shows pages in.
+const MAX_WIDTH: usize = 78;
+
+/// Section of the manual the pages are in, that of library functions.
+const SECTION: &str = "3";
+
+/// The man page of `item` in roff, with the `man` macros: its path and
+/// summary, its declaration, which for modules lists their items, its docs
+/// and where to read them online.
+pub(crate) fn man_page(item: &ItemDoc) -> Result<String> {
+    let krate = item.doc.crate_data();
+    let docs = match (&item.docs, &item.readme) {
+        (Some(docs), _) if !docs.trim().is_empty() => Some(docs),
+        (_, readme) => readme.as_ref(),
+    };
+    let source = match &krate.crate_version {
+        Some(version) => format!("{} {version}", crate_name(&item.path)),
+        None => crate_name(&item.path).to_string(),
+    };
+
+    let mut page = format!(
+        ".TH \"{}\" \"{SECTION}\" \"\" \"{}\" \"Rust API Documentation\"\n",
+        escape_roff(&item.path),
+        escape_roff(&source)
+    );
+    page.push_str(".SH NAME\n");
+    let kind = item
+        .kind
+        .map(|kind| format!("{} ", kind.keyword()))
+        .unwrap_or_default();
+    page.push_str(&escape_roff(&format!("{kind}{}", item.path)));
+    if let Some(summary) = docs.and_then(|docs| doc_summary(docs)) {
+        page.push_str(&format!(" \\- {}", escape_roff(&summary.replace('`', ""))));
+    }
+    page.push('\n');
+
+    let declaration = plain_declaration(&item.doc, &item.id, Some(MAX_WIDTH))?;
+    page.push_str(".SH SYNOPSIS\n.nf\n");
+    for line in declaration.trim_end().lines() {
+        if line.starts_with('.') || line.starts_with('\'') {
+            page.push_str("\\&");
+        }
+        page.push_str(&escape_roff(line));
+        page.push('\n');
+    }
+    page.push_str(".fi\n");
+
+    if let Some(docs) = docs {
+        page.push_str(".SH DESCRIPTION\n");
+        page.push_str(&markdown_to_roff(docs));
+    }
+    if let Some(url) = item.docs_url() {
+        page.push_str(&format!(".SH SEE ALSO\n{}\n", escape_roff(&url)));
+    }
+    Ok(page)
+}
+
+/// The crate an item path starts with.
+fn crate_name(path: &str) -> &str {
+    path.split("::").next().unwrap_or(path)
+}
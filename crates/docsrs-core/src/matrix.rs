@@ -0,0 +1,199 @@
+//! Which of the commonly implemented traits the types of a module or crate
+//! implement, see `docsrs <crate> --matrix`.
+
+use std::collections::{HashMap, HashSet};
+
+use anyhow::{Context, Result, bail};
+use colored::Colorize;
+use jsondoc::JsonDoc;
+use rustdoc_types::{GenericParamDefKind, Id, Impl};
+
+use crate::list::{EntryKind, list_items};
+use crate::{Query, Resolution, resolve_docs};
+
+/// The traits in the columns, in order, by their last path segment.
+const TRAITS: &[&str] = &[
+    "Clone",
+    "Copy",
+    "Debug",
+    "Display",
+    "Default",
+    "PartialEq",
+    "Eq",
+    "PartialOrd",
+    "Ord",
+    "Hash",
+    "Send",
+    "Sync",
+    "Unpin",
+    "Serialize",
+    "Deserialize",
+];
+
+/// Whether a type implements a trait.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Implements {
+    No,
+    Yes,
+    /// Only when its type parameters meet some bounds, e.g. `Vec<T>` is
+    /// `Clone` when `T: Clone`
+    WithBounds,
+}
+
+/// The commonly implemented traits of the structs and enums of a module.
+pub struct TraitMatrix {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub version: String,
+    /// Path of the module, or the crate
+    pub scope: String,
+    /// The traits that any of the types implement
+    pub traits: Vec<&'static str>,
+    /// Path of each type under the scope, and whether it implements each
+    /// trait
+    pub rows: Vec<(String, Vec<Implements>)>,
+}
+
+/// The trait matrix of the structs and enums under the path of `args`, or
+/// of the whole crate.
+pub fn trait_matrix(args: &Query) -> Result<TraitMatrix> {
+    let mut resolution = Resolution::default();
+    let doc = resolve_docs(&args.spec, &mut resolution)?.load_doc(args.no_cache, &*args.events)?;
+    let version = doc
+        .crate_data()
+        .crate_version
+        .clone()
+        .context("The docs don't say which version of the crate they're for")?;
+
+    // Paths start with the library name, `serde_json` for `serde-json`
+    let library = args.spec.name.replace('-', "_");
+    let scope = match &args.spec.path_prefix {
+        Some(prefix) => format!("{library}::{prefix}"),
+        None => library,
+    };
+    let prefix = format!("{scope}::");
+    let mut seen = HashSet::new();
+    let mut types: Vec<(String, Id)> = list_items(&doc)
+        .into_iter()
+        .filter(|item| matches!(item.kind, EntryKind::Struct | EntryKind::Enum))
+        .filter_map(|item| Some((item.path.strip_prefix(&prefix)?.to_string(), item.id)))
+        .filter(|(_, id)| seen.insert(*id))
+        .collect();
+    if types.is_empty() {
+        bail!("No structs or enums in {scope}");
+    }
+    types.sort();
+
+    let all_rows: Vec<(String, Vec<Implements>)> = types
+        .into_iter()
+        .map(|(path, id)| {
+            let implemented = implemented_traits(&doc, &id);
+            let row = TRAITS
+                .iter()
+                .map(|name| implemented.get(name).copied().unwrap_or(Implements::No))
+                .collect();
+            (path, row)
+        })
+        .collect();
+
+    // Leave out the traits none of the types implement
+    let columns: Vec<usize> = (0..TRAITS.len())
+        .filter(|&column| {
+            all_rows
+                .iter()
+                .any(|(_, row)| row[column] != Implements::No)
+        })
+        .collect();
+    Ok(TraitMatrix {
+        resolution,
+        crate_name: args.spec.name.clone(),
+        version,
+        scope,
+        traits: columns.iter().map(|&column| TRAITS[column]).collect(),
+        rows: all_rows
+            .into_iter()
+            .map(|(path, row)| (path, columns.iter().map(|&column| row[column]).collect()))
+            .collect(),
+    })
+}
+
+/// The traits among [`TRAITS`] that the type with `id` implements, by name.
+/// Implementations for other types, such as `impl<T: Debug> Debug for
+/// &T`, aren't counted.
+fn implemented_traits(doc: &JsonDoc, id: &Id) -> HashMap<&'static str, Implements> {
+    let mut implemented = HashMap::new();
+    for impl_ref in doc.impls_for(id) {
+        let impl_ = impl_ref.impl_;
+        let Some(trait_) = &impl_.trait_ else {
+            continue;
+        };
+        let name = trait_.path.rsplit("::").next().unwrap_or(&trait_.path);
+        let Some(name) = TRAITS.iter().find(|known| **known == name) else {
+            continue;
+        };
+        if impl_.is_negative || impl_.blanket_impl.is_some() {
+            continue;
+        }
+        let implements = if has_bounds(impl_) {
+            Implements::WithBounds
+        } else {
+            Implements::Yes
+        };
+        // An unconditional impl wins over one with bounds
+        let entry = implemented.entry(*name).or_insert(implements);
+        if implements == Implements::Yes {
+            *entry = Implements::Yes;
+        }
+    }
+    implemented
+}
+
+/// Whether `impl_` only applies when its type parameters meet bounds.
+fn has_bounds(impl_: &Impl) -> bool {
+    !impl_.generics.where_predicates.is_empty()
+        || impl_.generics.params.iter().any(|param| {
+            matches!(&param.kind, GenericParamDefKind::Type { bounds, .. } if !bounds.is_empty())
+        })
+}
+
+impl TraitMatrix {
+    /// Render a table with a row per type and a column per trait.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let header = format!(
+            "// traits of the types in {} {}: ✓ implemented, ~ with bounds on their type parameters",
+            self.scope, self.version
+        );
+        output.push_str(&format!("{}\n\n", header.bright_black()));
+
+        let name_width = self
+            .rows
+            .iter()
+            .map(|(path, _)| path.chars().count())
+            .max()
+            .unwrap_or(0);
+        output.push_str(&" ".repeat(name_width));
+        for name in &self.traits {
+            output.push_str(&format!("  {}", name.bold()));
+        }
+        output.push('\n');
+        for (path, row) in &self.rows {
+            output.push_str(&format!("{path:name_width$}"));
+            for (name, implements) in self.traits.iter().zip(row) {
+                let mark = match implements {
+                    Implements::Yes => "✓".green(),
+                    Implements::WithBounds => "~".yellow(),
+                    Implements::No => "-".bright_black(),
+                };
+                let padding = " ".repeat(name.len() - 1);
+                output.push_str(&format!("  {mark}{padding}"));
+            }
+            output.truncate(output.trim_end().len());
+            output.push('\n');
+        }
+        output
+    }
+}
@@ -0,0 +1,94 @@
+//! Async variants of the library entry points, for hosts running on tokio
+//! such as the MCP server.
+//!
+//! [`fetch_docs`] downloads over async HTTP and runs only reading the cache,
+//! decompressing and parsing on tokio's blocking thread pool, so a download
+//! holds no thread while it waits on the network. Sources that aren't HTTP
+//! servers, such as a [`crate::DirSource`], are fetched on the blocking pool.
+//! [`query`], [`lookup`] and [`run_cli`] resolve versions and may build docs
+//! locally, so they run on the blocking pool as a whole, and [`run_cli`]
+//! calls run one at a time as the blocking ones do.
+
+use std::sync::Arc;
+
+use anyhow::Result;
+use rustdoc_types::Crate;
+use tokio::task::{JoinError, spawn_blocking};
+
+use crate::docfetch::{load_cached_docs, parse_downloaded_docs, retry_not_found};
+use crate::source::{doc_source_for, download_async};
+use crate::{EventSink, Query, QueryResult};
+
+/// Async [`crate::fetch_docs`].
+pub async fn fetch_docs(
+    crate_name: &str,
+    version: &str,
+    use_cache: bool,
+    events: Arc<dyn EventSink>,
+) -> Result<Crate> {
+    let crate_name = crate_name.to_string();
+    let version = version.to_string();
+    match fetch_docs_inner(&crate_name, &version, use_cache, &events).await {
+        Ok(krate) => Ok(krate),
+        // Retries and local builds are rare, and run blocking
+        Err(err) => {
+            spawn_blocking(move || retry_not_found(&crate_name, &version, use_cache, &*events, err))
+                .await?
+        }
+    }
+}
+
+async fn fetch_docs_inner(
+    crate_name: &str,
+    version: &str,
+    use_cache: bool,
+    events: &Arc<dyn EventSink>,
+) -> Result<Crate> {
+    let cached = {
+        let (crate_name, version, events) =
+            (crate_name.to_string(), version.to_string(), events.clone());
+        spawn_blocking(move || load_cached_docs(&crate_name, &version, use_cache, &*events))
+            .await??
+    };
+    if let Some(krate) = cached {
+        return Ok(krate);
+    }
+
+    let (source, name) = doc_source_for(crate_name)?;
+    events.on_download_start(name, version, &source.location(name, version));
+    let compressed_data = match source.http_request(name, version) {
+        Some((url, token)) => download_async(&url, token.as_ref(), &**events).await?,
+        None => {
+            let (name, version, events) = (name.to_string(), version.to_string(), events.clone());
+            spawn_blocking(move || source.fetch(&name, &version, &*events)).await??
+        }
+    };
+    events.on_download_done(compressed_data.len() as u64);
+
+    let (crate_name, version, events) =
+        (crate_name.to_string(), version.to_string(), events.clone());
+    spawn_blocking(move || {
+        parse_downloaded_docs(&crate_name, &version, use_cache, &compressed_data, &*events)
+    })
+    .await?
+}
+
+/// Async [`crate::query`].
+pub async fn query(query: Query) -> Result<QueryResult> {
+    spawn_blocking(move || crate::query(&query)).await?
+}
+
+/// Async [`crate::lookup`].
+pub async fn lookup(spec: String, filter: Option<String>) -> Result<String> {
+    spawn_blocking(move || crate::lookup(&spec, filter.as_deref())).await?
+}
+
+/// Async [`crate::run_cli`]. Fails with the [`JoinError`] when the CLI
+/// panicked, apart from the errors it reports as its output.
+pub async fn run_cli(args: Vec<String>) -> Result<Result<String, String>, JoinError> {
+    spawn_blocking(move || {
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+        crate::run_cli(&args)
+    })
+    .await
+}
@@ -0,0 +1,192 @@
+//! The builder describing a docs query, run with [`crate::query`].
+
+use std::sync::Arc;
+
+use anyhow::Result;
+
+use crate::cli::Cli;
+use crate::crate_spec::CrateSpec;
+use crate::events::{EventSink, NoEvents, StderrEvents};
+use crate::list::{EntryKind, Qualifiers};
+use crate::util::normalize_crate_name;
+
+/// A docs query: which crate, and which of its items.
+///
+/// ```no_run
+/// # #[cfg(feature = "native")] {
+/// use docsrs_core::{EntryKind, Query, query};
+///
+/// let spawn = Query::crate_("tokio")
+///     .version("1.38")
+///     .path("task::spawn")
+///     .kind(EntryKind::Function);
+/// let result = query(&spawn)?;
+/// # }
+/// # anyhow::Ok(())
+/// ```
+///
+/// Without a path or filter the query answers with the crate root docs. A
+/// path shows the docs of the item at that path, and a filter searches the
+/// items under the path.
+#[derive(Clone)]
+pub struct Query {
+    pub(crate) spec: CrateSpec,
+    pub(crate) kind: Option<EntryKind>,
+    pub(crate) qualifiers: Qualifiers,
+    pub(crate) filter: Option<String>,
+    pub(crate) limit: Option<usize>,
+    pub(crate) tree: bool,
+    pub(crate) full_text: bool,
+    pub(crate) follow_reexports: bool,
+    pub(crate) no_cache: bool,
+    pub(crate) events: Arc<dyn EventSink>,
+}
+
+impl Query {
+    /// Query the crate `name`, at the version resolved from the current
+    /// project or else the latest one.
+    pub fn crate_(name: impl Into<String>) -> Self {
+        let name = name.into();
+        Self::from_spec(CrateSpec {
+            name: normalize_crate_name(&name),
+            original_name: name,
+            version: None,
+            path_prefix: None,
+        })
+    }
+
+    /// Query a `crate[@version][::path]` spec, as given on the command line.
+    pub fn parse(spec: &str) -> Result<Self> {
+        Ok(Self::from_spec(CrateSpec::parse(spec)?))
+    }
+
+    pub(crate) fn from_spec(spec: CrateSpec) -> Self {
+        Self {
+            spec,
+            kind: None,
+            qualifiers: Qualifiers::default(),
+            filter: None,
+            limit: None,
+            tree: false,
+            full_text: false,
+            follow_reexports: false,
+            no_cache: false,
+            events: Arc::new(NoEvents),
+        }
+    }
+
+    /// Build the query given by the command line arguments. Progress is
+    /// printed to stderr.
+    pub fn from_cli(args: &Cli) -> Result<Self> {
+        let spec = args
+            .crate_spec
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("Missing required argument: CRATE_SPEC"))?;
+        let mut query = Self::for_spec(args, spec)
+            .tree(args.tree)
+            .qualifiers(Qualifiers {
+                is_const: args.const_,
+                is_async: args.async_,
+                is_unsafe: args.unsafe_,
+            })
+            .full_text(args.full_text)
+            .follow_reexports(args.follow_reexports);
+        query.filter = args.filter.clone();
+        Ok(query)
+    }
+
+    /// The query for `spec`, given to a subcommand or picked, with the
+    /// caching of the command line arguments. Progress is printed to stderr.
+    pub(crate) fn for_spec(args: &Cli, spec: CrateSpec) -> Self {
+        Self::from_spec(spec)
+            .no_cache(args.no_cache)
+            .events(Arc::new(StderrEvents))
+    }
+
+    /// Use this version requirement instead of resolving the version.
+    pub fn version(mut self, version: impl Into<String>) -> Self {
+        self.spec.version = Some(version.into());
+        self
+    }
+
+    /// Look up the item at `path` within the crate, e.g. `task::spawn`, or
+    /// search under it when a filter is set.
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        let path = path.into();
+        let path = path.trim_end_matches("::");
+        self.spec.path_prefix = (!path.is_empty()).then(|| path.to_string());
+        self
+    }
+
+    /// Only match items of this kind, like a `fn@` disambiguator.
+    pub fn kind(mut self, kind: EntryKind) -> Self {
+        self.kind = Some(kind);
+        self
+    }
+
+    /// Only list functions with all of these qualifiers, e.g. every
+    /// `const fn` under the path.
+    pub fn qualifiers(mut self, qualifiers: Qualifiers) -> Self {
+        self.qualifiers = qualifiers;
+        self
+    }
+
+    /// Search for items whose path contains `filter`.
+    pub fn filter(mut self, filter: impl Into<String>) -> Self {
+        self.filter = Some(filter.into());
+        self
+    }
+
+    /// List at most this many items.
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// List the items as a tree.
+    pub fn tree(mut self, tree: bool) -> Self {
+        self.tree = tree;
+        self
+    }
+
+    /// Also match the filter against the words of item docs.
+    pub fn full_text(mut self, full_text: bool) -> Self {
+        self.full_text = full_text;
+        self
+    }
+
+    /// Show items re-exported from other crates from the docs of the crate
+    /// defining them.
+    pub fn follow_reexports(mut self, follow_reexports: bool) -> Self {
+        self.follow_reexports = follow_reexports;
+        self
+    }
+
+    /// Download fresh docs instead of using the cache.
+    pub fn no_cache(mut self, no_cache: bool) -> Self {
+        self.no_cache = no_cache;
+        self
+    }
+
+    /// The query for the docs of `item`, one of the items this query listed.
+    #[cfg(feature = "tui")]
+    pub(crate) fn for_item(&self, item: &crate::ListItem) -> Self {
+        let mut query = self.clone().kind(item.kind).tree(false);
+        query.filter = None;
+        query.qualifiers = Qualifiers::default();
+        let crate_prefix = format!("{}::", self.spec.name);
+        query.path(item.path.strip_prefix(&crate_prefix).unwrap_or_default())
+    }
+
+    /// Whether this query lists items, as a tree or search, rather than
+    /// showing the docs at its path.
+    pub(crate) fn lists_items(&self) -> bool {
+        self.tree || self.filter.is_some() || !self.qualifiers.is_empty()
+    }
+
+    /// Report downloads and parsing to `events` instead of ignoring them.
+    pub fn events(mut self, events: Arc<dyn EventSink>) -> Self {
+        self.events = events;
+        self
+    }
+}
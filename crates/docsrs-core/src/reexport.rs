@@ -0,0 +1,85 @@
+// Re-exports are only followed with the `native` feature, which loads the
+// docs of the defining crate
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
+use anyhow::{Result, bail};
+use jsondoc::JsonDoc;
+use rustdoc_types::{Id, ItemEnum};
+
+#[cfg(feature = "native")]
+use crate::crate_spec::CrateSpec;
+#[cfg(feature = "native")]
+use crate::docfetch::load_sysroot_docs;
+#[cfg(feature = "native")]
+use crate::events::EventSink;
+
+/// Crates whose docs come with the toolchain rather than docs.rs.
+pub(crate) const SYSROOT_CRATES: &[&str] = &["std", "core", "alloc", "proc_macro", "test"];
+
+/// A `pub use` of an item defined in another crate, such as
+/// `pub use std::collections::HashMap`.
+pub(crate) struct ExternalReexport {
+    /// Name of the crate defining the item.
+    pub crate_name: String,
+
+    /// The path as written in the `use`, e.g. `std::collections::HashMap`.
+    pub source: String,
+
+    /// The path where the item is defined, e.g.
+    /// `std::collections::hash::map::HashMap`, if known.
+    pub canonical_path: Option<String>,
+}
+
+impl ExternalReexport {
+    /// Returns the re-export behind `id`, if `id` is a `pub use` of an item
+    /// that isn't part of this crate's docs.
+    pub(crate) fn find(doc: &JsonDoc, id: &Id) -> Option<Self> {
+        let krate = doc.crate_data();
+        let ItemEnum::Use(use_) = &krate.index.get(id)?.inner else {
+            return None;
+        };
+        if use_.is_glob || use_.id.is_some_and(|id| krate.index.contains_key(&id)) {
+            return None;
+        }
+
+        let summary = use_.id.and_then(|id| krate.paths.get(&id));
+        let crate_name = match summary {
+            Some(summary) => krate.external_crates.get(&summary.crate_id)?.name.clone(),
+            None => use_.source.split("::").next()?.to_string(),
+        };
+        if crate_name == "crate" || crate_name == "self" || crate_name == "super" {
+            return None;
+        }
+
+        Some(Self {
+            crate_name,
+            source: use_.source.clone(),
+            canonical_path: summary.map(|summary| summary.path.join("::")),
+        })
+    }
+
+    /// Load the docs of the crate defining the item.
+    #[cfg(feature = "native")]
+    pub(crate) fn load_docs(&self, no_cache: bool, events: &dyn EventSink) -> Result<JsonDoc> {
+        let krate = if SYSROOT_CRATES.contains(&self.crate_name.as_str()) {
+            load_sysroot_docs(&self.crate_name)?
+        } else {
+            let spec = CrateSpec::parse(&self.crate_name)?;
+            // Resolution messages refer to the dependency, not the crate the
+            // user asked for, so they are left out
+            crate::load_crate(&spec, no_cache, &mut crate::Resolution::default(), events)?
+        };
+        Ok(JsonDoc::from(krate))
+    }
+
+    /// Find the re-exported item in the docs of its crate.
+    pub(crate) fn find_target(&self, doc: &JsonDoc) -> Result<Id> {
+        let candidates = [Some(self.source.as_str()), self.canonical_path.as_deref()];
+        for path in candidates.into_iter().flatten() {
+            if let Some(id) = doc.find_item_by_path(path) {
+                return Ok(id);
+            }
+        }
+        bail!("No item found at {} in {}", self.source, self.crate_name)
+    }
+}
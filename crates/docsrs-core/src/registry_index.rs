@@ -0,0 +1,351 @@
+//! Entries of sparse registry indexes, which list every published version
+//! of a crate with its features and dependencies. Crates are looked up in
+//! the crates.io index, or in the index of their registry when given by
+//! their qualified name, `registry+name`.
+
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use anyhow::{Context, Result, bail};
+use cargo_metadata::semver::{Version, VersionReq};
+use serde::Deserialize;
+
+use crate::docfetch::{get_crate_cache_file, is_offline};
+use crate::source::{get, registry, split_qualified_name};
+
+/// How long fetching an index file may take.
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// How long a cached index file is used to find the newest versions of a
+/// crate before fetching it again.
+const INDEX_TTL: Duration = Duration::from_secs(60 * 60);
+
+/// How long a cached index file is trusted to tell whether a crate has a
+/// newer release, so checking costs at most a request a day per crate.
+const UPDATE_CHECK_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+static UPDATE_CHECK: AtomicBool = AtomicBool::new(true);
+
+/// Check whether crates resolved from the lockfile or the cache have a
+/// newer release, see [`newer_version`].
+pub(crate) fn set_update_check(enabled: bool) {
+    UPDATE_CHECK.store(enabled, Ordering::Relaxed);
+}
+
+/// Whether newer releases are checked for, see [`set_update_check`].
+pub(crate) fn update_check() -> bool {
+    UPDATE_CHECK.load(Ordering::Relaxed)
+}
+
+/// A published version of a crate, one line of its index file.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct IndexEntry {
+    pub vers: String,
+    #[serde(default)]
+    pub deps: Vec<IndexDep>,
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+    /// Features using `dep:` or `?/` syntax, kept apart so older cargo
+    /// versions can still read the index
+    #[serde(default)]
+    features2: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    pub yanked: bool,
+}
+
+/// A dependency of a published version.
+#[derive(Clone, Debug, Deserialize)]
+pub(crate) struct IndexDep {
+    /// Name the crate uses for the dependency
+    pub name: String,
+    /// Version requirement, e.g. `^1.0`
+    pub req: String,
+    #[serde(default)]
+    pub optional: bool,
+    /// `dev` or `build`, or `normal` or nothing for normal dependencies
+    pub kind: Option<String>,
+    /// Platform the dependency is only used on, e.g. `cfg(windows)`
+    pub target: Option<String>,
+    /// Name of the crate depended on, when it's renamed
+    pub package: Option<String>,
+    /// Index of the registry the dependency is from, when it isn't the
+    /// registry of the crate
+    pub registry: Option<String>,
+}
+
+impl IndexDep {
+    /// Name of the crate depended on.
+    pub(crate) fn crate_name(&self) -> &str {
+        self.package.as_deref().unwrap_or(&self.name)
+    }
+}
+
+impl IndexEntry {
+    /// All features of the version with what each one enables, including
+    /// the implicit feature of each optional dependency that no feature
+    /// enables with `dep:`.
+    pub(crate) fn features(&self) -> BTreeMap<String, Vec<String>> {
+        let mut features = self.features.clone();
+        features.extend(self.features2.clone());
+        let explicit: Vec<String> = features
+            .values()
+            .flatten()
+            .filter_map(|enabled| enabled.strip_prefix("dep:"))
+            .map(str::to_string)
+            .collect();
+        for dep in self.deps.iter().filter(|dep| dep.optional) {
+            if !explicit.contains(&dep.name) {
+                features
+                    .entry(dep.name.clone())
+                    .or_insert_with(|| vec![format!("dep:{}", dep.name)]);
+            }
+        }
+        features
+    }
+}
+
+/// Path of a crate's file in the index: `1/a`, `2/ab`, `3/a/abc` or
+/// `ab/cd/abcd...`, lowercased.
+fn index_path(crate_name: &str) -> String {
+    let name = crate_name.to_lowercase();
+    match name.len() {
+        1 => format!("1/{name}"),
+        2 => format!("2/{name}"),
+        3 => format!("3/{}/{name}", &name[..1]),
+        _ => format!("{}/{}/{name}", &name[..2], &name[2..4]),
+    }
+}
+
+/// Every published version of `crate_name`, oldest first. The cached index
+/// file is used while it's younger than [`INDEX_TTL`], or when fetching a
+/// newer one fails.
+pub(crate) fn entries(crate_name: &str) -> Result<Vec<IndexEntry>> {
+    entries_within(crate_name, INDEX_TTL)
+}
+
+/// Every published version of `crate_name`, like [`entries`], using the
+/// cached index file while it's younger than `ttl`.
+fn entries_within(crate_name: &str, ttl: Duration) -> Result<Vec<IndexEntry>> {
+    let cache_path = get_crate_cache_file(crate_name, "index.jsonl")?;
+    let cached = fs::metadata(&cache_path).ok().map(|metadata| {
+        let age = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.elapsed().ok());
+        age.is_some_and(|age| age < ttl)
+    });
+    if cached == Some(true) || (cached.is_some() && is_offline()) {
+        let contents = fs::read_to_string(&cache_path)
+            .with_context(|| format!("Failed to read {}", cache_path.display()))?;
+        return Ok(parse_entries(&contents));
+    }
+    if is_offline() {
+        bail!("The registry index of {crate_name} isn't cached, and docsrs is offline");
+    }
+    match fetch(crate_name) {
+        Ok(contents) => {
+            save(&cache_path, &contents);
+            Ok(parse_entries(&contents))
+        }
+        Err(e) => match fs::read_to_string(&cache_path) {
+            Ok(contents) => Ok(parse_entries(&contents)),
+            Err(_) => Err(e),
+        },
+    }
+}
+
+/// The requirement a version of a spec stands for: `latest` for any
+/// version, a full version for exactly that one, and a requirement as is.
+pub(crate) fn version_req(version: &str) -> Result<VersionReq> {
+    if version == "latest" {
+        return Ok(VersionReq::STAR);
+    }
+    let req = match Version::parse(version) {
+        Ok(version) => format!("={version}"),
+        Err(_) => version.to_string(),
+    };
+    VersionReq::parse(&req).with_context(|| format!("Invalid version {version}"))
+}
+
+/// The newest version of `crate_name` matching `req` that isn't yanked.
+pub(crate) fn newest_matching(crate_name: &str, req: &VersionReq) -> Result<IndexEntry> {
+    entries(crate_name)?
+        .into_iter()
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| Some((Version::parse(&entry.vers).ok()?, entry)))
+        .filter(|(version, _)| req.matches(version))
+        .max_by(|(a, _), (b, _)| a.cmp(b))
+        .map(|(_, entry)| entry)
+        .with_context(|| format!("No published version of {crate_name} matches {req}"))
+}
+
+/// The newest release of `crate_name` when it's newer than `version`.
+/// Prereleases only count when `version` is one. `None` when checking is
+/// turned off or the index can't be read.
+pub(crate) fn newer_version(crate_name: &str, version: &str) -> Option<String> {
+    if !update_check() {
+        return None;
+    }
+    let viewed = Version::parse(version).ok()?;
+    let entries = entries_within(crate_name, UPDATE_CHECK_TTL).ok()?;
+    let newest = newest_release(&entries, !viewed.pre.is_empty())?;
+    (newest > viewed).then(|| newest.to_string())
+}
+
+/// The newest version in `entries` that isn't yanked, leaving out
+/// prereleases unless `prereleases` is set.
+fn newest_release(entries: &[IndexEntry], prereleases: bool) -> Option<Version> {
+    entries
+        .iter()
+        .filter(|entry| !entry.yanked)
+        .filter_map(|entry| Version::parse(&entry.vers).ok())
+        .filter(|version| prereleases || version.pre.is_empty())
+        .max()
+}
+
+/// The index entry of crate@version. The index file is cached, and only
+/// fetched again when it doesn't list the version yet.
+pub(crate) fn entry(crate_name: &str, version: &str) -> Result<IndexEntry> {
+    let cache_path = get_crate_cache_file(crate_name, "index.jsonl")?;
+    let find = |contents: &str| {
+        parse_entries(contents)
+            .into_iter()
+            .find(|entry| entry.vers == version)
+    };
+    if let Ok(contents) = fs::read_to_string(&cache_path)
+        && let Some(entry) = find(&contents)
+    {
+        return Ok(entry);
+    }
+
+    if is_offline() {
+        bail!("The registry index of {crate_name} isn't cached, and docsrs is offline");
+    }
+    let contents = fetch(crate_name)?;
+    save(&cache_path, &contents);
+    find(&contents)
+        .with_context(|| format!("The registry index has no version {version} of {crate_name}"))
+}
+
+/// Cache an index file. Only a later lookup is slower when this fails.
+fn save(cache_path: &Path, contents: &str) {
+    if let Some(dir) = cache_path.parent() {
+        let _ = fs::create_dir_all(dir).and_then(|()| fs::write(cache_path, contents));
+    }
+}
+
+/// Download the index file of `crate_name`, from the index of its registry.
+fn fetch(crate_name: &str) -> Result<String> {
+    let (url, token) = match split_qualified_name(crate_name) {
+        (Some(registry_name), name) => {
+            let registry = registry(registry_name)?;
+            let url = format!("{}{}", registry.index_url()?, index_path(name));
+            (url, registry.token)
+        }
+        (None, name) => (
+            format!("https://index.crates.io/{}", index_path(name)),
+            None,
+        ),
+    };
+    let mut response = get(&url, token.as_ref())
+        .config()
+        .timeout_global(Some(TIMEOUT))
+        .build()
+        .call()
+        .with_context(|| format!("Failed to fetch {url}"))?;
+    response
+        .body_mut()
+        .read_to_string()
+        .with_context(|| format!("Failed to read {url}"))
+}
+
+/// The entries of an index file, one JSON object per line. Lines that
+/// don't parse, e.g. from a newer index format, are skipped.
+fn parse_entries(contents: &str) -> Vec<IndexEntry> {
+    contents
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_index_path() {
+        assert_eq!(index_path("a"), "1/a");
+        assert_eq!(index_path("cc"), "2/cc");
+        assert_eq!(index_path("syn"), "3/s/syn");
+        assert_eq!(index_path("Serde_json"), "se/rd/serde_json");
+    }
+
+    #[test]
+    fn test_parse_entries_merges_features() {
+        let entries = parse_entries(concat!(
+            r#"{"name":"demo","vers":"1.0.0","deps":[],"features":{"default":["std"],"std":[]},"yanked":false}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.1.0","deps":[{"name":"serde","req":"^1","optional":true,"kind":"normal","target":null}],"features":{"std":[]},"features2":{"serde":["dep:serde"]},"yanked":true}"#,
+            "\n",
+        ));
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].features()["default"], ["std"]);
+
+        let newer = &entries[1];
+        assert!(newer.yanked);
+        assert_eq!(newer.deps[0].crate_name(), "serde");
+        assert_eq!(newer.deps[0].req, "^1");
+        let features = newer.features();
+        assert_eq!(features.keys().collect::<Vec<_>>(), ["serde", "std"]);
+        assert_eq!(features["serde"], ["dep:serde"]);
+    }
+
+    #[test]
+    fn test_optional_deps_are_implicit_features() {
+        let entries = parse_entries(
+            r#"{"name":"demo","vers":"1.0.0","deps":[{"name":"log","req":"^0.4","optional":true},{"name":"libc","req":"^0.2","optional":false}],"features":{}}"#,
+        );
+        let features = entries[0].features();
+        assert_eq!(features.keys().collect::<Vec<_>>(), ["log"]);
+        assert_eq!(features["log"], ["dep:log"]);
+    }
+
+    #[test]
+    fn test_newest_release() {
+        let entries = parse_entries(concat!(
+            r#"{"name":"demo","vers":"1.0.0","deps":[],"features":{}}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.2.0","deps":[],"features":{}}"#,
+            "\n",
+            r#"{"name":"demo","vers":"1.3.0","deps":[],"features":{},"yanked":true}"#,
+            "\n",
+            r#"{"name":"demo","vers":"2.0.0-rc.1","deps":[],"features":{}}"#,
+            "\n",
+        ));
+        assert_eq!(newest_release(&entries, false), Some(Version::new(1, 2, 0)));
+        assert_eq!(
+            newest_release(&entries, true),
+            Some(Version::parse("2.0.0-rc.1").unwrap())
+        );
+    }
+
+    #[test]
+    fn test_version_req() {
+        assert_eq!(version_req("latest").unwrap(), VersionReq::STAR);
+        assert!(
+            version_req("1.2.3")
+                .unwrap()
+                .matches(&Version::new(1, 2, 3))
+        );
+        assert!(
+            !version_req("1.2.3")
+                .unwrap()
+                .matches(&Version::new(1, 2, 4))
+        );
+        assert!(version_req("1.2").unwrap().matches(&Version::new(1, 9, 0)));
+        assert!(version_req("nope").is_err());
+    }
+}
@@ -0,0 +1,575 @@
+//! Structured answers to a docs query, and their rendering as terminal text.
+
+use std::borrow::Cow;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use colored::Colorize;
+use jsondoc::JsonDoc;
+use rustdoc_fmt::{Colorizer, DefaultLinkResolver, format_markdown_with};
+use rustdoc_types::Id;
+
+use crate::cli::{CodeFrameStyle, GroupBy};
+use crate::doc;
+use crate::list::{
+    EntryKind, ListItem, render_candidates, render_grouped, render_list, render_tree,
+};
+use crate::urls;
+
+/// The answer to a docs query, see [`crate::query`].
+pub enum QueryResult {
+    /// The docs of a single item
+    ItemDoc(ItemDoc),
+    /// Several items, from a search, a tree or an ambiguous path
+    MatchList(MatchList),
+    /// The docs of the crate root
+    CrateOverview(ItemDoc),
+}
+
+/// How the crate version was resolved.
+#[derive(Clone, Debug, Default)]
+pub struct Resolution {
+    /// Where the version came from, e.g. `tokio@1.47.1 (from Cargo.toml)`
+    pub message: Option<String>,
+    /// Problems that didn't stop the query, such as a failed local build
+    /// falling back to cached docs
+    pub warnings: Vec<String>,
+    /// Set when the docs were built from a crate of the current workspace
+    pub local: Option<LocalCrate>,
+    /// What the crate's `Cargo.toml` says about it, when known
+    pub crate_info: Option<CrateInfo>,
+    /// Registry the crate comes from, when it isn't crates.io
+    pub registry: Option<String>,
+    /// A newer release than the version the docs are of, when docsrs picked
+    /// that version rather than the query
+    pub notice: Option<String>,
+    /// The version of the crate viewed last, when docsrs picked another one
+    /// since, see `--what-changed`
+    pub previous_version: Option<String>,
+}
+
+/// Package metadata of a crate, shown above the crate root docs.
+#[derive(Clone, Debug, Default)]
+pub struct CrateInfo {
+    /// One-line description of the crate
+    pub description: Option<String>,
+    /// SPDX license expression, e.g. `MIT OR Apache-2.0`
+    pub license: Option<String>,
+    /// URL of the source repository
+    pub repository: Option<String>,
+    /// URL of the crate's docs on docs.rs, for published crates
+    pub docs_url: Option<String>,
+    /// Minimum supported Rust version, the `rust-version` field
+    pub rust_version: Option<String>,
+    /// Rust edition, e.g. `2021`
+    pub edition: Option<String>,
+}
+
+impl CrateInfo {
+    /// The comment lines shown in the crate header, e.g.
+    /// `// license MIT, edition 2021, rust-version 1.70`, leaving out
+    /// whatever isn't known.
+    fn header_lines(&self) -> Vec<String> {
+        let mut lines = Vec::new();
+        if let Some(description) = &self.description {
+            // Descriptions are often wrapped in Cargo.toml
+            let description = description.split_whitespace().collect::<Vec<_>>().join(" ");
+            lines.push(format!("// {description}"));
+        }
+        let fields: Vec<String> = [
+            ("license", &self.license),
+            ("edition", &self.edition),
+            ("rust-version", &self.rust_version),
+        ]
+        .into_iter()
+        .filter_map(|(name, value)| Some(format!("{name} {}", value.as_ref()?)))
+        .collect();
+        if !fields.is_empty() {
+            lines.push(format!("// {}", fields.join(", ")));
+        }
+        if let Some(repository) = &self.repository {
+            lines.push(format!("// repository {repository}"));
+        }
+        if let Some(docs_url) = &self.docs_url {
+            lines.push(format!("// docs {docs_url}"));
+        }
+        lines
+    }
+}
+
+/// A crate of the current workspace, documented by `cargo doc`.
+#[derive(Clone, Debug)]
+pub struct LocalCrate {
+    /// Package name, as `cargo doc -p` takes it
+    pub package: String,
+    /// The `target/doc` directory its docs are built into
+    pub doc_dir: PathBuf,
+}
+
+/// The docs of one item.
+pub struct ItemDoc {
+    pub resolution: Resolution,
+    /// Full path of the item, as queried
+    pub path: String,
+    pub kind: Option<EntryKind>,
+    /// Id of the item in the docs it was found in
+    pub id: Id,
+    /// Set when the item is a re-export of an item from another crate
+    pub reexport: Option<Reexport>,
+    /// The signature, without colors or line breaks
+    pub signature: String,
+    /// The doc comment, as markdown
+    pub docs: Option<String>,
+    /// The crate's README, as markdown, shown instead of crate root docs
+    /// that are empty
+    pub readme: Option<String>,
+    /// Docs the item was found in, kept to render its children
+    pub(crate) doc: JsonDoc,
+}
+
+/// Where a re-exported item is defined.
+#[derive(Clone, Debug)]
+pub enum Reexport {
+    /// The docs of the defining crate were loaded, and are the ones shown
+    Followed {
+        /// Path of the item in the defining crate
+        source: String,
+    },
+    /// The item is documented as re-exported
+    NotFollowed {
+        /// Name of the defining crate
+        crate_name: String,
+    },
+}
+
+/// A list of items.
+pub struct MatchList {
+    pub resolution: Resolution,
+    pub kind: MatchKind,
+    pub items: Vec<ListItem>,
+    /// Number of matches before the list was cut at the query's limit
+    pub total: usize,
+    /// Version of the crate the items are in, when its docs say
+    pub version: Option<String>,
+}
+
+/// Why a [`MatchList`] lists its items.
+#[derive(Clone, Debug)]
+pub enum MatchKind {
+    /// Items whose path matches `filter`, or all items when nothing matched
+    Search { filter: String, matched: bool },
+    /// Every item under `root`, shown as a tree
+    Tree { root: String },
+    /// Items sharing `path`, with the spec that selects each of them
+    Ambiguous { path: String, specs: Vec<String> },
+}
+
+/// Styling used when rendering a [`QueryResult`].
+#[derive(Clone)]
+pub struct StyleConfig {
+    pub colorizer: Cow<'static, Colorizer>,
+    /// Signatures and summaries wider than this are wrapped or truncated
+    pub max_width: Option<usize>,
+    /// Show the first sentence of each item's docs in lists
+    pub summaries: bool,
+    pub group_by: Option<GroupBy>,
+}
+
+impl Default for StyleConfig {
+    fn default() -> Self {
+        Self {
+            colorizer: Cow::Borrowed(Colorizer::get()),
+            max_width: None,
+            summaries: false,
+            group_by: None,
+        }
+    }
+}
+
+impl StyleConfig {
+    /// Build the styling from the command line flags, sharing the global
+    /// colorizer when no flag changes it.
+    pub fn from_cli(args: &crate::cli::Cli) -> anyhow::Result<Self> {
+        let max_width = args.width.or_else(terminal_width);
+        Ok(Self {
+            colorizer: build_colorizer(args)?,
+            max_width,
+            summaries: args.summaries.enabled(terminal_width().is_some()),
+            group_by: args.group_by,
+        })
+    }
+}
+
+impl QueryResult {
+    pub fn resolution(&self) -> &Resolution {
+        match self {
+            QueryResult::ItemDoc(item) | QueryResult::CrateOverview(item) => &item.resolution,
+            QueryResult::MatchList(list) => &list.resolution,
+        }
+    }
+
+    /// Render the result as shown by the CLI: resolution notes, a `//`
+    /// description line and the docs or list.
+    pub fn render(&self, style: &StyleConfig) -> anyhow::Result<String> {
+        let (description, body) = match self {
+            QueryResult::ItemDoc(item) => {
+                let mut description = item.description().bright_black().to_string();
+                if let Some(use_statement) = item.use_statement() {
+                    description.push('\n');
+                    description.push_str(&use_statement);
+                }
+                (description, item.render(style)?)
+            }
+            QueryResult::CrateOverview(item) => {
+                let mut description = format!("// showing mod {} (crate root)", item.path);
+                let info = item.resolution.crate_info.as_ref();
+                for line in info.map(CrateInfo::header_lines).unwrap_or_default() {
+                    description.push('\n');
+                    description.push_str(&line);
+                }
+                (description.bright_black().to_string(), item.render(style)?)
+            }
+            QueryResult::MatchList(list) => (
+                list.description().bright_black().to_string(),
+                list.render(style),
+            ),
+        };
+
+        let mut output = String::new();
+        let resolution = self.resolution();
+        if let Some(message) = &resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        if let Some(notice) = &resolution.notice {
+            output.push_str(&format!("{}\n", format!("// {notice}").bright_black()));
+        }
+        if let Some(previous) = &resolution.previous_version {
+            let note = format!(
+                "// note: you viewed {previous} last time, --what-changed lists the API changes since"
+            );
+            output.push_str(&format!("{}\n", note.bright_black()));
+        }
+        if (resolution.message.is_some()
+            || resolution.notice.is_some()
+            || resolution.previous_version.is_some())
+            && !resolution.warnings.is_empty()
+        {
+            output.push('\n');
+        }
+        for warning in &resolution.warnings {
+            output.push_str(&format!("Warning: {}\n", warning));
+        }
+        output.push_str(&format!("{}\n\n{}", description, body));
+        Ok(output)
+    }
+}
+
+impl ItemDoc {
+    pub(crate) fn new(
+        resolution: Resolution,
+        path: String,
+        kind: Option<EntryKind>,
+        id: Id,
+        reexport: Option<Reexport>,
+        doc: JsonDoc,
+    ) -> anyhow::Result<Self> {
+        let signature = doc::plain_signature(&doc, &id)?;
+        let docs = doc
+            .crate_data()
+            .index
+            .get(&id)
+            .and_then(|item| item.docs.clone());
+        Ok(Self {
+            resolution,
+            path,
+            kind,
+            id,
+            reexport,
+            signature,
+            docs,
+            readme: None,
+            doc,
+        })
+    }
+
+    /// Whether the item has a doc comment with anything in it.
+    #[cfg(feature = "native")]
+    pub(crate) fn has_docs(&self) -> bool {
+        self.docs
+            .as_deref()
+            .is_some_and(|docs| !docs.trim().is_empty())
+    }
+
+    /// URL of the item's HTML docs on docs.rs, or on doc.rust-lang.org for
+    /// the standard library.
+    pub fn docs_url(&self) -> Option<String> {
+        urls::item_url(&self.doc, &self.id)
+    }
+
+    /// Path of the item's HTML page under the root of the HTML docs, such
+    /// as `target/doc`, with an anchor when it's documented on its parent's
+    /// page.
+    pub fn html_path(&self) -> Option<String> {
+        urls::html_path(&self.doc, &self.id)
+    }
+
+    /// The links in the item's docs, in order.
+    pub fn links(&self) -> Vec<rustdoc_fmt::DocLink> {
+        self.docs
+            .as_deref()
+            .map(rustdoc_fmt::collect_links)
+            .unwrap_or_default()
+    }
+
+    /// The first Rust code example in the item's docs, without hidden lines.
+    pub fn first_example(&self) -> Option<String> {
+        rustdoc_fmt::code_examples(self.docs.as_deref()?)
+            .into_iter()
+            .next()
+    }
+
+    /// The `use` declaration that imports the item, by the shortest of
+    /// the public paths it can be imported at, e.g. `use tokio::sync::Mutex;`
+    /// rather than by its definition path `tokio::sync::mutex::Mutex`. None
+    /// for the crate root and items that can't be imported, like methods.
+    pub fn use_statement(&self) -> Option<String> {
+        Some(format!("use {};", self.use_path()?))
+    }
+
+    fn use_path(&self) -> Option<String> {
+        match &self.reexport {
+            // The paths in `doc` are those of the defining crate
+            Some(_) => self.path.contains("::").then(|| self.path.clone()),
+            None => doc::import_path(&self.doc, &self.id, &self.path),
+        }
+    }
+
+    /// Code to start from when using the item: an `impl` block for a trait,
+    /// a call for a function. None for other kinds of items.
+    #[cfg(feature = "native")]
+    pub fn skeleton(&self, style: &StyleConfig) -> anyhow::Result<Option<String>> {
+        doc::skeleton(
+            &self.doc,
+            &self.id,
+            self.use_path().as_deref(),
+            &style.colorizer,
+            style.max_width,
+        )
+    }
+
+    fn description(&self) -> String {
+        let kind_str = self
+            .kind
+            .map(|k| format!("{} ", k.keyword()))
+            .unwrap_or_default();
+        let mut desc = format!("// found {}{}", kind_str, self.path);
+        match &self.reexport {
+            Some(Reexport::Followed { source }) => {
+                desc.push_str(&format!(", re-exported from {}", source));
+            }
+            Some(Reexport::NotFollowed { crate_name }) => {
+                desc.push_str(&format!(
+                    ", re-exported from {} (--follow-reexports shows its docs)",
+                    crate_name
+                ));
+            }
+            None => {}
+        }
+        desc
+    }
+
+    /// Render the docs, signature and children of the item.
+    pub fn render(&self, style: &StyleConfig) -> anyhow::Result<String> {
+        let mut output = String::new();
+        if let Some(readme) = &self.readme {
+            output.push_str(&format!("{}\n", "// (from README)".bright_black()));
+            let formatted = format_markdown_with(readme, &DefaultLinkResolver, &style.colorizer);
+            for line in formatted.lines() {
+                if line.is_empty() {
+                    output.push_str("///\n");
+                } else {
+                    output.push_str(&format!("/// {line}\n"));
+                }
+            }
+        }
+        output.push_str(&doc::signature_for_id(
+            &self.doc,
+            &self.id,
+            &style.colorizer,
+            style.max_width,
+        )?);
+        Ok(output)
+    }
+}
+
+impl MatchList {
+    /// List `items` of the crate at `version`, keeping the first `limit` of
+    /// them.
+    pub(crate) fn new(
+        resolution: Resolution,
+        kind: MatchKind,
+        mut items: Vec<ListItem>,
+        version: Option<String>,
+        limit: Option<usize>,
+    ) -> Self {
+        let total = items.len();
+        if let Some(limit) = limit {
+            items.truncate(limit);
+        }
+        Self {
+            resolution,
+            kind,
+            items,
+            total,
+            version,
+        }
+    }
+
+    fn description(&self) -> String {
+        // "20 of 143" when the list was cut at the limit
+        let truncated = self.items.len() < self.total;
+        let count = if truncated {
+            format!("{} of {}", self.items.len(), self.total)
+        } else {
+            self.total.to_string()
+        };
+        match &self.kind {
+            // Everything under the path, narrowed by qualifiers alone
+            MatchKind::Search { filter, .. } if filter.is_empty() => {
+                format!("// {} items", count)
+            }
+            MatchKind::Search {
+                filter,
+                matched: true,
+            } => format!("// {} items matching \"{}\"", count, filter),
+            MatchKind::Search {
+                filter,
+                matched: false,
+            } => format!(
+                "// no matches for \"{}\" \u{2014} showing {}{} items",
+                filter,
+                if truncated { "" } else { "all " },
+                count
+            ),
+            MatchKind::Tree { root } => {
+                let noun = if self.total == 1 { "item" } else { "items" };
+                format!("// tree of {} ({} {})", root, count, noun)
+            }
+            MatchKind::Ambiguous { path, .. } => format!(
+                "// {} items at {}, add a disambiguator to pick one",
+                self.items.len(),
+                path
+            ),
+        }
+    }
+
+    /// Render the items as a list or tree.
+    pub fn render(&self, style: &StyleConfig) -> String {
+        let colorizer = &style.colorizer;
+        match &self.kind {
+            MatchKind::Search { .. } => match style.group_by {
+                Some(GroupBy::Module) => {
+                    render_grouped(&self.items, colorizer, style.summaries, style.max_width)
+                }
+                None => render_list(&self.items, colorizer, style.summaries, style.max_width),
+            },
+            MatchKind::Tree { .. } => render_tree(&self.items, colorizer),
+            MatchKind::Ambiguous { specs, .. } => {
+                let candidates: Vec<(ListItem, String)> = self
+                    .items
+                    .iter()
+                    .cloned()
+                    .zip(specs.iter().cloned())
+                    .collect();
+                render_candidates(&candidates, colorizer, style.max_width)
+            }
+        }
+    }
+}
+
+/// Build the colorizer from the styling flags, sharing the global instance
+/// when no flag changes it.
+fn build_colorizer(args: &crate::cli::Cli) -> anyhow::Result<Cow<'static, Colorizer>> {
+    let mut colorizer = Cow::Borrowed(Colorizer::get());
+    if let Some(name) = &args.theme {
+        let depth = colorizer.color_depth();
+        let themed = Colorizer::with_bundled_theme(name).ok_or_else(|| {
+            anyhow::anyhow!(
+                "Unknown theme \"{}\". Available themes: {}",
+                name,
+                Colorizer::bundled_themes().collect::<Vec<_>>().join(", ")
+            )
+        })?;
+        colorizer = Cow::Owned(themed.with_color_depth(depth));
+    }
+    if let Some(path) = &args.theme_file {
+        let depth = colorizer.color_depth();
+        colorizer = Cow::Owned(
+            Colorizer::from_theme_file(path)
+                .with_context(|| format!("Failed to load theme file {}", path.display()))?
+                .with_color_depth(depth),
+        );
+    }
+    if let Some(depth) = args.color_depth.resolve() {
+        colorizer = Cow::Owned(colorizer.into_owned().with_color_depth(depth));
+    }
+    if args.code_frame != CodeFrameStyle::None {
+        colorizer = Cow::Owned(
+            colorizer
+                .into_owned()
+                .with_code_frame(args.code_frame.into()),
+        );
+    }
+    if args.syntect_signatures {
+        colorizer = Cow::Owned(colorizer.into_owned().with_syntect_signatures(true));
+    }
+    if args.line_numbers.is_some() {
+        colorizer = Cow::Owned(colorizer.into_owned().with_line_numbers(args.line_numbers));
+    }
+    Ok(colorizer)
+}
+
+/// Width of the terminal attached to stdout, if any.
+#[cfg(feature = "native")]
+fn terminal_width() -> Option<usize> {
+    terminal_size::terminal_size().map(|(terminal_size::Width(width), _)| usize::from(width))
+}
+
+#[cfg(not(feature = "native"))]
+fn terminal_width() -> Option<usize> {
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_crate_info_header_lines() {
+        let info = CrateInfo {
+            description: Some("An event-driven,\n    non-blocking I/O platform".to_string()),
+            license: Some("MIT".to_string()),
+            repository: Some("https://github.com/tokio-rs/tokio".to_string()),
+            docs_url: Some("https://docs.rs/tokio/1.38.0".to_string()),
+            rust_version: Some("1.63".to_string()),
+            edition: Some("2021".to_string()),
+        };
+        assert_eq!(
+            info.header_lines(),
+            [
+                "// An event-driven, non-blocking I/O platform",
+                "// license MIT, edition 2021, rust-version 1.63",
+                "// repository https://github.com/tokio-rs/tokio",
+                "// docs https://docs.rs/tokio/1.38.0",
+            ]
+        );
+
+        // Only what is known is shown
+        let info = CrateInfo {
+            edition: Some("2024".to_string()),
+            ..CrateInfo::default()
+        };
+        assert_eq!(info.header_lines(), ["// edition 2024"]);
+        assert!(CrateInfo::default().header_lines().is_empty());
+    }
+}
@@ -0,0 +1,230 @@
+use std::collections::{BTreeMap, BTreeSet};
+#[cfg(feature = "native")]
+use std::fs;
+#[cfg(feature = "native")]
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+#[cfg(feature = "native")]
+use anyhow::{Context, Result};
+use jsondoc::JsonDoc;
+use rustdoc_types::Id;
+use serde::{Deserialize, Serialize};
+
+#[cfg(feature = "native")]
+use crate::docfetch::get_cache_path;
+use crate::list::ListItem;
+
+/// Words shorter than this are left out of the index.
+const MIN_TERM_LEN: usize = 2;
+
+/// Size and modification time of the cached rustdoc JSON an index was built
+/// from; the index is stale once either changes.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+struct CacheStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+#[cfg(feature = "native")]
+impl CacheStamp {
+    /// The stamp of the rustdoc JSON cached at `docs`, if it's there.
+    fn of(docs: &Path) -> Option<Self> {
+        let metadata = fs::metadata(docs).ok()?;
+        Some(Self {
+            len: metadata.len(),
+            modified: metadata.modified().ok(),
+        })
+    }
+}
+
+/// The list items of a crate plus an inverted index of the words in their
+/// names and docs.
+///
+/// Cached next to the rustdoc JSON of a crate@version so that searches and
+/// trees can be answered without parsing the crate.
+#[derive(Serialize, Deserialize)]
+pub(crate) struct SearchIndex {
+    stamp: CacheStamp,
+    /// Version of the crate, which the cache key may only know as `latest`
+    #[serde(default)]
+    pub(crate) version: Option<String>,
+    items: Vec<ListItem>,
+    /// Lowercased word to the positions in `items` of the items using it
+    terms: BTreeMap<String, Vec<u32>>,
+}
+
+impl SearchIndex {
+    /// Index the public items of `doc`.
+    pub(crate) fn build(doc: &JsonDoc) -> Self {
+        Self::build_with(doc, |_, _| {})
+    }
+
+    /// Index the public items of `doc`, passing each to `found` with the
+    /// words of its name and docs as soon as it's indexed.
+    pub(crate) fn build_with(
+        doc: &JsonDoc,
+        mut found: impl FnMut(&ListItem, &BTreeSet<String>),
+    ) -> Self {
+        let mut items = Vec::new();
+        let mut terms: BTreeMap<String, Vec<u32>> = BTreeMap::new();
+        for item in doc.iter_items() {
+            let Some(list_item) = ListItem::from_jsondoc_item(item) else {
+                continue;
+            };
+            let position = items.len() as u32;
+            let name = list_item.segments().last().map(|(name, _)| name.as_str());
+            let docs = item.item().docs.as_deref();
+            let words: BTreeSet<String> = name.into_iter().chain(docs).flat_map(words).collect();
+            found(&list_item, &words);
+            for word in words {
+                terms.entry(word).or_default().push(position);
+            }
+            items.push(list_item);
+        }
+        Self {
+            stamp: CacheStamp::default(),
+            version: doc.crate_data().crate_version.clone(),
+            items,
+            terms,
+        }
+    }
+
+    /// Load the index of a cached crate@version, if one was saved for the
+    /// rustdoc JSON currently in the cache.
+    #[cfg(feature = "native")]
+    pub(crate) fn load_cached(crate_name: &str, version: &str) -> Option<Self> {
+        Self::load(&get_cache_path(crate_name, version).ok()?)
+    }
+
+    /// Save the index for the cached rustdoc JSON of crate@version. Nothing is
+    /// saved if the JSON isn't cached.
+    #[cfg(feature = "native")]
+    pub(crate) fn save_cached(&mut self, crate_name: &str, version: &str) -> Result<()> {
+        self.save(&get_cache_path(crate_name, version)?)
+    }
+
+    /// Load the index saved next to the rustdoc JSON cached at `docs`, if it
+    /// was built from the JSON that's there now.
+    #[cfg(feature = "native")]
+    pub(crate) fn load(docs: &Path) -> Option<Self> {
+        let stamp = CacheStamp::of(docs)?;
+        let data = fs::read(index_path(docs)).ok()?;
+        let index: Self = serde_json::from_slice(&data).ok()?;
+        (index.stamp == stamp).then_some(index)
+    }
+
+    /// Save the index next to the rustdoc JSON cached at `docs`, stamped
+    /// with the JSON that's there now. Nothing is saved without the JSON.
+    #[cfg(feature = "native")]
+    pub(crate) fn save(&mut self, docs: &Path) -> Result<()> {
+        let Some(stamp) = CacheStamp::of(docs) else {
+            return Ok(());
+        };
+        self.stamp = stamp;
+        let data = serde_json::to_vec(self)?;
+        fs::write(index_path(docs), data).context("Failed to save search index")
+    }
+
+    pub(crate) fn items(&self) -> &[ListItem] {
+        &self.items
+    }
+
+    /// Ids of the items whose name or docs contain every word of `query`,
+    /// matching words by prefix.
+    pub(crate) fn matching_docs(&self, query: &str) -> BTreeSet<Id> {
+        let mut matches: Option<BTreeSet<usize>> = None;
+        for word in words(query) {
+            let with_word: BTreeSet<usize> = self
+                .terms
+                .range(word.clone()..)
+                .take_while(|(term, _)| term.starts_with(&word))
+                .flat_map(|(_, positions)| positions.iter().map(|&i| i as usize))
+                .collect();
+            matches = Some(match matches {
+                Some(matches) => matches.intersection(&with_word).copied().collect(),
+                None => with_word,
+            });
+        }
+        matches
+            .unwrap_or_default()
+            .into_iter()
+            .map(|i| self.items[i].id)
+            .collect()
+    }
+}
+
+/// Whether `item_words`, those of an item's name and docs, have every word
+/// of `query`, matching words by prefix as [`SearchIndex::matching_docs`]
+/// does.
+#[cfg(feature = "native")]
+pub(crate) fn has_words(item_words: &BTreeSet<String>, query: &str) -> bool {
+    let mut query_words = words(query).peekable();
+    query_words.peek().is_some()
+        && query_words.all(|word| {
+            item_words
+                .range(word.clone()..)
+                .next()
+                .is_some_and(|found| found.starts_with(&word))
+        })
+}
+
+/// Where the index of the rustdoc JSON cached at `docs` is saved.
+#[cfg(feature = "native")]
+fn index_path(docs: &Path) -> PathBuf {
+    docs.with_extension("index")
+}
+
+/// Split text into lowercased words, treating `_` as part of a word.
+fn words(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '_')
+        .filter(|word| word.len() >= MIN_TERM_LEN)
+        .map(str::to_lowercase)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_words() {
+        let words: Vec<String> = words("Spawns a new `async_task`, see [Runtime].").collect();
+        assert_eq!(words, ["spawns", "new", "async_task", "see", "runtime"]);
+    }
+
+    #[cfg(feature = "native")]
+    #[test]
+    fn test_saved_index_is_reused_until_the_docs_change() {
+        use std::time::Duration;
+
+        let json = crate::test_docs("test_items.json");
+        let krate = crate::json::parse_rustdoc_json(fs::read(json).unwrap()).unwrap();
+        let mut index = SearchIndex::build(&JsonDoc::from(krate));
+
+        let dir = tempfile::tempdir().unwrap();
+        let docs = dir.path().join("1.0.0.zst");
+        assert!(SearchIndex::load(&docs).is_none());
+        // Nothing to stamp the index with before the docs are cached
+        index.save(&docs).unwrap();
+        assert!(!index_path(&docs).exists());
+
+        fs::write(&docs, b"docs").unwrap();
+        index.save(&docs).unwrap();
+        let loaded = SearchIndex::load(&docs).expect("index of unchanged docs");
+        assert_eq!(loaded.items().len(), index.items().len());
+        assert_eq!(loaded.version.as_deref(), Some("0.1.0"));
+
+        // Other docs of the same size, written later
+        let file = fs::File::options().write(true).open(&docs).unwrap();
+        let modified = file.metadata().unwrap().modified().unwrap();
+        file.set_modified(modified + Duration::from_secs(1))
+            .unwrap();
+        assert!(SearchIndex::load(&docs).is_none());
+
+        // Docs of another size
+        index.save(&docs).unwrap();
+        assert!(SearchIndex::load(&docs).is_some());
+        fs::write(&docs, b"newer docs").unwrap();
+        assert!(SearchIndex::load(&docs).is_none());
+    }
+}
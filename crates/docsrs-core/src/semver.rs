@@ -0,0 +1,236 @@
+//! Semver classification of public API changes, see `docsrs diff --semver`.
+
+use std::fmt;
+
+use cargo_metadata::semver::Version;
+use colored::Colorize;
+use rustdoc_fmt::{Token, tokens_to_string};
+use rustdoc_types::{Attribute, Id, Item, ItemEnum, StructKind};
+
+use crate::diff::{ApiDiff, ApiItem};
+
+/// How big a version bump a change needs, or a version bump is.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Patch,
+    Minor,
+    Major,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Level::Patch => "patch",
+            Level::Minor => "minor",
+            Level::Major => "major",
+        })
+    }
+}
+
+/// A public API change and the bump it needs.
+#[derive(Clone, Debug)]
+pub struct SemverChange {
+    pub level: Level,
+    /// Why the change needs this bump, e.g. "removed"
+    pub reason: &'static str,
+    /// Signature of the changed item, the new one for changed signatures
+    pub tokens: Vec<Token>,
+}
+
+/// The bump the API changes between two versions need, compared to the
+/// bump the version numbers make.
+#[derive(Clone, Debug)]
+pub struct SemverReport {
+    pub old_version: String,
+    pub new_version: String,
+    /// The bump the versions make, if both parse as semver
+    pub bump: Option<Level>,
+    /// The biggest bump any change needs
+    pub required: Level,
+    /// Changes, biggest bump first
+    pub changes: Vec<SemverChange>,
+}
+
+/// Classify the changes in `diff` and the bump between its versions.
+pub fn check_semver(diff: &ApiDiff) -> SemverReport {
+    let mut changes: Vec<SemverChange> = Vec::new();
+    for item in &diff.removed {
+        changes.push(change(Level::Major, "removed", item));
+    }
+    for change_ in &diff.changed {
+        changes.push(change(Level::Major, "signature changed", &change_.new));
+    }
+    for item in &diff.added {
+        let (level, reason) = classify_addition(diff, item);
+        changes.push(change(level, reason, item));
+    }
+    // Stable sort keeps the removed, changed, added order within a level
+    changes.sort_by_key(|change| std::cmp::Reverse(change.level));
+
+    let required = changes
+        .iter()
+        .map(|change| change.level)
+        .max()
+        .unwrap_or(Level::Patch);
+    SemverReport {
+        old_version: diff.old_version.clone(),
+        new_version: diff.new_version.clone(),
+        bump: version_bump(&diff.old_version, &diff.new_version),
+        required,
+        changes,
+    }
+}
+
+fn change(level: Level, reason: &'static str, item: &ApiItem) -> SemverChange {
+    SemverChange {
+        level,
+        reason,
+        tokens: item.tokens.clone(),
+    }
+}
+
+/// The bump an added item needs: additions are minor changes, unless they
+/// break code matching, constructing or implementing an existing item.
+fn classify_addition(diff: &ApiDiff, item: &ApiItem) -> (Level, &'static str) {
+    let index = &diff.new_doc.crate_data().index;
+    let parent = item.parent_id.as_ref().and_then(|id| index.get(id));
+    // A parent that is new itself can't have users to break
+    let parent_existed = item
+        .parent_id
+        .as_ref()
+        .is_some_and(|id| existed_before(diff, id));
+    let Some(added) = index.get(&item.id) else {
+        return (Level::Minor, "added");
+    };
+
+    match (&added.inner, parent.map(|parent| &parent.inner)) {
+        (ItemEnum::Variant(_), Some(ItemEnum::Enum(_)))
+            if parent_existed && !parent.is_some_and(is_non_exhaustive) =>
+        {
+            (Level::Major, "variant added to exhaustive enum")
+        }
+        (ItemEnum::StructField(_), Some(ItemEnum::Struct(struct_)))
+            if parent_existed
+                && !parent.is_some_and(is_non_exhaustive)
+                && !matches!(
+                    struct_.kind,
+                    StructKind::Plain {
+                        has_stripped_fields: true,
+                        ..
+                    }
+                ) =>
+        {
+            (Level::Major, "field added to exhaustive struct")
+        }
+        (ItemEnum::Function(function), Some(ItemEnum::Trait(_)))
+            if parent_existed && !function.has_body =>
+        {
+            (Level::Major, "required trait method added")
+        }
+        (ItemEnum::AssocType { type_: None, .. }, Some(ItemEnum::Trait(_))) if parent_existed => {
+            (Level::Major, "required associated type added")
+        }
+        (ItemEnum::AssocConst { value: None, .. }, Some(ItemEnum::Trait(_))) if parent_existed => {
+            (Level::Major, "required associated const added")
+        }
+        _ => (Level::Minor, "added"),
+    }
+}
+
+/// Whether the item with `id` in the new docs has a counterpart in the old
+/// docs, matched by path.
+fn existed_before(diff: &ApiDiff, id: &Id) -> bool {
+    let Some(path) = diff
+        .new_doc
+        .items_by_id(id)
+        .next()
+        .map(|item| item.path_string())
+    else {
+        return false;
+    };
+    diff.old_doc.find_item_by_path(&path).is_some()
+}
+
+fn is_non_exhaustive(item: &Item) -> bool {
+    item.attrs
+        .iter()
+        .any(|attr| matches!(attr, Attribute::NonExhaustive))
+}
+
+/// The bump from `old` to `new`, following Cargo's rules: in `0.y.z`
+/// versions `y` is the major version, and in `0.0.z` versions `z` is.
+fn version_bump(old: &str, new: &str) -> Option<Level> {
+    let old = Version::parse(old).ok()?;
+    let new = Version::parse(new).ok()?;
+    Some(match (old.major, old.minor) {
+        _ if old.major != new.major => Level::Major,
+        (0, _) if old.minor != new.minor => Level::Major,
+        (0, 0) if old.patch != new.patch => Level::Major,
+        (0, _) if old.patch != new.patch => Level::Minor,
+        _ if old.minor != new.minor => Level::Minor,
+        _ => Level::Patch,
+    })
+}
+
+impl SemverReport {
+    /// Whether the version bump is at least as big as the changes need.
+    pub fn is_correct(&self) -> Option<bool> {
+        self.bump.map(|bump| bump >= self.required)
+    }
+
+    /// Render the verdict followed by the changes with the bump each needs.
+    pub fn render(&self) -> String {
+        let bump = match self.bump {
+            Some(bump) => format!("a {bump} bump"),
+            None => "not a semver bump".to_string(),
+        };
+        let verdict = match self.is_correct() {
+            Some(true) => format!("the changes need {}: ok", self.required),
+            Some(false) => format!("but the changes need {}", self.required),
+            None => format!("the changes need {}", self.required),
+        };
+        let header = format!(
+            "// semver: {} -> {} is {}, {}",
+            self.old_version, self.new_version, bump, verdict
+        );
+        let mut output = match self.is_correct() {
+            Some(false) => format!("{}\n", header.red()),
+            _ => format!("{}\n", header.bright_black()),
+        };
+
+        if !self.changes.is_empty() {
+            output.push('\n');
+        }
+        for change in &self.changes {
+            let level = change.level.to_string();
+            let level = match change.level {
+                Level::Major => level.red(),
+                Level::Minor => level.yellow(),
+                Level::Patch => level.normal(),
+            };
+            output.push_str(&format!(
+                "{} {}  {}\n",
+                level,
+                tokens_to_string(&change.tokens),
+                format!("// {}", change.reason).bright_black()
+            ));
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_version_bump_follows_cargo_rules() {
+        assert_eq!(version_bump("1.2.3", "2.0.0"), Some(Level::Major));
+        assert_eq!(version_bump("1.2.3", "1.3.0"), Some(Level::Minor));
+        assert_eq!(version_bump("1.2.3", "1.2.4"), Some(Level::Patch));
+        assert_eq!(version_bump("0.4.20", "0.5.0"), Some(Level::Major));
+        assert_eq!(version_bump("0.4.20", "0.4.22"), Some(Level::Minor));
+        assert_eq!(version_bump("0.0.1", "0.0.2"), Some(Level::Major));
+        assert_eq!(version_bump("latest", "1.0.0"), None);
+    }
+}
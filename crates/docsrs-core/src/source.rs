@@ -0,0 +1,469 @@
+//! Where rustdoc JSON is downloaded from: docs.rs, a mirror of it, the
+//! docs server of a private registry, or a directory of docs such as test
+//! fixtures.
+
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io::Read;
+use std::path::PathBuf;
+use std::sync::{Arc, RwLock};
+
+use anyhow::{Context, Result, bail};
+
+use crate::crates_io;
+use crate::events::EventSink;
+use crate::suggest;
+use crate::util::normalize_crate_name;
+
+/// Serves the zstd-compressed rustdoc JSON of crate versions.
+pub trait DocSource: Send + Sync {
+    /// Where the docs of crate@version are fetched from, e.g. their URL.
+    fn location(&self, crate_name: &str, version: &str) -> String;
+
+    /// Fetch the zstd-compressed rustdoc JSON of crate@version, reporting
+    /// progress to `events`. Docs that don't exist fail with [`DocsNotFound`].
+    fn fetch(&self, crate_name: &str, version: &str, events: &dyn EventSink) -> Result<Vec<u8>>;
+
+    /// The URL crate@version is downloaded from and the token sent with it,
+    /// when the source is an HTTP server. The async API downloads these
+    /// without blocking, other sources are fetched on the blocking pool.
+    fn http_request(&self, crate_name: &str, version: &str) -> Option<(String, Option<Token>)> {
+        let _ = (crate_name, version);
+        None
+    }
+
+    /// Names of crates similar to `crate_name`, suggested when the source
+    /// has no docs for it. Lookup failures leave out the suggestions.
+    fn similar_crates(&self, crate_name: &str) -> Vec<String> {
+        let _ = crate_name;
+        Vec::new()
+    }
+}
+
+/// The source has no docs for the crate version.
+#[derive(Debug)]
+pub struct DocsNotFound {
+    pub location: String,
+}
+
+impl fmt::Display for DocsNotFound {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "No rustdoc JSON at {}", self.location)
+    }
+}
+
+impl std::error::Error for DocsNotFound {}
+
+/// Downloads docs over HTTP from docs.rs, or a server with the same URL
+/// layout: `{base_url}/crate/{name}/{version}/json`, or
+/// `{base_url}/crate/{name}/{version}/{target}/json` for the docs of another
+/// target than the default one.
+pub struct HttpSource {
+    base_url: String,
+    token: Option<Token>,
+}
+
+impl HttpSource {
+    pub fn docs_rs() -> Self {
+        Self::new("https://docs.rs")
+    }
+
+    /// A mirror of docs.rs at `base_url`.
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let base_url = base_url.into();
+        Self {
+            base_url: base_url.trim_end_matches('/').to_string(),
+            token: None,
+        }
+    }
+
+    /// Send `token` with every request, for a private mirror.
+    pub fn with_token(mut self, token: Token) -> Self {
+        self.token = Some(token);
+        self
+    }
+}
+
+/// A secret sent as the `Authorization` header to private docs servers and
+/// registry indexes. It's left out of `Debug` output, so it can't end up in
+/// logs or error messages.
+#[derive(Clone)]
+pub struct Token(String);
+
+impl Token {
+    pub fn new(token: impl Into<String>) -> Self {
+        Self(token.into())
+    }
+}
+
+impl fmt::Debug for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Token(<redacted>)")
+    }
+}
+
+/// A GET request to `url`, authorized with `token` when there is one.
+pub(crate) fn get(
+    url: &str,
+    token: Option<&Token>,
+) -> ureq::RequestBuilder<ureq::typestate::WithoutBody> {
+    let request = ureq::get(url);
+    match token {
+        Some(Token(token)) => request.header("Authorization", token),
+        None => request,
+    }
+}
+
+impl DocSource for HttpSource {
+    fn location(&self, crate_name: &str, version: &str) -> String {
+        match target() {
+            Some(target) => format!(
+                "{}/crate/{}/{}/{}/json",
+                self.base_url, crate_name, version, target
+            ),
+            None => format!("{}/crate/{}/{}/json", self.base_url, crate_name, version),
+        }
+    }
+
+    fn fetch(&self, crate_name: &str, version: &str, events: &dyn EventSink) -> Result<Vec<u8>> {
+        download(
+            &self.location(crate_name, version),
+            self.token.as_ref(),
+            events,
+        )
+    }
+
+    fn http_request(&self, crate_name: &str, version: &str) -> Option<(String, Option<Token>)> {
+        Some((self.location(crate_name, version), self.token.clone()))
+    }
+
+    fn similar_crates(&self, crate_name: &str) -> Vec<String> {
+        similar_names(
+            crate_name,
+            &crates_io::search_names(crate_name).unwrap_or_default(),
+        )
+    }
+}
+
+/// Download the file at `url`, reporting progress to `events`. A 404 fails
+/// with [`DocsNotFound`].
+fn download(url: &str, token: Option<&Token>, events: &dyn EventSink) -> Result<Vec<u8>> {
+    let mut response = match get(url, token).call() {
+        Err(ureq::Error::StatusCode(404)) => {
+            return Err(DocsNotFound {
+                location: url.to_string(),
+            }
+            .into());
+        }
+        response => response?,
+    };
+    let total = response.body().content_length();
+
+    let mut compressed_data = Vec::new();
+    let mut reader = response.body_mut().as_reader();
+    let mut chunk = vec![0; 64 * 1024];
+    loop {
+        let read = reader.read(&mut chunk)?;
+        if read == 0 {
+            break;
+        }
+        compressed_data.extend_from_slice(&chunk[..read]);
+        events.on_download_progress(compressed_data.len() as u64, total);
+    }
+    Ok(compressed_data)
+}
+
+/// Async [`download`], for hosts running on tokio.
+#[cfg(feature = "async")]
+pub(crate) async fn download_async(
+    url: &str,
+    token: Option<&Token>,
+    events: &dyn EventSink,
+) -> Result<Vec<u8>> {
+    let mut request = reqwest::Client::new().get(url);
+    if let Some(Token(token)) = token {
+        request = request.header("Authorization", token);
+    }
+    let mut response = request.send().await?;
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Err(DocsNotFound {
+            location: url.to_string(),
+        }
+        .into());
+    }
+    response = response.error_for_status()?;
+    let total = response.content_length();
+
+    let mut compressed_data = Vec::new();
+    while let Some(chunk) = response.chunk().await? {
+        compressed_data.extend_from_slice(&chunk);
+        events.on_download_progress(compressed_data.len() as u64, total);
+    }
+    Ok(compressed_data)
+}
+
+/// A registry besides crates.io, such as a private one, with a server
+/// hosting the rustdoc JSON of its crates.
+#[derive(Clone, Debug)]
+pub struct Registry {
+    /// Name of the registry, as in `.cargo/config.toml`
+    pub name: String,
+    /// URL of its sparse index, e.g. `sparse+https://example.com/index/`
+    pub index: String,
+    /// URL of the rustdoc JSON of a crate version, with `{name}` and
+    /// `{version}` in place of the crate's name and version
+    pub docs: String,
+    /// Sent to its index and docs server, when they need authorization
+    pub token: Option<Token>,
+}
+
+impl Registry {
+    /// URL of the sparse index, without the `sparse+` prefix, ending in `/`.
+    pub(crate) fn index_url(&self) -> Result<String> {
+        let Some(url) = self.index.strip_prefix("sparse+") else {
+            bail!(
+                "The index of registry {} isn't a sparse index (sparse+https://...): {}",
+                self.name,
+                self.index
+            );
+        };
+        Ok(format!("{}/", url.trim_end_matches('/')))
+    }
+
+    /// Whether `source` is this registry, e.g. the `source` cargo reports
+    /// for a package: `sparse+https://...` or `registry+https://...`.
+    fn is_source(&self, source: &str) -> bool {
+        let url = |source: &str| {
+            let url = source.split_once('+').map_or(source, |(_, url)| url);
+            url.trim_end_matches('/').to_string()
+        };
+        url(source) == url(&self.index)
+    }
+}
+
+impl DocSource for Registry {
+    fn location(&self, crate_name: &str, version: &str) -> String {
+        self.docs
+            .replace("{name}", crate_name)
+            .replace("{version}", version)
+    }
+
+    fn fetch(&self, crate_name: &str, version: &str, events: &dyn EventSink) -> Result<Vec<u8>> {
+        download(
+            &self.location(crate_name, version),
+            self.token.as_ref(),
+            events,
+        )
+    }
+
+    fn http_request(&self, crate_name: &str, version: &str) -> Option<(String, Option<Token>)> {
+        Some((self.location(crate_name, version), self.token.clone()))
+    }
+}
+
+/// Registries besides crates.io, by name.
+static REGISTRIES: RwLock<BTreeMap<String, Registry>> = RwLock::new(BTreeMap::new());
+
+/// Registry crates outside the project are looked up in, unless it's
+/// crates.io.
+static DEFAULT_REGISTRY: RwLock<Option<String>> = RwLock::new(None);
+
+/// Make `registries` known, for every later query in this process. Crates
+/// the project depends on from one of them are fetched from its docs
+/// server instead of docs.rs.
+pub fn set_registries(registries: impl IntoIterator<Item = Registry>) {
+    if let Ok(mut current) = REGISTRIES.write() {
+        *current = registries
+            .into_iter()
+            .map(|registry| (registry.name.clone(), registry))
+            .collect();
+    }
+}
+
+/// The registries given to [`set_registries`].
+pub(crate) fn registries() -> Vec<Registry> {
+    REGISTRIES
+        .read()
+        .map(|registries| registries.values().cloned().collect())
+        .unwrap_or_default()
+}
+
+/// Look up crates the project doesn't depend on in the registry `name`,
+/// one of those given to [`set_registries`], instead of crates.io.
+pub fn set_default_registry(name: Option<String>) {
+    if let Ok(mut current) = DEFAULT_REGISTRY.write() {
+        *current = name;
+    }
+}
+
+/// The registry crates outside the project are looked up in, unless it's
+/// crates.io.
+pub(crate) fn default_registry() -> Option<String> {
+    DEFAULT_REGISTRY.read().ok().and_then(|name| name.clone())
+}
+
+/// The registry called `name`.
+pub(crate) fn registry(name: &str) -> Result<Registry> {
+    let registries = REGISTRIES.read().ok();
+    let registry = registries
+        .as_ref()
+        .and_then(|registries| registries.get(name));
+    match registry {
+        Some(registry) => Ok(registry.clone()),
+        None => bail!("Unknown registry {name}, add it to [registries] in the config"),
+    }
+}
+
+/// Name of the registry with the index `source`, as cargo reports it for a
+/// package, when it's one of the registries set.
+pub(crate) fn registry_of_source(source: &str) -> Option<String> {
+    let registries = REGISTRIES.read().ok()?;
+    registries
+        .values()
+        .find(|registry| registry.is_source(source))
+        .map(|registry| registry.name.clone())
+}
+
+/// Name of a crate of `registry` as it is cached and fetched:
+/// `registry+name`, which can't clash with a crates.io crate, or just the
+/// name for crates.io.
+pub(crate) fn qualified_name(registry: Option<&str>, crate_name: &str) -> String {
+    match registry {
+        Some(registry) => format!("{registry}+{crate_name}"),
+        None => crate_name.to_string(),
+    }
+}
+
+/// The registry and the crate name in a [`qualified_name`].
+pub(crate) fn split_qualified_name(name: &str) -> (Option<&str>, &str) {
+    match name.split_once('+') {
+        Some((registry, crate_name)) => (Some(registry), crate_name),
+        None => (None, name),
+    }
+}
+
+/// Reads docs from a directory laid out like the cache:
+/// `{dir}/{name}/{version}.zst`, or uncompressed as `{dir}/{name}/{version}.json`.
+pub struct DirSource {
+    dir: PathBuf,
+}
+
+impl DirSource {
+    pub fn new(dir: impl Into<PathBuf>) -> Self {
+        Self { dir: dir.into() }
+    }
+}
+
+impl DocSource for DirSource {
+    fn location(&self, crate_name: &str, version: &str) -> String {
+        self.dir
+            .join(crate_name)
+            .join(format!("{version}.zst"))
+            .display()
+            .to_string()
+    }
+
+    /// Read the docs at once, reported as a single step of progress.
+    fn fetch(&self, crate_name: &str, version: &str, events: &dyn EventSink) -> Result<Vec<u8>> {
+        let crate_dir = self.dir.join(crate_name);
+        let compressed = crate_dir.join(format!("{version}.zst"));
+        let json = crate_dir.join(format!("{version}.json"));
+        let data = if compressed.exists() {
+            fs::read(&compressed)
+                .with_context(|| format!("Failed to read {}", compressed.display()))?
+        } else if json.exists() {
+            let data =
+                fs::read(&json).with_context(|| format!("Failed to read {}", json.display()))?;
+            zstd::encode_all(&data[..], 1).context("Failed to compress rustdoc JSON")?
+        } else {
+            return Err(DocsNotFound {
+                location: compressed.display().to_string(),
+            }
+            .into());
+        };
+        let size = data.len() as u64;
+        events.on_download_progress(size, Some(size));
+        Ok(data)
+    }
+
+    fn similar_crates(&self, crate_name: &str) -> Vec<String> {
+        let Ok(entries) = fs::read_dir(&self.dir) else {
+            return Vec::new();
+        };
+        let names: Vec<String> = entries
+            .flatten()
+            .filter_map(|entry| entry.file_name().into_string().ok())
+            .collect();
+        similar_names(crate_name, &names)
+    }
+}
+
+/// The crate names in `names` similar to `crate_name`, or none when the
+/// crate is among them and only the version is missing.
+fn similar_names(crate_name: &str, names: &[String]) -> Vec<String> {
+    let normalized = normalize_crate_name(crate_name);
+    if names
+        .iter()
+        .any(|name| normalize_crate_name(name) == normalized)
+    {
+        return Vec::new();
+    }
+    suggest::similar(crate_name, names.iter().map(String::as_str))
+}
+
+/// Source docs are fetched from, unless one is set: docs.rs.
+static DOC_SOURCE: RwLock<Option<Arc<dyn DocSource>>> = RwLock::new(None);
+
+/// Fetch docs from `source` instead of docs.rs, for every later query in
+/// this process.
+pub fn set_doc_source(source: Arc<dyn DocSource>) {
+    if let Ok(mut current) = DOC_SOURCE.write() {
+        *current = Some(source);
+    }
+}
+
+/// The source set with [`set_doc_source`], if any.
+pub(crate) fn doc_source_override() -> Option<Arc<dyn DocSource>> {
+    DOC_SOURCE.read().ok().and_then(|source| source.clone())
+}
+
+/// Fetch docs from `source`, or docs.rs when it's `None`, returning the
+/// source set before.
+pub(crate) fn replace_doc_source(source: Option<Arc<dyn DocSource>>) -> Option<Arc<dyn DocSource>> {
+    match DOC_SOURCE.write() {
+        Ok(mut current) => std::mem::replace(&mut *current, source),
+        Err(_) => None,
+    }
+}
+
+/// The source the docs of a crate are fetched from, given its
+/// [`qualified_name`], and its name there.
+pub(crate) fn doc_source_for(name: &str) -> Result<(Arc<dyn DocSource>, &str)> {
+    match split_qualified_name(name) {
+        (Some(registry_name), crate_name) => Ok((Arc::new(registry(registry_name)?), crate_name)),
+        (None, crate_name) => Ok((doc_source(), crate_name)),
+    }
+}
+
+/// The source docs are fetched from.
+pub(crate) fn doc_source() -> Arc<dyn DocSource> {
+    doc_source_override().unwrap_or_else(|| Arc::new(HttpSource::docs_rs()))
+}
+
+/// Target the docs are built for, unless one is set: the default target of
+/// docs.rs, usually `x86_64-unknown-linux-gnu`.
+static TARGET: RwLock<Option<String>> = RwLock::new(None);
+
+/// Fetch the docs built for `target`, e.g. `x86_64-pc-windows-msvc`, for
+/// every later query in this process. `None` goes back to the default one.
+pub fn set_target(target: Option<String>) {
+    if let Ok(mut current) = TARGET.write() {
+        *current = target;
+    }
+}
+
+/// The target docs are fetched for, when it isn't the default one.
+pub(crate) fn target() -> Option<String> {
+    TARGET.read().ok().and_then(|target| target.clone())
+}
@@ -0,0 +1,111 @@
+//! Size and health numbers of a crate's public API, see `docsrs <crate>
+//! --stats`.
+
+use std::collections::{BTreeMap, HashSet};
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+
+use crate::list::{EntryKind, list_items};
+use crate::{Query, Resolution, resolve_docs};
+
+/// Counts of the public items of a version of a crate. Items reachable at
+/// several paths count once.
+pub struct ApiStats {
+    pub resolution: Resolution,
+    pub crate_name: String,
+    pub version: String,
+    /// Number of items of each kind, in the order of [`EntryKind`]
+    pub by_kind: BTreeMap<EntryKind, usize>,
+    /// Items with docs
+    pub documented: usize,
+    /// Items marked `#[deprecated]`
+    pub deprecated: usize,
+    /// `unsafe fn`s
+    pub unsafe_fns: usize,
+}
+
+/// Count the public items of the crate in `args`.
+pub fn api_stats(args: &Query) -> Result<ApiStats> {
+    let mut resolution = Resolution::default();
+    let doc = resolve_docs(&args.spec, &mut resolution)?.load_doc(args.no_cache, &*args.events)?;
+    let version = doc
+        .crate_data()
+        .crate_version
+        .clone()
+        .context("The docs don't say which version of the crate they're for")?;
+
+    let mut seen = HashSet::new();
+    let mut stats = ApiStats {
+        resolution,
+        crate_name: args.spec.name.clone(),
+        version,
+        by_kind: BTreeMap::new(),
+        documented: 0,
+        deprecated: 0,
+        unsafe_fns: 0,
+    };
+    for item in list_items(&doc) {
+        if !seen.insert(item.id) {
+            continue;
+        }
+        *stats.by_kind.entry(item.kind).or_default() += 1;
+        if item.qualifiers.is_unsafe {
+            stats.unsafe_fns += 1;
+        }
+        let Some(data) = doc.crate_data().index.get(&item.id) else {
+            continue;
+        };
+        if data
+            .docs
+            .as_deref()
+            .is_some_and(|docs| !docs.trim().is_empty())
+        {
+            stats.documented += 1;
+        }
+        if data.deprecation.is_some() {
+            stats.deprecated += 1;
+        }
+    }
+    Ok(stats)
+}
+
+impl ApiStats {
+    /// Number of public items.
+    pub fn total(&self) -> usize {
+        self.by_kind.values().sum()
+    }
+
+    /// Render the counts as an aligned table, items by kind first.
+    pub fn render(&self) -> String {
+        let mut output = String::new();
+        if let Some(message) = &self.resolution.message {
+            output.push_str(&format!("{}\n", format!("// {message}").bright_black()));
+        }
+        let header = format!("// public API of {} {}", self.crate_name, self.version);
+        output.push_str(&format!("{}\n\n", header.bright_black()));
+
+        let total = self.total();
+        let mut rows = vec![("items".to_string(), total)];
+        rows.extend(
+            self.by_kind
+                .iter()
+                .map(|(kind, count)| (format!("  {}", kind.keyword()), *count)),
+        );
+        rows.push(("documented".to_string(), self.documented));
+        rows.push(("deprecated".to_string(), self.deprecated));
+        rows.push(("unsafe fn".to_string(), self.unsafe_fns));
+
+        let width = rows.iter().map(|(name, _)| name.len()).max().unwrap_or(0);
+        let count_width = total.to_string().len();
+        for (name, count) in rows {
+            output.push_str(&format!("{name:width$}  {count:>count_width$}"));
+            if name == "documented" {
+                let percent = count as f64 / total.max(1) as f64 * 100.0;
+                output.push_str(&format!(" ({percent:.1}%)"));
+            }
+            output.push('\n');
+        }
+        output
+    }
+}
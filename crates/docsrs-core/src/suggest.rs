@@ -0,0 +1,117 @@
+//! "Did you mean" suggestions for item paths and crate names that don't
+//! exist.
+
+/// Most suggestions offered at once.
+const MAX_SUGGESTIONS: usize = 3;
+
+/// The candidates most similar to `target`, best first: those naming the
+/// same item in another module or with different case, then those whose
+/// name is a few typos away.
+pub(crate) fn similar<'a>(
+    target: &str,
+    candidates: impl IntoIterator<Item = &'a str>,
+) -> Vec<String> {
+    let target_lower = target.to_lowercase();
+    let target_name = last_segment(&target_lower);
+    let max_distance = (target_name.chars().count() / 3).max(1);
+
+    let mut scored: Vec<(usize, &str)> = candidates
+        .into_iter()
+        .filter(|&candidate| candidate != target)
+        .filter_map(|candidate| {
+            let lower = candidate.to_lowercase();
+            let distance = edit_distance(target_name, last_segment(&lower));
+            (distance <= max_distance).then_some((distance, candidate))
+        })
+        .collect();
+    scored.sort();
+    scored.dedup_by(|a, b| a.1 == b.1);
+    scored
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate.to_string())
+        .collect()
+}
+
+/// A `did you mean ...?` hint offering `suggestions`, if there are any.
+pub(crate) fn did_you_mean(suggestions: &[String]) -> Option<String> {
+    match suggestions {
+        [] => None,
+        [only] => Some(format!("did you mean {only}?")),
+        [rest @ .., last] => Some(format!("did you mean {} or {}?", rest.join(", "), last)),
+    }
+}
+
+fn last_segment(path: &str) -> &str {
+    path.rsplit("::").next().unwrap_or(path)
+}
+
+/// Edit distance between `a` and `b` in chars, counting insertions,
+/// deletions, substitutions and swaps of adjacent chars.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let a: Vec<char> = a.chars().collect();
+    let b: Vec<char> = b.chars().collect();
+    // distances[i][j] is the distance between the first i chars of `a` and
+    // the first j chars of `b`
+    let mut distances = vec![vec![0; b.len() + 1]; a.len() + 1];
+    for (i, row) in distances.iter_mut().enumerate() {
+        row[0] = i;
+    }
+    distances[0] = (0..=b.len()).collect();
+    for i in 1..=a.len() {
+        for j in 1..=b.len() {
+            let substitution = distances[i - 1][j - 1] + usize::from(a[i - 1] != b[j - 1]);
+            let mut distance = substitution
+                .min(distances[i - 1][j] + 1)
+                .min(distances[i][j - 1] + 1);
+            if i > 1 && j > 1 && a[i - 1] == b[j - 2] && a[i - 2] == b[j - 1] {
+                distance = distance.min(distances[i - 2][j - 2] + 1);
+            }
+            distances[i][j] = distance;
+        }
+    }
+    distances[a.len()][b.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("spawn", "spawn"), 0);
+        assert_eq!(edit_distance("spwan", "spawn"), 1);
+        assert_eq!(edit_distance("optoins", "options"), 1);
+        assert_eq!(edit_distance("serd", "serde"), 1);
+        assert_eq!(edit_distance("", "abc"), 3);
+    }
+
+    #[test]
+    fn test_similar_prefers_same_name_in_other_module() {
+        let candidates = [
+            "tokio::task::JoinHandle",
+            "tokio::task::JoinSet",
+            "tokio::spawn",
+        ];
+        assert_eq!(
+            similar("tokio::JoinHandle", candidates),
+            ["tokio::task::JoinHandle"]
+        );
+        assert_eq!(similar("tokio::spwan", candidates), ["tokio::spawn"]);
+        assert!(similar("tokio::net", candidates).is_empty());
+    }
+
+    #[test]
+    fn test_did_you_mean_lists_suggestions() {
+        assert_eq!(did_you_mean(&[]), None);
+        assert_eq!(
+            did_you_mean(&["serde".to_string()]).as_deref(),
+            Some("did you mean serde?")
+        );
+        let three = ["a".to_string(), "b".to_string(), "c".to_string()];
+        assert_eq!(
+            did_you_mean(&three).as_deref(),
+            Some("did you mean a, b or c?")
+        );
+    }
+}
@@ -0,0 +1,170 @@
+//! Phase timings reported by `--timings`.
+//!
+//! Timings are recorded per thread, so concurrent queries (as in the MCP
+//! server) don't mix their phases.
+// Only the CLI reports timings, which needs the `native` feature
+#![cfg_attr(not(feature = "native"), allow(dead_code))]
+
+use std::cell::RefCell;
+use std::fmt::Write;
+#[cfg(all(feature = "native", not(feature = "simd-json")))]
+use std::io::{self, Read};
+use std::time::{Duration, Instant};
+
+/// A phase of answering a query.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub(crate) enum Phase {
+    /// Loading highlighting themes and detecting terminal colors
+    Setup,
+    /// Resolving the crate version from `Cargo.toml`
+    Resolve,
+    /// Building docs of a local crate with `cargo doc`
+    Build,
+    /// Downloading rustdoc JSON from docs.rs
+    Download,
+    /// Decompressing the zstd-compressed JSON
+    Decompress,
+    /// Deserializing the JSON
+    Parse,
+    /// Building the item tree with paths from the crate
+    Process,
+    /// Loading or building the search index
+    Index,
+    /// Everything else, mostly formatting the output
+    Render,
+}
+
+impl Phase {
+    fn label(self) -> &'static str {
+        match self {
+            Phase::Setup => "setup",
+            Phase::Resolve => "resolve",
+            Phase::Build => "build",
+            Phase::Download => "download",
+            Phase::Decompress => "decompress",
+            Phase::Parse => "parse",
+            Phase::Process => "process",
+            Phase::Index => "index",
+            Phase::Render => "render",
+        }
+    }
+}
+
+thread_local! {
+    /// Time spent per phase, in the order phases were first seen, while
+    /// timings are enabled on this thread.
+    static TIMINGS: RefCell<Option<Vec<(Phase, Duration)>>> = const { RefCell::new(None) };
+}
+
+/// Start recording timings on this thread, or stop when `enabled` is false.
+/// Anything recorded before is discarded.
+pub(crate) fn reset(enabled: bool) {
+    TIMINGS.with_borrow_mut(|timings| *timings = enabled.then(Vec::new));
+}
+
+/// Add `duration` to the time spent in `phase`.
+pub(crate) fn record(phase: Phase, duration: Duration) {
+    TIMINGS.with_borrow_mut(|timings| {
+        let Some(timings) = timings else {
+            return;
+        };
+        match timings.iter_mut().find(|(p, _)| *p == phase) {
+            Some((_, total)) => *total += duration,
+            None => timings.push((phase, duration)),
+        }
+    });
+}
+
+/// Run `f`, adding the time it takes to `phase`. The clock isn't read when
+/// timings aren't recorded, as it isn't available on every target.
+pub(crate) fn time<T>(phase: Phase, f: impl FnOnce() -> T) -> T {
+    if TIMINGS.with_borrow(Option::is_none) {
+        return f();
+    }
+    let start = Instant::now();
+    let result = f();
+    record(phase, start.elapsed());
+    result
+}
+
+/// Stop recording and format the recorded phases, one per line. Time in
+/// `total` not spent in any recorded phase is reported as rendering.
+pub(crate) fn finish(total: Duration) -> Option<String> {
+    let mut timings = TIMINGS.with_borrow_mut(Option::take)?;
+    let recorded: Duration = timings.iter().map(|(_, duration)| *duration).sum();
+    timings.push((Phase::Render, total.saturating_sub(recorded)));
+
+    let mut report = String::from("timings:\n");
+    for (phase, duration) in timings {
+        let _ = writeln!(
+            report,
+            "  {:<12}{:>10}",
+            phase.label(),
+            format_duration(duration)
+        );
+    }
+    let _ = writeln!(report, "  {:<12}{:>10}", "total", format_duration(total));
+    Some(report)
+}
+
+fn format_duration(duration: Duration) -> String {
+    format!("{:.1}ms", duration.as_secs_f64() * 1000.0)
+}
+
+/// A reader that keeps track of the time spent reading from `inner`, used to
+/// separate decompression from parsing when both happen in one pass.
+#[cfg(all(feature = "native", not(feature = "simd-json")))]
+pub(crate) struct TimedReader<R> {
+    inner: R,
+    pub(crate) elapsed: Duration,
+}
+
+#[cfg(all(feature = "native", not(feature = "simd-json")))]
+impl<R> TimedReader<R> {
+    pub(crate) fn new(inner: R) -> Self {
+        Self {
+            inner,
+            elapsed: Duration::ZERO,
+        }
+    }
+}
+
+#[cfg(all(feature = "native", not(feature = "simd-json")))]
+impl<R: Read> Read for TimedReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let start = Instant::now();
+        let result = self.inner.read(buf);
+        self.elapsed += start.elapsed();
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_finish_reports_remaining_time_as_render() {
+        reset(true);
+        record(Phase::Parse, Duration::from_millis(30));
+        record(Phase::Download, Duration::from_millis(50));
+        record(Phase::Parse, Duration::from_millis(20));
+        let report = finish(Duration::from_millis(120)).unwrap();
+        assert_eq!(
+            report,
+            "timings:\n  \
+             parse           50.0ms\n  \
+             download        50.0ms\n  \
+             render          20.0ms\n  \
+             total          120.0ms\n"
+        );
+        assert_eq!(finish(Duration::ZERO), None);
+    }
+
+    #[test]
+    fn test_disabled_records_nothing() {
+        reset(false);
+        record(Phase::Parse, Duration::from_millis(30));
+        assert_eq!(finish(Duration::ZERO), None);
+    }
+}
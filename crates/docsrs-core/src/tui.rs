@@ -0,0 +1,527 @@
+//! Interactive docs browser and item picker, see `docsrs --tui` and
+//! `docsrs --pick`.
+
+use ansi_to_tui::IntoText;
+use anyhow::{Context, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
+use jsondoc::JsonDoc;
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Modifier, Style};
+use ratatui::text::{Line, Span, Text};
+use ratatui::widgets::{Block, List, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame, TerminalOptions, Viewport};
+use rustdoc_fmt::{Output, tokens_to_string};
+use rustdoc_types::Id;
+
+use crate::doc;
+use crate::list::{EntryKind, ListItem, list_items};
+use crate::result::StyleConfig;
+
+/// Browse `doc` until the user quits, starting in `start`, the full path of
+/// the crate root or an item in it.
+pub(crate) fn browse(doc: &JsonDoc, start: &str, style: &StyleConfig) -> Result<()> {
+    let mut browser = Browser::new(doc, start);
+    // Docs are rendered with colors, which are turned into terminal styles
+    colored::control::set_override(true);
+    let mut terminal = init_terminal(ratatui::try_init())?;
+    let result = browser.run(&mut terminal, style);
+    ratatui::restore();
+    result
+}
+
+struct Browser<'a> {
+    doc: &'a JsonDoc,
+    /// Every listed item, sorted by path
+    items: Vec<ListItem>,
+    /// Paths of the items drilled into, the crate root first, with the
+    /// selection to restore when going back up from the next one
+    parents: Vec<(String, usize)>,
+    /// Search typed by the user, matched against the paths under the parent
+    filter: String,
+    list: ListState,
+    /// Lines the docs are scrolled down by
+    scroll: u16,
+    /// Height of the docs in the last frame, scrolled by half of it
+    docs_height: u16,
+    /// Docs of the selected item rendered at a width, kept between frames
+    rendered: Option<(Id, u16, Text<'static>)>,
+}
+
+impl<'a> Browser<'a> {
+    fn new(doc: &'a JsonDoc, start: &str) -> Self {
+        let mut items = list_items(doc);
+        items.sort_by(|item1, item2| item1.path.cmp(&item2.path));
+
+        let root = start.split("::").next().unwrap_or(start).to_string();
+        let mut browser = Self {
+            doc,
+            items,
+            parents: vec![(root.clone(), 0)],
+            filter: String::new(),
+            list: ListState::default().with_selected(Some(0)),
+            scroll: 0,
+            docs_height: 0,
+            rendered: None,
+        };
+
+        // Drill into each item on the way to `start` that has children
+        let mut path = root;
+        for segment in start.split("::").skip(1) {
+            path = format!("{path}::{segment}");
+            let Some(position) = browser.visible().iter().position(|&i| {
+                browser.items[i].path == path && browser.has_children(&browser.items[i])
+            }) else {
+                break;
+            };
+            browser.list.select(Some(position));
+            browser.enter();
+        }
+        browser
+    }
+
+    fn parent(&self) -> &str {
+        let (path, _) = self.parents.last().expect("the crate root is never left");
+        path
+    }
+
+    /// Positions in `items` of the children of the parent, or of every item
+    /// under it matching the filter while searching.
+    fn visible(&self) -> Vec<usize> {
+        let prefix = format!("{}::", self.parent());
+        let depth = self.parent().split("::").count() + 1;
+        let filter = self.filter.to_lowercase();
+        self.items
+            .iter()
+            .enumerate()
+            .filter(|(_, item)| {
+                let Some(rest) = item.path.strip_prefix(&prefix) else {
+                    return false;
+                };
+                if filter.is_empty() {
+                    item.segments().len() == depth
+                } else {
+                    rest.to_lowercase().contains(&filter)
+                }
+            })
+            .map(|(i, _)| i)
+            .collect()
+    }
+
+    fn selected(&self) -> Option<&ListItem> {
+        let visible = self.visible();
+        let i = *visible.get(self.list.selected()?)?;
+        Some(&self.items[i])
+    }
+
+    /// Whether `item` can be drilled into: a module, type or trait with
+    /// items under it.
+    fn has_children(&self, item: &ListItem) -> bool {
+        let containers = [
+            EntryKind::Module,
+            EntryKind::Struct,
+            EntryKind::Enum,
+            EntryKind::Trait,
+        ];
+        let prefix = format!("{}::", item.path);
+        containers.contains(&item.kind) && self.items.iter().any(|i| i.path.starts_with(&prefix))
+    }
+
+    fn select(&mut self, position: usize) {
+        self.list.select(Some(position));
+        self.scroll = 0;
+    }
+
+    /// Show the children of the selected item, if it has any.
+    fn enter(&mut self) {
+        let Some(item) = self.selected() else {
+            return;
+        };
+        if !self.has_children(item) {
+            return;
+        }
+        let path = item.path.clone();
+        let selection = self.list.selected().unwrap_or(0);
+        self.parents.push((path, selection));
+        self.filter.clear();
+        self.select(0);
+    }
+
+    /// Go back to the parent of the parent, selecting the item left.
+    fn up(&mut self) {
+        if self.parents.len() > 1 {
+            let (_, selection) = self.parents.pop().expect("checked above");
+            self.filter.clear();
+            self.select(selection);
+        }
+    }
+
+    fn run(&mut self, terminal: &mut DefaultTerminal, style: &StyleConfig) -> Result<()> {
+        loop {
+            terminal.draw(|frame| self.draw(frame, style))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && !self.handle_key(key)
+            {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Act on a key press, returning false when the user quits.
+    fn handle_key(&mut self, key: KeyEvent) -> bool {
+        let count = self.visible().len();
+        let selected = self.list.selected().unwrap_or(0);
+        let half_page = (self.docs_height / 2).max(1);
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => return false,
+            KeyCode::Esc if self.filter.is_empty() => return false,
+            KeyCode::Esc => {
+                self.filter.clear();
+                self.select(0);
+            }
+            KeyCode::Enter => self.enter(),
+            KeyCode::Backspace if self.filter.is_empty() => self.up(),
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.select(0);
+            }
+            KeyCode::Up => self.select(selected.saturating_sub(1)),
+            KeyCode::Down => self.select((selected + 1).min(count.saturating_sub(1))),
+            KeyCode::Home => self.select(0),
+            KeyCode::End => self.select(count.saturating_sub(1)),
+            KeyCode::PageUp => self.scroll = self.scroll.saturating_sub(half_page),
+            KeyCode::PageDown => self.scroll = self.scroll.saturating_add(half_page),
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.select(0);
+            }
+            _ => {}
+        }
+        true
+    }
+
+    fn draw(&mut self, frame: &mut Frame, style: &StyleConfig) {
+        let [main, status] =
+            Layout::vertical([Constraint::Fill(1), Constraint::Length(1)]).areas(frame.area());
+        let [list_area, docs_area] =
+            Layout::horizontal([Constraint::Percentage(35), Constraint::Percentage(65)])
+                .areas(main);
+
+        let entries: Vec<Line> = self
+            .visible()
+            .into_iter()
+            .map(|i| {
+                let item = &self.items[i];
+                let output = match self.filter.is_empty() {
+                    true => item.as_name_output(),
+                    false => item.as_output(),
+                };
+                output_line(output, style)
+            })
+            .collect();
+        let list = List::new(entries)
+            .block(Block::bordered().title(self.parent().to_string()))
+            .highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+
+        let (title, docs) = match self.selected() {
+            Some(item) => {
+                let (path, id) = (item.path.clone(), item.id);
+                (
+                    path,
+                    self.docs(id, docs_area.width.saturating_sub(2), style),
+                )
+            }
+            None => (String::new(), Text::default()),
+        };
+        self.docs_height = docs_area.height.saturating_sub(2);
+        let docs = Paragraph::new(docs)
+            .block(Block::bordered().title(title))
+            .scroll((self.scroll, 0));
+        frame.render_widget(docs, docs_area);
+
+        let help = if self.filter.is_empty() {
+            "type to search · enter: open · backspace: up · pgup/pgdn: scroll docs · esc: quit"
+                .to_string()
+        } else {
+            format!("search: {} · esc: clear", self.filter)
+        };
+        frame.render_widget(Line::from(help).style(Style::new().dim()), status);
+    }
+
+    /// Docs of the item with `id`, fitted to `width` columns.
+    fn docs(&mut self, id: Id, width: u16, style: &StyleConfig) -> Text<'static> {
+        if let Some((rendered_id, rendered_width, text)) = &self.rendered
+            && *rendered_id == id
+            && *rendered_width == width
+        {
+            return text.clone();
+        }
+        let text = doc::signature_for_id(self.doc, &id, &style.colorizer, Some(width.into()))
+            .and_then(|docs| Ok(docs.into_text()?))
+            .unwrap_or_else(|e| Text::from(format!("Failed to render docs: {e:#}")));
+        self.rendered = Some((id, width, text.clone()));
+        text
+    }
+}
+
+/// Rows of the picker, including the prompt.
+const PICKER_HEIGHT: u16 = 12;
+
+/// Let the user pick one of `items` with a fuzzy search below the cursor,
+/// returning its position, or `None` when the user gives up.
+pub(crate) fn pick(items: &[ListItem], style: &StyleConfig) -> Result<Option<usize>> {
+    let choices = items
+        .iter()
+        .map(|item| (item.path.clone(), item.as_output()))
+        .collect();
+    pick_from(choices, style)
+}
+
+/// Let the user pick one of the `name@version` specs, like [`pick`].
+pub(crate) fn pick_spec(specs: &[String], style: &StyleConfig) -> Result<Option<usize>> {
+    let choices = specs
+        .iter()
+        .map(|spec| {
+            let mut output = Output::new();
+            match spec.split_once('@') {
+                Some((name, version)) => output
+                    .identifier(name.to_string())
+                    .symbol("@")
+                    .identifier(version.to_string()),
+                None => output.identifier(spec.clone()),
+            };
+            (spec.clone(), output)
+        })
+        .collect();
+    pick_from(choices, style)
+}
+
+/// Let the user pick one of `choices`, each matched by its text and shown
+/// as its output.
+pub(crate) fn pick_from(
+    choices: Vec<(String, Output)>,
+    style: &StyleConfig,
+) -> Result<Option<usize>> {
+    let mut picker = Picker::new(choices);
+    colored::control::set_override(true);
+    let height = PICKER_HEIGHT.min(picker.choices.len() as u16 + 1);
+    let mut terminal = init_terminal(ratatui::try_init_with_options(TerminalOptions {
+        viewport: Viewport::Inline(height),
+    }))?;
+    let result = picker.run(&mut terminal, style);
+    // Leave no trace of the picker above the docs of the picked item
+    let cleared = terminal.clear();
+    ratatui::restore();
+    cleared?;
+    result
+}
+
+struct Picker {
+    /// Text to match and output to show of each choice
+    choices: Vec<(String, Output)>,
+    /// Fuzzy search typed by the user
+    filter: String,
+    /// Positions in `choices` of the matches, best first
+    matches: Vec<usize>,
+    list: ListState,
+}
+
+impl Picker {
+    fn new(choices: Vec<(String, Output)>) -> Self {
+        let mut picker = Self {
+            choices,
+            filter: String::new(),
+            matches: Vec::new(),
+            list: ListState::default(),
+        };
+        picker.update_matches();
+        picker
+    }
+
+    /// Match the items against the filter, best first and in list order
+    /// between equal matches.
+    fn update_matches(&mut self) {
+        let matcher = SkimMatcherV2::default();
+        let mut scored: Vec<(i64, usize)> = self
+            .choices
+            .iter()
+            .enumerate()
+            .filter_map(|(i, (text, _))| Some((matcher.fuzzy_match(text, &self.filter)?, i)))
+            .collect();
+        scored.sort_by_key(|&(score, i)| (std::cmp::Reverse(score), i));
+        self.matches = scored.into_iter().map(|(_, i)| i).collect();
+        self.list.select((!self.matches.is_empty()).then_some(0));
+    }
+
+    fn run(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        style: &StyleConfig,
+    ) -> Result<Option<usize>> {
+        loop {
+            terminal.draw(|frame| self.draw(frame, style))?;
+            if let Event::Key(key) = event::read()?
+                && key.kind == KeyEventKind::Press
+                && let Some(picked) = self.handle_key(key)
+            {
+                return Ok(picked);
+            }
+        }
+    }
+
+    /// Act on a key press, returning the outcome once the user picks an item
+    /// or gives up.
+    fn handle_key(&mut self, key: KeyEvent) -> Option<Option<usize>> {
+        let selected = self.list.selected().unwrap_or(0);
+        match key.code {
+            KeyCode::Char('c') if key.modifiers.contains(KeyModifiers::CONTROL) => {
+                return Some(None);
+            }
+            KeyCode::Esc => return Some(None),
+            KeyCode::Enter => {
+                let picked = self.list.selected().and_then(|i| self.matches.get(i));
+                return picked.map(|&i| Some(i));
+            }
+            KeyCode::Up => self.list.select(Some(selected.saturating_sub(1))),
+            KeyCode::Down => {
+                let last = self.matches.len().saturating_sub(1);
+                self.list.select(Some((selected + 1).min(last)));
+            }
+            KeyCode::Backspace => {
+                self.filter.pop();
+                self.update_matches();
+            }
+            KeyCode::Char(c) => {
+                self.filter.push(c);
+                self.update_matches();
+            }
+            _ => {}
+        }
+        None
+    }
+
+    fn draw(&mut self, frame: &mut Frame, style: &StyleConfig) {
+        let [prompt, list_area] =
+            Layout::vertical([Constraint::Length(1), Constraint::Fill(1)]).areas(frame.area());
+        let count = format!("  {}/{}", self.matches.len(), self.choices.len());
+        let prompt_line = Line::from(vec![
+            "> ".into(),
+            self.filter.clone().into(),
+            Span::styled(count, Style::new().dim()),
+        ]);
+        frame.render_widget(prompt_line, prompt);
+
+        let entries: Vec<Line> = self
+            .matches
+            .iter()
+            .map(|&i| output_line(self.choices[i].1.clone(), style))
+            .collect();
+        let list =
+            List::new(entries).highlight_style(Style::new().add_modifier(Modifier::REVERSED));
+        frame.render_stateful_widget(list, list_area, &mut self.list);
+    }
+}
+
+/// The terminal set up by `init`, restoring it when setting it up failed
+/// halfway.
+fn init_terminal(init: std::io::Result<DefaultTerminal>) -> Result<DefaultTerminal> {
+    init.inspect_err(|_| ratatui::restore())
+        .context("Failed to set up the terminal")
+}
+
+/// `output` colored as in the CLI output, as a single line.
+fn output_line(output: Output, style: &StyleConfig) -> Line<'static> {
+    let tokens = output.into_tokens();
+    let colored = style.colorizer.tokens(&tokens).into_text().ok();
+    colored
+        .and_then(|text| text.lines.into_iter().next())
+        .unwrap_or_else(|| Line::from(tokens_to_string(&tokens)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_rustdoc_json;
+
+    fn test_items() -> JsonDoc {
+        let docs = crate::test_docs("test_items.json");
+        JsonDoc::from(parse_rustdoc_json(std::fs::read(docs).unwrap()).unwrap())
+    }
+
+    fn visible_paths(browser: &Browser) -> Vec<String> {
+        let visible = browser.visible().into_iter();
+        visible.map(|i| browser.items[i].path.clone()).collect()
+    }
+
+    fn press(browser: &mut Browser, code: KeyCode) {
+        browser.handle_key(KeyEvent::new(code, KeyModifiers::NONE));
+    }
+
+    #[test]
+    fn test_enter_and_backspace_move_between_levels() {
+        let doc = test_items();
+        let mut browser = Browser::new(&doc, "test_items");
+        assert!(visible_paths(&browser).contains(&"test_items::builder".to_string()));
+
+        // The module, not the function of the same name
+        let builder = browser
+            .visible()
+            .into_iter()
+            .position(|i| {
+                let item = &browser.items[i];
+                item.path == "test_items::builder" && item.kind == EntryKind::Module
+            })
+            .unwrap();
+        browser.select(builder);
+        press(&mut browser, KeyCode::Enter);
+        assert_eq!(browser.parent(), "test_items::builder");
+        assert_eq!(visible_paths(&browser), ["test_items::builder::Options"]);
+
+        press(&mut browser, KeyCode::Backspace);
+        assert_eq!(browser.parent(), "test_items");
+        assert_eq!(browser.list.selected(), Some(builder));
+    }
+
+    #[test]
+    fn test_typing_searches_under_the_parent() {
+        let doc = test_items();
+        let mut browser = Browser::new(&doc, "test_items");
+        for c in "opt".chars() {
+            press(&mut browser, KeyCode::Char(c));
+        }
+        assert_eq!(visible_paths(&browser), ["test_items::builder::Options"]);
+
+        press(&mut browser, KeyCode::Esc);
+        assert!(browser.filter.is_empty());
+        assert!(visible_paths(&browser).len() > 1);
+    }
+
+    #[test]
+    fn test_start_path_opens_its_module() {
+        let doc = test_items();
+        let browser = Browser::new(&doc, "test_items::builder::Options");
+        assert_eq!(browser.parent(), "test_items::builder");
+    }
+
+    #[test]
+    fn test_picker_puts_best_match_first() {
+        let doc = test_items();
+        let items = list_items(&doc);
+        let choices = items
+            .iter()
+            .map(|item| (item.path.clone(), item.as_output()))
+            .collect();
+        let mut picker = Picker::new(choices);
+        assert_eq!(picker.matches.len(), items.len());
+
+        for c in "bopt".chars() {
+            picker.handle_key(KeyEvent::new(KeyCode::Char(c), KeyModifiers::NONE));
+        }
+        let picked = picker.handle_key(KeyEvent::new(KeyCode::Enter, KeyModifiers::NONE));
+        let picked = picked.flatten().map(|i| items[i].path.as_str());
+        assert_eq!(picked, Some("test_items::builder::Options"));
+    }
+}
@@ -0,0 +1,190 @@
+//! Links to the HTML docs of items, on docs.rs or built locally.
+
+use jsondoc::JsonDoc;
+use rustdoc_types::{Crate, Id, Item, ItemEnum, ItemKind, ItemSummary};
+
+use crate::reexport::SYSROOT_CRATES;
+
+/// The page documenting `id` in `doc`, relative to the root of the HTML
+/// docs, e.g. `tokio/task/fn.spawn.html`. Items without a page of their
+/// own, such as methods and fields, link to an anchor on their parent's
+/// page, e.g. `tokio/sync/struct.Mutex.html#method.lock`.
+pub(crate) fn html_path(doc: &JsonDoc, id: &Id) -> Option<String> {
+    let krate = doc.crate_data();
+    if let Some(summary) = krate.paths.get(id) {
+        return page_path(summary);
+    }
+    if let Some(ItemEnum::Use(use_)) = krate.index.get(id).map(|item| &item.inner) {
+        return html_path(doc, use_.id.as_ref()?);
+    }
+
+    let item = doc.items_by_id(id).next()?;
+    let parent = item
+        .ancestors()
+        .find(|ancestor| krate.paths.contains_key(&ancestor.item.item.id))?;
+    let in_trait = matches!(
+        item.parent().map(|parent| &parent.item.item.inner),
+        Some(ItemEnum::Trait(_))
+    );
+    let anchor = anchor(item.item(), in_trait)?;
+    Some(format!(
+        "{}#{anchor}",
+        html_path(doc, &parent.item.item.id)?
+    ))
+}
+
+/// URL of the HTML docs of `id` in `doc`: on docs.rs at the version of the
+/// docs, or on doc.rust-lang.org for the standard library.
+pub(crate) fn item_url(doc: &JsonDoc, id: &Id) -> Option<String> {
+    let path = html_path(doc, id)?;
+    Some(root_url(doc.crate_data(), path.split('/').next()?)? + &path)
+}
+
+/// URL of the HTML docs of `id`, an item of another crate than the one of
+/// `krate`, such as the standard library or a dependency.
+pub(crate) fn external_item_url(krate: &Crate, id: &Id) -> Option<String> {
+    let summary = krate
+        .paths
+        .get(id)
+        .filter(|summary| summary.crate_id != 0)?;
+    Some(root_url(krate, summary.path.first()?)? + &page_path(summary)?)
+}
+
+/// URL of the root of the HTML docs of `crate_name`, as seen from `krate`,
+/// with a trailing `/`.
+fn root_url(krate: &Crate, crate_name: &str) -> Option<String> {
+    let own_name = krate
+        .index
+        .get(&krate.root)
+        .and_then(|root| root.name.as_deref());
+
+    let root = if SYSROOT_CRATES.contains(&crate_name) {
+        "https://doc.rust-lang.org/stable/".to_string()
+    } else if own_name == Some(crate_name) {
+        let version = krate.crate_version.as_deref().unwrap_or("latest");
+        format!("https://docs.rs/{crate_name}/{version}/")
+    } else {
+        let html_root_url = krate
+            .external_crates
+            .values()
+            .find(|external| external.name == crate_name)
+            .and_then(|external| external.html_root_url.clone());
+        match html_root_url {
+            Some(url) if url.ends_with('/') => url,
+            Some(url) => format!("{url}/"),
+            None => format!("https://docs.rs/{crate_name}/latest/"),
+        }
+    };
+    Some(root)
+}
+
+/// Resolve `link`, a link relative to the page at `base` such as
+/// `../struct.Foo.html` or `#section`, to an absolute URL.
+pub(crate) fn join(base: &str, link: &str) -> String {
+    let base = base.split('#').next().unwrap_or(base);
+    if link.starts_with('#') {
+        return format!("{base}{link}");
+    }
+    // Keep the scheme and host, e.g. `https://docs.rs`
+    let path_start = base
+        .find("://")
+        .and_then(|scheme| base[scheme + 3..].find('/').map(|i| scheme + 3 + i))
+        .unwrap_or(base.len());
+    let (origin, path) = base.split_at(path_start);
+    let mut segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+    // The page itself isn't a directory
+    segments.pop();
+    for segment in link.split('/') {
+        match segment {
+            "" | "." => {}
+            ".." => {
+                segments.pop();
+            }
+            segment => segments.push(segment),
+        }
+    }
+    format!("{origin}/{}", segments.join("/"))
+}
+
+/// Whether `link` is a relative link to an HTML docs page, as written by
+/// hand in older crates, e.g. `../struct.Foo.html` or `./index.html#section`.
+pub(crate) fn is_relative_doc_link(link: &str) -> bool {
+    !link.contains("://")
+        && (link.starts_with('#')
+            || link.starts_with("./")
+            || link.starts_with("../")
+            || link
+                .split('#')
+                .next()
+                .is_some_and(|page| page.ends_with(".html")))
+}
+
+/// The page of an item with a path, as rustdoc names it.
+fn page_path(summary: &ItemSummary) -> Option<String> {
+    let (name, parents) = summary.path.split_last()?;
+    let prefix = match summary.kind {
+        ItemKind::Module => return Some(format!("{}/index.html", summary.path.join("/"))),
+        ItemKind::Variant => {
+            let (enum_name, enum_parents) = parents.split_last()?;
+            return Some(format!(
+                "{}/enum.{enum_name}.html#variant.{name}",
+                enum_parents.join("/")
+            ));
+        }
+        ItemKind::Struct => "struct",
+        ItemKind::Enum => "enum",
+        ItemKind::Union => "union",
+        ItemKind::Trait => "trait",
+        ItemKind::TraitAlias => "traitalias",
+        ItemKind::Function => "fn",
+        ItemKind::TypeAlias => "type",
+        ItemKind::Constant => "constant",
+        ItemKind::Static => "static",
+        ItemKind::Macro => "macro",
+        ItemKind::ProcAttribute => "attr",
+        ItemKind::ProcDerive => "derive",
+        ItemKind::Primitive => "primitive",
+        ItemKind::Keyword => "keyword",
+        _ => return None,
+    };
+    Some(format!("{}/{prefix}.{name}.html", parents.join("/")))
+}
+
+/// The anchor of an item documented on its parent's page.
+fn anchor(item: &Item, in_trait: bool) -> Option<String> {
+    let prefix = match &item.inner {
+        ItemEnum::Function(function) if in_trait && !function.has_body => "tymethod",
+        ItemEnum::Function(_) => "method",
+        ItemEnum::StructField(_) => "structfield",
+        ItemEnum::Variant(_) => "variant",
+        ItemEnum::AssocConst { .. } => "associatedconstant",
+        ItemEnum::AssocType { .. } => "associatedtype",
+        _ => return None,
+    };
+    Some(format!("{prefix}.{}", item.name.as_deref()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_join() {
+        let base = "https://docs.rs/tokio/1.0.0/tokio/task/fn.spawn.html";
+        assert_eq!(
+            join(base, "../struct.Foo.html"),
+            "https://docs.rs/tokio/1.0.0/tokio/struct.Foo.html"
+        );
+        assert_eq!(
+            join(base, "./index.html#section"),
+            "https://docs.rs/tokio/1.0.0/tokio/task/index.html#section"
+        );
+        assert_eq!(
+            join(&format!("{base}#examples"), "#panics"),
+            "https://docs.rs/tokio/1.0.0/tokio/task/fn.spawn.html#panics"
+        );
+        assert!(is_relative_doc_link("struct.Foo.html#method.bar"));
+        assert!(!is_relative_doc_link("Foo::bar"));
+        assert!(!is_relative_doc_link("https://example.com/a.html"));
+    }
+}
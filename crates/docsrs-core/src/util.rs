@@ -5,6 +5,7 @@ pub fn normalize_crate_name(name: &str) -> String {
 
 /// Return an alternate crate name by swapping underscores and hyphens.
 /// Returns `None` if the name contains neither.
+#[cfg(feature = "native")]
 pub fn alternate_crate_name(name: &str) -> Option<String> {
     if name.contains('_') {
         Some(name.replace('_', "-"))
@@ -15,11 +16,140 @@ pub fn alternate_crate_name(name: &str) -> Option<String> {
     }
 }
 
+/// Format a size in bytes for display, e.g. `1.5 MB`.
+#[cfg(feature = "native")]
+pub fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{bytes} B")
+    } else {
+        format!("{size:.1} {}", UNITS[unit])
+    }
+}
+
+/// Open `target`, a URL or file, in the browser: `$BROWSER` when set, or
+/// else the platform's default.
+#[cfg(feature = "native")]
+pub fn open_in_browser(target: &str) -> anyhow::Result<()> {
+    use std::process::{Command, Stdio};
+
+    let mut command = match std::env::var_os("BROWSER").filter(|browser| !browser.is_empty()) {
+        Some(browser) => Command::new(browser),
+        None if cfg!(target_os = "macos") => Command::new("open"),
+        None if cfg!(windows) => {
+            let mut command = Command::new("cmd");
+            command.args(["/C", "start", ""]);
+            command
+        }
+        None => Command::new("xdg-open"),
+    };
+    command
+        .arg(target)
+        .stdin(Stdio::null())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to open {target} in the browser: {e}"))?;
+    Ok(())
+}
+
+/// Show `text` through the shell command `pager`, e.g. `less -R`, waiting
+/// until the user quits it.
+#[cfg(feature = "native")]
+pub fn run_pager(pager: &str, text: &str) -> anyhow::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let mut command = if cfg!(windows) {
+        let mut command = Command::new("cmd");
+        command.arg("/C");
+        command
+    } else {
+        let mut command = Command::new("sh");
+        command.arg("-c");
+        command
+    };
+    let mut child = command
+        .arg(pager)
+        .stdin(Stdio::piped())
+        .spawn()
+        .map_err(|e| anyhow::anyhow!("Failed to run pager '{pager}': {e}"))?;
+    if let Some(mut stdin) = child.stdin.take() {
+        // Quitting the pager early closes the pipe, which is fine
+        match stdin.write_all(text.as_bytes()) {
+            Err(e) if e.kind() != std::io::ErrorKind::BrokenPipe => return Err(e.into()),
+            _ => {}
+        }
+    }
+    child.wait()?;
+    Ok(())
+}
+
+/// Put `text` on the system clipboard with the platform's clipboard tool:
+/// `pbcopy`, `clip`, or `wl-copy`, `xclip` or `xsel` on Linux.
+#[cfg(feature = "native")]
+pub fn copy_to_clipboard(text: &str) -> anyhow::Result<()> {
+    use std::io::Write;
+    use std::process::{Command, Stdio};
+
+    let tools: &[&[&str]] = if cfg!(target_os = "macos") {
+        &[&["pbcopy"]]
+    } else if cfg!(windows) {
+        &[&["clip"]]
+    } else {
+        &[
+            &["wl-copy"],
+            &["xclip", "-selection", "clipboard"],
+            &["xsel", "--clipboard", "--input"],
+        ]
+    };
+    for tool in tools {
+        let Ok(mut child) = Command::new(tool[0])
+            .args(&tool[1..])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()
+        else {
+            continue;
+        };
+        if let Some(mut stdin) = child.stdin.take() {
+            stdin.write_all(text.as_bytes())?;
+        }
+        if child.wait()?.success() {
+            return Ok(());
+        }
+    }
+    anyhow::bail!(
+        "No clipboard tool worked, tried: {}",
+        tools
+            .iter()
+            .map(|tool| tool[0])
+            .collect::<Vec<_>>()
+            .join(", ")
+    )
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
 
     #[test]
+    #[cfg(feature = "native")]
+    fn test_format_size() {
+        assert_eq!(format_size(512), "512 B");
+        assert_eq!(format_size(1536), "1.5 KB");
+        assert_eq!(format_size(3 * 1024 * 1024), "3.0 MB");
+    }
+
+    #[test]
+    #[cfg(feature = "native")]
     fn test_alternate_underscore_to_hyphen() {
         assert_eq!(
             alternate_crate_name("iroh_docs"),
@@ -28,6 +158,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "native")]
     fn test_alternate_hyphen_to_underscore() {
         assert_eq!(
             alternate_crate_name("serde-json"),
@@ -36,11 +167,13 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "native")]
     fn test_alternate_no_separator() {
         assert_eq!(alternate_crate_name("tokio"), None);
     }
 
     #[test]
+    #[cfg(feature = "native")]
     fn test_alternate_multiple_underscores() {
         assert_eq!(alternate_crate_name("a_b_c_d"), Some("a-b-c-d".to_string()));
     }
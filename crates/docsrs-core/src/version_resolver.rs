@@ -1,9 +1,13 @@
 use anyhow::{Context, Result};
 use cargo_metadata::{DependencyKind as CargoDependencyKind, Metadata, MetadataCommand, PackageId};
-use std::collections::{HashMap, HashSet, VecDeque};
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
 use std::env;
+use std::fs;
 use std::path::PathBuf;
+use std::sync::RwLock;
 
+use crate::result::CrateInfo;
+use crate::source::registry_of_source;
 use crate::util::normalize_crate_name;
 
 /// The kind of dependency
@@ -152,6 +156,80 @@ impl VersionResolver {
         Some(direct_matches.remove(0))
     }
 
+    /// Package metadata of `name@version` in the project. Only crates from
+    /// a registry get a docs.rs link, as workspace and path crates may not be
+    /// published.
+    pub fn crate_info(&self, name: &str, version: &str) -> Option<CrateInfo> {
+        let package = self
+            .metadata
+            .packages
+            .iter()
+            .find(|pkg| pkg.name.as_str() == name && pkg.version.to_string() == version)?;
+        let published = package
+            .source
+            .as_ref()
+            .is_some_and(|source| source.is_crates_io());
+        Some(CrateInfo {
+            description: package.description.clone(),
+            license: package.license.clone(),
+            repository: package.repository.clone(),
+            docs_url: published.then(|| format!("https://docs.rs/{name}/{version}")),
+            rust_version: package.rust_version.as_ref().map(ToString::to_string),
+            edition: Some(package.edition.to_string()),
+        })
+    }
+
+    /// The README of the package `name@version` in the project, from the
+    /// file its `readme` field names.
+    pub fn readme(&self, name: &str, version: &str) -> Option<String> {
+        let package = self
+            .metadata
+            .packages
+            .iter()
+            .find(|pkg| pkg.name.as_str() == name && pkg.version.to_string() == version)?;
+        let path = package
+            .manifest_path
+            .parent()?
+            .join(package.readme.as_ref()?);
+        fs::read_to_string(path).ok()
+    }
+
+    /// The directory of the `Cargo.toml` of the package `name@version` in the
+    /// project, e.g. in the registry sources for a dependency.
+    pub fn package_dir(&self, name: &str, version: &str) -> Option<PathBuf> {
+        let package = self
+            .metadata
+            .packages
+            .iter()
+            .find(|pkg| pkg.name.as_str() == name && pkg.version.to_string() == version)?;
+        Some(package.manifest_path.parent()?.as_std_path().to_path_buf())
+    }
+
+    /// The registry the package `name@version` comes from, when it's one
+    /// of the registries configured besides crates.io.
+    pub fn registry_of(&self, name: &str, version: &str) -> Option<String> {
+        let package = self
+            .metadata
+            .packages
+            .iter()
+            .find(|pkg| pkg.name.as_str() == name && pkg.version.to_string() == version)?;
+        registry_of_source(&package.source.as_ref()?.repr)
+    }
+
+    /// Features of the package `name@version` in the project, with what each
+    /// one enables.
+    pub fn package_features(
+        &self,
+        name: &str,
+        version: &str,
+    ) -> Option<BTreeMap<String, Vec<String>>> {
+        self.metadata
+            .packages
+            .iter()
+            .find(|pkg| pkg.name.as_str() == name && pkg.version.to_string() == version)
+            .map(|package| package.features.clone())
+    }
+
     /// Resolve a local workspace crate
     fn resolve_local_crate(&self, crate_name: &str) -> Option<ResolvedCrate> {
         for member_id in &self.metadata.workspace_members {
@@ -316,6 +394,30 @@ impl VersionResolver {
         None
     }
 
+    /// Every direct dependency of the workspace members that isn't itself a
+    /// workspace member, once per name and version, sorted by name.
+    pub fn direct_dependencies(&self) -> Vec<ResolvedCrate> {
+        let mut names: Vec<String> = self
+            .metadata
+            .packages
+            .iter()
+            .filter(|package| self.metadata.workspace_members.contains(&package.id))
+            .flat_map(|package| &package.dependencies)
+            .map(|dep| normalize_crate_name(&dep.name))
+            .filter(|name| !self.is_local_crate(name))
+            .collect();
+        names.sort();
+        names.dedup();
+
+        let mut results: Vec<ResolvedCrate> = names
+            .iter()
+            .flat_map(|name| self.find_direct_dependencies(name))
+            .collect();
+        results.sort_by(|a, b| (&a.name, &a.version).cmp(&(&b.name, &b.version)));
+        results.dedup_by(|a, b| a.name == b.name && a.version == b.version);
+        results
+    }
+
     /// Check if a crate is a local workspace member
     pub fn is_local_crate(&self, crate_name: &str) -> bool {
         self.metadata.workspace_members.iter().any(|member_id| {
@@ -349,6 +451,35 @@ impl VersionResolver {
     }
 }
 
+/// Versions used for crates queried without one, by normalized crate name,
+/// instead of the ones Cargo.toml resolves.
+static PINNED_VERSIONS: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Use `versions`, crate versions by name, for crates queried without a
+/// version, for every later query in this process.
+pub fn set_pinned_versions(versions: &BTreeMap<String, String>) {
+    if let Ok(mut pinned) = PINNED_VERSIONS.write() {
+        *pinned = versions
+            .iter()
+            .map(|(name, version)| (normalize_crate_name(name), version.clone()))
+            .collect();
+    }
+}
+
+/// The versions given to [`set_pinned_versions`], by normalized crate name.
+pub(crate) fn pinned_versions() -> BTreeMap<String, String> {
+    PINNED_VERSIONS
+        .read()
+        .map(|pinned| pinned.clone())
+        .unwrap_or_default()
+}
+
+/// The version pinned for `crate_name`, if any.
+pub(crate) fn pinned_version(crate_name: &str) -> Option<String> {
+    let pinned = PINNED_VERSIONS.read().ok()?;
+    pinned.get(&normalize_crate_name(crate_name)).cloned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -0,0 +1,56 @@
+//! The version of each crate viewed last, kept in its cache directory, to
+//! point out when docsrs picks another version, see `--what-changed`.
+
+use std::fs;
+
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+
+use crate::docfetch::get_crate_cache_file;
+
+const FILE_NAME: &str = "viewed.json";
+
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+struct Viewed {
+    /// Version viewed last
+    version: String,
+    /// Version viewed before `version`
+    previous: Option<String>,
+}
+
+fn load(name: &str) -> Option<Viewed> {
+    let data = fs::read(get_crate_cache_file(name, FILE_NAME).ok()?).ok()?;
+    serde_json::from_slice(&data).ok()
+}
+
+/// Remember that `version` of the crate `name` is viewed, returning the
+/// version viewed last when it's another one.
+pub(crate) fn record_viewed(name: &str, version: &str) -> Result<Option<String>> {
+    let stored = load(name);
+    if stored
+        .as_ref()
+        .is_some_and(|stored| stored.version == version)
+    {
+        return Ok(None);
+    }
+    let previous = stored.map(|stored| stored.version);
+    let viewed = Viewed {
+        version: version.to_string(),
+        previous: previous.clone(),
+    };
+    let path = get_crate_cache_file(name, FILE_NAME)?;
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).context("Failed to create cache directory")?;
+    }
+    fs::write(&path, serde_json::to_vec(&viewed)?)
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    Ok(previous)
+}
+
+/// The version of the crate `name` viewed before `version`, when that's
+/// the version viewed last.
+pub(crate) fn viewed_before(name: &str, version: &str) -> Option<String> {
+    load(name)
+        .filter(|viewed| viewed.version == version)
+        .and_then(|viewed| viewed.previous)
+}
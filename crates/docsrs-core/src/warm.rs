@@ -0,0 +1,208 @@
+//! Downloading the docs of many crates at once, as done by `--warm-cache`.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use anyhow::{Context, Result};
+use jsondoc::JsonDoc;
+
+use crate::docfetch::{cache_docs, fetch_docs};
+use crate::events::EventSink;
+use crate::search_index::SearchIndex;
+
+/// Number of crates fetched at once when `--jobs` isn't given.
+const DEFAULT_JOBS: usize = 8;
+
+/// What happened to one crate when warming the cache.
+pub(crate) enum WarmOutcome {
+    /// The docs were downloaded
+    Downloaded,
+    /// The docs were cached already
+    Cached,
+    /// Downloading or indexing the docs failed
+    Failed(anyhow::Error),
+}
+
+impl WarmOutcome {
+    /// The word reported for the outcome, e.g. `downloaded`.
+    fn status(&self) -> &'static str {
+        match self {
+            WarmOutcome::Downloaded => "downloaded",
+            WarmOutcome::Cached => "cached",
+            WarmOutcome::Failed(_) => "failed",
+        }
+    }
+}
+
+/// Download the docs of every `(name, version)` into the cache and build
+/// their search indexes, running up to `jobs` crates at once. Downloads and
+/// each crate finishing are reported to `events`.
+///
+/// Returns the outcome of every crate, in the order given.
+pub(crate) fn warm_cache(
+    crates: &[(String, String)],
+    jobs: Option<usize>,
+    events: &dyn EventSink,
+) -> Vec<WarmOutcome> {
+    let jobs = jobs
+        .unwrap_or_else(|| {
+            thread::available_parallelism().map_or(DEFAULT_JOBS, |n| n.get().min(DEFAULT_JOBS))
+        })
+        .clamp(1, crates.len().max(1));
+
+    let next = AtomicUsize::new(0);
+    let finished = AtomicUsize::new(0);
+    let outcomes: Mutex<Vec<Option<WarmOutcome>>> =
+        Mutex::new(crates.iter().map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..jobs {
+            scope.spawn(|| {
+                loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some((name, version)) = crates.get(index) else {
+                        break;
+                    };
+                    let outcome = match warm_crate(name, version, events) {
+                        Ok(true) => WarmOutcome::Downloaded,
+                        Ok(false) => WarmOutcome::Cached,
+                        Err(e) => WarmOutcome::Failed(e),
+                    };
+                    // Counted and reported under the lock, so the counts
+                    // are reported in order
+                    let mut outcomes = outcomes.lock().expect("no worker panicked");
+                    let done = finished.fetch_add(1, Ordering::Relaxed) + 1;
+                    events.on_crate_done(done, crates.len(), name, version, outcome.status());
+                    outcomes[index] = Some(outcome);
+                }
+            });
+        }
+    });
+
+    outcomes
+        .into_inner()
+        .expect("no worker panicked")
+        .into_iter()
+        .map(|outcome| outcome.expect("every crate was warmed"))
+        .collect()
+}
+
+/// Cache the docs of one crate and its search index, returning whether the
+/// docs were downloaded.
+fn warm_crate(name: &str, version: &str, events: &dyn EventSink) -> Result<bool> {
+    let downloaded = cache_docs(name, version, events)?;
+    if downloaded || SearchIndex::load_cached(name, version).is_none() {
+        let doc = JsonDoc::from(fetch_docs(name, version, true, events)?);
+        SearchIndex::build(&doc)
+            .save_cached(name, version)
+            .context("Failed to save search index")?;
+    }
+    Ok(downloaded)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::{Arc, MutexGuard};
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::docfetch::{replace_cache_dir, set_cache_dir};
+    use crate::source::{DirSource, replace_doc_source, set_doc_source};
+
+    /// Docs of test_items 1.0.0 and 2.0.0 served from a directory, cached in
+    /// another one. The process-wide source and cache directory point at them
+    /// until the fixture is dropped, so no later test inherits them, and no
+    /// other test using them runs meanwhile.
+    struct Fixture {
+        _source: TempDir,
+        _cache: TempDir,
+        _globals: MutexGuard<'static, ()>,
+    }
+
+    impl Fixture {
+        fn new() -> Self {
+            let globals = crate::lock_test_globals();
+            let json = crate::test_docs("test_items.json");
+            let source = tempfile::tempdir().unwrap();
+            fs::create_dir(source.path().join("test_items")).unwrap();
+            for version in ["1.0.0", "2.0.0"] {
+                let docs = source.path().join(format!("test_items/{version}.json"));
+                fs::copy(&json, docs).unwrap();
+            }
+            let cache = tempfile::tempdir().unwrap();
+            set_doc_source(Arc::new(DirSource::new(source.path())));
+            set_cache_dir(cache.path());
+            Self {
+                _source: source,
+                _cache: cache,
+                _globals: globals,
+            }
+        }
+    }
+
+    impl Drop for Fixture {
+        fn drop(&mut self) {
+            replace_doc_source(None);
+            replace_cache_dir(None);
+        }
+    }
+
+    /// Records the crates reported done, as `done/total name@version status`.
+    #[derive(Default)]
+    struct Recorder(Mutex<Vec<String>>);
+
+    impl EventSink for Recorder {
+        fn on_crate_done(
+            &self,
+            done: usize,
+            total: usize,
+            crate_name: &str,
+            version: &str,
+            status: &str,
+        ) {
+            let event = format!("{done}/{total} {crate_name}@{version} {status}");
+            self.0.lock().unwrap().push(event);
+        }
+    }
+
+    #[test]
+    fn test_warm_cache_outcomes_follow_the_crates() {
+        let _fixture = Fixture::new();
+        let crates = [
+            ("test_items", "1.0.0"),
+            ("missing", "1.0.0"),
+            ("test_items", "2.0.0"),
+        ]
+        .map(|(name, version)| (name.to_string(), version.to_string()));
+        let statuses = |outcomes: Vec<WarmOutcome>| -> Vec<&str> {
+            outcomes.iter().map(WarmOutcome::status).collect()
+        };
+
+        let events = Recorder::default();
+        let outcomes = warm_cache(&crates, Some(2), &events);
+        assert_eq!(statuses(outcomes), ["downloaded", "failed", "downloaded"]);
+        assert!(SearchIndex::load_cached("test_items", "2.0.0").is_some());
+        // Crates finish in any order, but each is counted once
+        let mut reported = events.0.into_inner().unwrap();
+        let counts: Vec<&str> = reported.iter().map(|e| &e[..3]).collect();
+        assert_eq!(counts, ["1/3", "2/3", "3/3"]);
+        reported
+            .iter_mut()
+            .for_each(|event| event.replace_range(..4, ""));
+        reported.sort();
+        assert_eq!(
+            reported,
+            [
+                "missing@1.0.0 failed",
+                "test_items@1.0.0 downloaded",
+                "test_items@2.0.0 downloaded"
+            ]
+        );
+
+        let outcomes = warm_cache(&crates, Some(3), &Recorder::default());
+        assert_eq!(statuses(outcomes), ["cached", "failed", "cached"]);
+    }
+}
@@ -0,0 +1,290 @@
+//! Searching the docs of every dependency of the project at once, see
+//! `docsrs --workspace <filter>`.
+
+use anyhow::{Context, Result};
+use colored::Colorize;
+use jsondoc::JsonDoc;
+
+use crate::docfetch::fetch_docs;
+use crate::events::EventSink;
+use crate::list::{EntryKind, ListItem, render_rows};
+use crate::search_index::SearchIndex;
+use crate::source::{qualified_name, split_qualified_name};
+use crate::version_resolver::{VersionResolver, pinned_version};
+use crate::{StyleConfig, split_disambiguator};
+
+/// The items of one dependency matching a workspace search.
+pub struct CrateMatches {
+    pub name: String,
+    pub version: String,
+    /// By path
+    pub items: Vec<ListItem>,
+}
+
+/// The items of the project's dependencies matching a filter.
+pub struct WorkspaceSearch {
+    pub filter: String,
+    /// Number of dependencies searched, including the ones that failed
+    pub searched: usize,
+    /// Dependencies with matches, by name
+    pub matches: Vec<CrateMatches>,
+    /// Dependencies whose docs couldn't be loaded, as `name@version` and why
+    pub failed: Vec<(String, String)>,
+}
+
+/// Search the direct dependencies of the project for items whose path
+/// contains `filter`, which may start with a disambiguator like `struct@`.
+/// The search indexes of cached docs are used, and the docs of others are
+/// fetched and cached. `found` is given the matches of each dependency as
+/// soon as it's searched.
+pub fn search_workspace(
+    filter: &str,
+    no_cache: bool,
+    events: &dyn EventSink,
+    found: impl FnMut(&CrateMatches) -> Result<()>,
+) -> Result<WorkspaceSearch> {
+    let (kind, filter) = split_disambiguator(filter)?;
+    let resolver = VersionResolver::new().context("--workspace needs a Cargo project")?;
+    let dependencies: Vec<(String, String)> = resolver
+        .direct_dependencies()
+        .iter()
+        .map(|dependency| {
+            let version = pinned_version(&dependency.name).unwrap_or(dependency.version.clone());
+            let registry = resolver.registry_of(&dependency.name, &dependency.version);
+            (
+                qualified_name(registry.as_deref(), &dependency.name),
+                version,
+            )
+        })
+        .collect();
+    search_dependencies(
+        &dependencies,
+        kind,
+        &filter,
+        |name, version| search_index(name, version, no_cache, events),
+        found,
+    )
+}
+
+/// Search the `dependencies`, as qualified name and version, for items of
+/// `kind` whose path contains `filter`, in the search indexes `index_of`
+/// gives for them.
+fn search_dependencies(
+    dependencies: &[(String, String)],
+    kind: Option<EntryKind>,
+    filter: &str,
+    index_of: impl Fn(&str, &str) -> Result<SearchIndex>,
+    mut found: impl FnMut(&CrateMatches) -> Result<()>,
+) -> Result<WorkspaceSearch> {
+    let mut matches = Vec::new();
+    let mut failed = Vec::new();
+    for (name, version) in dependencies {
+        let index = match index_of(name, version) {
+            Ok(index) => index,
+            Err(e) => {
+                failed.push((format!("{name}@{version}"), format!("{e:#}")));
+                continue;
+            }
+        };
+        let mut items: Vec<ListItem> = index
+            .items()
+            .iter()
+            .filter(|item| kind.is_none_or(|kind| item.kind == kind))
+            .filter(|item| item.path.contains(filter))
+            .cloned()
+            .collect();
+        if items.is_empty() {
+            continue;
+        }
+        items.sort_by(|a, b| a.path.cmp(&b.path));
+        let crate_matches = CrateMatches {
+            name: split_qualified_name(name).1.to_string(),
+            version: version.clone(),
+            items,
+        };
+        found(&crate_matches)?;
+        matches.push(crate_matches);
+    }
+    Ok(WorkspaceSearch {
+        filter: describe_filter(kind, filter),
+        searched: dependencies.len(),
+        matches,
+        failed,
+    })
+}
+
+/// The search index of `name@version`, built from its docs and cached
+/// when there's none in the cache yet.
+fn search_index(
+    name: &str,
+    version: &str,
+    no_cache: bool,
+    events: &dyn EventSink,
+) -> Result<SearchIndex> {
+    if !no_cache && let Some(index) = SearchIndex::load_cached(name, version) {
+        return Ok(index);
+    }
+    let doc = JsonDoc::from(fetch_docs(name, version, !no_cache, events)?);
+    let mut index = SearchIndex::build(&doc);
+    if !no_cache && let Err(e) = index.save_cached(name, version) {
+        events.on_warning(&format!("Failed to cache search index: {}", e));
+    }
+    Ok(index)
+}
+
+fn describe_filter(kind: Option<EntryKind>, filter: &str) -> String {
+    match kind {
+        Some(kind) => format!("{}@{filter}", kind.keyword()),
+        None => filter.to_string(),
+    }
+}
+
+impl WorkspaceSearch {
+    /// Render the matches under a header for each dependency, followed by the
+    /// dependencies that couldn't be searched.
+    pub fn render(&self, style: &StyleConfig) -> String {
+        let mut output = String::new();
+        let count: usize = self.matches.iter().map(|found| found.items.len()).sum();
+        let header = match count {
+            0 => format!(
+                "// no items matching \"{}\" in {} dependencies",
+                self.filter, self.searched
+            ),
+            1 => format!(
+                "// 1 item matching \"{}\" in 1 of {} dependencies",
+                self.filter, self.searched
+            ),
+            count => format!(
+                "// {count} items matching \"{}\" in {} of {} dependencies",
+                self.filter,
+                self.matches.len(),
+                self.searched
+            ),
+        };
+        output.push_str(&format!("{}\n", header.bright_black()));
+
+        for found in &self.matches {
+            output.push_str(&format!("\n{}@{}", found.name, found.version));
+            let rows = found
+                .items
+                .iter()
+                .map(|item| (item.as_output(), item.summary.as_deref()))
+                .collect();
+            for line in render_rows(
+                rows,
+                "    ",
+                &style.colorizer,
+                style.summaries,
+                style.max_width,
+            ) {
+                output.push_str(&format!("\n{line}"));
+            }
+            output.push('\n');
+        }
+
+        if !self.failed.is_empty() {
+            let header = match self.failed.len() {
+                1 => "// 1 dependency couldn't be searched:".to_string(),
+                count => format!("// {count} dependencies couldn't be searched:"),
+            };
+            output.push_str(&format!("\n{}\n", header.bright_black()));
+            for (spec, error) in &self.failed {
+                let error = error.lines().next().unwrap_or_default();
+                output.push_str(&format!(
+                    "{}\n",
+                    format!("//   {spec}: {error}").bright_black()
+                ));
+            }
+        }
+        output
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::docfetch::load_local_docs;
+
+    fn test_items_index() -> Result<SearchIndex> {
+        let docs = load_local_docs(&crate::test_docs("test_items.json"))?;
+        Ok(SearchIndex::build(&JsonDoc::from(docs)))
+    }
+
+    fn paths(found: &CrateMatches) -> Vec<&str> {
+        found.items.iter().map(|item| item.path.as_str()).collect()
+    }
+
+    #[test]
+    fn test_search_dependencies() {
+        let dependencies = [
+            ("test_items".to_string(), "1.0.0".to_string()),
+            ("internal+test_items".to_string(), "2.0.0".to_string()),
+            ("missing".to_string(), "0.1.0".to_string()),
+        ];
+        let index_of = |name: &str, _: &str| match name {
+            "missing" => anyhow::bail!("Crate 'missing@0.1.0' not found on docs.rs.\nmore"),
+            _ => test_items_index(),
+        };
+
+        let mut reported = Vec::new();
+        let search = search_dependencies(
+            &dependencies,
+            Some(EntryKind::Struct),
+            "Count",
+            index_of,
+            |found| {
+                reported.push(found.version.clone());
+                Ok(())
+            },
+        )
+        .unwrap();
+        assert_eq!(search.filter, "struct@Count");
+        assert_eq!(search.searched, 3);
+        assert_eq!(reported, ["1.0.0", "2.0.0"]);
+        // Registry crates are shown by their own name
+        let names: Vec<_> = search.matches.iter().map(|found| &found.name).collect();
+        assert_eq!(names, ["test_items", "test_items"]);
+        assert_eq!(paths(&search.matches[0]), ["test_items::Counter"]);
+        assert_eq!(
+            search.failed,
+            [(
+                "missing@0.1.0".to_string(),
+                "Crate 'missing@0.1.0' not found on docs.rs.\nmore".to_string()
+            )]
+        );
+
+        // Dependencies without matches are left out
+        let search =
+            search_dependencies(&dependencies[..1], None, "NoSuchItem", index_of, |_| Ok(()))
+                .unwrap();
+        assert!(search.matches.is_empty());
+        assert_eq!(search.searched, 1);
+    }
+
+    #[test]
+    fn test_render_workspace_search() {
+        colored::control::set_override(false);
+        let search = search_dependencies(
+            &[
+                ("test_items".to_string(), "1.0.0".to_string()),
+                ("missing".to_string(), "0.1.0".to_string()),
+            ],
+            Some(EntryKind::Struct),
+            "Count",
+            |name, _| match name {
+                "missing" => anyhow::bail!("not found\nmore"),
+                _ => test_items_index(),
+            },
+            |_| Ok(()),
+        )
+        .unwrap();
+        let style = StyleConfig::default();
+        assert_eq!(
+            search.render(&style),
+            "// 1 item matching \"struct@Count\" in 1 of 2 dependencies\n\
+             \ntest_items@1.0.0\n    struct test_items::Counter\n\
+             \n// 1 dependency couldn't be searched:\n\
+             //   missing@0.1.0: not found\n"
+        );
+    }
+}
@@ -8,6 +8,8 @@
 //!
 //!     cargo nextest run --workspace --run-ignored only
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -1,3 +1,5 @@
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -34,3 +36,62 @@ fn help_shows_usage() {
     assert!(stderr.is_empty());
     assert_snapshot!(stdout);
 }
+
+#[test]
+fn missing_theme_file_fails() {
+    let (stdout, stderr, success) = run_cli(&[
+        "--theme-file",
+        "/nonexistent/theme.tmTheme",
+        "test-visibility",
+    ]);
+    assert!(!success, "CLI should fail with a missing theme file");
+    assert!(stdout.is_empty());
+    assert!(
+        stderr.starts_with("Failed to load theme file /nonexistent/theme.tmTheme"),
+        "unexpected error: {stderr}"
+    );
+}
+
+#[test]
+fn invalid_color_depth_fails() {
+    let (stdout, stderr, success) = run_cli(&["--color-depth", "8", "test-visibility"]);
+    assert!(!success, "CLI should fail with an invalid color depth");
+    assert!(stdout.is_empty());
+    assert_snapshot!(stderr, @r"
+    error: invalid value '8' for '--color-depth <DEPTH>': Invalid color depth: 8
+
+    For more information, try '--help'.
+    ");
+}
+
+#[test]
+fn unknown_theme_lists_available_themes() {
+    let (stdout, stderr, success) = run_cli(&["--theme", "nope", "test-visibility"]);
+    assert!(!success, "CLI should fail with an unknown theme");
+    assert!(stdout.is_empty());
+    assert_snapshot!(stderr, @r#"Unknown theme "nope". Available themes: InspiredGitHub, Solarized (dark), Solarized (light), base16-eighties.dark, base16-mocha.dark, base16-ocean.dark, base16-ocean.light"#);
+}
+
+#[test]
+fn double_dash_looks_up_crates_named_like_subcommands() {
+    use clap::Parser;
+    use docsrs_core::cli::Cli;
+
+    for name in ["diff", "search", "history", "export", "bookmark"] {
+        let cli = Cli::try_parse_from(["docsrs", "--", name, "Thing"]).unwrap();
+        assert!(cli.command.is_none(), "{name} ran as a subcommand");
+        assert_eq!(cli.crate_spec.unwrap().name, name);
+        assert_eq!(cli.filter.as_deref(), Some("Thing"));
+    }
+    let cli = Cli::try_parse_from(["docsrs", "history"]).unwrap();
+    assert!(cli.command.is_some());
+}
+
+#[test]
+fn history_references_are_only_expanded_by_the_command_line() {
+    let (stdout, stderr, success) = run_cli(&["!!", "--offline"]);
+    assert!(!success, "!! isn't a crate");
+    assert!(stdout.is_empty());
+    // Looked up as a crate rather than replaced with the last query
+    assert_snapshot!(stderr, @"Docs for '!!@latest' aren't cached, and docsrs is offline");
+}
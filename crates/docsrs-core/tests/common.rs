@@ -1,4 +1,15 @@
+#![cfg(feature = "native")]
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Once, OnceLock};
+
+use docsrs_core::{DirSource, set_doc_source};
+use serde_json::{Value, json};
+
+#[allow(dead_code)]
 pub fn run_cli(args: &[&str]) -> (String, String, bool) {
+    isolate_user_files();
     // Disable colors for consistent test output
     colored::control::set_override(false);
 
@@ -13,14 +24,100 @@ pub fn run_cli(args: &[&str]) -> (String, String, bool) {
 /// for managing the global colored::control::set_override state.
 #[allow(dead_code)]
 pub fn run_cli_raw(args: &[&str]) -> (String, String, bool) {
+    isolate_user_files();
     match docsrs_core::run_cli(args) {
         Ok(stdout) => (normalize_output(&stdout), String::new(), true),
         Err(stderr) => (String::new(), stderr, false),
     }
 }
 
+/// Keep the tests away from the user's files: they get an empty config of
+/// their own, and no history.
+pub fn isolate_user_files() {
+    static ISOLATED: Once = Once::new();
+    ISOLATED.call_once(|| {
+        let config = Path::new(env!("CARGO_TARGET_TMPDIR")).join("docsrs-test-config.toml");
+        fs::write(&config, "").unwrap();
+        // SAFETY: the tests only read the environment through `std::env`,
+        // which locks it against this write
+        unsafe {
+            std::env::set_var("DOCSRS_CONFIG", config);
+            std::env::set_var("DOCSRS_HISTORY", "");
+        }
+    });
+}
+
 /// Normalize output by replacing machine-specific paths with placeholders
 fn normalize_output(output: &str) -> String {
     // No machine-specific paths in the new comment format
     output.to_string()
 }
+
+/// Serve the generated docs of test-items from a directory, so nothing is
+/// downloaded: as `test_items@1.0.0`, and as `2.0.0` with the `Shape!`
+/// macro removed and `retries` made a `u32`. The latter is also the latest
+/// release of the workspace's `test-items`, as `0.1.0`.
+///
+/// The doc source is global, so it's set once and the directory kept for
+/// the whole test process: tests running at the same time all read from it.
+/// Tests adding crates of their own give them names no other test uses.
+#[allow(dead_code)]
+pub fn fixture_source() -> &'static Path {
+    static FIXTURE: OnceLock<tempfile::TempDir> = OnceLock::new();
+    FIXTURE
+        .get_or_init(|| {
+            isolate_user_files();
+            let dir = tempfile::tempdir_in(env!("CARGO_TARGET_TMPDIR")).unwrap();
+            write_fixture_docs(dir.path());
+            set_doc_source(Arc::new(DirSource::new(dir.path())));
+            dir
+        })
+        .path()
+}
+
+/// Path of the rustdoc JSON `file` of a test crate, written to `target/doc`
+/// by `scripts/generate-test-docs.sh`.
+#[allow(dead_code)]
+pub fn test_docs(file: &str) -> PathBuf {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../../target/doc")
+        .join(file);
+    assert!(
+        path.exists(),
+        "{} is missing, run scripts/generate-test-docs.sh first",
+        path.display()
+    );
+    path
+}
+
+fn write_fixture_docs(dir: &Path) {
+    let docs_path = test_docs("test_items.json");
+    let docs = fs::read_to_string(docs_path).unwrap();
+    let mut docs: Value = serde_json::from_str(&docs).unwrap();
+    fs::create_dir(dir.join("test_items")).unwrap();
+    docs["crate_version"] = json!("1.0.0");
+    fs::write(dir.join("test_items/1.0.0.json"), docs.to_string()).unwrap();
+
+    docs["crate_version"] = json!("2.0.0");
+    let root = docs["root"].to_string();
+    let index = docs["index"].as_object_mut().unwrap();
+    let find = |index: &serde_json::Map<String, Value>, name: &str, kind: &str| {
+        index
+            .iter()
+            .find(|(_, item)| item["name"] == name && item["inner"].get(kind).is_some())
+            .map(|(id, _)| id.clone())
+            .unwrap()
+    };
+    let macro_id: u64 = find(index, "Shape", "macro").parse().unwrap();
+    let items = index[&root]["inner"]["module"]["items"]
+        .as_array_mut()
+        .unwrap();
+    items.retain(|id| id != &json!(macro_id));
+    let run_id = find(index, "run_with_callback", "function");
+    index[&run_id]["inner"]["function"]["sig"]["inputs"][1][1] = json!({"primitive": "u32"});
+    fs::write(dir.join("test_items/2.0.0.json"), docs.to_string()).unwrap();
+
+    docs["crate_version"] = json!("0.1.0");
+    fs::create_dir(dir.join("test-items")).unwrap();
+    fs::write(dir.join("test-items/latest.json"), docs.to_string()).unwrap();
+}
@@ -0,0 +1,182 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use std::fs;
+
+use common::fixture_source;
+use docsrs_core::Query;
+use docsrs_core::diff::{diff_local, diff_versions};
+use docsrs_core::semver::check_semver;
+use insta::assert_snapshot;
+use serde_json::{Value, json};
+
+#[test]
+fn diff_lists_removed_and_changed_items() {
+    colored::control::set_override(false);
+    fixture_source();
+
+    let old = Query::parse("test_items@1.0.0").unwrap().no_cache(true);
+    let diff = diff_versions(&old, "2.0.0").unwrap();
+    assert_snapshot!(diff.render(), @r"
+    // test_items 1.0.0 -> 2.0.0: 1 removed, 0 added, 1 changed
+
+    // removed
+    -pub macro test_items::Shape!
+
+    // changed
+    -pub fn test_items::run_with_callback<F, R>(name: &str, retries: usize, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    +pub fn test_items::run_with_callback<F, R>(name: &str, retries: u32, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    ");
+}
+
+#[test]
+fn semver_flags_removals_as_major() {
+    colored::control::set_override(false);
+    fixture_source();
+
+    let old = Query::parse("test_items@1.0.0").unwrap().no_cache(true);
+    let report = check_semver(&diff_versions(&old, "2.0.0").unwrap());
+    assert_eq!(report.is_correct(), Some(true));
+    assert_snapshot!(report.render(), @r"
+    // semver: 1.0.0 -> 2.0.0 is a major bump, the changes need major: ok
+
+    major pub macro test_items::Shape!  // removed
+    major pub fn test_items::run_with_callback<F, R>(name: &str, retries: u32, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send  // signature changed
+    ");
+}
+
+#[test]
+fn semver_classifies_additions_that_break_users() {
+    colored::control::set_override(false);
+    let dir = fixture_source();
+
+    // The docs of test-semver as 2.0.0, and as 1.0.0 without the second
+    // variant or field of each type and without the trait's items
+    let docs_path = common::test_docs("test_semver.json");
+    let mut docs: Value = serde_json::from_str(&fs::read_to_string(docs_path).unwrap()).unwrap();
+    fs::create_dir_all(dir.join("test_semver")).unwrap();
+    docs["crate_version"] = json!("2.0.0");
+    fs::write(dir.join("test_semver/2.0.0.json"), docs.to_string()).unwrap();
+
+    docs["crate_version"] = json!("1.0.0");
+    let index = docs["index"].as_object_mut().unwrap();
+    let id_of = |index: &serde_json::Map<String, Value>, name: &str| {
+        let (id, _) = index.iter().find(|(_, item)| item["name"] == name).unwrap();
+        id.clone()
+    };
+    for parent in [
+        "Exhaustive",
+        "NonExhaustive",
+        "Fields",
+        "NonExhaustiveFields",
+        "PrivateFields",
+    ] {
+        let parent_id = id_of(index, parent);
+        let inner = &index[&parent_id]["inner"];
+        let ids = match inner.get("enum") {
+            Some(enum_) => &enum_["variants"],
+            None => &inner["struct"]["kind"]["plain"]["fields"],
+        };
+        let mut ids = ids.as_array().unwrap().clone();
+        ids.retain(|id| {
+            let name = &index[&id.to_string()]["name"];
+            name != "second" && name != "Second"
+        });
+        let inner = &mut index[&parent_id]["inner"];
+        match inner.get_mut("enum") {
+            Some(enum_) => enum_["variants"] = json!(ids),
+            None => inner["struct"]["kind"]["plain"]["fields"] = json!(ids),
+        }
+    }
+    let trait_id = id_of(index, "Implement").to_string();
+    index[&trait_id]["inner"]["trait"]["items"] = json!([]);
+    fs::write(dir.join("test_semver/1.0.0.json"), docs.to_string()).unwrap();
+
+    let old = Query::parse("test_semver@1.0.0").unwrap().no_cache(true);
+    let report = check_semver(&diff_versions(&old, "2.0.0").unwrap());
+    assert_snapshot!(report.render(), @"
+    // semver: 1.0.0 -> 2.0.0 is a major bump, the changes need major: ok
+
+    major pub test_semver::Exhaustive::Second  // variant added to exhaustive enum
+    major pub test_semver::Fields::second: u8  // field added to exhaustive struct
+    major pub type test_semver::Implement::Output  // required associated type added
+    major pub const test_semver::Implement::REQUIRED: u8  // required associated const added
+    major pub fn test_semver::Implement::required(&self) -> Self::Output  // required trait method added
+    minor pub const test_semver::Implement::PROVIDED: u8  // added
+    minor pub fn test_semver::Implement::provided(&self)  // added
+    minor pub test_semver::NonExhaustive::Second  // added
+    minor pub test_semver::NonExhaustiveFields::second: u8  // added
+    minor pub test_semver::PrivateFields::second: u8  // added
+    ");
+}
+
+#[test]
+fn diff_local_compares_the_workspace_with_the_latest_release() {
+    colored::control::set_override(false);
+    fixture_source();
+
+    let local = Query::parse("test-items").unwrap().no_cache(true);
+    let diff = diff_local(&local).unwrap();
+    assert_snapshot!(diff.render(), @"
+    // version 0.1.0 (local)
+    // test_items 0.1.0 -> 0.1.0: 0 removed, 1 added, 1 changed
+
+    // added
+    +pub macro test_items::Shape!
+
+    // changed
+    -pub fn test_items::run_with_callback<F, R>(name: &str, retries: u32, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    +pub fn test_items::run_with_callback<F, R>(name: &str, retries: usize, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    ");
+}
+
+#[test]
+fn what_changed_compares_with_the_version_viewed_before() {
+    colored::control::set_override(false);
+    let dir = fixture_source();
+    // The cache directory is global too, so it's kept for the whole process
+    // like the fixture; the crate is only cached by this test
+    let cache = dir.join("cache");
+    let cache_dir = cache.to_str().unwrap();
+
+    // A crate outside the project, whose latest release is 1.0.0 and then
+    // 2.0.0
+    let release = |version: &str| {
+        let docs = fs::read_to_string(dir.join(format!("test_items/{version}.json")));
+        let mut docs: Value = serde_json::from_str(&docs.unwrap()).unwrap();
+        let root = docs["root"].to_string();
+        docs["index"][&root]["name"] = json!("fixture_crate");
+        fs::create_dir_all(dir.join("fixture-crate")).unwrap();
+        fs::write(
+            dir.join(format!("fixture-crate/{version}.json")),
+            docs.to_string(),
+        )
+        .unwrap();
+        fs::write(dir.join("fixture-crate/latest.json"), docs.to_string()).unwrap();
+        // Cached docs of `latest` would hide the new release
+        let _ = fs::remove_file(cache.join("fixture-crate/latest.zst"));
+    };
+    let run = |args: &[&str]| docsrs_core::run_cli(&[args, &["--cache-dir", cache_dir]].concat());
+
+    release("1.0.0");
+    let first = run(&["fixture-crate"]).unwrap();
+    assert!(!first.contains("--what-changed"), "{first}");
+    release("2.0.0");
+    let second = run(&["fixture-crate"]).unwrap();
+    assert_snapshot!(second.lines().take(2).collect::<Vec<_>>().join("\n"), @"
+    // fixture-crate@latest
+    // note: you viewed 1.0.0 last time, --what-changed lists the API changes since
+    ");
+    assert_snapshot!(run(&["fixture-crate", "--what-changed"]).unwrap(), @"
+    // fixture-crate@latest
+    // fixture_crate 1.0.0 -> 2.0.0: 1 removed, 0 added, 1 changed
+
+    // removed
+    -pub macro fixture_crate::Shape!
+
+    // changed
+    -pub fn fixture_crate::run_with_callback<F, R>(name: &str, retries: usize, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    +pub fn fixture_crate::run_with_callback<F, R>(name: &str, retries: u32, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    ");
+}
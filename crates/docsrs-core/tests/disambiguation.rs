@@ -0,0 +1,57 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use common::run_cli;
+use insta::assert_snapshot;
+
+#[test]
+fn shared_path_lists_candidates() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Shape"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // 2 items at test_items::Shape, add a disambiguator to pick one
+
+    macro test_items::Shape!  // test-items::macro@Shape
+    struct test_items::Shape  // test-items::struct@Shape
+    ");
+}
+
+#[test]
+fn disambiguator_picks_one_item() {
+    let (stdout, stderr, success) = run_cli(&["test-items::mod@builder"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found mod test_items::builder
+    use test_items::builder;
+
+    /// A module with the same name as a function
+    pub mod test_items::builder
+
+    pub struct Options
+    ");
+}
+
+#[test]
+fn macro_suffix_picks_macro() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Shape!"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found macro test_items::Shape
+    use test_items::Shape;
+
+    /// A macro with the same name as a struct
+    pub macro test_items::Shape!
+    ");
+}
+
+#[test]
+fn unknown_disambiguator_fails() {
+    let (stdout, stderr, success) = run_cli(&["test-items::union@Shape"]);
+    assert!(!success, "CLI should fail with an unknown disambiguator");
+    assert!(stdout.is_empty());
+    assert_snapshot!(stderr, @r#"Unknown disambiguator "union". Use one of: mod, struct, enum, trait, fn, const, static, type, macro"#);
+}
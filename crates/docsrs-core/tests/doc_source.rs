@@ -0,0 +1,159 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use std::fs;
+use std::io::{self, Write};
+use std::sync::mpsc::{Receiver, Sender, channel};
+use std::thread;
+
+use common::{fixture_source, run_cli, test_docs};
+use docsrs_core::{EntryKind, Query, QueryResult, query};
+
+#[test]
+fn docs_are_fetched_from_the_configured_source() {
+    fixture_source();
+
+    let shape = Query::parse("test_items@1.0.0::struct@Shape")
+        .unwrap()
+        .no_cache(true);
+    let QueryResult::ItemDoc(item) = query(&shape).unwrap() else {
+        panic!("expected the docs of one item");
+    };
+    assert_eq!(item.kind, Some(EntryKind::Struct));
+
+    let missing = Query::parse("test_items@3.0.0").unwrap().no_cache(true);
+    let err = query(&missing).err().expect("version isn't in the source");
+    assert!(err.to_string().contains("not found"), "{err:#}");
+}
+
+#[test]
+fn missing_crate_suggests_similar_names() {
+    fixture_source();
+
+    let typo = Query::parse("test_itemz@1.0.0").unwrap().no_cache(true);
+    let err = query(&typo).err().expect("crate isn't in the source");
+    assert!(
+        err.to_string()
+            .ends_with("did you mean test_items or test-items?"),
+        "{err:#}"
+    );
+}
+
+#[test]
+fn cache_dir_only_applies_to_its_call() {
+    let dir = fixture_source();
+    // A crate only this test fetches
+    let docs = fs::read_to_string(dir.join("test_items/1.0.0.json")).unwrap();
+    let mut docs: serde_json::Value = serde_json::from_str(&docs).unwrap();
+    let root = docs["root"].to_string();
+    docs["index"][&root]["name"] = serde_json::json!("scoped_crate");
+    fs::create_dir_all(dir.join("scoped-crate")).unwrap();
+    let source = dir.join("scoped-crate/1.0.0.json");
+    fs::write(&source, docs.to_string()).unwrap();
+
+    let cache = tempfile::tempdir_in(env!("CARGO_TARGET_TMPDIR")).unwrap();
+    let cache_dir = cache.path().to_str().unwrap();
+    let (_, stderr, success) = run_cli(&["scoped-crate@1.0.0", "--cache-dir", cache_dir]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert!(cache.path().join("scoped-crate").exists());
+
+    // Only the cache of that call has the docs now
+    fs::remove_file(&source).unwrap();
+    let scoped = Query::parse("scoped-crate@1.0.0").unwrap();
+    let err = query(&scoped)
+        .err()
+        .expect("docs are only in the call's cache");
+    assert!(err.to_string().contains("not found"), "{err:#}");
+}
+
+#[test]
+fn docs_are_read_from_a_json_file() {
+    let docs = test_docs("test_items.json");
+    let docs = docs.to_str().unwrap();
+
+    let shape = format!("{docs}::struct@Shape");
+    let (output, stderr, success) = run_cli(&[&shape]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert!(
+        output.starts_with("// found struct test_items::Shape\n"),
+        "{output}"
+    );
+
+    // With --json-path, the crate name is optional
+    for spec in ["Draw", "test_items::Draw"] {
+        let (output, stderr, success) = run_cli(&["--json-path", docs, spec]);
+        assert!(success, "CLI should succeed: {stderr}");
+        assert!(
+            output.starts_with("// found trait test_items::Draw\n"),
+            "{output}"
+        );
+    }
+}
+
+/// Passes each line written to it on to the test, holding on to the first
+/// one until the test lets it go.
+struct LineChannel {
+    lines: Sender<String>,
+    release: Option<Receiver<()>>,
+    buffer: Vec<u8>,
+}
+
+impl Write for LineChannel {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.buffer.extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        while let Some(end) = self.buffer.iter().position(|&b| b == b'\n') {
+            let line: Vec<u8> = self.buffer.drain(..=end).collect();
+            let _ = self.lines.send(String::from_utf8_lossy(&line).into_owned());
+            if let Some(release) = self.release.take() {
+                let _ = release.recv();
+            }
+        }
+        Ok(())
+    }
+}
+
+#[test]
+fn jsonl_lines_are_written_while_the_crate_is_listed() {
+    let dir = fixture_source();
+    // The cache directory is global too, so it's kept for the whole process
+    // like the fixture
+    let cache = dir.join("cache");
+    let cache_dir = cache.to_str().unwrap().to_string();
+    // Only saved once every item has been listed
+    let index = cache.join("test_items/2.0.0.index");
+
+    let (lines, received) = channel();
+    let (release, released) = channel();
+    let listing = thread::spawn(move || {
+        let mut stream = LineChannel {
+            lines,
+            release: Some(released),
+            buffer: Vec::new(),
+        };
+        let args = ["test_items@2.0.0", "e", "--format", "jsonl"];
+        docsrs_core::run_cli_to(
+            &[&args[..], &["--cache-dir", &cache_dir]].concat(),
+            &mut stream,
+            false,
+        )
+    });
+
+    let first = received.recv().unwrap();
+    assert!(!index.exists(), "{first}");
+    release.send(()).unwrap();
+    let output = listing.join().unwrap().unwrap();
+    assert!(output.is_empty(), "{output}");
+    assert!(index.exists());
+
+    let lines: Vec<String> = std::iter::once(first).chain(received).collect();
+    assert!(lines.len() > 1, "{lines:?}");
+    for line in &lines {
+        let line: serde_json::Value = serde_json::from_str(line).unwrap();
+        assert!(line["path"].as_str().unwrap().contains('e'), "{line}");
+    }
+}
@@ -0,0 +1,57 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use std::sync::Mutex;
+use std::time::Duration;
+
+use common::fixture_source;
+use docsrs_core::{EventSink, fetch_docs};
+
+/// Records the events it receives, in order.
+#[derive(Default)]
+struct Recorder(Mutex<Vec<String>>);
+
+impl Recorder {
+    fn push(&self, event: String) {
+        self.0.lock().unwrap().push(event);
+    }
+}
+
+impl EventSink for Recorder {
+    fn on_download_start(&self, crate_name: &str, version: &str, _url: &str) {
+        self.push(format!("start {crate_name}@{version}"));
+    }
+
+    fn on_download_progress(&self, downloaded: u64, total: Option<u64>) {
+        assert_eq!(Some(downloaded), total);
+        self.push("progress".to_string());
+    }
+
+    fn on_download_done(&self, bytes: u64) {
+        assert!(bytes > 0);
+        self.push("done".to_string());
+    }
+
+    fn on_parse_done(&self, crate_name: &str, version: &str, _elapsed: Duration) {
+        self.push(format!("parsed {crate_name}@{version}"));
+    }
+}
+
+#[test]
+fn fetching_reports_download_and_parse_in_order() {
+    fixture_source();
+
+    let events = Recorder::default();
+    let krate = fetch_docs("test_items", "1.0.0", false, &events).unwrap();
+    assert_eq!(krate.crate_version.as_deref(), Some("1.0.0"));
+    assert_eq!(
+        events.0.into_inner().unwrap(),
+        [
+            "start test_items@1.0.0",
+            "progress",
+            "done",
+            "parsed test_items@1.0.0"
+        ]
+    );
+}
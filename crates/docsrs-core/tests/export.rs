@@ -0,0 +1,43 @@
+#![cfg(feature = "native")]
+
+use std::fs;
+
+use docsrs_core::Query;
+use docsrs_core::export::export_docs;
+use insta::assert_snapshot;
+
+#[test]
+fn export_writes_a_page_per_item_with_relative_links() {
+    let out = tempfile::tempdir().unwrap();
+    let export = export_docs(&Query::parse("test-items").unwrap(), out.path()).unwrap();
+    assert_eq!(export.dir, out.path().join("test_items"));
+
+    let builder = export.dir.join("builder");
+    let mut files: Vec<String> = fs::read_dir(&builder)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    files.sort();
+    assert_eq!(files, ["index.md", "struct.Options.md"]);
+
+    let module = fs::read_to_string(builder.join("index.md")).unwrap();
+    assert_snapshot!(module, @"
+    # mod test_items::builder
+
+    A module with the same name as a function
+
+    ## Items
+
+    - [`struct Options`](struct.Options.md): Options for the `builder()`, see `Builder::finish`
+    ");
+    let options = fs::read_to_string(builder.join("struct.Options.md")).unwrap();
+    assert_snapshot!(options, @"
+    # struct test_items::builder::Options
+
+    ```rust
+    pub struct test_items::builder::Options
+    ```
+
+    Options for the [`builder()`](../fn.builder.md), see [`Builder::finish`]
+    ");
+}
@@ -0,0 +1,334 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use common::run_cli;
+use insta::assert_snapshot;
+
+#[test]
+fn summaries_are_aligned_after_entries() {
+    let (stdout, stderr, success) =
+        run_cli(&["--summaries", "always", "test-visibility", "public"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    // version 0.1.0 (local)
+    // 5 items matching "public"
+
+    fn test_visibility::public_function                         // A public function
+    mod test_visibility::public_module                          // Public module with nested visibility
+    struct test_visibility::public_module::NestedPublic         // Public item in public module
+    mod test_visibility::public_module::inner                   // Nested submodule
+    struct test_visibility::public_module::inner::DeeplyNested  // Public item in nested module
+    "#);
+}
+
+#[test]
+fn summaries_are_truncated_to_width() {
+    let (stdout, stderr, success) = run_cli(&[
+        "--summaries",
+        "always",
+        "--width",
+        "60",
+        "test-visibility",
+        "public",
+    ]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    // version 0.1.0 (local)
+    // 5 items matching "public"
+
+    fn test_visibility::public_function   // A public function
+    mod test_visibility::public_module    // Public module with …
+    struct test_visibility::public_module::NestedPublic  // Public item in publ…
+    mod test_visibility::public_module::inner  // Nested submodule
+    struct test_visibility::public_module::inner::DeeplyNested  // Public item in nest…
+    "#);
+}
+
+#[test]
+fn tree_shows_module_hierarchy() {
+    let (stdout, stderr, success) = run_cli(&["--tree", "test-visibility"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // tree of test_visibility (13 items)
+
+    mod test_visibility
+    ├── const PUBLIC_CONST
+    ├── type PublicAlias
+    ├── enum PublicEnum
+    ├── struct PublicStruct
+    ├── trait PublicTrait
+    │   └── fn method
+    ├── struct PublicTupleStruct
+    ├── fn public_function
+    └── mod public_module
+        ├── struct NestedPublic
+        └── mod inner
+            └── struct DeeplyNested
+    ");
+}
+
+#[test]
+fn tree_is_scoped_by_path_and_filter() {
+    let (stdout, stderr, success) = run_cli(&["--tree", "test-reexports::selective", "Foo"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // tree of test_reexports::selective (1 item)
+
+    mod test_reexports
+    └── mod selective
+        └── struct Foo
+    ");
+}
+
+#[test]
+fn group_by_module_shows_module_headers() {
+    let (stdout, stderr, success) = run_cli(&["--group-by", "module", "test-visibility", "public"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    // version 0.1.0 (local)
+    // 5 items matching "public"
+
+    mod test_visibility
+        fn public_function
+        mod public_module
+
+    mod test_visibility::public_module
+        struct NestedPublic
+        mod inner
+
+    mod test_visibility::public_module::inner
+        struct DeeplyNested
+    "#);
+}
+
+#[test]
+fn group_by_module_aligns_summaries_across_groups() {
+    let (stdout, stderr, success) = run_cli(&[
+        "--group-by",
+        "module",
+        "--summaries",
+        "always",
+        "test-visibility",
+        "public",
+    ]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    // version 0.1.0 (local)
+    // 5 items matching "public"
+
+    mod test_visibility
+        fn public_function   // A public function
+        mod public_module    // Public module with nested visibility
+
+    mod test_visibility::public_module
+        struct NestedPublic  // Public item in public module
+        mod inner            // Nested submodule
+
+    mod test_visibility::public_module::inner
+        struct DeeplyNested  // Public item in nested module
+    "#);
+}
+
+#[test]
+fn full_text_matches_words_in_docs() {
+    let (stdout, stderr, success) = run_cli(&["--full-text", "test-items", "same name"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    // version 0.1.0 (local)
+    // 4 items matching "same name"
+
+    macro test_items::Shape!
+    struct test_items::Shape
+    fn test_items::builder
+    mod test_items::builder
+    "#);
+}
+
+#[test]
+fn qualifier_flags_list_matching_functions() {
+    let (stdout, stderr, success) = run_cli(&["--const", "test-items"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // 2 items
+
+    fn test_items::column_width
+    fn test_items::column_width_at
+    ");
+}
+
+#[test]
+fn jsonl_format_writes_a_line_per_item() {
+    let (stdout, stderr, success) = run_cli(&["--const", "test-items", "--format", "jsonl"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    {"crate":"test-items","path":"test_items::column_width_at","kind":"fn","qualifiers":["const","unsafe"],"summary":"Width of the column that `chars` points to, usable in constants","handle":"test-items::fn@column_width_at"}
+    {"crate":"test-items","path":"test_items::column_width","kind":"fn","qualifiers":["const"],"summary":"Width of a column of `chars` characters, usable in constants","handle":"test-items::fn@column_width"}
+    "#);
+}
+
+#[test]
+fn jsonl_handles_look_up_the_items_again() {
+    let (stdout, stderr, success) = run_cli(&["test-items", "Shape", "--format", "jsonl"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    let handles: Vec<String> = stdout
+        .lines()
+        .map(|line| {
+            let line: serde_json::Value = serde_json::from_str(line).unwrap();
+            line["handle"].as_str().unwrap().to_string()
+        })
+        .collect();
+    assert_eq!(
+        handles,
+        ["test-items::macro@Shape", "test-items::struct@Shape"]
+    );
+
+    let (stdout, stderr, success) = run_cli(&[&handles[1]]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert!(stdout.contains("pub struct test_items::Shape"), "{stdout}");
+}
+
+#[test]
+fn qualifier_flags_combine() {
+    let (stdout, stderr, success) = run_cli(&["--const", "--unsafe", "test-items"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found fn test_items::column_width_at
+    use test_items::column_width_at;
+
+    /// Width of the column that `chars` points to, usable in constants
+    ///
+    /// Safety
+    ///
+    /// `chars` must point to a valid `usize`.
+    pub const unsafe fn test_items::column_width_at(chars: *const usize) -> usize
+    ");
+}
+
+#[test]
+fn features_list_maps_items_to_features() {
+    let (stdout, stderr, success) = run_cli(&["test-items", "--features-list"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    // version 0.1.0 (local)
+    // 3 features of test_items 0.1.0
+
+    default = ["layout"]
+    emoji = ["layout"]
+    layout = []
+        fn test_items::is_ascii_only
+        mod test_items::layout
+        fn test_items::layout_columns
+    "#);
+}
+
+#[test]
+fn check_links_reports_broken_intra_doc_links() {
+    let (stdout, stderr, success) = run_cli(&["test-items", "--check-links"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // 1 broken intra-doc link in test_items 0.1.0
+
+    test_items::builder::Options: [Builder::finish]
+    ");
+}
+
+#[test]
+fn stats_counts_items_by_kind() {
+    let (stdout, stderr, success) = run_cli(&["test-items", "--stats"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // public API of test_items 0.1.0
+
+    items       44
+      mod        3
+      struct    10
+      trait      8
+      fn        22
+      macro      1
+    documented  44 (100.0%)
+    deprecated   0
+    unsafe fn    2
+    ");
+}
+
+#[test]
+fn graph_shows_modules_and_reexports() {
+    let (stdout, stderr, success) = run_cli(&[
+        "test-reexports",
+        "--graph",
+        "modules",
+        "--graph-format",
+        "dot",
+    ]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    // version 0.1.0 (local)
+    // modules of test_reexports 0.1.0, and where their re-exports come from (dashed)
+    digraph "test_reexports" {
+        rankdir=LR;
+        node [shape=box, fontname="monospace"];
+        "test_reexports";
+        "test_reexports::reexported";
+        "test_reexports::reroot";
+        "test_reexports::selective";
+        "test_reexports::traits";
+        "test_reexports::type_aliases";
+        "test_reexports::variants";
+        "test_reexports::visibility_change";
+        "alloc::vec" [style=dashed];
+        "std::collections::hash::map" [style=dashed];
+        "test_items" [style=dashed];
+        "test_reexports::deeply::nested::module" [style=dashed];
+        "test_reexports::inner" [style=dashed];
+        "test_reexports::selective::internal" [style=dashed];
+        "test_reexports::visibility_change::private_module" [style=dashed];
+        "test_reexports" -> "test_reexports::reexported";
+        "test_reexports" -> "test_reexports::reroot";
+        "test_reexports" -> "test_reexports::selective";
+        "test_reexports" -> "test_reexports::traits";
+        "test_reexports" -> "test_reexports::type_aliases";
+        "test_reexports" -> "test_reexports::variants";
+        "test_reexports" -> "test_reexports::visibility_change";
+        "test_reexports" -> "alloc::vec" [style=dashed, label="MyVec"];
+        "test_reexports" -> "std::collections::hash::map" [style=dashed, label="HashMap"];
+        "test_reexports" -> "test_items" [style=dashed, label="run_with_callback"];
+        "test_reexports" -> "test_reexports::deeply::nested::module" [style=dashed, label="DeeplyNestedItem"];
+        "test_reexports" -> "test_reexports::inner" [style=dashed, label="InnerStruct, InnerEnum, inner_function, RenamedStruct +5 more"];
+        "test_reexports" -> "test_reexports::traits" [style=dashed, label="MyTrait, TraitImpl"];
+        "test_reexports" -> "test_reexports::type_aliases" [style=dashed, label="MyType"];
+        "test_reexports::reroot" -> "test_reexports" [style=dashed, label="RootStruct"];
+        "test_reexports::selective" -> "test_reexports::selective::internal" [style=dashed, label="Bar, Foo"];
+        "test_reexports::variants" -> "test_reexports::inner" [style=dashed, label="InnerEnum::*"];
+        "test_reexports::visibility_change" -> "test_reexports::visibility_change::private_module" [style=dashed, label="PublicItem"];
+    }
+    "#);
+}
+
+#[test]
+fn matrix_shows_traits_of_types() {
+    let (stdout, stderr, success) = run_cli(&["test-items", "--matrix"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // traits of the types in test_items 0.1.0: ✓ implemented, ~ with bounds on their type parameters
+
+                      Default  Send  Sync  Unpin
+    Counter           -        ✓     ✓     ✓
+    DefaultHasher     ✓        ✓     ✓     ✓
+    Highlighted       -        ✓     ✓     ✓
+    Pair              -        ~     ~     ~
+    Shape             -        ✓     ✓     ✓
+    Styled            -        ✓     ✓     ✓
+    Table             -        ~     ~     ~
+    Text              -        ✓     ✓     ✓
+    builder::Options  -        ✓     ✓     ✓
+    layout::Column    -        ✓     ✓     ✓
+    ");
+}
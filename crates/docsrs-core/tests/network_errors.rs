@@ -1,5 +1,7 @@
 //! Error-path tests.
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -8,6 +8,8 @@
 //! Path-prefix scoping (see `filter_by_path_prefix`) narrows the candidate
 //! set BEFORE the filter precedence runs.
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -7,6 +7,8 @@
 //! They use `run_cli_raw` (which does NOT force colors off) and explicitly
 //! reset the override at the end so other tests in this binary aren't affected.
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::{run_cli, run_cli_raw};
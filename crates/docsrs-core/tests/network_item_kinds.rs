@@ -10,6 +10,8 @@
 //! kinds is exercised indirectly via the local `test-visibility` crate
 //! (`tests/visibility.rs::public_const_is_found`).
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -5,6 +5,8 @@
 //!   4. Filter multi       (multiple matches)          → `// N items matching "{filter}"`
 //!   5. Filter no-match    (zero matches)              → `// no matches for "{filter}" — showing all N items`
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -3,6 +3,8 @@
 //! Uses pinned versions of external crates. Bumping a version requires
 //! re-running and re-snapshotting — don't do it casually.
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
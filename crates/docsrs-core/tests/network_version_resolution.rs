@@ -6,6 +6,8 @@
 //! Assertions on the header only (not body) — body contents are too volatile
 //! to snapshot for these tests.
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
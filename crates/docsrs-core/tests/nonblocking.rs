@@ -0,0 +1,100 @@
+#![cfg(feature = "async")]
+
+mod common;
+
+use std::fs;
+use std::io::{BufRead, BufReader, Write};
+use std::net::TcpListener;
+use std::path::Path;
+use std::sync::Arc;
+use std::thread;
+
+use common::fixture_source;
+use docsrs_core::{EntryKind, NoEvents, Query, QueryResult, Registry, nonblocking, set_registries};
+
+/// Serve the docs in `dir` over HTTP as `/{name}/{version}`, compressed as
+/// docs.rs serves them, returning the server's URL.
+fn serve_docs(dir: &'static Path) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    thread::spawn(move || {
+        for stream in listener.incoming() {
+            let mut stream = stream.unwrap();
+            let mut request = String::new();
+            let mut reader = BufReader::new(&stream);
+            reader.read_line(&mut request).unwrap();
+            // Skip the headers
+            let mut line = String::new();
+            while reader.read_line(&mut line).unwrap() > 2 {
+                line.clear();
+            }
+            let path = request.split(' ').nth(1).unwrap().trim_start_matches('/');
+            let (status, body) = match fs::read(dir.join(format!("{path}.json"))) {
+                Ok(json) => ("200 OK", zstd::encode_all(&json[..], 1).unwrap()),
+                Err(_) => ("404 Not Found", Vec::new()),
+            };
+            let head = format!(
+                "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                body.len()
+            );
+            stream.write_all(head.as_bytes()).unwrap();
+            stream.write_all(&body).unwrap();
+        }
+    });
+    url
+}
+
+#[tokio::test]
+async fn docs_are_downloaded_over_http() {
+    let url = serve_docs(fixture_source());
+    set_registries([Registry {
+        name: "served".to_string(),
+        index: format!("sparse+{url}/index/"),
+        docs: format!("{url}/{{name}}/{{version}}"),
+        token: None,
+    }]);
+
+    let krate = nonblocking::fetch_docs("served+test_items", "1.0.0", false, Arc::new(NoEvents));
+    assert_eq!(krate.await.unwrap().crate_version.as_deref(), Some("1.0.0"));
+
+    let missing = nonblocking::fetch_docs("served+test_items", "3.0.0", false, Arc::new(NoEvents));
+    let err = missing.await.expect_err("version isn't served");
+    assert!(
+        err.to_string()
+            .contains("not found on the docs server of registry served"),
+        "{err:#}"
+    );
+}
+
+#[tokio::test]
+async fn docs_are_fetched_and_queried_without_blocking() {
+    fixture_source();
+
+    let (old, new) = tokio::join!(
+        nonblocking::fetch_docs("test_items", "1.0.0", false, Arc::new(NoEvents)),
+        nonblocking::fetch_docs("test_items", "2.0.0", false, Arc::new(NoEvents)),
+    );
+    assert_eq!(old.unwrap().crate_version.as_deref(), Some("1.0.0"));
+    assert_eq!(new.unwrap().crate_version.as_deref(), Some("2.0.0"));
+
+    let shape = Query::parse("test_items@1.0.0::struct@Shape")
+        .unwrap()
+        .no_cache(true);
+    let QueryResult::ItemDoc(item) = nonblocking::query(shape).await.unwrap() else {
+        panic!("expected the docs of one item");
+    };
+    assert_eq!(item.kind, Some(EntryKind::Struct));
+
+    let missing = Query::parse("test_items@3.0.0").unwrap().no_cache(true);
+    let err = nonblocking::query(missing).await.err();
+    assert!(err.is_some_and(|err| err.to_string().contains("not found")));
+}
+
+#[tokio::test]
+async fn cli_runs_without_blocking() {
+    fixture_source();
+
+    let args = vec!["test_items@1.0.0::Shape".to_string(), "--no-cache".into()];
+    let output = nonblocking::run_cli(args).await.unwrap().unwrap();
+    assert!(output.contains("Shape"), "{output}");
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use docsrs_core::{
+    EntryKind, MatchKind, Qualifiers, Query, QueryResult, StyleConfig, parse_rustdoc_json, query,
+    query_crate,
+};
+
+fn query_spec(spec: &str) -> QueryResult {
+    query(&Query::parse(spec).unwrap()).expect("query should succeed")
+}
+
+#[test]
+fn item_path_returns_item_doc() {
+    let QueryResult::ItemDoc(item) = query_spec("test-items::Shape!") else {
+        panic!("expected the docs of one item");
+    };
+    assert_eq!(item.path, "test_items::Shape");
+    assert_eq!(item.kind, Some(EntryKind::Macro));
+    assert!(item.reexport.is_none());
+    assert!(item.signature.contains("Shape"), "{}", item.signature);
+}
+
+#[test]
+fn shared_path_returns_candidates() {
+    let QueryResult::MatchList(list) = query_spec("test-items::Shape") else {
+        panic!("expected a list of candidates");
+    };
+    let MatchKind::Ambiguous { specs, .. } = &list.kind else {
+        panic!("expected an ambiguous path, got {:?}", list.kind);
+    };
+    assert_eq!(
+        specs,
+        &["test-items::macro@Shape", "test-items::struct@Shape"]
+    );
+    assert_eq!(list.items.len(), 2);
+}
+
+#[test]
+fn crate_name_returns_overview() {
+    let result = query_spec("test-items");
+    assert!(matches!(result, QueryResult::CrateOverview(_)));
+    assert!(result.resolution().message.is_some());
+}
+
+#[test]
+fn builder_kind_picks_one_item() {
+    let shape = Query::crate_("test-items")
+        .path("Shape")
+        .kind(EntryKind::Struct);
+    let QueryResult::ItemDoc(item) = query(&shape).unwrap() else {
+        panic!("expected the docs of one item");
+    };
+    assert_eq!(item.kind, Some(EntryKind::Struct));
+}
+
+#[test]
+fn method_path_returns_method_doc() {
+    let QueryResult::ItemDoc(item) = query_spec("test-items::Text::new") else {
+        panic!("expected the docs of the inherent method");
+    };
+    assert_eq!(item.path, "test_items::Text::new");
+    assert_eq!(item.kind, Some(EntryKind::Function));
+
+    // Searching for the path finds the method too
+    let search = Query::crate_("test-items").filter("Styled::color");
+    let QueryResult::ItemDoc(item) = query(&search).unwrap() else {
+        panic!("expected the docs of the method");
+    };
+    assert_eq!(item.path, "test_items::Styled::color");
+}
+
+#[test]
+fn builder_limit_cuts_search_results() {
+    let search = Query::crate_("test-visibility").filter("public").limit(2);
+    let QueryResult::MatchList(list) = query(&search).unwrap() else {
+        panic!("expected a list of matches");
+    };
+    assert_eq!(list.items.len(), 2);
+    assert_eq!(list.total, 5);
+}
+
+#[test]
+fn builder_qualifiers_list_functions() {
+    let async_fns = Query::crate_("test-items").qualifiers(Qualifiers {
+        is_async: true,
+        ..Qualifiers::default()
+    });
+    let QueryResult::ItemDoc(item) = query(&async_fns).unwrap() else {
+        panic!("expected the docs of the only async fn");
+    };
+    assert_eq!(item.path, "test_items::load_text");
+}
+
+#[test]
+fn query_crate_uses_loaded_docs() {
+    let docs = common::test_docs("test_items.json");
+    let json = std::fs::read(docs).unwrap();
+    let krate = parse_rustdoc_json(json).unwrap();
+
+    let QueryResult::ItemDoc(item) =
+        query_crate(krate, &Query::parse("test-items::Shape!").unwrap()).unwrap()
+    else {
+        panic!("expected the docs of one item");
+    };
+    assert_eq!(item.path, "test_items::Shape");
+    assert!(item.resolution.message.is_none());
+}
+
+#[test]
+fn missing_item_suggests_similar_paths() {
+    let err = query(&Query::parse("test-items::Optoins").unwrap())
+        .err()
+        .unwrap();
+    assert_eq!(
+        err.to_string(),
+        "No item found at test_items::Optoins\n\ndid you mean test_items::builder::Options?"
+    );
+}
+
+#[test]
+fn item_doc_links_to_its_html_page() {
+    let QueryResult::ItemDoc(item) = query_spec("test-items::struct@Shape") else {
+        panic!("expected the docs of one item");
+    };
+    assert_eq!(
+        item.html_path().as_deref(),
+        Some("test_items/struct.Shape.html")
+    );
+    assert_eq!(
+        item.docs_url().as_deref(),
+        Some("https://docs.rs/test_items/0.1.0/test_items/struct.Shape.html")
+    );
+    let local = item
+        .resolution
+        .local
+        .expect("test-items is a workspace crate");
+    assert_eq!(local.package, "test-items");
+    assert!(
+        local.doc_dir.ends_with("target/doc"),
+        "{}",
+        local.doc_dir.display()
+    );
+
+    let QueryResult::CrateOverview(root) = query_spec("test-items") else {
+        panic!("expected the crate root docs");
+    };
+    assert_eq!(root.html_path().as_deref(), Some("test_items/index.html"));
+}
+
+#[test]
+fn crate_overview_reports_package_metadata() {
+    let QueryResult::CrateOverview(root) = query_spec("test-items") else {
+        panic!("expected the crate root docs");
+    };
+    let info = root
+        .resolution
+        .crate_info
+        .expect("test-items is in the project");
+    assert_eq!(
+        info.description.as_deref(),
+        Some("Items with various signature shapes, for testing docsrs")
+    );
+    assert_eq!(info.license.as_deref(), Some("MIT OR Apache-2.0"));
+    assert_eq!(
+        info.repository.as_deref(),
+        Some("https://github.com/human-solutions/mx-docsrs")
+    );
+    assert_eq!(info.edition.as_deref(), Some("2024"));
+    assert_eq!(info.rust_version.as_deref(), Some("1.85.0"));
+    // Workspace crates aren't on docs.rs
+    assert_eq!(info.docs_url, None);
+}
+
+#[test]
+fn crate_overview_shows_readme_above_the_root_module() {
+    let QueryResult::CrateOverview(mut root) = query_spec("test-items") else {
+        panic!("expected the crate root docs");
+    };
+    // The crate root is documented, so its README isn't needed
+    assert_eq!(root.readme, None);
+
+    root.readme = Some("# Demo\n\nA *demo* crate.".to_string());
+    colored::control::set_override(false);
+    let rendered = QueryResult::CrateOverview(root)
+        .render(&StyleConfig::default())
+        .unwrap();
+    let readme = rendered
+        .split_once("// (from README)\n")
+        .map(|(_, readme)| readme)
+        .expect("the README banner");
+    assert!(
+        readme.starts_with("/// Demo\n///\n/// A demo crate.\n"),
+        "{readme}"
+    );
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -53,6 +55,7 @@ fn renamed_struct_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found struct test_reexports::RenamedStruct
+    use test_reexports::RenamedStruct;
 
     /// A struct defined in inner module
     pub struct test_reexports::ChainedReexport {
@@ -70,6 +73,7 @@ fn deeply_nested_reexport() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found struct test_reexports::DeeplyNestedItem
+    use test_reexports::DeeplyNestedItem;
 
     /// A deeply nested struct
     pub struct test_reexports::DeeplyNestedItem {
@@ -87,6 +91,7 @@ fn selective_foo_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found struct test_reexports::selective::Foo
+    use test_reexports::selective::Foo;
 
     pub struct test_reexports::selective::Foo
     ");
@@ -99,6 +104,7 @@ fn selective_bar_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found struct test_reexports::selective::Bar
+    use test_reexports::selective::Bar;
 
     pub struct test_reexports::selective::Bar
     ");
@@ -147,3 +153,117 @@ fn trait_impl_reexport() {
     struct test_reexports::traits::TraitImpl
     "#);
 }
+
+// --- Glob re-exports ---
+
+#[test]
+fn glob_reexport_lists_module_items() {
+    let (stdout, stderr, success) = run_cli(&["test-reexports::reexported"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found mod test_reexports::reexported
+    use test_reexports::reexported;
+
+    /// Module that re-exports everything from inner
+    pub mod test_reexports::reexported
+
+    pub const INNER_CONST
+    pub type InnerAlias
+    pub enum InnerEnum
+    pub struct InnerStruct
+    pub trait InnerTrait
+    pub fn inner_function
+    ");
+}
+
+#[test]
+fn enum_glob_reexport_finds_variant() {
+    let (stdout, stderr, success) = run_cli(&["test-reexports::variants::Variant2"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found test_reexports::variants::Variant2
+    use test_reexports::variants::Variant2;
+
+    pub test_reexports::InnerEnum::Variant2(i32)
+    ");
+}
+
+#[test]
+fn renamed_reexports_keep_their_names_in_module_listing() {
+    let (stdout, stderr, success) = run_cli(&["test-reexports"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // showing mod test_reexports (crate root)
+    // edition 2024
+
+    /// Test crate for re-export patterns in rustdoc JSON
+    ///
+    /// This crate contains various re-export patterns to test how the docsrs
+    /// tool handles `pub use` statements and item discovery through re-exports.
+    pub mod test_reexports
+
+    pub struct ChainedReexport
+    pub struct DeeplyNestedItem
+    pub const INNER_CONST
+    pub type InnerAlias
+    pub enum InnerEnum
+    pub struct InnerStruct
+    pub trait InnerTrait
+    pub trait MyTrait
+    pub type MyType
+    pub struct RenamedStruct
+    pub struct RootStruct
+    pub struct TraitImpl
+    pub fn inner_function
+    pub mod reexported
+    pub fn renamed_function
+    pub mod reroot
+    pub mod selective
+    pub mod traits
+    pub mod type_aliases
+    pub mod variants
+    pub mod visibility_change
+    ");
+}
+
+// --- External crate re-exports ---
+
+#[test]
+fn external_reexport_points_to_its_crate() {
+    let (stdout, stderr, success) = run_cli(&["test-reexports::run_with_callback"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found test_reexports::run_with_callback, re-exported from test_items (--follow-reexports shows its docs)
+    use test_reexports::run_with_callback;
+
+    /// Re-export from another crate of the workspace
+    pub use test_reexports::run_with_callback
+    ");
+}
+
+#[test]
+fn follow_reexports_shows_the_item_from_its_crate() {
+    let (stdout, stderr, success) =
+        run_cli(&["--follow-reexports", "test-reexports::run_with_callback"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found fn test_reexports::run_with_callback, re-exported from test_items::run_with_callback
+    use test_reexports::run_with_callback;
+
+    /// A function whose signature doesn't fit on one line
+    pub fn test_items::run_with_callback<F, R>(name: &str, retries: usize, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    ");
+}
+
+#[test]
+fn use_stmt_takes_the_shortest_reexport() {
+    let (stdout, stderr, success) =
+        run_cli(&["test-reexports::reexported::InnerStruct", "--use-stmt"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"use test_reexports::InnerStruct;");
+}
@@ -0,0 +1,38 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use std::fs;
+
+use common::{fixture_source, run_cli};
+use docsrs_core::{Query, query};
+
+#[test]
+fn flags_only_apply_to_their_call() {
+    let dir = fixture_source();
+    // A crate only this test fetches
+    let docs = fs::read_to_string(dir.join("test_items/1.0.0.json")).unwrap();
+    let mut docs: serde_json::Value = serde_json::from_str(&docs).unwrap();
+    let root = docs["root"].to_string();
+    docs["index"][&root]["name"] = serde_json::json!("offline_crate");
+    fs::create_dir_all(dir.join("offline-crate")).unwrap();
+    fs::write(dir.join("offline-crate/1.0.0.json"), docs.to_string()).unwrap();
+
+    let cache = tempfile::tempdir_in(env!("CARGO_TARGET_TMPDIR")).unwrap();
+    let cache_dir = cache.path().to_str().unwrap();
+    let (_, stderr, success) = run_cli(&[
+        "offline-crate@1.0.0",
+        "--offline",
+        "--color",
+        "always",
+        "--cache-dir",
+        cache_dir,
+    ]);
+    assert!(!success);
+    assert!(stderr.contains("docsrs is offline"), "{stderr}");
+
+    // Neither --offline nor --color outlive the call
+    assert!(!colored::control::SHOULD_COLORIZE.should_colorize());
+    let spec = Query::parse("offline-crate@1.0.0").unwrap().no_cache(true);
+    query(&spec).unwrap();
+}
@@ -0,0 +1,324 @@
+#![cfg(feature = "native")]
+
+mod common;
+
+use common::run_cli;
+use insta::assert_snapshot;
+
+#[test]
+fn long_signature_stays_on_one_line_without_width() {
+    let (stdout, stderr, success) = run_cli(&["test-items::run_with_callback"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found fn test_items::run_with_callback
+    use test_items::run_with_callback;
+
+    /// A function whose signature doesn't fit on one line
+    pub fn test_items::run_with_callback<F, R>(name: &str, retries: usize, callback: F) -> Option<R> where F: FnOnce(&str) -> R, R: Clone + Send
+    ");
+}
+
+#[test]
+fn long_signature_wraps_at_width() {
+    let (stdout, stderr, success) = run_cli(&["--width", "60", "test-items::run_with_callback"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found fn test_items::run_with_callback
+    use test_items::run_with_callback;
+
+    /// A function whose signature doesn't fit on one line
+    pub fn test_items::run_with_callback<F, R>(
+        name: &str,
+        retries: usize,
+        callback: F,
+    ) -> Option<R>
+    where
+        F: FnOnce(&str) -> R,
+        R: Clone + Send,
+    ");
+}
+
+#[test]
+fn wrapped_impl_header_puts_body_on_its_own_line() {
+    let (stdout, stderr, success) = run_cli(&["--width", "60", "test-items::Pair"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r"
+    // version 0.1.0 (local)
+    // found struct test_items::Pair
+    use test_items::Pair;
+
+    /// Two named values, named after both
+    pub struct test_items::Pair<A, B>(pub A, pub B)
+
+    /* ======== Trait Implementations ======== */
+    impl<A, B> test_items::Named for test_items::Pair<A, B>
+    where
+        A: test_items::Named + Clone + Send,
+        B: test_items::Named + Clone + Send,
+    { .. }
+
+    /* ======== Blanket Implementations ======== */
+    impl<T> test_items::Greet for T
+    where
+        T: test_items::Named + ?Sized,
+    { .. }
+    ");
+}
+
+#[test]
+fn deref_methods_follow_the_chain() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Highlighted"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found struct test_items::Highlighted
+    use test_items::Highlighted;
+
+    /// Styled text that is highlighted, which derefs to its styled text
+    pub struct test_items::Highlighted(pub test_items::Styled)
+
+    /* ======== Methods from Deref<Target = test_items::Styled> ======== */
+    /// Name of the color
+    pub fn color(&self) -> &str
+
+    /* ======== Methods from Deref<Target = test_items::Text> ======== */
+    /// Width of the text in columns
+    pub fn width(&self) -> usize
+
+    /* ======== Methods from Deref<Target = str> ======== */
+    // see the docs of str
+
+    /* ======== Trait Implementations ======== */
+    impl core::ops::deref::Deref for test_items::Highlighted { .. }
+    ");
+}
+
+#[test]
+fn trait_methods_split_into_required_and_provided() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Draw"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found trait test_items::Draw
+    use test_items::Draw;
+
+    /// Something that can be drawn
+    pub trait test_items::Draw {
+        /// Where it is drawn
+        type Canvas;
+
+        // Required method
+        /// Draw on the canvas
+        fn draw(&self, canvas: &mut Self::Canvas);
+
+        // Provided methods
+        /// Draw twice, e.g. for a bold look
+        fn draw_twice(&self, canvas: &mut Self::Canvas) { .. }
+        /// Whether drawing shows anything
+        fn is_visible(&self) -> bool { .. }
+    }
+
+    // dyn compatible: yes
+    ");
+}
+
+#[test]
+fn generic_defaults_are_shown() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Table"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found struct test_items::Table
+    use test_items::Table;
+
+    /// A table with a default hasher and a default capacity
+    pub struct test_items::Table<K, V, S = test_items::DefaultHasher, const N: usize = 16> {
+        /// The entries, in insertion order
+        pub entries: Vec<(K, V)>,
+        /// Hashes the keys
+        pub hasher: S,
+    }
+    ");
+}
+
+#[test]
+fn local_blanket_impls_are_listed() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Text"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found struct test_items::Text
+    use test_items::Text;
+
+    /// Text measured in columns
+    pub struct test_items::Text(_)
+
+    /* ======== Methods ======== */
+    /// Create text from a string
+    pub fn new(text: &str) -> Self
+    /// Width of the text in columns
+    pub fn width(&self) -> usize
+
+    /* ======== Methods from Deref<Target = str> ======== */
+    // see the docs of str
+
+    /* ======== Trait Implementations ======== */
+    impl core::ops::deref::Deref for test_items::Text { .. }
+    impl test_items::Named for test_items::Text { .. }  // supertrait of Introduce
+    impl test_items::Introduce for test_items::Text { .. }  // supertrait of Shout
+    impl test_items::Shout for test_items::Text { .. }
+    impl test_items::Shade for test_items::Text { .. }
+    impl test_items::Tint for test_items::Text { .. }
+
+    /* ======== Blanket Implementations ======== */
+    impl<T> test_items::Greet for T where T: test_items::Named + ?Sized { .. }
+    ");
+}
+
+#[test]
+fn supertrait_hierarchy_is_shown() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Shout"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found trait test_items::Shout
+    use test_items::Shout;
+
+    /// Introduces itself loudly
+    pub trait test_items::Shout: test_items::Introduce {
+        // Provided method
+        /// A loud introduction
+        fn shout(&self) -> String { .. }
+    }
+
+    // dyn compatible: yes
+
+    /* ======== Supertraits ======== */
+    test_items::Shout: test_items::Introduce
+    test_items::Introduce: test_items::Named
+    ");
+}
+
+#[test]
+fn dyn_incompatible_trait_lists_reasons() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Make"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found trait test_items::Make
+    use test_items::Make;
+
+    /// Makes values from parts, which keeps it from being used as `dyn Make`
+    pub trait test_items::Make {
+        // Required methods
+        /// Make a default value
+        fn make() -> Self;
+        /// Describe a value made from a part
+        fn describe<P: Into<String>>(&self, part: P) -> String;
+        /// Whether the value equals another one
+        fn same_as(&self, other: &Self) -> bool;
+        /// A copy of the value, only for sized types
+        fn copy_made(&self) -> Self where Self: Sized;
+    }
+
+    // dyn compatible: no (fn make has no self receiver, fn describe is generic, fn same_as takes or returns Self)
+    ");
+}
+
+#[test]
+fn sealed_traits_are_marked() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Shade"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found trait test_items::Shade
+    use test_items::Shade;
+
+    /// A shade of a color, sealed by a private supertrait
+    pub trait test_items::Shade: test_items::private::Sealed {
+        // Required method
+        /// How dark the shade is
+        fn darkness(&self) -> u8;
+    }
+
+    // dyn compatible: yes
+    // sealed: cannot be implemented outside this crate (private supertrait private::Sealed)
+    ");
+
+    let (stdout, stderr, success) = run_cli(&["test-items::Tint"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    // version 0.1.0 (local)
+    // found trait test_items::Tint
+    use test_items::Tint;
+
+    /// A tint of a color, sealed by a required method taking a private type
+    pub trait test_items::Tint {
+        // Required methods
+        /// How strong the tint is
+        fn strength(&self) -> u8;
+        /// Only callable within this crate
+        fn seal(&self, token: test_items::private::Token);
+    }
+
+    // dyn compatible: yes
+    // sealed: cannot be implemented outside this crate (method seal takes private type private::Token)
+    ");
+}
+
+#[test]
+fn skeleton_implements_the_required_items_of_a_trait() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Draw", "--skeleton"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    use test_items::Draw;
+
+    impl Draw for MyType {
+        type Canvas = /* ... */;
+
+        fn draw(&self, canvas: &mut Self::Canvas) {
+            todo!()
+        }
+    }
+    ");
+}
+
+#[test]
+fn skeleton_calls_a_method() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Text::width", "--skeleton"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @"
+    use test_items::Text;
+
+    let text: Text = todo!();
+    let result = text.width();
+    ");
+}
+
+#[test]
+fn man_format_writes_a_man_page() {
+    let (stdout, stderr, success) = run_cli(&["test-items::Named", "--format", "man"]);
+    assert!(success, "CLI should succeed: {stderr}");
+    assert_snapshot!(stdout, @r#"
+    .TH "test_items::Named" "3" "" "test_items 0.1.0" "Rust API Documentation"
+    .SH NAME
+    trait test_items::Named \- Something with a name
+    .SH SYNOPSIS
+    .nf
+    pub trait test_items::Named {
+        // Required method
+        /// The name
+        fn name(&self) \-> String;
+    }
+
+    // dyn compatible: yes
+    .fi
+    .SH DESCRIPTION
+    .PP
+    Something with a name
+    .SH SEE ALSO
+    https://docs.rs/test_items/0.1.0/test_items/trait.Named.html
+    "#);
+}
@@ -2,6 +2,8 @@
 //! integration-test binary so it runs in its own process and never races
 //! other tests that touch the cache or filesystem.
 
+#![cfg(feature = "native")]
+
 mod common;
 
 use std::fs;
@@ -1,3 +1,5 @@
+#![cfg(feature = "native")]
+
 mod common;
 
 use common::run_cli;
@@ -12,6 +14,7 @@ fn public_struct_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found struct test_visibility::PublicStruct
+    use test_visibility::PublicStruct;
 
     /// A fully public struct
     pub struct test_visibility::PublicStruct {
@@ -32,6 +35,7 @@ fn public_enum_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found enum test_visibility::PublicEnum
+    use test_visibility::PublicEnum;
 
     /// A public enum
     pub enum test_visibility::PublicEnum {
@@ -50,6 +54,7 @@ fn public_function_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found fn test_visibility::public_function
+    use test_visibility::public_function;
 
     /// A public function
     pub fn test_visibility::public_function() -> String
@@ -63,6 +68,7 @@ fn public_const_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found const test_visibility::PUBLIC_CONST
+    use test_visibility::PUBLIC_CONST;
 
     /// Public constant
     pub const test_visibility::PUBLIC_CONST: i32
@@ -76,6 +82,7 @@ fn public_type_alias_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found type test_visibility::PublicAlias
+    use test_visibility::PublicAlias;
 
     /// Public type alias
     pub type test_visibility::PublicAlias = test_visibility::PublicStruct
@@ -86,17 +93,22 @@ fn public_type_alias_is_found() {
 fn public_trait_is_found() {
     let (stdout, stderr, success) = run_cli(&["test-visibility", "PublicTrait"]);
     assert!(success, "CLI should succeed: {stderr}");
-    assert_snapshot!(stdout, @r"
+    assert_snapshot!(stdout, @"
     // version 0.1.0 (local)
     // found trait test_visibility::PublicTrait
+    use test_visibility::PublicTrait;
 
     /// A trait to test trait visibility
     pub trait test_visibility::PublicTrait {
         /// Associated type
         type Item;
+
+        // Required method
         /// Trait method
         fn method(&self) -> Self::Item;
     }
+
+    // dyn compatible: yes
     ");
 }
 
@@ -107,6 +119,7 @@ fn nested_public_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found struct test_visibility::public_module::NestedPublic
+    use test_visibility::public_module::NestedPublic;
 
     /// Public item in public module
     pub struct test_visibility::public_module::NestedPublic
@@ -120,6 +133,7 @@ fn deeply_nested_is_found() {
     assert_snapshot!(stdout, @r"
     // version 0.1.0 (local)
     // found struct test_visibility::public_module::inner::DeeplyNested
+    use test_visibility::public_module::inner::DeeplyNested;
 
     /// Public item in nested module
     pub struct test_visibility::public_module::inner::DeeplyNested
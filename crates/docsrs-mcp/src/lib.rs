@@ -8,6 +8,7 @@ use rmcp::service::{RequestContext, RoleServer};
 use rmcp::{ErrorData as McpError, ServerHandler, tool, tool_router};
 use schemars::JsonSchema;
 use serde::Deserialize;
+use tokio::task::JoinError;
 
 #[derive(Clone)]
 pub struct DocsRsServer {
@@ -58,22 +59,18 @@ Examples:
         &self,
         params: Parameters<LookupDocsParams>,
     ) -> Result<CallToolResult, McpError> {
-        let crate_spec = params.0.crate_spec;
-        let filter = params.0.filter;
-
-        let result = tokio::task::spawn_blocking(move || {
-            let mut args: Vec<&str> = vec![&crate_spec];
-            if let Some(ref filter) = filter {
-                args.push(filter);
-            }
-            docsrs_core::run_cli(&args)
-        })
-        .await
-        .map_err(|e| McpError::internal_error(e.to_string(), None))?;
+        // Looked up with the config applied, and never as a subcommand, so a
+        // spec like "history" is a crate
+        let params = params.0;
+        let result = docsrs_core::nonblocking::lookup(params.crate_spec, params.filter).await;
 
         match result {
             Ok(docs) => Ok(CallToolResult::success(vec![Content::text(docs)])),
-            Err(e) => Ok(CallToolResult::error(vec![Content::text(e)])),
+            // The query panicked
+            Err(e) if e.is::<JoinError>() => Err(McpError::internal_error(e.to_string(), None)),
+            Err(e) => Ok(CallToolResult::error(vec![Content::text(
+                docsrs_core::error_message(&e),
+            )])),
         }
     }
 }
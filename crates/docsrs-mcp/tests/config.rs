@@ -0,0 +1,80 @@
+use std::fs;
+use std::path::Path;
+use std::sync::Arc;
+
+use docsrs_core::{DirSource, set_doc_source};
+use docsrs_mcp::DocsRsServer;
+use rmcp::ClientHandler;
+use rmcp::model::{CallToolRequestParams, ClientCapabilities, ClientInfo, Implementation};
+use rmcp::service::ServiceExt;
+
+#[derive(Clone)]
+struct TestClient;
+
+impl ClientHandler for TestClient {
+    fn get_info(&self) -> ClientInfo {
+        ClientInfo::new(
+            ClientCapabilities::default(),
+            Implementation::new("test-client", "0.1.0"),
+        )
+    }
+}
+
+#[tokio::test]
+async fn lookup_docs_uses_the_config() {
+    // The generated docs of test-items served as test_items@1.0.0, so
+    // nothing is downloaded
+    let docs = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../target/doc/test_items.json");
+    assert!(
+        docs.exists(),
+        "{} is missing, run scripts/generate-test-docs.sh first",
+        docs.display()
+    );
+    let source = tempfile::tempdir().unwrap();
+    fs::create_dir(source.path().join("test_items")).unwrap();
+    fs::copy(&docs, source.path().join("test_items/1.0.0.json")).unwrap();
+    set_doc_source(Arc::new(DirSource::new(source.path())));
+
+    let dir = tempfile::tempdir().unwrap();
+    let cache = dir.path().join("cache");
+    let config = dir.path().join("config.toml");
+    fs::write(
+        &config,
+        format!(
+            "cache-dir = {:?}\n\n[aliases]\nti = \"test_items\"\n",
+            cache.to_str().unwrap()
+        ),
+    )
+    .unwrap();
+    // SAFETY: this test binary has no other test reading the environment
+    unsafe {
+        std::env::set_var("DOCSRS_CONFIG", &config);
+        std::env::set_var("DOCSRS_HISTORY", "");
+    }
+
+    let (client_io, server_io) = tokio::io::duplex(1024 * 1024);
+    let server = tokio::spawn(async move {
+        let _ = DocsRsServer::new()
+            .serve(server_io)
+            .await
+            .unwrap()
+            .waiting()
+            .await;
+    });
+    let client = TestClient.serve(client_io).await.unwrap();
+    let arguments = serde_json::json!({ "crate_spec": "ti@1.0.0::struct@Shape" });
+    let request = CallToolRequestParams::new("lookup_docs")
+        .with_arguments(arguments.as_object().cloned().unwrap());
+    let result = client.call_tool(request).await.unwrap();
+    drop(client);
+    server.abort();
+
+    let output = result.content[0].as_text().unwrap().text.clone();
+    assert!(!result.is_error.unwrap_or(false), "{output}");
+    assert!(
+        output.starts_with("// found struct test_items::Shape\n"),
+        "{output}"
+    );
+    // Cached where the config says
+    assert!(cache.join("test_items").exists());
+}
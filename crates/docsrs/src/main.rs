@@ -28,7 +28,7 @@ async fn main() {
 
 fn run_cli(args: &[String]) {
     let args_refs: Vec<&str> = args.iter().map(|s| s.as_str()).collect();
-    match docsrs_core::run_cli(&args_refs) {
+    match docsrs_core::run_cli_to(&args_refs, &mut std::io::stdout(), true) {
         Ok(output) => {
             print!("{}", output);
             process::exit(0);
@@ -1,6 +1,7 @@
 use rustdoc_types::{Attribute, Impl, Item};
 
-#[derive(Debug, Clone, PartialEq, Eq)]
+/// Classification of an impl block, used to decide which impls are shown.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImplKind {
     /// E.g. `impl Foo` or `impl<'a> Foo<'a>`
     Inherent,
@@ -19,6 +20,7 @@ pub enum ImplKind {
 }
 
 impl ImplKind {
+    /// Classify the impl block `impl_`, whose item is `impl_item`.
     pub fn from(impl_item: &Item, impl_: &Impl) -> Self {
         let has_blanket_impl = impl_.blanket_impl.is_some();
         let is_automatically_derived = impl_item.attrs.contains(&Attribute::AutomaticallyDerived);
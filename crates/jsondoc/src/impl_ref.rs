@@ -0,0 +1,33 @@
+use rustdoc_types::{Impl, Item};
+
+use crate::impl_kind::ImplKind;
+
+/// An impl block in the crate, with its [`ImplKind`] classification.
+///
+/// Returned by [`JsonDoc::impls_for`](crate::JsonDoc::impls_for) and
+/// [`JsonDoc::implementors_of`](crate::JsonDoc::implementors_of).
+#[derive(Clone, Copy, Debug)]
+pub struct ImplRef<'c> {
+    /// The item of the impl block, for its id, docs and attributes.
+    pub item: &'c Item,
+
+    /// The impl block itself.
+    pub impl_: &'c Impl,
+
+    /// What kind of impl this is.
+    pub kind: ImplKind,
+}
+
+impl<'c> ImplRef<'c> {
+    /// Wrap `item` if it is an impl block.
+    pub fn from_item(item: &'c Item) -> Option<Self> {
+        match &item.inner {
+            rustdoc_types::ItemEnum::Impl(impl_) => Some(Self {
+                item,
+                impl_,
+                kind: ImplKind::from(item, impl_),
+            }),
+            _ => None,
+        }
+    }
+}
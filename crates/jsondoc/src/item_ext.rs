@@ -1,4 +1,4 @@
-use rustdoc_types::{Id, Item, ItemEnum, Struct, StructKind, VariantKind};
+use rustdoc_types::{Id, Item, ItemEnum, ItemKind, MacroKind, Struct, StructKind, VariantKind};
 
 /// Extension trait for rustdoc_types::Item providing utility methods.
 pub trait ItemExt {
@@ -7,6 +7,9 @@ pub trait ItemExt {
 
     /// Returns the impls for this item if applicable.
     fn impls(&self) -> Option<&[Id]>;
+
+    /// Returns the kind of this item.
+    fn kind(&self) -> ItemKind;
 }
 
 impl ItemExt for Item {
@@ -43,4 +46,34 @@ impl ItemExt for Item {
             _ => None,
         }
     }
+
+    fn kind(&self) -> ItemKind {
+        match &self.inner {
+            ItemEnum::Module(_) => ItemKind::Module,
+            ItemEnum::ExternCrate { .. } => ItemKind::ExternCrate,
+            ItemEnum::Use(_) => ItemKind::Use,
+            ItemEnum::Union(_) => ItemKind::Union,
+            ItemEnum::Struct(_) => ItemKind::Struct,
+            ItemEnum::StructField(_) => ItemKind::StructField,
+            ItemEnum::Enum(_) => ItemKind::Enum,
+            ItemEnum::Variant(_) => ItemKind::Variant,
+            ItemEnum::Function(_) => ItemKind::Function,
+            ItemEnum::Trait(_) => ItemKind::Trait,
+            ItemEnum::TraitAlias(_) => ItemKind::TraitAlias,
+            ItemEnum::Impl(_) => ItemKind::Impl,
+            ItemEnum::TypeAlias(_) => ItemKind::TypeAlias,
+            ItemEnum::Constant { .. } => ItemKind::Constant,
+            ItemEnum::Static(_) => ItemKind::Static,
+            ItemEnum::ExternType => ItemKind::ExternType,
+            ItemEnum::Macro(_) => ItemKind::Macro,
+            ItemEnum::ProcMacro(p) => match p.kind {
+                MacroKind::Bang => ItemKind::Macro,
+                MacroKind::Attr => ItemKind::ProcAttribute,
+                MacroKind::Derive => ItemKind::ProcDerive,
+            },
+            ItemEnum::Primitive(_) => ItemKind::Primitive,
+            ItemEnum::AssocConst { .. } => ItemKind::AssocConst,
+            ItemEnum::AssocType { .. } => ItemKind::AssocType,
+        }
+    }
 }
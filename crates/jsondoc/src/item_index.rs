@@ -0,0 +1,88 @@
+use rustdoc_types::{Id, ItemEnum};
+use std::collections::HashMap;
+
+use crate::jsondoc_item::JsonDocItem;
+
+/// The processed items of a crate, with lookup tables by path and by id so
+/// that navigating to an item doesn't scan every item.
+pub(crate) struct ItemIndex<'c> {
+    /// The items in processing order.
+    pub(crate) items: Vec<JsonDocItem<'c>>,
+
+    /// Positions in `items` by path string, for items shown by name (i.e.
+    /// not reached through an impl).
+    by_path: HashMap<String, Vec<usize>>,
+
+    /// Position of the item of an impl, like a method, by the path of the
+    /// type it's implemented on and its name, as in `Vec::push`. Items of
+    /// inherent impls shadow those of trait impls, as they do in Rust.
+    by_impl_path: HashMap<String, usize>,
+
+    /// Positions in `items` by id. Re-exported items have several.
+    by_id: HashMap<Id, Vec<usize>>,
+}
+
+impl<'c> ItemIndex<'c> {
+    pub(crate) fn new(items: Vec<JsonDocItem<'c>>) -> Self {
+        let mut by_path: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut by_impl_path: HashMap<String, usize> = HashMap::new();
+        let mut by_id: HashMap<Id, Vec<usize>> = HashMap::new();
+        for (position, item) in items.iter().enumerate() {
+            if !item.is_hidden() {
+                by_path
+                    .entry(item.path_string())
+                    .or_default()
+                    .push(position);
+            } else if let Some(parent) = item.parent()
+                && let ItemEnum::Impl(impl_) = &parent.item.item.inner
+            {
+                let inherent = impl_.trait_.is_none();
+                by_impl_path
+                    .entry(item.path_string())
+                    .and_modify(|current| {
+                        if inherent && !Self::is_in_inherent_impl(&items[*current]) {
+                            *current = position;
+                        }
+                    })
+                    .or_insert(position);
+            }
+            by_id.entry(item.id()).or_default().push(position);
+        }
+        Self {
+            items,
+            by_path,
+            by_impl_path,
+            by_id,
+        }
+    }
+
+    fn is_in_inherent_impl(item: &JsonDocItem<'_>) -> bool {
+        item.parent().is_some_and(|parent| {
+            matches!(&parent.item.item.inner, ItemEnum::Impl(impl_) if impl_.trait_.is_none())
+        })
+    }
+
+    /// Items at exactly `path`, in processing order. Without any, the item of
+    /// an impl at the path, like the method `Vec::push`.
+    pub(crate) fn by_path(&self, path: &str) -> impl Iterator<Item = &JsonDocItem<'c>> {
+        let positions = self.by_path.get(path);
+        let in_impl = positions
+            .is_none()
+            .then(|| self.by_impl_path.get(path))
+            .flatten();
+        self.lookup(positions)
+            .chain(in_impl.map(|&position| &self.items[position]))
+    }
+
+    /// Items with the given id, in processing order.
+    pub(crate) fn by_id(&self, id: &Id) -> impl Iterator<Item = &JsonDocItem<'c>> {
+        self.lookup(self.by_id.get(id))
+    }
+
+    fn lookup(&self, positions: Option<&Vec<usize>>) -> impl Iterator<Item = &JsonDocItem<'c>> {
+        positions
+            .into_iter()
+            .flatten()
+            .map(|&position| &self.items[position])
+    }
+}
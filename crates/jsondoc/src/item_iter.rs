@@ -0,0 +1,125 @@
+use rustdoc_types::{ItemKind, Visibility};
+
+use crate::{item_ext::ItemExt, jsondoc_item::JsonDocItem};
+
+/// Iterator over the items of a [`JsonDoc`](crate::JsonDoc), created by
+/// [`JsonDoc::iter_items`](crate::JsonDoc::iter_items).
+///
+/// Filters are applied lazily while iterating and can be combined:
+///
+/// ```ignore
+/// let structs = doc
+///     .iter_items()
+///     .kinds(&[ItemKind::Struct])
+///     .path_prefix("tokio::sync")
+///     .exclude_deprecated();
+/// ```
+#[derive(Clone)]
+pub struct ItemIter<'a, 'c> {
+    items: std::slice::Iter<'a, JsonDocItem<'c>>,
+    kinds: Option<Vec<ItemKind>>,
+    path_prefix: Option<String>,
+    public_only: bool,
+    deprecated: Option<bool>,
+    include_hidden: bool,
+}
+
+impl<'a, 'c> ItemIter<'a, 'c> {
+    pub(crate) fn new(items: &'a [JsonDocItem<'c>]) -> Self {
+        Self {
+            items: items.iter(),
+            kinds: None,
+            path_prefix: None,
+            public_only: false,
+            deprecated: None,
+            include_hidden: true,
+        }
+    }
+
+    /// Only yield items of the given kinds.
+    pub fn kinds(mut self, kinds: &[ItemKind]) -> Self {
+        self.kinds = Some(kinds.to_vec());
+        self
+    }
+
+    /// Only yield items at or below `prefix`, e.g. `tokio::sync` matches
+    /// `tokio::sync` and `tokio::sync::Mutex` but not `tokio::sync_ext`.
+    pub fn path_prefix(mut self, prefix: impl Into<String>) -> Self {
+        self.path_prefix = Some(prefix.into());
+        self
+    }
+
+    /// Only yield items declared `pub` (or public by default, like enum
+    /// variants and trait items).
+    pub fn public_only(mut self) -> Self {
+        self.public_only = true;
+        self
+    }
+
+    /// Skip items marked `#[deprecated]`.
+    pub fn exclude_deprecated(mut self) -> Self {
+        self.deprecated = Some(false);
+        self
+    }
+
+    /// Only yield items marked `#[deprecated]`.
+    pub fn deprecated_only(mut self) -> Self {
+        self.deprecated = Some(true);
+        self
+    }
+
+    /// Skip items only reachable through an impl, such as inherent and
+    /// trait methods.
+    pub fn exclude_hidden(mut self) -> Self {
+        self.include_hidden = false;
+        self
+    }
+
+    fn matches(&self, item: &JsonDocItem<'c>) -> bool {
+        let inner = item.item();
+        if let Some(kinds) = &self.kinds
+            && !kinds.contains(&inner.kind())
+        {
+            return false;
+        }
+        if self.public_only && !matches!(inner.visibility, Visibility::Public | Visibility::Default)
+        {
+            return false;
+        }
+        if let Some(deprecated) = self.deprecated
+            && inner.deprecation.is_some() != deprecated
+        {
+            return false;
+        }
+        if !self.include_hidden && item.is_hidden() {
+            return false;
+        }
+        if let Some(prefix) = &self.path_prefix {
+            let path = item.path_string();
+            let below = path
+                .strip_prefix(prefix.as_str())
+                .is_some_and(|rest| rest.is_empty() || rest.starts_with("::"));
+            if !below {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+impl<'a, 'c> Iterator for ItemIter<'a, 'c> {
+    type Item = &'a JsonDocItem<'c>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some(item) = self.items.next() {
+            if self.matches(item) {
+                return Some(item);
+            }
+        }
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, self.items.size_hint().1)
+    }
+}
@@ -1,10 +1,12 @@
 use ouroboros::self_referencing;
-use rustdoc_types::{Crate, Id, Impl, Item, ItemEnum, Module, Type, Use};
+use rustdoc_types::{Crate, Enum, Id, Impl, Item, ItemEnum, Module, Type, Use, Visibility};
 use std::collections::{HashMap, VecDeque};
 
 use crate::{
-    crate_wrapper::CrateWrapper, impl_kind::ImplKind, item_ext::ItemExt, jsondoc_item::JsonDocItem,
+    crate_wrapper::CrateWrapper, impl_kind::ImplKind, impl_ref::ImplRef, item_ext::ItemExt,
+    item_index::ItemIndex, item_iter::ItemIter, jsondoc_item::JsonDocItem,
     path_component::PathComponent, unprocessed_item::UnprocessedItem,
+    visibility_policy::ItemVisibilityPolicy,
 };
 
 /// JSON documentation for a Rust crate.
@@ -16,77 +18,202 @@ pub struct JsonDoc {
     /// The owned crate data.
     crate_data: Crate,
 
-    /// Processed items that borrow from crate_data.
+    /// Processed items that borrow from crate_data, indexed by path and id.
     #[borrows(crate_data)]
     #[covariant]
-    items: Vec<JsonDocItem<'this>>,
+    index: ItemIndex<'this>,
 }
 
 impl From<Crate> for JsonDoc {
     /// Create a new JsonDoc by processing the given Crate.
     /// Takes ownership of the Crate.
     fn from(crate_: Crate) -> Self {
+        Self::with_visibility(crate_, ItemVisibilityPolicy::default())
+    }
+}
+
+impl JsonDoc {
+    /// Create a new JsonDoc, including items according to `policy`.
+    /// Useful for rustdoc JSON built with `--document-private-items`.
+    pub fn with_visibility(crate_: Crate, policy: ItemVisibilityPolicy) -> Self {
         JsonDocBuilder {
             crate_data: crate_,
-            items_builder: |crate_ref: &Crate| process_crate(crate_ref),
+            index_builder: |crate_ref: &Crate| {
+                ItemIndex::new(process_crate(crate_ref, policy, None))
+            },
+        }
+        .build()
+    }
+
+    /// Create a new JsonDoc for looking up the item at `path` (like
+    /// `"tokio::sync::Mutex"`). Only the items along the path have their impl
+    /// blocks walked, which is most of the work for a large crate.
+    ///
+    /// Since other impls aren't processed, [`Self::id_to_items`] only knows
+    /// the methods and associated items of the items along the path.
+    pub fn for_path(crate_: Crate, path: &str) -> Self {
+        let scope: Vec<String> = path.split("::").map(str::to_string).collect();
+        JsonDocBuilder {
+            crate_data: crate_,
+            index_builder: |crate_ref: &Crate| {
+                ItemIndex::new(process_crate(
+                    crate_ref,
+                    ItemVisibilityPolicy::default(),
+                    Some(scope),
+                ))
+            },
         }
         .build()
     }
-}
 
-impl JsonDoc {
     /// Returns the processed items.
     pub fn items(&self) -> &[JsonDocItem<'_>] {
-        self.borrow_items()
+        &self.borrow_index().items
     }
 
     /// Map IDs to their public items.
     pub fn id_to_items(&self) -> HashMap<&Id, Vec<&JsonDocItem<'_>>> {
         let mut map: HashMap<&Id, Vec<&JsonDocItem<'_>>> = HashMap::new();
-        for item in self.borrow_items() {
+        for item in self.items() {
             map.entry(&item.item().id).or_default().push(item);
         }
         map
     }
 
+    /// Returns the items with the given ID, one per path it's reachable at.
+    pub fn items_by_id(&self, id: &Id) -> impl Iterator<Item = &JsonDocItem<'_>> {
+        self.borrow_index().by_id(id)
+    }
+
+    /// Returns the first processed item with the given ID.
+    pub fn item_by_id(&self, id: &Id) -> Option<&JsonDocItem<'_>> {
+        self.items_by_id(id).next()
+    }
+
+    /// Returns the items nested directly in `parent` at its path, e.g. the
+    /// items of a module or the variants of an enum. Items reached through
+    /// several paths only count as children of the path they're listed under.
+    /// Names are compared too, since a module re-exported under another name
+    /// (`pub use inner as other;`) has the same id at both paths.
+    pub fn children<'a>(
+        &'a self,
+        parent: &'a JsonDocItem<'_>,
+    ) -> impl Iterator<Item = &'a JsonDocItem<'a>> {
+        self.items().iter().filter(move |item| {
+            item.parent_id() == Some(parent.id())
+                && item.path().len() == parent.path().len() + 1
+                && item
+                    .ancestors()
+                    .zip(parent.path().iter().rev())
+                    .all(|(a, b)| {
+                        a.item.item.id == b.item.item.id && a.item.name() == b.item.name()
+                    })
+        })
+    }
+
+    /// Returns the impl blocks of a struct, enum, union or primitive, in
+    /// declaration order. Use [`ImplKind::is_active`] to skip the blanket and
+    /// auto trait impls that aren't shown by default.
+    pub fn impls_for(&self, id: &Id) -> Vec<ImplRef<'_>> {
+        let crate_ = self.borrow_crate_data();
+        crate_
+            .index
+            .get(id)
+            .filter(|item| !matches!(item.inner, ItemEnum::Trait(_)))
+            .and_then(|item| item.impls())
+            .into_iter()
+            .flatten()
+            .filter_map(|impl_id| crate_.index.get(impl_id))
+            .filter_map(ImplRef::from_item)
+            .collect()
+    }
+
+    /// Returns the impl blocks implementing a trait. Traits from other crates,
+    /// like `Clone`, aren't in the index, so for those the impls of this crate
+    /// are searched instead.
+    pub fn implementors_of(&self, trait_id: &Id) -> Vec<ImplRef<'_>> {
+        let crate_ = self.borrow_crate_data();
+        match crate_.index.get(trait_id) {
+            Some(Item {
+                inner: ItemEnum::Trait(trait_),
+                ..
+            }) => trait_
+                .implementations
+                .iter()
+                .filter_map(|impl_id| crate_.index.get(impl_id))
+                .filter_map(ImplRef::from_item)
+                .collect(),
+            _ => {
+                let mut impls: Vec<ImplRef<'_>> = crate_
+                    .index
+                    .values()
+                    .filter_map(ImplRef::from_item)
+                    .filter(|impl_ref| {
+                        impl_ref
+                            .impl_
+                            .trait_
+                            .as_ref()
+                            .is_some_and(|trait_| trait_.id == *trait_id)
+                    })
+                    .collect();
+                // The index is unordered; keep the output stable
+                impls.sort_by_key(|impl_ref| impl_ref.item.id);
+                impls
+            }
+        }
+    }
+
     /// Returns the crate root module ID.
     pub fn crate_root_id(&self) -> Id {
         self.borrow_crate_data().root
     }
 
     /// Find the item ID for an exact path like "tokio::task".
-    /// Returns None if no item exists at that exact path.
+    /// Returns None if no item exists at that exact path. When several items
+    /// share the path, the first one is returned; see
+    /// [`Self::find_items_by_path`].
     pub fn find_item_by_path(&self, path: &str) -> Option<Id> {
-        for item in self.borrow_items() {
-            // Skip hidden items
-            if item.path().iter().any(|seg| seg.hide) {
-                continue;
-            }
-
-            // Build the path string
-            let item_path: String = item
-                .path()
-                .iter()
-                .filter_map(|seg| seg.item.name())
-                .collect::<Vec<_>>()
-                .join("::");
+        self.find_items_by_path(path).into_iter().next()
+    }
 
-            if item_path == path {
-                return Some(item.id());
+    /// Find the IDs of all items at an exact path. Items in different
+    /// namespaces can share a path, like a struct and a macro both named
+    /// `Foo`, or a function and a module.
+    pub fn find_items_by_path(&self, path: &str) -> Vec<Id> {
+        let mut ids: Vec<Id> = Vec::new();
+        for item in self.borrow_index().by_path(path) {
+            if !ids.contains(&item.id()) {
+                ids.push(item.id());
             }
         }
-        None
+        ids
+    }
+
+    /// Iterate over the processed items, with combinators to filter by kind,
+    /// path prefix, visibility and deprecation.
+    pub fn iter_items(&self) -> ItemIter<'_, '_> {
+        ItemIter::new(self.items())
     }
 
     /// Access the underlying crate data.
     pub fn crate_data(&self) -> &Crate {
         self.borrow_crate_data()
     }
+
+    /// Take back the crate data, e.g. to process it again with other settings.
+    pub fn into_crate(self) -> Crate {
+        self.into_heads().crate_data
+    }
 }
 
-/// Process a crate into a list of public items.
-fn process_crate(crate_: &Crate) -> Vec<JsonDocItem<'_>> {
-    let mut processor = Processor::new(crate_);
+/// Process a crate into a list of items visible under `policy`, optionally
+/// only walking the impls of the items along a path.
+fn process_crate(
+    crate_: &Crate,
+    policy: ItemVisibilityPolicy,
+    scope: Option<Vec<String>>,
+) -> Vec<JsonDocItem<'_>> {
+    let mut processor = Processor::new(crate_, policy, scope);
     processor.add_to_work_queue(vec![], None, crate_.root);
     processor.run();
     processor.output
@@ -102,14 +229,23 @@ struct Processor<'c> {
 
     /// The output. A list of processed items.
     output: Vec<JsonDocItem<'c>>,
+
+    /// Which items to include based on their visibility.
+    policy: ItemVisibilityPolicy,
+
+    /// If set, only the items along this path (one name per segment) have
+    /// their impls processed.
+    scope: Option<Vec<String>>,
 }
 
 impl<'c> Processor<'c> {
-    fn new(crate_: &'c Crate) -> Self {
+    fn new(crate_: &'c Crate, policy: ItemVisibilityPolicy, scope: Option<Vec<String>>) -> Self {
         Processor {
             crate_: CrateWrapper::new(crate_),
             work_queue: VecDeque::new(),
             output: vec![],
+            policy,
+            scope,
         }
     }
 
@@ -130,12 +266,66 @@ impl<'c> Processor<'c> {
     /// Processes the entire work queue.
     fn run(&mut self) {
         while let Some(unprocessed_item) = self.work_queue.pop_front() {
-            if let Some(item) = self.crate_.get_item(unprocessed_item.id) {
+            if let Some(item) = self.crate_.get_item(unprocessed_item.id)
+                && self.is_included(item, &unprocessed_item)
+            {
                 self.process_any_item(item, unprocessed_item);
             }
         }
     }
 
+    /// Whether `item` is on the path being resolved, or no path is: its
+    /// names, skipping impl blocks, start the path.
+    fn is_in_scope(&self, item: &JsonDocItem<'c>) -> bool {
+        let Some(scope) = &self.scope else {
+            return true;
+        };
+        let mut names = item.path().iter().filter_map(|seg| seg.item.name());
+        let mut scope = scope.iter();
+        names.all(|name| scope.next().is_some_and(|expected| name == expected))
+    }
+
+    /// Whether the item's visibility is included by the policy.
+    ///
+    /// Rustdoc records private items as restricted to their own module (or as
+    /// `pub(crate)` at the crate root, which is equivalent), so those only
+    /// count as crate-visible when the restriction reaches further out.
+    fn is_included(&mut self, item: &'c Item, unprocessed_item: &UnprocessedItem<'c>) -> bool {
+        match (&item.visibility, self.policy) {
+            (_, ItemVisibilityPolicy::AllWithPrivate) | (Visibility::Public, _) => true,
+            (_, ItemVisibilityPolicy::PublicOnly) if item.visibility != Visibility::Default => {
+                false
+            }
+            (Visibility::Crate, _) => true,
+            (Visibility::Restricted { parent, .. }, _) => {
+                let own_module = unprocessed_item
+                    .parent_path
+                    .iter()
+                    .rev()
+                    .find(|component| matches!(component.item.item.inner, ItemEnum::Module(_)))
+                    .map(|component| component.item.item.id);
+                own_module != Some(*parent)
+            }
+            (Visibility::Default, _) => self.is_public_by_default(item, unprocessed_item.parent_id),
+        }
+    }
+
+    /// Items without a visibility modifier are private, except for impls,
+    /// enum variants and the items of variants, traits and trait impls.
+    fn is_public_by_default(&mut self, item: &'c Item, parent_id: Option<Id>) -> bool {
+        if matches!(item.inner, ItemEnum::Impl(_) | ItemEnum::Variant(_)) {
+            return true;
+        }
+        let Some(parent) = parent_id.and_then(|id| self.crate_.get_item(id)) else {
+            return true;
+        };
+        match &parent.inner {
+            ItemEnum::Variant(_) | ItemEnum::Trait(_) => true,
+            ItemEnum::Impl(impl_) => impl_.trait_.is_some(),
+            _ => false,
+        }
+    }
+
     /// Process any item.
     fn process_any_item(&mut self, item: &'c Item, unprocessed_item: UnprocessedItem<'c>) {
         match &item.inner {
@@ -155,32 +345,42 @@ impl<'c> Processor<'c> {
         }
     }
 
-    /// Handle `pub use foo::*` wildcard imports.
+    /// Handle `pub use foo::*` wildcard imports of modules and enums, by
+    /// adding the target's items as if they were declared in the importing
+    /// module.
     fn process_use_glob_item(
         &mut self,
         use_: &'c Use,
         unprocessed_item: UnprocessedItem<'c>,
         item: &'c Item,
     ) {
-        if let Some(Item {
-            inner: ItemEnum::Module(Module { items, .. }),
-            ..
-        }) = use_
+        let target = use_
             .id
-            .and_then(|id| self.get_item_if_not_in_path(&unprocessed_item.parent_path, id))
-        {
-            for &item_id in items {
-                self.add_to_work_queue(
-                    unprocessed_item.parent_path.clone(),
-                    unprocessed_item.parent_id,
-                    item_id,
+            .and_then(|id| self.get_item_if_not_in_path(&unprocessed_item.parent_path, id));
+        let items = match target {
+            Some(Item {
+                inner: ItemEnum::Module(Module { items, .. }),
+                ..
+            }) => items,
+            Some(Item {
+                inner: ItemEnum::Enum(Enum { variants, .. }),
+                ..
+            }) => variants,
+            _ => {
+                self.process_item(
+                    unprocessed_item,
+                    item,
+                    Some(format!("<<{}::*>>", use_.source)),
                 );
+                return;
             }
-        } else {
-            self.process_item(
-                unprocessed_item,
-                item,
-                Some(format!("<<{}::*>>", use_.source)),
+        };
+
+        for &item_id in items {
+            self.add_to_work_queue(
+                unprocessed_item.parent_path.clone(),
+                unprocessed_item.parent_id,
+                item_id,
             );
         }
     }
@@ -262,7 +462,12 @@ impl<'c> Processor<'c> {
         let finished_item = unprocessed_item.finish(item, overridden_name, type_);
 
         let children = item.children();
-        let impls = item.impls().into_iter().flatten();
+        // When resolving a path, only the impls along it are needed
+        let impls = item
+            .impls()
+            .filter(|_| self.is_in_scope(&finished_item))
+            .into_iter()
+            .flatten();
 
         for &id in children {
             self.add_to_work_queue(finished_item.path().into(), Some(item.id), id);
@@ -1,6 +1,7 @@
 use rustdoc_types::{Id, Item};
+use serde::{Serialize, Serializer, ser::SerializeStruct};
 
-use crate::path_component::PathComponent;
+use crate::{item_ext::ItemExt, path_component::PathComponent};
 
 /// This struct represents one public item of a crate.
 /// Conceptually it wraps a single [`Item`] even though the path to the item
@@ -41,7 +42,57 @@ impl<'c> JsonDocItem<'c> {
         self.id
     }
 
+    /// The path component this item is nested in: a module, a type, a trait,
+    /// or the impl block of a method. `None` for the crate root.
+    pub fn parent(&self) -> Option<&PathComponent<'c>> {
+        self.ancestors().next()
+    }
+
+    /// The path components this item is nested in, nearest first and ending
+    /// with the crate root. For example, the module defining a method is
+    /// found with:
+    ///
+    /// ```ignore
+    /// item.ancestors()
+    ///     .find(|ancestor| matches!(ancestor.item.item.inner, ItemEnum::Module(_)))
+    /// ```
+    pub fn ancestors(&self) -> impl Iterator<Item = &PathComponent<'c>> {
+        self.path().iter().rev().skip(1)
+    }
+
+    /// The item's path joined with `::`, e.g. `tokio::sync::Mutex`.
+    pub fn path_string(&self) -> String {
+        self.path()
+            .iter()
+            .filter_map(|seg| seg.item.name())
+            .collect::<Vec<_>>()
+            .join("::")
+    }
+
+    /// Whether any segment of the path is hidden, i.e. the item is reached
+    /// through an impl rather than by name.
+    pub fn is_hidden(&self) -> bool {
+        self.path().iter().any(|seg| seg.hide)
+    }
+
     pub fn path_contains_renamed_item(&self) -> bool {
         self.path().iter().any(|m| m.item.overridden_name.is_some())
     }
 }
+
+/// Serializes the item's identity and path, not the full rustdoc [`Item`]:
+/// `{"id": 42, "parent_id": 7, "kind": "struct", "path": "tokio::sync::Mutex",
+/// "components": [...]}`. The rendered signature tokens are serialized by
+/// `PublicItem` in docsrs-core, which renders the item; this crate has no
+/// renderer.
+impl Serialize for JsonDocItem<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("JsonDocItem", 5)?;
+        state.serialize_field("id", &self.id)?;
+        state.serialize_field("parent_id", &self.parent_id)?;
+        state.serialize_field("kind", &self.item().kind())?;
+        state.serialize_field("path", &self.path_string())?;
+        state.serialize_field("components", &self.path)?;
+        state.end()
+    }
+}
@@ -5,15 +5,22 @@
 
 mod crate_wrapper;
 mod impl_kind;
+mod impl_ref;
 mod item_ext;
+mod item_index;
+mod item_iter;
 mod jsondoc;
 mod jsondoc_item;
 mod nameable_item;
 mod path_component;
 mod unprocessed_item;
+mod visibility_policy;
 
 pub use impl_kind::ImplKind;
+pub use impl_ref::ImplRef;
+pub use item_iter::ItemIter;
 pub use jsondoc::JsonDoc;
 pub use jsondoc_item::JsonDocItem;
 pub use nameable_item::NameableItem;
 pub use path_component::PathComponent;
+pub use visibility_policy::ItemVisibilityPolicy;
@@ -1,6 +1,7 @@
 use rustdoc_types::Type;
+use serde::{Serialize, Serializer, ser::SerializeStruct};
 
-use crate::nameable_item::NameableItem;
+use crate::{item_ext::ItemExt, nameable_item::NameableItem};
 
 /// A public item in a public API can only be referenced via a path. For example
 /// `mod_a::mod_b::StructC`. A `PathComponent` represents one component of such
@@ -21,3 +22,15 @@ pub struct PathComponent<'c> {
     /// If `true`, do not render this path component to users.
     pub hide: bool,
 }
+
+/// Serializes as `{"name": "Mutex", "kind": "struct", "id": 42, "hide": false}`.
+impl Serialize for PathComponent<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut state = serializer.serialize_struct("PathComponent", 4)?;
+        state.serialize_field("name", &self.item.name())?;
+        state.serialize_field("kind", &self.item.item.kind())?;
+        state.serialize_field("id", &self.item.item.id)?;
+        state.serialize_field("hide", &self.hide)?;
+        state.end()
+    }
+}
@@ -0,0 +1,21 @@
+/// Which items [`JsonDoc`](crate::JsonDoc) includes, based on their declared
+/// visibility.
+///
+/// Rustdoc JSON built with `--document-private-items` contains every item of
+/// the crate. The policy decides how much of that is processed; for regular
+/// JSON, which only has the public API, all policies give the same result.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum ItemVisibilityPolicy {
+    /// Only `pub` items, and items that are public by default like enum
+    /// variants and trait items.
+    #[default]
+    PublicOnly,
+
+    /// Also `pub(crate)`, `pub(super)` and `pub(in path)` items. Private
+    /// items at the crate root are included too, since rustdoc records them
+    /// as `pub(crate)`.
+    CrateVisible,
+
+    /// Every item, including private ones.
+    AllWithPrivate,
+}
@@ -0,0 +1,353 @@
+//! Tests of processing the rustdoc JSON of the test-items crate, built by
+//! `scripts/generate-test-docs.sh`.
+
+use std::path::Path;
+
+use jsondoc::{ImplKind, ItemVisibilityPolicy, JsonDoc};
+use rustdoc_types::{Crate, ItemEnum, ItemKind};
+
+fn test_items() -> Crate {
+    load("target/doc/test_items.json")
+}
+
+/// Docs of test-visibility built with `--document-private-items`.
+fn test_visibility_private() -> Crate {
+    load("target/private-items/doc/test_visibility.json")
+}
+
+fn load(path: &str) -> Crate {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("../..")
+        .join(path);
+    let json = std::fs::read(&path).unwrap_or_else(|e| {
+        panic!(
+            "{}: {e}, run scripts/generate-test-docs.sh first",
+            path.display()
+        )
+    });
+    serde_json::from_slice(&json).unwrap()
+}
+
+#[test]
+fn for_path_finds_the_items_of_the_full_doc() {
+    let full = JsonDoc::from(test_items());
+    for path in [
+        "test_items",
+        "test_items::Shape",
+        "test_items::Text::width",
+        "test_items::builder::Options",
+        "test_items::Draw",
+    ] {
+        let scoped = JsonDoc::for_path(test_items(), path);
+        let ids = full.find_items_by_path(path);
+        assert!(!ids.is_empty(), "{path}");
+        assert_eq!(scoped.find_items_by_path(path), ids, "{path}");
+
+        let children = |doc: &JsonDoc, id| {
+            let item = doc.item_by_id(id).unwrap();
+            let mut children: Vec<String> = doc
+                .children(item)
+                .map(|child| child.path_string())
+                .collect();
+            children.sort();
+            children
+        };
+        for id in &ids {
+            assert_eq!(children(&scoped, id), children(&full, id), "{path}");
+        }
+    }
+}
+
+#[test]
+fn for_path_skips_the_impls_off_the_path() {
+    let full = JsonDoc::from(test_items());
+    let scoped = JsonDoc::for_path(test_items(), "test_items::Text");
+    assert!(scoped.items().len() < full.items().len());
+    assert!(
+        !scoped
+            .find_items_by_path("test_items::Text::width")
+            .is_empty()
+    );
+    assert!(
+        scoped
+            .find_items_by_path("test_items::Styled::color")
+            .is_empty()
+    );
+    assert!(
+        !full
+            .find_items_by_path("test_items::Styled::color")
+            .is_empty()
+    );
+}
+
+fn paths<'a>(items: impl Iterator<Item = &'a jsondoc::JsonDocItem<'a>>) -> Vec<String> {
+    let mut paths: Vec<String> = items.map(|item| item.path_string()).collect();
+    paths.sort();
+    paths.dedup();
+    paths
+}
+
+#[test]
+fn iter_items_filters_by_kind() {
+    let doc = JsonDoc::from(test_items());
+    let structs: Vec<_> = doc.iter_items().kinds(&[ItemKind::Struct]).collect();
+    assert!(
+        structs
+            .iter()
+            .all(|item| matches!(item.item().inner, ItemEnum::Struct(_)))
+    );
+    let structs = paths(structs.into_iter());
+    assert!(
+        structs.contains(&"test_items::Shape".to_string()),
+        "{structs:?}"
+    );
+    assert!(structs.contains(&"test_items::builder::Options".to_string()));
+
+    let traits_and_fns = paths(
+        doc.iter_items()
+            .kinds(&[ItemKind::Trait, ItemKind::Function])
+            .exclude_hidden()
+            .path_prefix("test_items::builder"),
+    );
+    assert_eq!(traits_and_fns, ["test_items::builder"]);
+}
+
+#[test]
+fn iter_items_filters_by_path_prefix() {
+    let doc = JsonDoc::from(test_items());
+    let builder = paths(doc.iter_items().path_prefix("test_items::builder"));
+    assert_eq!(
+        builder,
+        ["test_items::builder", "test_items::builder::Options"]
+    );
+    assert_eq!(doc.iter_items().path_prefix("test_items::build").count(), 0);
+}
+
+#[test]
+fn iter_items_skips_hidden_items() {
+    let doc = JsonDoc::from(test_items());
+    let all = paths(doc.iter_items());
+    let named = paths(doc.iter_items().exclude_hidden());
+    assert!(all.contains(&"test_items::Text::width".to_string()));
+    assert!(!named.contains(&"test_items::Text::width".to_string()));
+    assert!(named.contains(&"test_items::Text".to_string()));
+}
+
+#[test]
+fn iter_items_filters_by_deprecation() {
+    // Nothing in test-items is deprecated
+    let doc = JsonDoc::from(test_items());
+    assert_eq!(doc.iter_items().deprecated_only().count(), 0);
+    assert_eq!(
+        doc.iter_items().exclude_deprecated().count(),
+        doc.items().len()
+    );
+    assert_eq!(doc.iter_items().public_only().count(), doc.items().len());
+}
+
+#[test]
+fn visibility_policy_includes_items_by_visibility() {
+    let included = |policy| {
+        let doc = JsonDoc::with_visibility(test_visibility_private(), policy);
+        let paths = paths(doc.items().iter());
+        move |path: &str| paths.contains(&format!("test_visibility::{path}"))
+    };
+
+    let public = included(ItemVisibilityPolicy::PublicOnly);
+    assert!(public("PublicStruct::new"));
+    assert!(public("public_module::inner::DeeplyNested"));
+    assert!(!public("CrateVisibleStruct"));
+    assert!(!public("PublicStruct::crate_method"));
+    assert!(!public("private_module::ItemInPrivateModule"));
+
+    let crate_visible = included(ItemVisibilityPolicy::CrateVisible);
+    assert!(crate_visible("CrateVisibleStruct"));
+    assert!(crate_visible("PublicStruct::crate_method"));
+    assert!(crate_visible("public_module::NestedSuperVisible"));
+    assert!(crate_visible("public_module::inner::VisibleToOuterModule"));
+    // Private at the crate root, which rustdoc records as `pub(crate)`
+    assert!(crate_visible("PrivateStruct"));
+    assert!(!crate_visible("public_module::NestedPrivate"));
+
+    let all = included(ItemVisibilityPolicy::AllWithPrivate);
+    assert!(all("public_module::NestedPrivate"));
+    assert!(all("PublicStruct::private_method"));
+}
+
+#[test]
+fn visibility_policy_keeps_public_docs_as_they_are() {
+    let public = JsonDoc::from(load("target/doc/test_visibility.json"));
+    let all = JsonDoc::with_visibility(
+        load("target/doc/test_visibility.json"),
+        ItemVisibilityPolicy::AllWithPrivate,
+    );
+    assert_eq!(paths(all.items().iter()), paths(public.items().iter()));
+}
+
+#[test]
+fn ancestors_walk_up_to_the_crate_root() {
+    let doc = JsonDoc::from(load("target/doc/test_visibility.json"));
+    let id = doc.find_items_by_path("test_visibility::public_module::inner::DeeplyNested")[0];
+    let item = doc.item_by_id(&id).unwrap();
+
+    let names: Vec<_> = item
+        .ancestors()
+        .map(|ancestor| ancestor.item.name())
+        .collect();
+    assert_eq!(
+        names,
+        [
+            Some("inner"),
+            Some("public_module"),
+            Some("test_visibility")
+        ]
+    );
+    assert_eq!(item.parent().unwrap().item.name(), Some("inner"));
+
+    let root = item.ancestors().last().unwrap();
+    assert_eq!(root.item.item.id, doc.crate_root_id());
+    assert!(
+        doc.item_by_id(&root.item.item.id)
+            .unwrap()
+            .parent()
+            .is_none()
+    );
+}
+
+#[test]
+fn methods_are_nested_in_their_impl_block() {
+    let doc = JsonDoc::from(test_items());
+    let id = doc.find_items_by_path("test_items::Text::width")[0];
+    let method = doc.item_by_id(&id).unwrap();
+
+    let impl_block = method.parent().unwrap();
+    assert!(matches!(impl_block.item.item.inner, ItemEnum::Impl(_)));
+    let names: Vec<_> = method
+        .ancestors()
+        .skip(1)
+        .map(|ancestor| ancestor.item.name())
+        .collect();
+    assert_eq!(names, [Some("Text"), Some("test_items")]);
+}
+
+#[test]
+fn inherent_methods_shadow_trait_methods_of_the_same_name() {
+    let doc = JsonDoc::from(test_items());
+    let ids = doc.find_items_by_path("test_items::Counter::name");
+    assert_eq!(ids.len(), 1);
+    let method = doc.item_by_id(&ids[0]).unwrap();
+    let ItemEnum::Impl(impl_) = &method.parent().unwrap().item.item.inner else {
+        panic!("expected a method of an impl");
+    };
+    assert!(impl_.trait_.is_none());
+    assert_eq!(
+        method.item().docs.as_deref(),
+        Some("The inherent name, which shadows `Named::name`")
+    );
+}
+
+#[test]
+fn items_found_by_name_hide_impl_items_at_the_same_path() {
+    let doc = JsonDoc::from(test_items());
+    let ids = doc.find_items_by_path("test_items::Counter::count");
+    assert_eq!(ids.len(), 1);
+    let item = doc.item_by_id(&ids[0]).unwrap();
+    assert!(matches!(item.item().inner, ItemEnum::StructField(_)));
+
+    // Without an item by name, the path finds the impl's item
+    let ids = doc.find_items_by_path("test_items::Text::width");
+    assert!(matches!(
+        doc.item_by_id(&ids[0]).unwrap().item().inner,
+        ItemEnum::Function(_)
+    ));
+}
+
+/// The traits `impls` implement, `None` for inherent impls, in order.
+fn impl_traits(impls: &[jsondoc::ImplRef]) -> Vec<Option<String>> {
+    impls
+        .iter()
+        .map(|impl_ref| {
+            impl_ref
+                .impl_
+                .trait_
+                .as_ref()
+                .map(|trait_| trait_.path.clone())
+        })
+        .collect()
+}
+
+/// The types `impls` are for, like `Text`.
+fn impl_types(impls: &[jsondoc::ImplRef]) -> Vec<String> {
+    impls
+        .iter()
+        .map(|impl_ref| match &impl_ref.impl_.for_ {
+            rustdoc_types::Type::ResolvedPath(path) => path.path.clone(),
+            other => format!("{other:?}"),
+        })
+        .collect()
+}
+
+#[test]
+fn impls_for_lists_the_impls_of_a_type() {
+    let doc = JsonDoc::from(test_items());
+    let text = doc.find_item_by_path("test_items::Text").unwrap();
+    let impls = doc.impls_for(&text);
+
+    let active: Vec<_> = impls
+        .iter()
+        .filter(|impl_ref| impl_ref.kind.is_active())
+        .copied()
+        .collect();
+    let traits = [
+        None,
+        Some("Deref"),
+        Some("Named"),
+        Some("Introduce"),
+        Some("Shout"),
+        Some("Shade"),
+        Some("Tint"),
+    ];
+    assert_eq!(
+        impl_traits(&active),
+        traits.map(|trait_| trait_.map(String::from))
+    );
+    assert_eq!(active[0].kind, ImplKind::Inherent);
+    // Blanket impls like `Greet` and auto traits like `Send` are listed too
+    assert!(
+        impls
+            .iter()
+            .any(|impl_ref| matches!(impl_ref.kind, ImplKind::Blanket))
+    );
+    assert!(
+        impls
+            .iter()
+            .any(|impl_ref| matches!(impl_ref.kind, ImplKind::AutoTrait))
+    );
+
+    // Traits have implementors rather than impls
+    let named = doc.find_item_by_path("test_items::Named").unwrap();
+    assert!(doc.impls_for(&named).is_empty());
+}
+
+#[test]
+fn implementors_of_a_trait_of_the_crate() {
+    let doc = JsonDoc::from(test_items());
+    let introduce = doc.find_item_by_path("test_items::Introduce").unwrap();
+    let impls = doc.implementors_of(&introduce);
+    assert_eq!(impl_types(&impls), ["Text"]);
+    assert_eq!(impl_traits(&impls), [Some("Introduce".to_string())]);
+}
+
+#[test]
+fn implementors_of_a_trait_of_another_crate() {
+    let doc = JsonDoc::from(test_items());
+    let deref = doc
+        .crate_data()
+        .paths
+        .iter()
+        .find(|(_, summary)| summary.path == ["core", "ops", "deref", "Deref"])
+        .map(|(id, _)| *id)
+        .unwrap();
+    let impls = doc.implementors_of(&deref);
+    assert_eq!(impl_types(&impls), ["Text", "Styled", "Highlighted"]);
+}
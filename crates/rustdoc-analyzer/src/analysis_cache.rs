@@ -0,0 +1,117 @@
+//! Cache per-crate analysis results, so iterating on report generation
+//! doesn't redo all parsing
+
+use crate::doc_extractor::DocEntry;
+use crate::docfetch::get_cache_file;
+use crate::markdown_analyzer::MarkdownStats;
+use crate::stats::CrateStats;
+use anyhow::{Context, Result, bail};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+
+/// Version of the cached results, bumped when the analysis changes so
+/// results of older analyzers are redone
+const FORMAT: u32 = 1;
+
+/// Results of analyzing a crate version
+#[derive(Serialize, Deserialize)]
+pub struct CrateAnalysis {
+    format: u32,
+    pub stats: CrateStats,
+    pub entries: Vec<(DocEntry, MarkdownStats)>,
+}
+
+impl CrateAnalysis {
+    pub fn new(stats: CrateStats, entries: Vec<(DocEntry, MarkdownStats)>) -> Self {
+        Self {
+            format: FORMAT,
+            stats,
+            entries,
+        }
+    }
+}
+
+/// Load the cached analysis of a crate version, if there is one from this
+/// version of the analysis
+pub fn load_analysis(crate_name: &str, version: &str) -> Result<CrateAnalysis> {
+    read_analysis(&get_cache_file(crate_name, version, "analysis.json.zst")?)
+}
+
+/// Cache the analysis of a crate version
+pub fn save_analysis(crate_name: &str, version: &str, analysis: &CrateAnalysis) -> Result<()> {
+    write_analysis(
+        &get_cache_file(crate_name, version, "analysis.json.zst")?,
+        analysis,
+    )
+}
+
+fn read_analysis(path: &Path) -> Result<CrateAnalysis> {
+    let compressed = fs::read(path).context("Cache miss")?;
+    let json = zstd::decode_all(&compressed[..]).context("Failed to decompress cached analysis")?;
+    let analysis: CrateAnalysis =
+        serde_json::from_slice(&json).context("Failed to parse cached analysis")?;
+    if analysis.format != FORMAT {
+        bail!("Cached analysis is from another version of the analyzer");
+    }
+    Ok(analysis)
+}
+
+fn write_analysis(path: &Path, analysis: &CrateAnalysis) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let json = serde_json::to_vec(analysis)?;
+    let compressed = zstd::encode_all(&json[..], 3)?;
+    fs::write(path, compressed).context("Failed to save analysis to cache")?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_analyzer::analyze_markdown;
+
+    fn analysis() -> CrateAnalysis {
+        let entry = DocEntry {
+            crate_name: "demo".to_string(),
+            item_path: "demo::run".to_string(),
+            item_kind: "function".to_string(),
+            doc_string: "Run the demo.\n\n```rust\ndemo::run();\n```".to_string(),
+        };
+        let stats = analyze_markdown(&entry.doc_string);
+        let crate_stats = CrateStats {
+            docs_count: 1,
+            total_chars: entry.doc_string.len(),
+            stats: stats.clone(),
+            most_complex: Vec::new(),
+        };
+        CrateAnalysis::new(crate_stats, vec![(entry, stats)])
+    }
+
+    #[test]
+    fn test_saved_analysis_round_trips() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("demo/1.0.0.analysis.json.zst");
+        assert!(read_analysis(&path).is_err());
+
+        write_analysis(&path, &analysis()).unwrap();
+        let loaded = read_analysis(&path).unwrap();
+        assert_eq!(loaded.stats.docs_count, 1);
+        assert_eq!(loaded.stats.stats.code_blocks, 1);
+        assert_eq!(loaded.entries.len(), 1);
+        assert_eq!(loaded.entries[0].0.item_path, "demo::run");
+    }
+
+    #[test]
+    fn test_analysis_of_another_format_is_a_miss() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("demo/1.0.0.analysis.json.zst");
+        let mut old = analysis();
+        old.format = FORMAT + 1;
+        write_analysis(&path, &old).unwrap();
+
+        let err = read_analysis(&path).err().expect("format differs");
+        assert!(err.to_string().contains("another version"), "{err:#}");
+    }
+}
@@ -0,0 +1,85 @@
+//! Rank docs by how complex their markdown is, so formatter work can start
+//! with the hardest real-world inputs
+
+use crate::doc_extractor::DocEntry;
+use crate::markdown_analyzer::MarkdownStats;
+use crate::stats::feature_counts;
+use serde::{Deserialize, Serialize};
+
+/// A doc string and how complex its markdown is
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ComplexDoc {
+    pub item_path: String,
+    pub item_kind: String,
+    pub score: usize,
+    pub char_count: usize,
+    pub nesting_depth: usize,
+    /// Features used, by display name
+    pub features: Vec<String>,
+}
+
+/// Complexity of a doc string: ten points per distinct feature used, five
+/// per level of nesting and one per hundred characters
+pub fn complexity_score(stats: &MarkdownStats, char_count: usize) -> usize {
+    let features = feature_counts(stats)
+        .into_iter()
+        .filter(|(_, count)| *count > 0)
+        .count();
+    features * 10 + stats.nesting_depth_max * 5 + char_count / 100
+}
+
+/// The `count` most complex of `entries`, most complex first
+pub fn most_complex(entries: &[(DocEntry, MarkdownStats)], count: usize) -> Vec<ComplexDoc> {
+    let mut docs: Vec<ComplexDoc> = entries
+        .iter()
+        .map(|(entry, stats)| ComplexDoc {
+            item_path: entry.item_path.clone(),
+            item_kind: entry.item_kind.clone(),
+            score: complexity_score(stats, entry.doc_string.len()),
+            char_count: entry.doc_string.len(),
+            nesting_depth: stats.nesting_depth_max,
+            features: feature_counts(stats)
+                .into_iter()
+                .filter(|(_, count)| *count > 0)
+                .map(|(name, _)| name.to_string())
+                .collect(),
+        })
+        .collect();
+    docs.sort_by(|a, b| b.score.cmp(&a.score).then(a.item_path.cmp(&b.item_path)));
+    docs.truncate(count);
+    docs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::markdown_analyzer::analyze_markdown;
+
+    fn entry(item_path: &str, doc_string: &str) -> (DocEntry, MarkdownStats) {
+        let doc = DocEntry {
+            crate_name: "demo".to_string(),
+            item_path: item_path.to_string(),
+            item_kind: "function".to_string(),
+            doc_string: doc_string.to_string(),
+        };
+        (doc, analyze_markdown(doc_string))
+    }
+
+    #[test]
+    fn test_most_complex() {
+        let entries = vec![
+            entry("demo::plain", "Just text."),
+            entry(
+                "demo::nested",
+                "- one\n  - `two`\n    - [three](https://example.com)\n\n```rust\nlet x = 1;\n```",
+            ),
+            entry("demo::code", "Uses `code`."),
+        ];
+        let docs = most_complex(&entries, 2);
+        assert_eq!(docs.len(), 2);
+        assert_eq!(docs[0].item_path, "demo::nested");
+        assert_eq!(docs[1].item_path, "demo::code");
+        assert!(docs[0].features.contains(&"Nested lists".to_string()));
+        assert!(docs[0].score > docs[1].score);
+    }
+}
@@ -1,5 +1,10 @@
 //! List of crates to analyze for markdown patterns
 
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::fs;
+use std::path::Path;
+
 /// Category of a crate for analysis
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum CrateCategory {
@@ -301,6 +306,75 @@ pub static CRATES: &[CrateInfo] = &[
     },
 ];
 
+/// A crate to analyze, from the built-in list or a crates file
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CrateEntry {
+    pub name: String,
+    #[serde(default = "latest")]
+    pub version: String,
+    #[serde(default = "uncategorized")]
+    pub category: String,
+}
+
+fn latest() -> String {
+    "latest".to_string()
+}
+
+fn uncategorized() -> String {
+    "uncategorized".to_string()
+}
+
+impl From<&CrateInfo> for CrateEntry {
+    fn from(info: &CrateInfo) -> Self {
+        Self {
+            name: info.name.to_string(),
+            version: info.version.to_string(),
+            category: info.category.as_str().to_string(),
+        }
+    }
+}
+
+/// A crates file: a `[[crates]]` table per crate
+#[derive(Deserialize)]
+#[serde(deny_unknown_fields)]
+struct CratesFile {
+    crates: Vec<CrateEntry>,
+}
+
+/// The crates to analyze: the ones in `path` when given, else [`CRATES`].
+///
+/// ```toml
+/// [[crates]]
+/// name = "serde"
+/// version = "1.0.210"  # default: latest
+/// category = "parsing" # default: uncategorized
+/// ```
+pub fn load_crate_list(path: Option<&Path>) -> Result<Vec<CrateEntry>> {
+    let Some(path) = path else {
+        return Ok(CRATES.iter().map(CrateEntry::from).collect());
+    };
+    let contents = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read crates file {}", path.display()))?;
+    parse_crate_list(&contents).with_context(|| format!("Invalid crates file {}", path.display()))
+}
+
+fn parse_crate_list(contents: &str) -> Result<Vec<CrateEntry>> {
+    let file: CratesFile = toml::from_str(contents)?;
+    Ok(file.crates)
+}
+
+/// Categories of `crates`, in the order they first appear
+pub fn categories_of(crates: &[CrateEntry]) -> Vec<&str> {
+    let mut categories: Vec<&str> = Vec::new();
+    for entry in crates {
+        if !categories.contains(&entry.category.as_str()) {
+            categories.push(&entry.category);
+        }
+    }
+    categories
+}
+
 /// Get crates filtered by category
 pub fn crates_by_category(category: CrateCategory) -> impl Iterator<Item = &'static CrateInfo> {
     CRATES.iter().filter(move |c| c.category == category)
@@ -341,4 +415,32 @@ mod tests {
         assert_eq!(async_count, 5);
         assert_eq!(utilities_count, 10);
     }
+
+    #[test]
+    fn test_parse_crate_list() {
+        let crates = parse_crate_list(
+            r#"
+            [[crates]]
+            name = "serde"
+            version = "1.0.210"
+            category = "parsing"
+
+            [[crates]]
+            name = "my-crate"
+            "#,
+        )
+        .unwrap();
+        assert_eq!(crates[0].version, "1.0.210");
+        assert_eq!(crates[1].version, "latest");
+        assert_eq!(categories_of(&crates), ["parsing", "uncategorized"]);
+
+        assert!(parse_crate_list("[[crates]]\nnmae = \"serde\"\n").is_err());
+    }
+
+    #[test]
+    fn test_builtin_crate_list() {
+        let crates = load_crate_list(None).unwrap();
+        assert_eq!(crates.len(), CRATES.len());
+        assert_eq!(categories_of(&crates).len(), all_categories().count());
+    }
 }
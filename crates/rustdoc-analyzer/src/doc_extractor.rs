@@ -1,9 +1,10 @@
 //! Extract documentation strings from rustdoc JSON
 
 use rustdoc_types::{Crate, Id, Item, ItemEnum};
+use serde::{Deserialize, Serialize};
 
 /// Represents an extracted documentation string with metadata
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DocEntry {
     pub crate_name: String,
     pub item_path: String,
@@ -70,6 +70,11 @@ fn validate_path_component(value: &str, component_name: &str) -> Result<()> {
 }
 
 fn get_cache_path(crate_name: &str, version: &str) -> Result<PathBuf> {
+    get_cache_file(crate_name, version, "zst")
+}
+
+/// Path of the cached file with `extension` for a crate version
+pub(crate) fn get_cache_file(crate_name: &str, version: &str, extension: &str) -> Result<PathBuf> {
     validate_path_component(crate_name, "crate name")?;
     validate_path_component(version, "version")?;
 
@@ -80,7 +85,7 @@ fn get_cache_path(crate_name: &str, version: &str) -> Result<PathBuf> {
 
     let safe_cache_path = canonical_cache_dir
         .join(crate_name)
-        .join(format!("{}.zst", version));
+        .join(format!("{}.{}", version, extension));
 
     if !safe_cache_path.starts_with(&canonical_cache_dir) {
         bail!("Path traversal detected: resulting path escapes cache directory");
@@ -0,0 +1,351 @@
+//! Self-contained HTML report, for sharing analysis results
+
+use crate::markdown_analyzer::MarkdownStats;
+use crate::stats::{AnalysisReport, CrateStats, feature_counts, format_number, prevalence_of};
+
+/// Styles for the tables and bar charts
+const STYLE: &str = r#"
+body { font-family: system-ui, sans-serif; margin: 2em auto; max-width: 70em; padding: 0 1em; color: #222; }
+h1, h2, h3 { font-weight: 600; }
+.cards { display: flex; gap: 1em; flex-wrap: wrap; }
+.card { border: 1px solid #ddd; border-radius: 6px; padding: 0.8em 1.2em; }
+.card .value { font-size: 1.6em; font-weight: 600; }
+table { border-collapse: collapse; margin: 0.5em 0 1.5em; }
+th, td { padding: 0.3em 0.8em; text-align: left; border-bottom: 1px solid #eee; }
+th { cursor: pointer; user-select: none; background: #f6f6f6; }
+th::after { content: " \2195"; color: #aaa; }
+td.num { text-align: right; font-variant-numeric: tabular-nums; }
+td.chart { width: 20em; }
+.bar { background: #4a7bd0; height: 0.9em; border-radius: 2px; }
+details { margin: 0.4em 0; }
+summary { cursor: pointer; font-weight: 600; }
+"#;
+
+/// Sorts a table by the column of the header clicked, numbers by the
+/// `data-value` of their cells
+const SCRIPT: &str = r#"
+document.querySelectorAll("table.sortable th").forEach(th => {
+  th.addEventListener("click", () => {
+    const table = th.closest("table");
+    const body = table.tBodies[0];
+    const ascending = th.dataset.order !== "asc";
+    table.querySelectorAll("th").forEach(other => delete other.dataset.order);
+    th.dataset.order = ascending ? "asc" : "desc";
+    const key = row => {
+      const cell = row.cells[th.cellIndex];
+      return cell.dataset.value !== undefined ? parseFloat(cell.dataset.value) : cell.textContent;
+    };
+    const rows = Array.from(body.rows).sort((a, b) => {
+      const [x, y] = [key(a), key(b)];
+      const order = typeof x === "number" ? x - y : x.localeCompare(y);
+      return ascending ? order : -order;
+    });
+    rows.forEach(row => body.appendChild(row));
+  });
+});
+"#;
+
+/// Generate a self-contained HTML report from the analysis: sortable
+/// tables, bar charts of feature frequencies and a section per crate.
+pub fn generate_html_report(report: &AnalysisReport) -> String {
+    let stats = &report.stats;
+    let mut html = String::new();
+
+    html.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    html.push_str("<title>Rustdoc Markdown Analysis Report</title>\n");
+    html.push_str(&format!("<style>{STYLE}</style>\n</head>\n<body>\n"));
+    html.push_str("<h1>Rustdoc Markdown Analysis Report</h1>\n");
+    html.push_str(&format!(
+        "<p>Generated: {}</p>\n",
+        escape(&report.generated_at)
+    ));
+
+    // Summary
+    html.push_str("<div class=\"cards\">\n");
+    for (label, value) in [
+        ("Crates analyzed", stats.total_crates.to_string()),
+        ("Doc strings analyzed", stats.total_docs.to_string()),
+        (
+            "Total markdown characters",
+            format_number(stats.total_chars),
+        ),
+    ] {
+        html.push_str(&format!(
+            "<div class=\"card\"><div class=\"value\">{value}</div>{label}</div>\n"
+        ));
+    }
+    html.push_str("</div>\n");
+
+    // Feature frequency
+    html.push_str("<h2>Feature Frequency</h2>\n");
+    let features = feature_counts(&stats.aggregate);
+    let rows = features
+        .iter()
+        .map(|(name, count)| {
+            let prevalence = prevalence_of(report, name);
+            vec![
+                Cell::Text(name.to_string()),
+                Cell::Number(*count as f64, format_number(*count)),
+                Cell::Number(prevalence, format!("{:.1}%", prevalence * 100.0)),
+            ]
+        })
+        .collect();
+    push_chart_table(&mut html, &["Feature", "Count", "Est. Prevalence"], rows, 1);
+
+    push_language_table(&mut html, &stats.aggregate);
+
+    // Link types
+    html.push_str("<h2>Link Types</h2>\n");
+    let link_types = &stats.aggregate.link_types;
+    let rows = [
+        ("Intra-doc", link_types.intra_doc),
+        ("Internal (.html)", link_types.internal_doc),
+        ("External (http/https)", link_types.external_http),
+        ("Anchor (#)", link_types.anchor),
+    ]
+    .into_iter()
+    .map(|(name, count)| {
+        vec![
+            Cell::Text(name.to_string()),
+            Cell::Number(count as f64, count.to_string()),
+            percent_of(count, stats.aggregate.links),
+        ]
+    })
+    .collect();
+    push_chart_table(&mut html, &["Type", "Count", "% of links"], rows, 1);
+
+    // Crates, with a link to the section of each
+    html.push_str("<h2>Crates</h2>\n");
+    let mut crates: Vec<(&String, &CrateStats)> = stats.per_crate.iter().collect();
+    crates.sort_by(|a, b| a.0.cmp(b.0));
+    html.push_str("<table class=\"sortable\">\n<thead><tr>");
+    for header in [
+        "Crate",
+        "Docs",
+        "Characters",
+        "Code blocks",
+        "Inline code",
+        "Links",
+        "Lists",
+        "Tables",
+        "Headings",
+    ] {
+        html.push_str(&format!("<th>{header}</th>"));
+    }
+    html.push_str("</tr></thead>\n<tbody>\n");
+    for (name, crate_stats) in &crates {
+        let md = &crate_stats.stats;
+        html.push_str(&format!(
+            "<tr><td><a href=\"#crate-{id}\">{name}</a></td>",
+            id = anchor(name),
+            name = escape(name)
+        ));
+        for count in [
+            crate_stats.docs_count,
+            crate_stats.total_chars,
+            md.code_blocks,
+            md.inline_code,
+            md.links,
+            md.lists,
+            md.tables,
+            md.headings,
+        ] {
+            html.push_str(&format!(
+                "<td class=\"num\" data-value=\"{count}\">{count}</td>"
+            ));
+        }
+        html.push_str("</tr>\n");
+    }
+    html.push_str("</tbody>\n</table>\n");
+
+    // Drill-down per crate
+    html.push_str("<h2>Per Crate</h2>\n");
+    for (name, crate_stats) in &crates {
+        html.push_str(&format!(
+            "<details id=\"crate-{}\">\n<summary>{} ({} docs)</summary>\n",
+            anchor(name),
+            escape(name),
+            crate_stats.docs_count
+        ));
+        let rows = feature_counts(&crate_stats.stats)
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(feature, count)| {
+                vec![
+                    Cell::Text(feature.to_string()),
+                    Cell::Number(count as f64, count.to_string()),
+                    Cell::Number(
+                        per_doc(count, crate_stats.docs_count),
+                        format!("{:.2}", per_doc(count, crate_stats.docs_count)),
+                    ),
+                ]
+            })
+            .collect();
+        push_chart_table(&mut html, &["Feature", "Count", "Per doc"], rows, 1);
+        push_language_table(&mut html, &crate_stats.stats);
+        if !crate_stats.most_complex.is_empty() {
+            html.push_str("<h3>Most Complex Docs</h3>\n");
+            let rows = crate_stats
+                .most_complex
+                .iter()
+                .map(|doc| {
+                    vec![
+                        Cell::Text(doc.item_path.clone()),
+                        Cell::Number(doc.score as f64, doc.score.to_string()),
+                        Cell::Number(doc.char_count as f64, doc.char_count.to_string()),
+                        Cell::Text(doc.features.join(", ")),
+                    ]
+                })
+                .collect();
+            push_chart_table(&mut html, &["Item", "Score", "Chars", "Features"], rows, 1);
+        }
+        html.push_str("</details>\n");
+    }
+
+    html.push_str(&format!("<script>{SCRIPT}</script>\n</body>\n</html>\n"));
+    html
+}
+
+/// A table cell: text, or a number sorted by its value and shown as text.
+enum Cell {
+    Text(String),
+    Number(f64, String),
+}
+
+/// Add a sortable table of `rows`, with a bar chart of the numbers in
+/// column `chart_column` in a last column.
+fn push_chart_table(
+    html: &mut String,
+    headers: &[&str],
+    rows: Vec<Vec<Cell>>,
+    chart_column: usize,
+) {
+    if rows.is_empty() {
+        html.push_str("<p>None.</p>\n");
+        return;
+    }
+    let max = rows
+        .iter()
+        .filter_map(|row| match row.get(chart_column) {
+            Some(Cell::Number(value, _)) => Some(*value),
+            _ => None,
+        })
+        .fold(0.0, f64::max);
+
+    html.push_str("<table class=\"sortable\">\n<thead><tr>");
+    for header in headers {
+        html.push_str(&format!("<th>{}</th>", escape(header)));
+    }
+    html.push_str("<th>Chart</th></tr></thead>\n<tbody>\n");
+    for row in rows {
+        html.push_str("<tr>");
+        let mut width = 0.0;
+        for (column, cell) in row.iter().enumerate() {
+            match cell {
+                Cell::Text(text) => html.push_str(&format!("<td>{}</td>", escape(text))),
+                Cell::Number(value, text) => {
+                    if column == chart_column && max > 0.0 {
+                        width = value / max * 100.0;
+                    }
+                    html.push_str(&format!(
+                        "<td class=\"num\" data-value=\"{value}\">{}</td>",
+                        escape(text)
+                    ));
+                }
+            }
+        }
+        html.push_str(&format!(
+            "<td class=\"chart\" data-value=\"{width:.1}\"><div class=\"bar\" style=\"width: {width:.1}%\"></div></td></tr>\n"
+        ));
+    }
+    html.push_str("</tbody>\n</table>\n");
+}
+
+/// Add the table of the ten most used code block languages in `stats`.
+fn push_language_table(html: &mut String, stats: &MarkdownStats) {
+    html.push_str("<h3>Code Block Languages</h3>\n");
+    let mut languages: Vec<_> = stats.code_block_languages.iter().collect();
+    languages.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+    let rows = languages
+        .into_iter()
+        .take(10)
+        .map(|(language, count)| {
+            vec![
+                Cell::Text(language.clone()),
+                Cell::Number(*count as f64, count.to_string()),
+                percent_of(*count, stats.code_blocks),
+            ]
+        })
+        .collect();
+    push_chart_table(html, &["Language", "Count", "% of code blocks"], rows, 1);
+}
+
+/// `count` as a percentage of `total`.
+fn percent_of(count: usize, total: usize) -> Cell {
+    let percent = count as f64 / total.max(1) as f64 * 100.0;
+    Cell::Number(percent, format!("{percent:.1}%"))
+}
+
+/// Average of `count` over `docs` doc strings.
+fn per_doc(count: usize, docs: usize) -> f64 {
+    count as f64 / docs.max(1) as f64
+}
+
+/// `id` attribute for the section of a crate.
+fn anchor(name: &str) -> String {
+    name.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .collect()
+}
+
+/// Escape text for HTML content and attribute values.
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::AggregateStats;
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(
+            escape("<a href=\"x\">&</a>"),
+            "&lt;a href=&quot;x&quot;&gt;&amp;&lt;/a&gt;"
+        );
+    }
+
+    #[test]
+    fn test_report_has_a_section_per_crate() {
+        let mut crate_stats = CrateStats {
+            docs_count: 4,
+            total_chars: 200,
+            ..CrateStats::default()
+        };
+        crate_stats.stats.code_blocks = 2;
+        crate_stats
+            .stats
+            .code_block_languages
+            .insert("rust".to_string(), 2);
+        let mut stats = AggregateStats {
+            total_crates: 1,
+            total_docs: 4,
+            total_chars: 200,
+            ..AggregateStats::default()
+        };
+        stats.aggregate.merge(&crate_stats.stats);
+        stats
+            .per_crate
+            .insert("serde_json".to_string(), crate_stats);
+
+        let html = generate_html_report(&AnalysisReport::new(stats));
+        assert!(html.contains("<a href=\"#crate-serde-json\">serde_json</a>"));
+        assert!(html.contains("<details id=\"crate-serde-json\">"));
+        assert!(html.contains("<td>Code blocks</td><td class=\"num\" data-value=\"2\">2</td>"));
+        assert!(html.contains("style=\"width: 100.0%\""));
+    }
+}
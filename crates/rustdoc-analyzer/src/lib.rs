@@ -1,15 +1,34 @@
+mod analysis_cache;
+mod complexity;
 mod corpus;
 pub mod crate_list;
 mod doc_extractor;
 mod docfetch;
+mod html_report;
+mod local;
 mod markdown_analyzer;
+mod render_check;
 mod stats;
+mod stats_diff;
+mod top_crates;
 
+pub use analysis_cache::{CrateAnalysis, load_analysis, save_analysis};
+pub use complexity::{ComplexDoc, complexity_score, most_complex};
 pub use corpus::{CorpusSnippet, SnippetCategory, SnippetSelector, TestCorpus};
-pub use crate_list::{CRATES, CrateCategory, CrateInfo, all_categories, crates_by_category};
+pub use crate_list::{
+    CRATES, CrateCategory, CrateEntry, CrateInfo, all_categories, categories_of,
+    crates_by_category, load_crate_list,
+};
 pub use doc_extractor::{DocEntry, extract_docs};
 pub use docfetch::{clear_cache, fetch_docs};
+pub use html_report::generate_html_report;
+pub use local::{LocalPackage, build_local_docs, local_packages};
 pub use markdown_analyzer::{LinkTypeStats, MarkdownStats, analyze_markdown};
+pub use render_check::{
+    RenderIssue, SnippetCheck, check_snippet, generate_validation_report, validate_corpus,
+};
 pub use stats::{
     AggregateStats, AnalysisReport, CrateStats, chrono_lite_now, generate_markdown_report,
 };
+pub use stats_diff::generate_diff_report;
+pub use top_crates::top_crates;
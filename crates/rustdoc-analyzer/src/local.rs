@@ -0,0 +1,101 @@
+//! Build the rustdoc JSON of the crates in a local workspace, to analyze
+//! a project's own documentation
+
+use anyhow::{Context, Result, bail};
+use cargo_metadata::MetadataCommand;
+use rustdoc_types::Crate;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+/// A workspace member with a library target, whose docs can be built
+#[derive(Debug, Clone)]
+pub struct LocalPackage {
+    pub name: String,
+    pub version: String,
+    manifest_path: PathBuf,
+    /// Where rustdoc writes the JSON of the library
+    doc_path: PathBuf,
+}
+
+/// The members of the workspace of `manifest_path`, or of the current
+/// directory, with a library target
+pub fn local_packages(manifest_path: Option<&Path>) -> Result<Vec<LocalPackage>> {
+    let mut command = MetadataCommand::new();
+    command.no_deps();
+    if let Some(path) = manifest_path {
+        command.manifest_path(path);
+    }
+    let metadata = command
+        .exec()
+        .map_err(|e| anyhow::anyhow!("Failed to execute cargo metadata: {e}"))?;
+
+    let doc_dir: PathBuf = metadata.target_directory.join("doc").into();
+    let mut packages: Vec<LocalPackage> = metadata
+        .workspace_packages()
+        .into_iter()
+        .filter_map(|package| {
+            let lib = package
+                .targets
+                .iter()
+                .find(|target| target.is_lib() || target.is_proc_macro())?;
+            Some(LocalPackage {
+                name: package.name.to_string(),
+                version: package.version.to_string(),
+                manifest_path: package.manifest_path.clone().into(),
+                doc_path: doc_dir.join(format!("{}.json", lib.name.replace('-', "_"))),
+            })
+        })
+        .collect();
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+/// Build the rustdoc JSON of `package` with the nightly toolchain, and load it
+pub fn build_local_docs(package: &LocalPackage) -> Result<Crate> {
+    let output = Command::new("cargo")
+        .args(["+nightly", "doc", "--no-deps", "--lib", "-p", &package.name])
+        .arg("--manifest-path")
+        .arg(&package.manifest_path)
+        .env("RUSTDOCFLAGS", "-Z unstable-options --output-format=json")
+        .output()
+        .context("Failed to run cargo")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        if stderr.contains("toolchain 'nightly'") || stderr.contains("no such command: `+nightly`")
+        {
+            bail!(
+                "Nightly toolchain required to build rustdoc JSON.\n\
+                 Install with: rustup toolchain install nightly"
+            );
+        }
+        bail!("Failed to build documentation:\n{}", stderr);
+    }
+
+    let json = fs::read(&package.doc_path)
+        .with_context(|| format!("Failed to read {}", package.doc_path.display()))?;
+    serde_json::from_slice(&json).context("Failed to parse rustdoc JSON")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_packages_of_this_workspace() {
+        let manifest = Path::new(env!("CARGO_MANIFEST_DIR")).join("../../Cargo.toml");
+        let packages = local_packages(Some(&manifest)).unwrap();
+        let names: Vec<&str> = packages.iter().map(|p| p.name.as_str()).collect();
+        assert!(names.is_sorted(), "{names:?}");
+        // Binary-only members have no library docs to build
+        assert!(!names.contains(&"cargo-docsrs"), "{names:?}");
+
+        let test_items = packages
+            .iter()
+            .find(|p| p.name == "test-items")
+            .expect("test-items is a workspace member");
+        assert_eq!(test_items.version, "0.1.0");
+        assert!(test_items.manifest_path.ends_with("test-items/Cargo.toml"));
+        assert!(test_items.doc_path.ends_with("doc/test_items.json"));
+    }
+}
@@ -3,9 +3,13 @@
 use anyhow::{Context, Result};
 use clap::{Parser, Subcommand};
 use rustdoc_analyzer::{
-    AggregateStats, AnalysisReport, CRATES, CrateStats, SnippetSelector, TestCorpus,
-    analyze_markdown, extract_docs, fetch_docs, generate_markdown_report,
+    AggregateStats, AnalysisReport, CrateAnalysis, CrateEntry, CrateStats, DocEntry, MarkdownStats,
+    SnippetSelector, TestCorpus, analyze_markdown, build_local_docs, categories_of, extract_docs,
+    fetch_docs, generate_diff_report, generate_html_report, generate_markdown_report,
+    generate_validation_report, load_analysis, load_crate_list, local_packages, most_complex,
+    save_analysis, top_crates, validate_corpus,
 };
+use rustdoc_types::Crate;
 use std::fs;
 use std::path::{Path, PathBuf};
 
@@ -19,7 +23,8 @@ struct Cli {
 
 #[derive(Subcommand)]
 enum Commands {
-    /// Analyze all configured crates
+    /// Analyze all configured crates, writing JSON, markdown and HTML
+    /// reports
     Analyze {
         /// Output directory for results
         #[arg(short, long, default_value = "target/rustdoc-analysis")]
@@ -28,6 +33,46 @@ enum Commands {
         /// Skip crate download cache
         #[arg(long)]
         no_cache: bool,
+
+        /// Analyze crates again instead of reusing the cached results of
+        /// earlier runs, e.g. after changing the analysis
+        #[arg(long)]
+        reanalyze: bool,
+
+        /// TOML file listing the crates to analyze, instead of the built-in
+        /// list
+        #[arg(long, value_name = "FILE", conflicts_with = "top")]
+        crates_file: Option<PathBuf>,
+
+        /// Analyze the N most downloaded crates on crates.io instead of the
+        /// built-in list
+        #[arg(long, value_name = "N")]
+        top: Option<usize>,
+
+        /// Only take the --top crates from this crates.io category, e.g.
+        /// `parser-implementations`
+        #[arg(long, value_name = "SLUG", requires = "top")]
+        category: Option<String>,
+
+        /// How many of the most complex docs of each crate to report
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        most_complex: usize,
+    },
+    /// Analyze the library crates of a local workspace, building their
+    /// rustdoc JSON with the nightly toolchain
+    Local {
+        /// Cargo.toml of the workspace (default: found from the current
+        /// directory)
+        #[arg(long, value_name = "PATH")]
+        manifest_path: Option<PathBuf>,
+
+        /// Output directory for results
+        #[arg(short, long, default_value = "target/rustdoc-analysis/local")]
+        output: PathBuf,
+
+        /// How many of the most complex docs of each crate to report
+        #[arg(long, value_name = "N", default_value_t = 10)]
+        most_complex: usize,
     },
     /// Analyze a single crate
     Single {
@@ -38,67 +83,199 @@ enum Commands {
         #[arg(short, long, default_value = "latest")]
         version: String,
     },
+    /// Compare the stats.json of two analysis runs
+    Diff {
+        /// stats.json of the earlier run
+        old: PathBuf,
+
+        /// stats.json of the later run
+        new: PathBuf,
+
+        /// Write the markdown report to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+    /// Render every corpus snippet with rustdoc-fmt, reporting panics and
+    /// content lost in the output
+    Validate {
+        /// Corpus index written by `analyze`
+        #[arg(long, default_value = "target/rustdoc-analysis/corpus/index.json")]
+        corpus: PathBuf,
+
+        /// Where to write the markdown report
+        #[arg(short, long, default_value = "target/rustdoc-analysis/validation.md")]
+        output: PathBuf,
+    },
     /// Clear the download cache
     ClearCache,
     /// List configured crates
-    List,
+    List {
+        /// TOML file listing the crates to analyze, instead of the built-in
+        /// list
+        #[arg(long, value_name = "FILE")]
+        crates_file: Option<PathBuf>,
+    },
 }
 
 fn main() -> Result<()> {
     let cli = Cli::parse();
 
     match cli.command {
-        Commands::Analyze { output, no_cache } => {
-            run_full_analysis(&output, !no_cache)?;
+        Commands::Analyze {
+            output,
+            no_cache,
+            reanalyze,
+            crates_file,
+            top,
+            category,
+            most_complex,
+        } => {
+            let crates = match top {
+                Some(count) => top_crates(count, category.as_deref())?,
+                None => load_crate_list(crates_file.as_deref())?,
+            };
+            let reuse_results = !no_cache && !reanalyze;
+            run_full_analysis(&crates, &output, !no_cache, reuse_results, most_complex)?;
+        }
+        Commands::Local {
+            manifest_path,
+            output,
+            most_complex,
+        } => {
+            run_local_analysis(manifest_path.as_deref(), &output, most_complex)?;
         }
         Commands::Single { name, version } => {
             run_single_analysis(&name, &version)?;
         }
+        Commands::Diff { old, new, output } => {
+            let report = generate_diff_report(&load_report(&old)?, &load_report(&new)?);
+            match output {
+                Some(path) => {
+                    fs::write(&path, report)?;
+                    println!("Wrote: {}", path.display());
+                }
+                None => print!("{}", report),
+            }
+        }
+        Commands::Validate { corpus, output } => {
+            run_validation(&corpus, &output)?;
+        }
         Commands::ClearCache => {
             rustdoc_analyzer::clear_cache()?;
         }
-        Commands::List => {
-            list_crates();
+        Commands::List { crates_file } => {
+            list_crates(&load_crate_list(crates_file.as_deref())?);
         }
     }
 
     Ok(())
 }
 
-fn run_full_analysis(output_dir: &PathBuf, use_cache: bool) -> Result<()> {
-    // Create output directory
-    fs::create_dir_all(output_dir)?;
-    fs::create_dir_all(output_dir.join("corpus"))?;
-
+fn run_full_analysis(
+    crates: &[CrateEntry],
+    output_dir: &Path,
+    use_cache: bool,
+    reuse_results: bool,
+    complex_count: usize,
+) -> Result<()> {
     let mut stats = AggregateStats::default();
-    let mut all_entries: Vec<(rustdoc_analyzer::DocEntry, rustdoc_analyzer::MarkdownStats)> =
-        Vec::new();
+    let mut all_entries = Vec::new();
 
-    let total_crates = CRATES.len();
+    let total_crates = crates.len();
 
-    for (i, crate_info) in CRATES.iter().enumerate() {
+    for (i, crate_info) in crates.iter().enumerate() {
         println!(
             "[{}/{}] Analyzing {} ({})",
             i + 1,
             total_crates,
             crate_info.name,
-            crate_info.category.as_str()
+            crate_info.category
         );
 
-        match analyze_crate(crate_info.name, crate_info.version, use_cache) {
-            Ok((crate_stats, entries)) => {
-                stats.total_crates += 1;
-                stats.total_docs += crate_stats.docs_count;
-                stats.total_chars += crate_stats.total_chars;
-                stats.aggregate.merge(&crate_stats.stats);
-                stats
-                    .per_crate
-                    .insert(crate_info.name.to_string(), crate_stats);
-                all_entries.extend(entries);
+        let analysis = if reuse_results {
+            load_analysis(&crate_info.name, &crate_info.version).ok()
+        } else {
+            None
+        };
+        let cached = analysis.is_some();
+        let analysis = match analysis {
+            Some(analysis) => Ok(analysis),
+            None => analyze_crate(&crate_info.name, &crate_info.version, use_cache).map(
+                |(crate_stats, entries)| {
+                    let analysis = CrateAnalysis::new(crate_stats, entries);
+                    if use_cache
+                        && let Err(e) =
+                            save_analysis(&crate_info.name, &crate_info.version, &analysis)
+                    {
+                        eprintln!("  Warning: Failed to cache analysis: {}", e);
+                    }
+                    analysis
+                },
+            ),
+        };
+
+        match analysis {
+            Ok(CrateAnalysis {
+                stats: crate_stats,
+                entries,
+                ..
+            }) => {
+                let docs_count = crate_stats.docs_count;
+                add_crate(
+                    &mut stats,
+                    &mut all_entries,
+                    &crate_info.name,
+                    (crate_stats, entries),
+                    complex_count,
+                );
                 println!(
-                    "  ✓ {} docs analyzed",
-                    stats.per_crate[crate_info.name].docs_count
+                    "  ✓ {} docs analyzed{}",
+                    docs_count,
+                    if cached { " (cached)" } else { "" }
+                );
+            }
+            Err(e) => {
+                eprintln!("  ✗ Failed: {}", e);
+            }
+        }
+    }
+
+    write_reports(output_dir, stats, &all_entries, total_crates)
+}
+
+/// Analyze the library crates of a local workspace, building their docs
+fn run_local_analysis(
+    manifest_path: Option<&Path>,
+    output_dir: &Path,
+    complex_count: usize,
+) -> Result<()> {
+    let packages = local_packages(manifest_path)?;
+    if packages.is_empty() {
+        anyhow::bail!("The workspace has no library crates to analyze");
+    }
+
+    let mut stats = AggregateStats::default();
+    let mut all_entries = Vec::new();
+
+    for (i, package) in packages.iter().enumerate() {
+        println!(
+            "[{}/{}] Analyzing {} {}",
+            i + 1,
+            packages.len(),
+            package.name,
+            package.version
+        );
+        match build_local_docs(package).map(|krate| analyze_docs(&krate, &package.name)) {
+            Ok(analysis) => {
+                let docs_count = analysis.0.docs_count;
+                add_crate(
+                    &mut stats,
+                    &mut all_entries,
+                    &package.name,
+                    analysis,
+                    complex_count,
                 );
+                println!("  ✓ {} docs analyzed", docs_count);
             }
             Err(e) => {
                 eprintln!("  ✗ Failed: {}", e);
@@ -106,6 +283,37 @@ fn run_full_analysis(output_dir: &PathBuf, use_cache: bool) -> Result<()> {
         }
     }
 
+    write_reports(output_dir, stats, &all_entries, packages.len())
+}
+
+/// Add the analysis of crate `name` to `stats` and `all_entries`
+fn add_crate(
+    stats: &mut AggregateStats,
+    all_entries: &mut Vec<(DocEntry, MarkdownStats)>,
+    name: &str,
+    (mut crate_stats, entries): (CrateStats, Vec<(DocEntry, MarkdownStats)>),
+    complex_count: usize,
+) {
+    crate_stats.most_complex = most_complex(&entries, complex_count);
+    stats.total_crates += 1;
+    stats.total_docs += crate_stats.docs_count;
+    stats.total_chars += crate_stats.total_chars;
+    stats.aggregate.merge(&crate_stats.stats);
+    stats.per_crate.insert(name.to_string(), crate_stats);
+    all_entries.extend(entries);
+}
+
+/// Write the JSON, markdown and HTML reports and the corpus to `output_dir`
+fn write_reports(
+    output_dir: &Path,
+    stats: AggregateStats,
+    all_entries: &[(DocEntry, MarkdownStats)],
+    total_crates: usize,
+) -> Result<()> {
+    // Create output directory
+    fs::create_dir_all(output_dir)?;
+    fs::create_dir_all(output_dir.join("corpus"))?;
+
     println!("\n=== Analysis Complete ===");
     println!("Crates analyzed: {}/{}", stats.total_crates, total_crates);
     println!("Total docs: {}", stats.total_docs);
@@ -125,9 +333,14 @@ fn run_full_analysis(output_dir: &PathBuf, use_cache: bool) -> Result<()> {
     fs::write(&md_path, &md)?;
     println!("Wrote: {}", md_path.display());
 
+    // Write HTML report
+    let html_path = output_dir.join("stats.html");
+    fs::write(&html_path, generate_html_report(&report))?;
+    println!("Wrote: {}", html_path.display());
+
     // Generate corpus
     let selector = SnippetSelector::new();
-    let corpus = selector.select(&all_entries);
+    let corpus = selector.select(all_entries);
 
     // Write corpus index
     let corpus_path = output_dir.join("corpus").join("index.json");
@@ -143,18 +356,54 @@ fn run_full_analysis(output_dir: &PathBuf, use_cache: bool) -> Result<()> {
     Ok(())
 }
 
+fn run_validation(corpus_path: &Path, output: &Path) -> Result<()> {
+    let json = fs::read_to_string(corpus_path)
+        .with_context(|| format!("Failed to read {}", corpus_path.display()))?;
+    let corpus: TestCorpus = serde_json::from_str(&json)
+        .with_context(|| format!("Failed to parse {}", corpus_path.display()))?;
+
+    let checks = validate_corpus(&corpus);
+    let failed = checks
+        .iter()
+        .filter(|check| !check.issues.is_empty())
+        .count();
+    for check in checks.iter().filter(|check| !check.issues.is_empty()) {
+        let kinds: Vec<&str> = check.issues.iter().map(|issue| issue.kind()).collect();
+        println!("  ✗ {}: {}", check.id, kinds.join(", "));
+    }
+
+    if let Some(dir) = output.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::write(output, generate_validation_report(&corpus, &checks))?;
+    println!("Wrote: {}", output.display());
+
+    if failed > 0 {
+        anyhow::bail!("{} of {} snippets failed validation", failed, checks.len());
+    }
+    println!("All {} snippets rendered", checks.len());
+    Ok(())
+}
+
+fn load_report(path: &Path) -> Result<AnalysisReport> {
+    let json =
+        fs::read_to_string(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&json).with_context(|| format!("Failed to parse {}", path.display()))
+}
+
 fn analyze_crate(
     name: &str,
     version: &str,
     use_cache: bool,
-) -> Result<(
-    CrateStats,
-    Vec<(rustdoc_analyzer::DocEntry, rustdoc_analyzer::MarkdownStats)>,
-)> {
+) -> Result<(CrateStats, Vec<(DocEntry, MarkdownStats)>)> {
     let krate = fetch_docs(name, version, use_cache)
         .with_context(|| format!("Failed to fetch docs for {}", name))?;
 
-    let docs = extract_docs(&krate, name);
+    Ok(analyze_docs(&krate, name))
+}
+
+fn analyze_docs(krate: &Crate, name: &str) -> (CrateStats, Vec<(DocEntry, MarkdownStats)>) {
+    let docs = extract_docs(krate, name);
 
     let mut crate_stats = CrateStats::default();
     let mut entries = Vec::new();
@@ -167,13 +416,13 @@ fn analyze_crate(
         entries.push((doc, md_stats));
     }
 
-    Ok((crate_stats, entries))
+    (crate_stats, entries)
 }
 
 fn run_single_analysis(name: &str, version: &str) -> Result<()> {
     println!("Analyzing {} @ {}", name, version);
 
-    let (crate_stats, _entries) = analyze_crate(name, version, true)?;
+    let (crate_stats, entries) = analyze_crate(name, version, true)?;
 
     println!("\n=== {} ===", name);
     println!("Docs: {}", crate_stats.docs_count);
@@ -195,6 +444,19 @@ fn run_single_analysis(name: &str, version: &str) -> Result<()> {
         }
     }
 
+    let complex = most_complex(&entries, 5);
+    if !complex.is_empty() {
+        println!("\nMost complex docs:");
+        for doc in complex {
+            println!(
+                "  {} ({}): {}",
+                doc.item_path,
+                doc.score,
+                doc.features.join(", ")
+            );
+        }
+    }
+
     Ok(())
 }
 
@@ -240,18 +502,16 @@ fn sanitize_filename(name: &str) -> String {
         .collect()
 }
 
-fn list_crates() {
-    use rustdoc_analyzer::{all_categories, crates_by_category};
-
+fn list_crates(crates: &[CrateEntry]) {
     println!("Configured crates for analysis:\n");
 
-    for category in all_categories() {
-        println!("{}:", category.as_str());
-        for crate_info in crates_by_category(category) {
+    for category in categories_of(crates) {
+        println!("{}:", category);
+        for crate_info in crates.iter().filter(|c| c.category == category) {
             println!("  - {} @ {}", crate_info.name, crate_info.version);
         }
         println!();
     }
 
-    println!("Total: {} crates", CRATES.len());
+    println!("Total: {} crates", crates.len());
 }
@@ -0,0 +1,261 @@
+//! Render-through validation of the corpus: every snippet goes through
+//! `rustdoc_fmt::format_markdown`, and whatever didn't survive is flagged
+
+use crate::corpus::{CorpusSnippet, SnippetCategory, TestCorpus};
+use crate::stats::chrono_lite_now;
+use pulldown_cmark::{Event, Options, Parser, Tag, TagEnd};
+use rustdoc_fmt::{DefaultLinkResolver, format_markdown, strip_ansi};
+use std::collections::HashSet;
+use std::panic::{self, AssertUnwindSafe};
+
+/// A problem with how a snippet rendered
+#[derive(Debug, Clone, PartialEq)]
+pub enum RenderIssue {
+    /// The formatter panicked, with this message
+    Panic(String),
+    /// Nothing came out for markdown with text in it
+    EmptyOutput,
+    /// Words from table cells are missing from the output
+    DroppedTable(Vec<String>),
+    /// Words from the text are missing from the output
+    LostContent(Vec<String>),
+}
+
+impl RenderIssue {
+    pub fn kind(&self) -> &'static str {
+        match self {
+            RenderIssue::Panic(_) => "panic",
+            RenderIssue::EmptyOutput => "empty output",
+            RenderIssue::DroppedTable(_) => "dropped table",
+            RenderIssue::LostContent(_) => "lost content",
+        }
+    }
+
+    fn details(&self) -> String {
+        match self {
+            RenderIssue::Panic(message) => message.clone(),
+            RenderIssue::EmptyOutput => String::new(),
+            RenderIssue::DroppedTable(words) | RenderIssue::LostContent(words) => {
+                let shown: Vec<&str> = words.iter().take(8).map(String::as_str).collect();
+                let more = words.len().saturating_sub(shown.len());
+                if more > 0 {
+                    format!("missing {} and {more} more", shown.join(", "))
+                } else {
+                    format!("missing {}", shown.join(", "))
+                }
+            }
+        }
+    }
+}
+
+/// The result of rendering one snippet
+#[derive(Debug, Clone)]
+pub struct SnippetCheck {
+    pub id: String,
+    pub category: SnippetCategory,
+    pub issues: Vec<RenderIssue>,
+}
+
+/// Render every snippet of `corpus`, silencing the panic messages of the
+/// ones that panic
+pub fn validate_corpus(corpus: &TestCorpus) -> Vec<SnippetCheck> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let checks = corpus
+        .snippets
+        .iter()
+        .map(|snippet| SnippetCheck {
+            id: snippet.id.clone(),
+            category: snippet.category,
+            issues: check_snippet(&snippet.markdown),
+        })
+        .collect();
+    panic::set_hook(hook);
+    checks
+}
+
+/// Render `markdown` and compare the words in the output with those in its
+/// text. Code blocks aren't compared, as rustdoc hides some of their lines.
+pub fn check_snippet(markdown: &str) -> Vec<RenderIssue> {
+    let rendered = match panic::catch_unwind(AssertUnwindSafe(|| {
+        format_markdown(markdown, &DefaultLinkResolver)
+    })) {
+        Ok(rendered) => strip_ansi(&rendered),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|message| message.to_string())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".to_string());
+            return vec![RenderIssue::Panic(message)];
+        }
+    };
+
+    let (text_words, table_words) = source_words(markdown);
+    if rendered.trim().is_empty() {
+        return if text_words.is_empty() && table_words.is_empty() {
+            Vec::new()
+        } else {
+            vec![RenderIssue::EmptyOutput]
+        };
+    }
+
+    let rendered_words: HashSet<&str> = words(&rendered).collect();
+    let missing = |source: Vec<String>| -> Vec<String> {
+        let mut seen = HashSet::new();
+        source
+            .into_iter()
+            .filter(|word| !rendered_words.contains(word.as_str()) && seen.insert(word.clone()))
+            .collect()
+    };
+    let mut issues = Vec::new();
+    let dropped = missing(table_words);
+    if !dropped.is_empty() {
+        issues.push(RenderIssue::DroppedTable(dropped));
+    }
+    let lost = missing(text_words);
+    if !lost.is_empty() {
+        issues.push(RenderIssue::LostContent(lost));
+    }
+    issues
+}
+
+/// Words of the text and inline code of `markdown`, outside of tables and
+/// in table cells
+fn source_words(markdown: &str) -> (Vec<String>, Vec<String>) {
+    let mut options = Options::empty();
+    options.insert(Options::ENABLE_TABLES);
+    options.insert(Options::ENABLE_STRIKETHROUGH);
+
+    let mut text_words = Vec::new();
+    let mut table_words = Vec::new();
+    let mut in_table = false;
+    let mut in_code_block = false;
+    for event in Parser::new_ext(markdown, options) {
+        match event {
+            Event::Start(Tag::Table(_)) => in_table = true,
+            Event::End(TagEnd::Table) => in_table = false,
+            Event::Start(Tag::CodeBlock(_)) => in_code_block = true,
+            Event::End(TagEnd::CodeBlock) => in_code_block = false,
+            Event::Text(text) | Event::Code(text) if !in_code_block => {
+                let target = if in_table {
+                    &mut table_words
+                } else {
+                    &mut text_words
+                };
+                target.extend(words(&text).map(str::to_string));
+            }
+            _ => {}
+        }
+    }
+    (text_words, table_words)
+}
+
+fn words(text: &str) -> impl Iterator<Item = &str> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+}
+
+/// Generate a markdown report of the checks: coverage per category, then
+/// the snippets with issues
+pub fn generate_validation_report(corpus: &TestCorpus, checks: &[SnippetCheck]) -> String {
+    let mut md = String::new();
+
+    md.push_str("# Rustdoc-fmt Corpus Validation\n\n");
+    md.push_str(&format!("Generated: {}\n\n", chrono_lite_now()));
+
+    let passed = checks
+        .iter()
+        .filter(|check| check.issues.is_empty())
+        .count();
+    md.push_str("## Summary\n\n");
+    md.push_str(&format!("- **Snippets rendered**: {}\n", checks.len()));
+    md.push_str(&format!(
+        "- **Passed**: {} ({:.1}%)\n\n",
+        passed,
+        passed as f64 / checks.len().max(1) as f64 * 100.0
+    ));
+
+    md.push_str("## Coverage by Category\n\n");
+    md.push_str(
+        "| Category | Snippets | Passed | Panics | Empty | Dropped tables | Lost content |\n",
+    );
+    md.push_str(
+        "|----------|----------|--------|--------|-------|----------------|--------------|\n",
+    );
+    for category in SnippetCategory::all() {
+        let in_category: Vec<&SnippetCheck> = checks
+            .iter()
+            .filter(|check| check.category == *category)
+            .collect();
+        if in_category.is_empty() {
+            continue;
+        }
+        let with = |kind: &str| {
+            in_category
+                .iter()
+                .filter(|check| check.issues.iter().any(|issue| issue.kind() == kind))
+                .count()
+        };
+        md.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} |\n",
+            category.as_str(),
+            in_category.len(),
+            in_category
+                .iter()
+                .filter(|check| check.issues.is_empty())
+                .count(),
+            with("panic"),
+            with("empty output"),
+            with("dropped table"),
+            with("lost content"),
+        ));
+    }
+
+    let failures: Vec<&SnippetCheck> = checks
+        .iter()
+        .filter(|check| !check.issues.is_empty())
+        .collect();
+    if !failures.is_empty() {
+        md.push_str("\n## Issues\n\n");
+        for check in failures {
+            let source = corpus
+                .snippets
+                .iter()
+                .find(|snippet| snippet.id == check.id)
+                .map(|snippet: &CorpusSnippet| snippet.source_path.as_str())
+                .unwrap_or(&check.id);
+            md.push_str(&format!("### `{}`\n\n", source));
+            for issue in &check.issues {
+                let details = issue.details();
+                if details.is_empty() {
+                    md.push_str(&format!("- **{}**\n", issue.kind()));
+                } else {
+                    md.push_str(&format!("- **{}**: {}\n", issue.kind(), details));
+                }
+            }
+            md.push('\n');
+        }
+    }
+
+    md
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_check_snippet() {
+        assert_eq!(
+            check_snippet("Some **bold** text with `code` and a [link](Vec)."),
+            vec![]
+        );
+        assert_eq!(check_snippet("```rust\n# fn main() {}\n```"), vec![]);
+        assert_eq!(check_snippet(""), vec![]);
+        assert_eq!(
+            check_snippet("| Name | Kind |\n|------|------|\n| foo | bar |\n"),
+            vec![]
+        );
+    }
+}
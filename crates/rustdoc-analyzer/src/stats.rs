@@ -1,5 +1,6 @@
 //! Statistics aggregation and reporting
 
+use crate::complexity::ComplexDoc;
 use crate::markdown_analyzer::MarkdownStats;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -20,6 +21,9 @@ pub struct CrateStats {
     pub docs_count: usize,
     pub total_chars: usize,
     pub stats: MarkdownStats,
+    /// The most complex docs of the crate, most complex first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub most_complex: Vec<ComplexDoc>,
 }
 
 /// Full analysis report
@@ -195,33 +199,8 @@ pub fn generate_markdown_report(report: &AnalysisReport) -> String {
     md.push_str("| Feature | Count | Est. Prevalence |\n");
     md.push_str("|---------|-------|----------------|\n");
 
-    let mut features: Vec<(&str, usize)> = vec![
-        ("Code blocks", agg.code_blocks),
-        ("Inline code", agg.inline_code),
-        ("Links", agg.links),
-        ("Lists", agg.lists),
-        ("Nested lists", agg.nested_lists),
-        ("Headings", agg.headings),
-        ("Emphasis", agg.emphasis),
-        ("Strong", agg.strong),
-        ("Block quotes", agg.block_quotes),
-        ("Tables", agg.tables),
-        ("Strikethrough", agg.strikethrough),
-        ("Images", agg.images),
-        ("Footnotes", agg.footnote_definitions),
-        ("Task lists", agg.task_list_markers),
-        ("HTML blocks", agg.html_blocks),
-    ];
-
-    // Sort by count descending
-    features.sort_by_key(|f| std::cmp::Reverse(f.1));
-
-    for (name, count) in features {
-        let prevalence = report
-            .feature_prevalence
-            .get(&name.to_lowercase().replace(' ', "_"))
-            .copied()
-            .unwrap_or(0.0);
+    for (name, count) in feature_counts(agg) {
+        let prevalence = prevalence_of(report, name);
         md.push_str(&format!(
             "| {} | {} | {:.1}% |\n",
             name,
@@ -333,10 +312,73 @@ pub fn generate_markdown_report(report: &AnalysisReport) -> String {
         md.push_str(&format!("- **Images**: {} occurrences\n", agg.images));
     }
 
+    // Most complex docs, per crate
+    let mut crates: Vec<(&String, &CrateStats)> = report
+        .stats
+        .per_crate
+        .iter()
+        .filter(|(_, crate_stats)| !crate_stats.most_complex.is_empty())
+        .collect();
+    if !crates.is_empty() {
+        crates.sort_by(|a, b| a.0.cmp(b.0));
+        md.push_str("\n## Most Complex Docs\n");
+        for (name, crate_stats) in crates {
+            md.push_str(&format!("\n### {}\n\n", name));
+            md.push_str("| Item | Kind | Score | Chars | Depth | Features |\n");
+            md.push_str("|------|------|-------|-------|-------|----------|\n");
+            for doc in &crate_stats.most_complex {
+                md.push_str(&format!(
+                    "| `{}` | {} | {} | {} | {} | {} |\n",
+                    doc.item_path,
+                    doc.item_kind,
+                    doc.score,
+                    doc.char_count,
+                    doc.nesting_depth,
+                    doc.features.join(", ")
+                ));
+            }
+        }
+    }
+
     md
 }
 
-fn format_number(n: usize) -> String {
+/// Count of each markdown feature in `stats`, by display name, most
+/// frequent first.
+pub(crate) fn feature_counts(stats: &MarkdownStats) -> Vec<(&'static str, usize)> {
+    let mut features = vec![
+        ("Code blocks", stats.code_blocks),
+        ("Inline code", stats.inline_code),
+        ("Links", stats.links),
+        ("Lists", stats.lists),
+        ("Nested lists", stats.nested_lists),
+        ("Headings", stats.headings),
+        ("Emphasis", stats.emphasis),
+        ("Strong", stats.strong),
+        ("Block quotes", stats.block_quotes),
+        ("Tables", stats.tables),
+        ("Strikethrough", stats.strikethrough),
+        ("Images", stats.images),
+        ("Footnotes", stats.footnote_definitions),
+        ("Task lists", stats.task_list_markers),
+        ("HTML blocks", stats.html_blocks),
+    ];
+
+    // Sort by count descending
+    features.sort_by_key(|f| std::cmp::Reverse(f.1));
+    features
+}
+
+/// Estimated prevalence of the feature with display name `name`.
+pub(crate) fn prevalence_of(report: &AnalysisReport, name: &str) -> f64 {
+    report
+        .feature_prevalence
+        .get(&name.to_lowercase().replace(' ', "_"))
+        .copied()
+        .unwrap_or(0.0)
+}
+
+pub(crate) fn format_number(n: usize) -> String {
     if n >= 1_000_000 {
         format!("{:.1}M", n as f64 / 1_000_000.0)
     } else if n >= 1_000 {
@@ -0,0 +1,194 @@
+//! Compare two analysis reports, to see how markdown feature usage shifted
+//! between runs or corpora
+
+use crate::stats::{AnalysisReport, feature_counts, format_number, prevalence_of};
+use std::collections::BTreeSet;
+
+/// Generate a markdown report of what changed from `old` to `new`
+pub fn generate_diff_report(old: &AnalysisReport, new: &AnalysisReport) -> String {
+    let mut md = String::new();
+
+    md.push_str("# Rustdoc Markdown Analysis Diff\n\n");
+    md.push_str(&format!(
+        "Old: {}  \nNew: {}\n\n",
+        old.generated_at, new.generated_at
+    ));
+
+    // Summary
+    md.push_str("## Summary\n\n");
+    md.push_str("| Metric | Old | New | Change |\n");
+    md.push_str("|--------|-----|-----|--------|\n");
+    for (name, old_value, new_value) in [
+        (
+            "Crates analyzed",
+            old.stats.total_crates,
+            new.stats.total_crates,
+        ),
+        (
+            "Doc strings analyzed",
+            old.stats.total_docs,
+            new.stats.total_docs,
+        ),
+        (
+            "Total markdown characters",
+            old.stats.total_chars,
+            new.stats.total_chars,
+        ),
+    ] {
+        md.push_str(&format!(
+            "| {} | {} | {} | {} |\n",
+            name,
+            format_number(old_value),
+            format_number(new_value),
+            signed_count(old_value, new_value)
+        ));
+    }
+
+    // Feature prevalence, biggest shifts first
+    md.push_str("\n## Feature Prevalence\n\n");
+    md.push_str("| Feature | Old count | New count | Old prevalence | New prevalence | Change |\n");
+    md.push_str("|---------|-----------|-----------|----------------|----------------|--------|\n");
+    let old_counts = feature_counts(&old.stats.aggregate);
+    let mut rows: Vec<(&str, usize, usize, f64, f64)> = feature_counts(&new.stats.aggregate)
+        .into_iter()
+        .map(|(name, new_count)| {
+            let old_count = old_counts
+                .iter()
+                .find(|(old_name, _)| *old_name == name)
+                .map_or(0, |(_, count)| *count);
+            (
+                name,
+                old_count,
+                new_count,
+                prevalence_of(old, name) * 100.0,
+                prevalence_of(new, name) * 100.0,
+            )
+        })
+        .collect();
+    rows.sort_by(|a, b| (b.4 - b.3).abs().total_cmp(&(a.4 - a.3).abs()));
+    for (name, old_count, new_count, old_pct, new_pct) in rows {
+        md.push_str(&format!(
+            "| {} | {} | {} | {:.1}% | {:.1}% | {} |\n",
+            name,
+            format_number(old_count),
+            format_number(new_count),
+            old_pct,
+            new_pct,
+            signed_points(old_pct, new_pct)
+        ));
+    }
+
+    // Code block languages, as shares of all code blocks
+    md.push_str("\n## Code Block Languages\n\n");
+    md.push_str("| Language | Old % of code blocks | New % of code blocks | Change |\n");
+    md.push_str("|----------|----------------------|----------------------|--------|\n");
+    let old_agg = &old.stats.aggregate;
+    let new_agg = &new.stats.aggregate;
+    let languages: BTreeSet<&String> = old_agg
+        .code_block_languages
+        .keys()
+        .chain(new_agg.code_block_languages.keys())
+        .collect();
+    let mut rows: Vec<(&String, f64, f64)> = languages
+        .into_iter()
+        .map(|lang| {
+            let share = |counts: &std::collections::HashMap<String, usize>, total: usize| {
+                counts.get(lang).copied().unwrap_or(0) as f64 / total.max(1) as f64 * 100.0
+            };
+            (
+                lang,
+                share(&old_agg.code_block_languages, old_agg.code_blocks),
+                share(&new_agg.code_block_languages, new_agg.code_blocks),
+            )
+        })
+        .collect();
+    rows.sort_by(|a, b| (b.2 - b.1).abs().total_cmp(&(a.2 - a.1).abs()));
+    for (lang, old_pct, new_pct) in rows.into_iter().take(10) {
+        md.push_str(&format!(
+            "| {} | {:.1}% | {:.1}% | {} |\n",
+            lang,
+            old_pct,
+            new_pct,
+            signed_points(old_pct, new_pct)
+        ));
+    }
+
+    // Corpus changes
+    let old_crates: BTreeSet<&String> = old.stats.per_crate.keys().collect();
+    let new_crates: BTreeSet<&String> = new.stats.per_crate.keys().collect();
+    let added: Vec<&str> = new_crates
+        .difference(&old_crates)
+        .map(|name| name.as_str())
+        .collect();
+    let removed: Vec<&str> = old_crates
+        .difference(&new_crates)
+        .map(|name| name.as_str())
+        .collect();
+    if !added.is_empty() || !removed.is_empty() {
+        md.push_str("\n## Corpus Changes\n\n");
+        if !added.is_empty() {
+            md.push_str(&format!("- **Added**: {}\n", added.join(", ")));
+        }
+        if !removed.is_empty() {
+            md.push_str(&format!("- **Removed**: {}\n", removed.join(", ")));
+        }
+    }
+
+    md
+}
+
+/// Change between two counts, with its sign
+fn signed_count(old: usize, new: usize) -> String {
+    if new >= old {
+        format!("+{}", format_number(new - old))
+    } else {
+        format!("-{}", format_number(old - new))
+    }
+}
+
+/// Change between two percentages in percentage points, with its sign
+fn signed_points(old: f64, new: f64) -> String {
+    format!("{:+.1} pp", new - old)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stats::{AggregateStats, CrateStats};
+
+    fn report(crate_name: &str, docs: usize, code_blocks: usize, tables: usize) -> AnalysisReport {
+        let mut crate_stats = CrateStats {
+            docs_count: docs,
+            ..CrateStats::default()
+        };
+        crate_stats.stats.code_blocks = code_blocks;
+        crate_stats.stats.tables = tables;
+        crate_stats
+            .stats
+            .code_block_languages
+            .insert("rust".to_string(), code_blocks);
+        let mut stats = AggregateStats {
+            total_crates: 1,
+            total_docs: docs,
+            ..AggregateStats::default()
+        };
+        stats.aggregate.merge(&crate_stats.stats);
+        stats.per_crate.insert(crate_name.to_string(), crate_stats);
+        AnalysisReport::new(stats)
+    }
+
+    #[test]
+    fn test_diff_report() {
+        let old = report("serde", 10, 5, 0);
+        let new = report("tokio", 20, 5, 4);
+        let md = generate_diff_report(&old, &new);
+
+        assert!(md.contains("| Doc strings analyzed | 10 | 20 | +10 |"));
+        assert!(md.contains("| Code blocks | 5 | 5 | 50.0% | 25.0% | -25.0 pp |"));
+        assert!(md.contains("| Tables | 0 | 4 | 0.0% | 20.0% | +20.0 pp |"));
+        assert!(md.contains("| rust | 100.0% | 100.0% | +0.0 pp |"));
+        assert!(md.contains("- **Added**: tokio\n- **Removed**: serde\n"));
+        // Biggest shift first
+        assert!(md.find("| Code blocks").unwrap() < md.find("| Tables").unwrap());
+    }
+}
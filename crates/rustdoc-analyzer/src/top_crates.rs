@@ -0,0 +1,116 @@
+//! The most downloaded crates on crates.io, as a corpus that stays
+//! representative without maintaining a list
+
+use crate::crate_list::CrateEntry;
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use std::thread;
+use std::time::Duration;
+
+/// Most crates crates.io returns per page
+const PER_PAGE: usize = 100;
+
+/// Pause between pages, as crates.io asks crawlers to make at most one
+/// request per second
+const PAGE_DELAY: Duration = Duration::from_secs(1);
+
+#[derive(Deserialize)]
+struct CratesPage {
+    crates: Vec<ListedCrate>,
+}
+
+#[derive(Deserialize)]
+struct ListedCrate {
+    name: String,
+    max_stable_version: Option<String>,
+    max_version: String,
+}
+
+/// The `count` most downloaded crates, in the crates.io category with the
+/// slug `category` when given, at their newest stable version. Their
+/// category in the analysis is that slug, or `top`.
+pub fn top_crates(count: usize, category: Option<&str>) -> Result<Vec<CrateEntry>> {
+    // The same page size throughout, so pages don't overlap
+    let per_page = PER_PAGE.min(count).max(1);
+    let mut crates = Vec::new();
+    let mut page = 1;
+    while crates.len() < count {
+        if page > 1 {
+            thread::sleep(PAGE_DELAY);
+        }
+        let listed = fetch_page(page, per_page, category)?;
+        let done = listed.len() < per_page;
+        crates.extend(
+            listed
+                .into_iter()
+                .map(|listed| entry(listed, category.unwrap_or("top"))),
+        );
+        if done {
+            break;
+        }
+        page += 1;
+    }
+    crates.truncate(count);
+    Ok(crates)
+}
+
+fn entry(listed: ListedCrate, category: &str) -> CrateEntry {
+    CrateEntry {
+        version: listed.max_stable_version.unwrap_or(listed.max_version),
+        name: listed.name,
+        category: category.to_string(),
+    }
+}
+
+/// Page `page` of the crates, `per_page` at a time, most downloaded first.
+fn fetch_page(page: usize, per_page: usize, category: Option<&str>) -> Result<Vec<ListedCrate>> {
+    let mut request = ureq::get("https://crates.io/api/v1/crates")
+        // crates.io rejects requests that don't say who is asking
+        .header(
+            "User-Agent",
+            "rustdoc-analyzer (https://github.com/human-solutions/mx-docsrs)",
+        )
+        .query("sort", "downloads")
+        .query("page", page.to_string())
+        .query("per_page", per_page.to_string());
+    if let Some(category) = category {
+        request = request.query("category", category);
+    }
+    let mut response = request
+        .call()
+        .with_context(|| format!("Failed to list page {page} of the top crates"))?;
+    parse_page(&response.body_mut().read_to_string()?)
+}
+
+fn parse_page(json: &str) -> Result<Vec<ListedCrate>> {
+    let page: CratesPage =
+        serde_json::from_str(json).context("Failed to read the crates.io response")?;
+    Ok(page.crates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_page() {
+        let listed = parse_page(
+            r#"{
+                "crates": [
+                    {"name": "syn", "max_stable_version": "2.0.87", "max_version": "2.0.87", "downloads": 1},
+                    {"name": "new", "max_stable_version": null, "max_version": "0.1.0-alpha.1"}
+                ],
+                "meta": {"total": 2}
+            }"#,
+        )
+        .unwrap();
+        let crates: Vec<CrateEntry> = listed
+            .into_iter()
+            .map(|listed| entry(listed, "top"))
+            .collect();
+        assert_eq!(crates[0].name, "syn");
+        assert_eq!(crates[0].version, "2.0.87");
+        assert_eq!(crates[1].version, "0.1.0-alpha.1");
+        assert_eq!(crates[1].category, "top");
+    }
+}
@@ -0,0 +1,95 @@
+//! Building and rendering token sequences, as done for every entry of a list
+//! and every signature.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, criterion_group, criterion_main};
+use rustdoc_fmt::{Colorizer, Output, tokens_to_string, wrap_signature};
+
+/// Number of signatures rendered per iteration, about the size of a large
+/// crate's item list.
+const ITEMS: usize = 5_000;
+
+/// `pub fn spawn_{i}<F>(future: F) -> JoinHandle<F::Output> where F: Future + Send + 'static`
+fn signature(i: usize) -> Output {
+    let mut out = Output::new().qualifier_pub();
+    out.kind("fn").whitespace().function(format!("spawn_{i}"));
+    out.symbol("<").generic("F").symbol(">(");
+    out.identifier("future")
+        .symbol(":")
+        .whitespace()
+        .generic("F");
+    out.symbol(")");
+    let mut out = out.symbol_arrow();
+    out.type_("JoinHandle")
+        .symbol("<")
+        .generic("F")
+        .symbol("::");
+    out.type_("Output").symbol(">").whitespace();
+    out.keyword("where").whitespace().generic("F");
+    let mut out = out.symbol_colon();
+    out.type_("Future");
+    let mut out = out.symbol_plus();
+    out.type_("Send");
+    let mut out = out.symbol_plus();
+    out.lifetime("'static");
+    out
+}
+
+/// `struct crate::module_{i}::Item`, as in search results
+fn list_entry(i: usize) -> Output {
+    let mut out = Output::new();
+    out.kind("struct").whitespace();
+    out.identifier("crate").symbol("::");
+    out.identifier(format!("module_{i}")).symbol("::");
+    out.type_("Item");
+    out
+}
+
+fn bench_build(c: &mut Criterion) {
+    c.bench_function("build signatures", |b| {
+        b.iter(|| {
+            for i in 0..ITEMS {
+                black_box(signature(i).into_tokens());
+            }
+        })
+    });
+    c.bench_function("build list entries", |b| {
+        b.iter(|| {
+            for i in 0..ITEMS {
+                black_box(list_entry(i).into_tokens());
+            }
+        })
+    });
+}
+
+fn bench_render(c: &mut Criterion) {
+    let signatures: Vec<_> = (0..ITEMS).map(|i| signature(i).into_tokens()).collect();
+    let colorizer = Colorizer::get();
+    colored::control::set_override(true);
+
+    c.bench_function("colorize signatures", |b| {
+        b.iter(|| {
+            for tokens in &signatures {
+                black_box(colorizer.tokens(tokens));
+            }
+        })
+    });
+    c.bench_function("signatures to string", |b| {
+        b.iter(|| {
+            for tokens in &signatures {
+                black_box(tokens_to_string(tokens));
+            }
+        })
+    });
+    c.bench_function("wrap signatures", |b| {
+        b.iter(|| {
+            for tokens in &signatures {
+                black_box(wrap_signature(tokens, 40, 0));
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_build, bench_render);
+criterion_main!(benches);
@@ -0,0 +1,195 @@
+//! Terminal color depth detection and color quantization.
+
+/// Number of colors the terminal can display.
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Default)]
+pub enum ColorDepth {
+    /// 24-bit RGB colors.
+    #[default]
+    TrueColor,
+    /// The xterm 256-color palette.
+    Ansi256,
+    /// The 16 basic ANSI colors.
+    Ansi16,
+}
+
+/// Default xterm RGB values for the 16 basic ANSI colors, in SGR order
+/// (30-37, then bright 90-97).
+const ANSI16_PALETTE: [(u8, u8, u8); 16] = [
+    (0, 0, 0),
+    (205, 0, 0),
+    (0, 205, 0),
+    (205, 205, 0),
+    (0, 0, 238),
+    (205, 0, 205),
+    (0, 205, 205),
+    (229, 229, 229),
+    (127, 127, 127),
+    (255, 0, 0),
+    (0, 255, 0),
+    (255, 255, 0),
+    (92, 92, 255),
+    (255, 0, 255),
+    (0, 255, 255),
+    (255, 255, 255),
+];
+
+/// Channel levels of the 6x6x6 color cube in the 256-color palette.
+const CUBE_LEVELS: [u8; 6] = [0, 95, 135, 175, 215, 255];
+
+impl ColorDepth {
+    /// Detect the color depth from the environment, `None` when the
+    /// terminal shows no colors at all.
+    ///
+    /// - `TERM=dumb` means no colors
+    /// - `COLORTERM=truecolor` or `COLORTERM=24bit` means 24-bit support
+    /// - Terminals known for 24-bit support (kitty, Alacritty, WezTerm) and
+    ///   `*-direct` terminfo entries such as `tmux-direct` mean 24-bit
+    /// - A `TERM` containing `256color` means the 256-color palette
+    /// - Any other `TERM` falls back to the 16 basic colors
+    /// - Without `TERM` (e.g. Windows consoles) 24-bit color is assumed
+    pub fn detect() -> Option<Self> {
+        Self::from_env_values(
+            std::env::var("COLORTERM").ok().as_deref(),
+            std::env::var("TERM").ok().as_deref(),
+        )
+    }
+
+    fn from_env_values(colorterm: Option<&str>, term: Option<&str>) -> Option<Self> {
+        if term == Some("dumb") {
+            return None;
+        }
+        if let Some(colorterm) = colorterm
+            && matches!(colorterm.to_lowercase().as_str(), "truecolor" | "24bit")
+        {
+            return Some(Self::TrueColor);
+        }
+        match term {
+            Some("xterm-kitty" | "alacritty" | "wezterm") => Some(Self::TrueColor),
+            Some(term) if term.ends_with("-direct") => Some(Self::TrueColor),
+            Some(term) if term.contains("256color") => Some(Self::Ansi256),
+            Some(term) if !term.is_empty() => Some(Self::Ansi16),
+            _ => Some(Self::TrueColor),
+        }
+    }
+
+    /// SGR parameters selecting `(r, g, b)` as the foreground color.
+    pub fn fg_sgr(self, r: u8, g: u8, b: u8) -> String {
+        match self {
+            Self::TrueColor => format!("38;2;{r};{g};{b}"),
+            Self::Ansi256 => format!("38;5;{}", nearest_ansi256(r, g, b)),
+            Self::Ansi16 => ansi16_code(nearest_ansi16(r, g, b), 30).to_string(),
+        }
+    }
+
+    /// SGR parameters selecting `(r, g, b)` as the background color.
+    pub fn bg_sgr(self, r: u8, g: u8, b: u8) -> String {
+        match self {
+            Self::TrueColor => format!("48;2;{r};{g};{b}"),
+            Self::Ansi256 => format!("48;5;{}", nearest_ansi256(r, g, b)),
+            Self::Ansi16 => ansi16_code(nearest_ansi16(r, g, b), 40).to_string(),
+        }
+    }
+}
+
+/// Squared euclidean distance between two RGB colors.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let dr = i32::from(a.0) - i32::from(b.0);
+    let dg = i32::from(a.1) - i32::from(b.1);
+    let db = i32::from(a.2) - i32::from(b.2);
+    (dr * dr + dg * dg + db * db) as u32
+}
+
+/// Index (0-15) of the closest basic ANSI color.
+fn nearest_ansi16(r: u8, g: u8, b: u8) -> u8 {
+    (0..16u8)
+        .min_by_key(|&i| distance((r, g, b), ANSI16_PALETTE[i as usize]))
+        .unwrap_or(7)
+}
+
+/// SGR code for a basic ANSI color index, given the base (30 for fg, 40 for bg).
+fn ansi16_code(index: u8, base: u8) -> u8 {
+    if index < 8 {
+        base + index
+    } else {
+        base + 60 + index - 8
+    }
+}
+
+/// Index (16-255) of the closest color in the xterm 256-color palette,
+/// choosing between the color cube and the grayscale ramp.
+fn nearest_ansi256(r: u8, g: u8, b: u8) -> u8 {
+    let cube_index = |c: u8| {
+        (0..6)
+            .min_by_key(|&i| (i32::from(CUBE_LEVELS[i]) - i32::from(c)).abs())
+            .unwrap_or(0)
+    };
+    let (ri, gi, bi) = (cube_index(r), cube_index(g), cube_index(b));
+    let cube_rgb = (CUBE_LEVELS[ri], CUBE_LEVELS[gi], CUBE_LEVELS[bi]);
+    let cube = 16 + 36 * ri as u8 + 6 * gi as u8 + bi as u8;
+
+    // Grayscale ramp 232-255 covers 8, 18, ..., 238
+    let avg = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray_step = (avg.saturating_sub(3) / 10).min(23) as u8;
+    let gray_level = 8 + 10 * gray_step;
+    let gray = 232 + gray_step;
+
+    if distance((r, g, b), (gray_level, gray_level, gray_level)) < distance((r, g, b), cube_rgb) {
+        gray
+    } else {
+        cube
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_env_values() {
+        let cases = [
+            (
+                Some("truecolor"),
+                Some("xterm-256color"),
+                Some(ColorDepth::TrueColor),
+            ),
+            (Some("24bit"), None, Some(ColorDepth::TrueColor)),
+            (None, Some("xterm-256color"), Some(ColorDepth::Ansi256)),
+            (None, Some("xterm"), Some(ColorDepth::Ansi16)),
+            (None, None, Some(ColorDepth::TrueColor)),
+            (None, Some("xterm-kitty"), Some(ColorDepth::TrueColor)),
+            (None, Some("alacritty"), Some(ColorDepth::TrueColor)),
+            (None, Some("wezterm"), Some(ColorDepth::TrueColor)),
+            (None, Some("tmux-direct"), Some(ColorDepth::TrueColor)),
+            (None, Some("dumb"), None),
+            (Some("truecolor"), Some("dumb"), None),
+        ];
+        for (colorterm, term, expected) in cases {
+            assert_eq!(
+                ColorDepth::from_env_values(colorterm, term),
+                expected,
+                "COLORTERM={colorterm:?} TERM={term:?}"
+            );
+        }
+    }
+
+    #[test]
+    fn test_nearest_ansi256() {
+        assert_eq!(nearest_ansi256(0, 0, 0), 16);
+        assert_eq!(nearest_ansi256(255, 255, 255), 231);
+        assert_eq!(nearest_ansi256(255, 0, 0), 196);
+        assert_eq!(nearest_ansi256(128, 128, 128), 244);
+    }
+
+    #[test]
+    fn test_ansi16_sgr() {
+        assert_eq!(ColorDepth::Ansi16.fg_sgr(200, 10, 10), "31");
+        assert_eq!(ColorDepth::Ansi16.fg_sgr(250, 250, 250), "97");
+        assert_eq!(ColorDepth::Ansi16.bg_sgr(0, 0, 0), "40");
+    }
+
+    #[test]
+    fn test_truecolor_sgr() {
+        assert_eq!(ColorDepth::TrueColor.fg_sgr(1, 2, 3), "38;2;1;2;3");
+        assert_eq!(ColorDepth::Ansi256.bg_sgr(255, 0, 0), "48;5;196");
+    }
+}
@@ -4,16 +4,19 @@
 //! decisions, extracting colors from syntect themes for consistency between
 //! code block syntax highlighting and token-based signature coloring.
 
+use std::path::Path;
 use std::sync::LazyLock;
 
 use colored::Colorize;
+use syntect::LoadingError;
 use syntect::easy::HighlightLines;
-use syntect::highlighting::{Color as SyntectColor, ThemeSet};
+use syntect::highlighting::{Color as SyntectColor, Style, Theme, ThemeSet};
 use syntect::parsing::SyntaxSet;
-use syntect::util::{LinesWithEndings, as_24_bit_terminal_escaped};
+use syntect::util::LinesWithEndings;
 use terminal_colorsaurus::{QueryOptions, ThemeMode, theme_mode};
 
-use crate::Token;
+use crate::color_depth::ColorDepth;
+use crate::{Token, tokens_to_string};
 
 /// Global syntax set (loaded once on first use)
 static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_newlines);
@@ -22,7 +25,25 @@ static SYNTAX_SET: LazyLock<SyntaxSet> = LazyLock::new(SyntaxSet::load_defaults_
 static THEME_SET: LazyLock<ThemeSet> = LazyLock::new(ThemeSet::load_defaults);
 
 /// Global colorizer instance
-static COLORIZER: LazyLock<Colorizer> = LazyLock::new(Colorizer::new);
+static COLORIZER: LazyLock<Colorizer> = LazyLock::new(Colorizer::detect);
+
+/// Bundled theme used on dark terminals.
+const DARK_THEME: &str = "base16-eighties.dark";
+
+/// Bundled theme used on light terminals.
+const LIGHT_THEME: &str = "InspiredGitHub";
+
+/// How code blocks are framed once their markdown fences are stripped.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq)]
+pub enum CodeFrame {
+    /// Indented code only.
+    #[default]
+    None,
+    /// A header line with the block's language.
+    Label,
+    /// A light box-drawing border on the left, headed by the block's language.
+    Box,
+}
 
 /// Color scheme extracted from a syntect theme for token coloring.
 #[derive(Debug, Clone)]
@@ -44,7 +65,7 @@ struct ColorScheme {
 
 impl ColorScheme {
     /// Extract a color scheme from the given syntect theme.
-    fn from_theme(theme: &syntect::highlighting::Theme) -> Self {
+    fn from_theme(theme: &Theme) -> Self {
         let foreground = theme.settings.foreground.unwrap_or(SyntectColor {
             r: 255,
             g: 255,
@@ -122,37 +143,122 @@ impl ColorScheme {
 /// - Token coloring (signatures, fields, methods)
 /// - Markdown element styling (headings, emphasis, code)
 /// - Syntax highlighting for code blocks
+///
+/// Use [`Colorizer::get`] for the shared instance that detects the terminal
+/// theme, or [`Colorizer::with_theme`] to build an independent instance
+/// (e.g. when embedding the formatters in a library or in tests).
+#[derive(Debug, Clone)]
 pub struct Colorizer {
     scheme: ColorScheme,
-    theme_name: &'static str,
+    theme: Theme,
     is_dark: bool,
+    depth: ColorDepth,
+    line_numbers_min: Option<usize>,
+    code_frame: CodeFrame,
+    syntect_signatures: bool,
 }
 
 impl Colorizer {
-    /// Create a new colorizer, detecting theme from terminal.
-    fn new() -> Self {
-        // Detect terminal theme (dark/light)
-        // Skip terminal detection in test environments to avoid hangs with cargo-nextest
-        // See: https://github.com/bash/terminal-colorsaurus/issues/38
-        let (theme_name, is_dark) = if Self::is_test_environment() {
-            ("base16-eighties.dark", true)
-        } else {
-            match theme_mode(QueryOptions::default()) {
-                Ok(ThemeMode::Light) => ("InspiredGitHub", false),
-                Ok(ThemeMode::Dark) | Err(_) => ("base16-eighties.dark", true),
-            }
-        };
-
-        let theme = &THEME_SET.themes[theme_name];
-        let scheme = ColorScheme::from_theme(theme);
-
+    /// Create a colorizer using the given syntect theme.
+    ///
+    /// `is_dark` selects the heading backgrounds, which contrast against the
+    /// terminal background rather than the theme. Colors are emitted as
+    /// 24-bit escapes; see [`Colorizer::with_color_depth`].
+    pub fn with_theme(theme: Theme, is_dark: bool) -> Self {
         Self {
-            scheme,
-            theme_name,
+            scheme: ColorScheme::from_theme(&theme),
+            theme,
             is_dark,
+            depth: ColorDepth::TrueColor,
+            line_numbers_min: None,
+            code_frame: CodeFrame::None,
+            syntect_signatures: false,
         }
     }
 
+    /// Quantize all emitted colors to the given depth.
+    pub fn with_color_depth(mut self, depth: ColorDepth) -> Self {
+        self.depth = depth;
+        self
+    }
+
+    /// Show a line number gutter on code blocks with at least `min_lines` lines.
+    ///
+    /// `None` disables line numbers (the default).
+    pub fn with_line_numbers(mut self, min_lines: Option<usize>) -> Self {
+        self.line_numbers_min = min_lines;
+        self
+    }
+
+    /// Frame code blocks and label them with their language.
+    pub fn with_code_frame(mut self, frame: CodeFrame) -> Self {
+        self.code_frame = frame;
+        self
+    }
+
+    /// Color token sequences by running their text through syntect's Rust
+    /// grammar, so signatures match the colors of highlighted examples exactly.
+    ///
+    /// By default each token is colored by its kind, which is more robust for
+    /// fragments (fields, variants) that aren't complete Rust items.
+    pub fn with_syntect_signatures(mut self, enabled: bool) -> Self {
+        self.syntect_signatures = enabled;
+        self
+    }
+
+    /// The color depth used for emitted escapes.
+    pub fn color_depth(&self) -> ColorDepth {
+        self.depth
+    }
+
+    /// Create a colorizer using the bundled default theme for a dark or light terminal.
+    pub fn with_default_theme(is_dark: bool) -> Self {
+        let name = if is_dark { DARK_THEME } else { LIGHT_THEME };
+        Self::with_theme(THEME_SET.themes[name].clone(), is_dark)
+    }
+
+    /// Create a colorizer using one of the themes bundled with syntect.
+    ///
+    /// Returns `None` if no bundled theme has this name; see
+    /// [`Colorizer::bundled_themes`].
+    pub fn with_bundled_theme(name: &str) -> Option<Self> {
+        let theme = THEME_SET.themes.get(name)?.clone();
+        let is_dark = theme_is_dark(&theme).unwrap_or_else(Self::detect_dark_terminal);
+        Some(Self::with_theme(theme, is_dark))
+    }
+
+    /// Names of the themes bundled with syntect, in alphabetical order.
+    pub fn bundled_themes() -> impl Iterator<Item = &'static str> {
+        THEME_SET.themes.keys().map(String::as_str)
+    }
+
+    /// Create a colorizer from a `.tmTheme` file.
+    ///
+    /// Whether the theme is dark is derived from its background color, falling
+    /// back to terminal detection when the theme doesn't define one.
+    pub fn from_theme_file(path: impl AsRef<Path>) -> Result<Self, LoadingError> {
+        let theme = ThemeSet::get_theme(path)?;
+        let is_dark = theme_is_dark(&theme).unwrap_or_else(Self::detect_dark_terminal);
+        Ok(Self::with_theme(theme, is_dark))
+    }
+
+    /// Create a new colorizer, detecting theme and color depth from terminal.
+    pub fn detect() -> Self {
+        Self::with_default_theme(Self::detect_dark_terminal())
+            .with_color_depth(ColorDepth::detect().unwrap_or_default())
+    }
+
+    /// Query the terminal for its theme mode, defaulting to dark.
+    fn detect_dark_terminal() -> bool {
+        // Skip terminal detection in test environments to avoid hangs with cargo-nextest
+        // See: https://github.com/bash/terminal-colorsaurus/issues/38
+        // No need to query the terminal either when colors are disabled
+        if Self::is_test_environment() || !Self::is_enabled() {
+            return true;
+        }
+        !matches!(theme_mode(QueryOptions::default()), Ok(ThemeMode::Light))
+    }
+
     /// Check if we're running in a test environment where terminal queries may hang.
     fn is_test_environment() -> bool {
         // NEXTEST is set by cargo-nextest
@@ -161,12 +267,19 @@ impl Colorizer {
     }
 
     /// Get the global colorizer instance.
+    ///
+    /// The terminal theme is detected on first use.
     #[inline]
     pub fn get() -> &'static Self {
         &COLORIZER
     }
 
     /// Check if colors are enabled (respects global override).
+    ///
+    /// Every styling method, including code block highlighting and heading
+    /// backgrounds, returns plain text when this is false. Without an override
+    /// this follows `colored`'s handling of `NO_COLOR`, `CLICOLOR` and
+    /// `CLICOLOR_FORCE`.
     #[inline]
     pub fn is_enabled() -> bool {
         colored::control::SHOULD_COLORIZE.should_colorize()
@@ -174,31 +287,51 @@ impl Colorizer {
 
     // ========== Token Coloring ==========
 
-    /// Colorize a single token.
-    fn colorize_token(&self, token: &Token) -> String {
+    /// Colorize a slice of tokens to a string.
+    pub fn tokens(&self, tokens: &[Token]) -> String {
         if !Self::is_enabled() {
-            return token.text().to_string();
+            return tokens_to_string(tokens);
+        }
+        if self.syntect_signatures {
+            return self.highlight_rust(&tokens_to_string(tokens));
         }
 
-        match token {
-            Token::Symbol(text) | Token::Annotation(text) => text.to_string(),
-            Token::Qualifier(text)
-            | Token::Kind(text)
-            | Token::Self_(text)
-            | Token::Lifetime(text)
-            | Token::Keyword(text) => self.apply_color(text, self.scheme.keyword),
-            Token::Function(text) => self.apply_color(text, self.scheme.function),
-            Token::Generic(text) | Token::Primitive(text) | Token::Type(text) => {
-                self.apply_color(text, self.scheme.type_)
+        // Each color's SGR parameters are formatted once, on first use
+        let colors = [
+            self.scheme.keyword,
+            self.scheme.function,
+            self.scheme.type_,
+            self.scheme.identifier,
+        ];
+        let mut sgrs: [Option<String>; 4] = Default::default();
+        let mut output = String::new();
+        for token in tokens {
+            let color = match token {
+                Token::Symbol(_) | Token::Annotation(_) | Token::Whitespace => None,
+                Token::Qualifier(_)
+                | Token::Kind(_)
+                | Token::Self_(_)
+                | Token::Lifetime(_)
+                | Token::Keyword(_) => Some(0),
+                Token::Function(_) => Some(1),
+                Token::Generic(_) | Token::Primitive(_) | Token::Type(_) => Some(2),
+                Token::Identifier(_) => Some(3),
+            };
+            match color {
+                Some(i) => {
+                    let color = colors[i];
+                    let sgr =
+                        sgrs[i].get_or_insert_with(|| self.depth.fg_sgr(color.r, color.g, color.b));
+                    output.push_str("\x1b[");
+                    output.push_str(sgr);
+                    output.push('m');
+                    output.push_str(token.text());
+                    output.push_str("\x1b[0m");
+                }
+                None => output.push_str(token.text()),
             }
-            Token::Identifier(text) => self.apply_color(text, self.scheme.identifier),
-            Token::Whitespace => " ".to_string(),
         }
-    }
-
-    /// Colorize a slice of tokens to a string.
-    pub fn tokens(&self, tokens: &[Token]) -> String {
-        tokens.iter().map(|t| self.colorize_token(t)).collect()
+        output
     }
 
     // ========== Markdown Styling ==========
@@ -236,11 +369,12 @@ impl Colorizer {
         // Add # prefix based on level, with padding
         let prefix = "#".repeat(level as usize);
         let padded = format!(" {} {} ", prefix, text);
-        padded
-            .bold()
-            .truecolor(fg_r, fg_g, fg_b)
-            .on_truecolor(bg_r, bg_g, bg_b)
-            .to_string()
+        format!(
+            "\x1b[1;{};{}m{}\x1b[0m",
+            self.depth.fg_sgr(fg_r, fg_g, fg_b),
+            self.depth.bg_sgr(bg_r, bg_g, bg_b),
+            padded
+        )
     }
 
     /// Style text as emphasis (italic).
@@ -328,8 +462,15 @@ impl Colorizer {
     /// - For Rust code, processes hidden lines (`# ` prefix)
     /// - Applies syntax highlighting based on language
     /// - Falls back to plain text for unknown languages
-    /// - Adds 4-space indentation to each line
+    /// - Adds 2-space indentation to each line
+    /// - Adds a frame and language label if configured
     pub fn code_block(&self, code: &str, language: &str) -> String {
+        let body = self.code_block_body(code, language);
+        self.frame_code_block(body, language)
+    }
+
+    /// Highlight the lines of a code block, without any frame.
+    fn code_block_body(&self, code: &str, language: &str) -> String {
         // Determine if this is Rust code
         let is_rust = is_rust_language(language);
 
@@ -340,8 +481,10 @@ impl Colorizer {
             code.to_string()
         };
 
+        let gutter_width = self.gutter_width(&processed_code);
+
         if !Self::is_enabled() {
-            return format_plain(&processed_code);
+            return format_plain(&processed_code, gutter_width);
         }
 
         // Find syntax definition
@@ -356,18 +499,17 @@ impl Colorizer {
             })
             .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
 
-        // Get theme
-        let theme = &THEME_SET.themes[self.theme_name];
-
-        let mut highlighter = HighlightLines::new(syntax, theme);
+        let mut highlighter = HighlightLines::new(syntax, &self.theme);
         let mut output = String::new();
 
-        for line in LinesWithEndings::from(&processed_code) {
+        for (index, line) in LinesWithEndings::from(&processed_code).enumerate() {
+            output.push_str("  "); // 2-space indent
+            if let Some(width) = gutter_width {
+                output.push_str(&format!("{}", gutter(index + 1, width).dimmed()));
+            }
             match highlighter.highlight_line(line, &SYNTAX_SET) {
                 Ok(ranges) => {
-                    output.push_str("  "); // 2-space indent
-                    let escaped = as_24_bit_terminal_escaped(&ranges[..], false);
-                    output.push_str(&escaped);
+                    output.push_str(&self.escape_ranges(&ranges));
                     // Ensure line ends with newline
                     if !line.ends_with('\n') {
                         output.push('\n');
@@ -375,7 +517,6 @@ impl Colorizer {
                 }
                 Err(_) => {
                     // Fallback on error
-                    output.push_str("  ");
                     output.push_str(line);
                     if !line.ends_with('\n') {
                         output.push('\n');
@@ -389,19 +530,130 @@ impl Colorizer {
         output
     }
 
+    /// Turn highlighted ranges into foreground color escapes at the configured depth.
+    fn escape_ranges(&self, ranges: &[(Style, &str)]) -> String {
+        ranges
+            .iter()
+            .map(|(style, text)| {
+                let fg = style.foreground;
+                format!("\x1b[{}m{}", self.depth.fg_sgr(fg.r, fg.g, fg.b), text)
+            })
+            .collect()
+    }
+
+    /// Highlight Rust source inline, without indentation or frame.
+    fn highlight_rust(&self, code: &str) -> String {
+        let syntax = SYNTAX_SET
+            .find_syntax_by_extension("rs")
+            .unwrap_or_else(|| SYNTAX_SET.find_syntax_plain_text());
+        let mut highlighter = HighlightLines::new(syntax, &self.theme);
+        let mut output = String::new();
+        for line in LinesWithEndings::from(code) {
+            match highlighter.highlight_line(line, &SYNTAX_SET) {
+                Ok(ranges) => output.push_str(&self.escape_ranges(&ranges)),
+                Err(_) => output.push_str(line),
+            }
+        }
+        output.push_str("\x1b[0m");
+        output
+    }
+
+    /// Wrap a highlighted code block body in the configured frame.
+    fn frame_code_block(&self, body: String, language: &str) -> String {
+        if self.code_frame == CodeFrame::None {
+            return body;
+        }
+
+        // Keep the trailing color reset after the frame
+        let (body, reset) = match body.strip_suffix("\x1b[0m") {
+            Some(stripped) => (stripped, "\x1b[0m"),
+            None => (body.as_str(), ""),
+        };
+        let label = language_label(language);
+        let style = |text: String| {
+            if Self::is_enabled() {
+                text.dimmed().to_string()
+            } else {
+                text
+            }
+        };
+
+        let mut output = String::new();
+        match self.code_frame {
+            CodeFrame::None => unreachable!(),
+            CodeFrame::Label => {
+                output.push_str(&format!(
+                    "  {}\n",
+                    style(format!("\u{2500}\u{2500} {label}"))
+                ));
+                output.push_str(body);
+            }
+            CodeFrame::Box => {
+                output.push_str(&format!(
+                    "  {}\n",
+                    style(format!("\u{250c}\u{2500} {label}"))
+                ));
+                for line in body.lines() {
+                    let line = line.strip_prefix("  ").unwrap_or(line);
+                    output.push_str(&format!("  {}{}\n", style("\u{2502} ".to_string()), line));
+                }
+                output.push_str(&format!("  {}\n", style("\u{2514}\u{2500}".to_string())));
+            }
+        }
+        output.push_str(reset);
+        output
+    }
+
     // ========== Helpers ==========
 
-    /// Apply a syntect color to text using the colored crate.
+    /// Width of the line number gutter for a code block, if one should be shown.
+    fn gutter_width(&self, code: &str) -> Option<usize> {
+        let min_lines = self.line_numbers_min?;
+        let line_count = code.lines().count();
+        (line_count >= min_lines).then(|| line_count.to_string().len())
+    }
+
+    /// Apply a syntect color to text, quantized to the configured color depth.
     fn apply_color(&self, text: &str, color: SyntectColor) -> String {
-        text.truecolor(color.r, color.g, color.b).to_string()
+        format!(
+            "\x1b[{}m{}\x1b[0m",
+            self.depth.fg_sgr(color.r, color.g, color.b),
+            text
+        )
     }
 }
 
+/// Classify a theme as dark or light from the luminance of its background color.
+fn theme_is_dark(theme: &Theme) -> Option<bool> {
+    let bg = theme.settings.background?;
+    let luminance = 0.299 * f32::from(bg.r) + 0.587 * f32::from(bg.g) + 0.114 * f32::from(bg.b);
+    Some(luminance < 128.0)
+}
+
+/// Language shown in a code block label: rustdoc treats unlabeled blocks and
+/// attribute-only fences (`no_run`, `ignore`, ...) as Rust.
+fn language_label(language: &str) -> &str {
+    let first = language.split(',').next().unwrap_or(language).trim();
+    if is_rust_language(first) {
+        "rust"
+    } else {
+        first
+    }
+}
+
+/// Line number gutter text, right-aligned to `width` digits.
+fn gutter(line_number: usize, width: usize) -> String {
+    format!("{:>width$} \u{2502} ", line_number)
+}
+
 /// Format code without syntax highlighting (plain text).
-fn format_plain(code: &str) -> String {
+fn format_plain(code: &str, gutter_width: Option<usize>) -> String {
     let mut output = String::new();
-    for line in code.lines() {
+    for (index, line) in code.lines().enumerate() {
         output.push_str("  ");
+        if let Some(width) = gutter_width {
+            output.push_str(&gutter(index + 1, width));
+        }
         output.push_str(line);
         output.push('\n');
     }
@@ -409,7 +661,7 @@ fn format_plain(code: &str) -> String {
 }
 
 /// Check if the language identifier indicates Rust.
-fn is_rust_language(lang: &str) -> bool {
+pub(crate) fn is_rust_language(lang: &str) -> bool {
     // Handle common rustdoc language annotations
     let lang_lower = lang.to_lowercase();
     matches!(
@@ -428,7 +680,7 @@ fn is_rust_language(lang: &str) -> bool {
 /// - `##` = escape (shows single `#`)
 /// - `#!` = NOT hidden (inner attribute like `#![allow(...)]`)
 /// - `#[` = NOT hidden (outer attribute like `#[derive(...)]`)
-fn process_rust_hidden_lines(code: &str) -> String {
+pub(crate) fn process_rust_hidden_lines(code: &str) -> String {
     code.lines()
         .filter_map(|line| {
             let trimmed = line.trim_start();
@@ -513,19 +765,69 @@ mod tests {
     #[test]
     fn test_format_plain() {
         let code = "let x = 1;\nlet y = 2;";
-        let result = format_plain(code);
+        let result = format_plain(code, None);
         assert_eq!(result, "  let x = 1;\n  let y = 2;\n");
     }
 
+    #[test]
+    fn test_format_plain_line_numbers() {
+        let code = (1..=10)
+            .map(|i| format!("let x{i} = {i};"))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let result = format_plain(&code, Some(2));
+        let lines: Vec<_> = result.lines().collect();
+        assert_eq!(lines[0], "   1 \u{2502} let x1 = 1;");
+        assert_eq!(lines[9], "  10 \u{2502} let x10 = 10;");
+    }
+
+    #[test]
+    fn test_language_label() {
+        assert_eq!(language_label(""), "rust");
+        assert_eq!(language_label("rust,no_run"), "rust");
+        assert_eq!(language_label("toml"), "toml");
+        assert_eq!(language_label("json,ignore"), "json");
+    }
+
+    #[test]
+    fn test_code_frame_no_colors() {
+        colored::control::set_override(false);
+        let label = Colorizer::with_default_theme(true).with_code_frame(CodeFrame::Label);
+        assert_eq!(
+            label.code_block("[package]", "toml"),
+            "  \u{2500}\u{2500} toml\n  [package]\n"
+        );
+        let boxed = Colorizer::with_default_theme(true)
+            .with_code_frame(CodeFrame::Box)
+            .with_line_numbers(Some(1));
+        assert_eq!(
+            boxed.code_block("a\nb", "text"),
+            "  \u{250c}\u{2500} text\n  \u{2502} 1 \u{2502} a\n  \u{2502} 2 \u{2502} b\n  \u{2514}\u{2500}\n"
+        );
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_line_numbers_threshold() {
+        colored::control::set_override(false);
+        let colorizer = Colorizer::with_default_theme(true).with_line_numbers(Some(3));
+        assert_eq!(colorizer.code_block("a\nb", "text"), "  a\n  b\n");
+        assert_eq!(
+            colorizer.code_block("a\nb\nc", "text"),
+            "  1 \u{2502} a\n  2 \u{2502} b\n  3 \u{2502} c\n"
+        );
+        colored::control::unset_override();
+    }
+
     #[test]
     fn test_colorizer_tokens_no_colors() {
         colored::control::set_override(false);
         let colorizer = Colorizer::get();
         let tokens = vec![
-            Token::Keyword("fn".to_string()),
+            Token::Keyword("fn".into()),
             Token::Whitespace,
-            Token::Function("main".to_string()),
-            Token::Symbol("()".to_string()),
+            Token::Function("main".into()),
+            Token::Symbol("()".into()),
         ];
         let result = colorizer.tokens(&tokens);
         assert_eq!(result, "fn main()");
@@ -551,11 +853,95 @@ mod tests {
         colored::control::unset_override();
     }
 
+    #[test]
+    fn test_with_theme_uses_given_theme() {
+        colored::control::set_override(true);
+        let theme = THEME_SET.themes["Solarized (light)"].clone();
+        let expected = ColorScheme::from_theme(&theme).keyword;
+        let colorizer = Colorizer::with_theme(theme, false);
+        let result = colorizer.tokens(&[Token::Keyword("fn".into())]);
+        assert_eq!(
+            result,
+            format!(
+                "\x1b[38;2;{};{};{}mfn\x1b[0m",
+                expected.r, expected.g, expected.b
+            )
+        );
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_color_depth_quantizes_tokens() {
+        colored::control::set_override(true);
+        let colorizer = Colorizer::with_default_theme(true).with_color_depth(ColorDepth::Ansi256);
+        let result = colorizer.tokens(&[Token::Keyword("fn".into())]);
+        assert!(result.starts_with("\x1b[38;5;"), "got: {:?}", result);
+        let result = colorizer.code_block("fn main() {}", "rust");
+        assert!(!result.contains("38;2;"), "got: {:?}", result);
+        colored::control::unset_override();
+    }
+
+    #[test]
+    fn test_syntect_signatures() {
+        let tokens = vec![
+            Token::Keyword("fn".into()),
+            Token::Whitespace,
+            Token::Function("main".into()),
+            Token::Symbol("()".into()),
+        ];
+        let colorizer = Colorizer::with_default_theme(true).with_syntect_signatures(true);
+
+        colored::control::set_override(true);
+        let highlighted = colorizer.tokens(&tokens);
+        colored::control::set_override(false);
+        let plain = colorizer.tokens(&tokens);
+        colored::control::unset_override();
+
+        assert!(
+            highlighted.starts_with("\x1b[38;2;"),
+            "got: {:?}",
+            highlighted
+        );
+        assert!(highlighted.ends_with("\x1b[0m"));
+        assert_eq!(plain, "fn main()");
+    }
+
+    #[test]
+    fn test_theme_is_dark() {
+        assert_eq!(theme_is_dark(&THEME_SET.themes[DARK_THEME]), Some(true));
+        assert_eq!(theme_is_dark(&THEME_SET.themes[LIGHT_THEME]), Some(false));
+        assert_eq!(theme_is_dark(&Theme::default()), None);
+    }
+
+    #[test]
+    fn test_with_bundled_theme() {
+        assert!(Colorizer::bundled_themes().any(|name| name == "Solarized (dark)"));
+        let colorizer = Colorizer::with_bundled_theme("Solarized (dark)").unwrap();
+        assert!(colorizer.is_dark);
+        let colorizer = Colorizer::with_bundled_theme("base16-ocean.light").unwrap();
+        assert!(!colorizer.is_dark);
+        assert!(Colorizer::with_bundled_theme("no-such-theme").is_none());
+    }
+
+    #[test]
+    fn test_from_theme_file_missing() {
+        assert!(Colorizer::from_theme_file("/nonexistent/theme.tmTheme").is_err());
+    }
+
+    #[test]
+    fn test_with_default_theme_heading_contrast() {
+        colored::control::set_override(true);
+        let dark = Colorizer::with_default_theme(true).heading("Title", 1);
+        let light = Colorizer::with_default_theme(false).heading("Title", 1);
+        assert_ne!(dark, light);
+        colored::control::unset_override();
+    }
+
     #[test]
     fn test_colorizer_with_colors() {
         colored::control::set_override(true);
         let colorizer = Colorizer::get();
-        let tokens = vec![Token::Keyword("fn".to_string())];
+        let tokens = vec![Token::Keyword("fn".into())];
         let result = colorizer.tokens(&tokens);
         // Should contain ANSI escape codes
         assert!(
@@ -3,17 +3,36 @@
 //! This crate provides:
 //! - [`Token`] and [`Output`] for building syntax-colored token sequences
 //! - [`Colorizer`] for terminal styling and syntax highlighting
+//! - [`ColorDepth`] for truecolor/256/16-color output
 //! - [`format_markdown`] for rendering markdown to terminal output
+//! - [`code_examples`] for extracting the Rust examples from markdown
+//! - [`collect_links`] for listing the links in markdown
 //! - [`LinkResolver`] trait for custom link resolution
+//! - [`display_width`] and friends for measuring styled text
+//! - [`wrap_signature`] for breaking long signatures across lines
 
+mod color_depth;
 mod colorizer;
 mod link_resolver;
 mod markdown;
 mod output;
+mod roff;
 mod tokens;
+mod width;
+mod wrap;
 
-pub use colorizer::Colorizer;
-pub use link_resolver::{DefaultLinkResolver, LinkResolver};
-pub use markdown::format_markdown;
+pub use color_depth::ColorDepth;
+pub use colorizer::{CodeFrame, Colorizer};
+pub use link_resolver::{
+    DefaultLinkResolver, IntraDocLinkResolver, Link, LinkResolution, LinkResolver,
+};
+pub use markdown::{
+    DocLink, DocLinkKind, code_examples, collect_links, format_markdown, format_markdown_with,
+    rewrite_links,
+};
 pub use output::Output;
+pub use roff::{escape_roff, markdown_to_roff};
+pub use syntect::highlighting::Theme;
 pub use tokens::{Token, tokens_to_string};
+pub use width::{display_width, pad_to_width, strip_ansi, truncate_to_width};
+pub use wrap::wrap_signature;
@@ -1,10 +1,17 @@
 //! Trait for resolving documentation links.
 
+use std::fmt;
+
 /// Trait for resolving documentation links in markdown.
 ///
 /// Implement this trait to provide custom link resolution logic,
-/// such as resolving intra-doc links to fully qualified paths.
+/// such as resolving intra-doc links to fully qualified paths. Context about
+/// the item whose docs are formatted, like rustdoc's map of its intra-doc
+/// links, is kept by the resolver.
 pub trait LinkResolver {
+    /// Resolve a link to what is shown for it.
+    fn resolve(&self, link: &Link<'_>) -> LinkResolution;
+
     /// Resolve a link to display text.
     ///
     /// # Arguments
@@ -13,22 +20,189 @@ pub trait LinkResolver {
     ///
     /// # Returns
     /// The text to display (may include URL for external links)
-    fn resolve_link(&self, link_text: &str, dest_url: &str) -> String;
+    fn resolve_link(&self, link_text: &str, dest_url: &str) -> String {
+        self.resolve(&Link::new(link_text, dest_url)).to_string()
+    }
+
+    /// Whether `reference`, the target of a shortcut or reference-style link
+    /// without a definition such as ``[`Vec`]``, is an intra-doc link this
+    /// resolver knows.
+    ///
+    /// Those are passed to [`resolve`](Self::resolve) with `reference` as
+    /// the destination; other undefined links keep their brackets.
+    fn is_intra_doc_link(&self, _reference: &str) -> bool {
+        false
+    }
+}
+
+/// A link in markdown, as passed to [`LinkResolver::resolve`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Link<'a> {
+    /// The visible text of the link
+    pub text: &'a str,
+    /// The destination as written, e.g. a URL, ``[`Vec`]`` or `fn@Vec::push`
+    pub dest: &'a str,
+    /// The namespace an intra-doc link is disambiguated to: `fn` for
+    /// `fn@push` and `push()`, `macro` for `macro@vec` and `vec!`, ...
+    pub disambiguator: Option<&'a str>,
+    /// The item path of an intra-doc link, without backticks and
+    /// disambiguator, e.g. `Vec::push`
+    pub target: &'a str,
+}
+
+impl<'a> Link<'a> {
+    /// The link with text `text` to `dest`, with the disambiguator and
+    /// target of `dest` taken apart.
+    pub fn new(text: &'a str, dest: &'a str) -> Self {
+        let target = dest.trim().trim_matches('`');
+        let (disambiguator, target) = match target.split_once('@') {
+            Some((prefix, rest))
+                if !prefix.is_empty() && prefix.chars().all(|c| c.is_ascii_lowercase()) =>
+            {
+                (Some(prefix), rest)
+            }
+            _ => {
+                if let Some(function) = target.strip_suffix("()") {
+                    (Some("fn"), function)
+                } else if let Some(rest) = ["!()", "![]", "!{}", "!"]
+                    .iter()
+                    .find_map(|suffix| target.strip_suffix(suffix))
+                {
+                    (Some("macro"), rest)
+                } else {
+                    (None, target)
+                }
+            }
+        };
+        Self {
+            text,
+            dest,
+            disambiguator,
+            target,
+        }
+    }
+
+    /// The text shown for the link. A disambiguator prefix is left out when
+    /// the text repeats the destination, as rustdoc does: ``[`fn@push`]`` is
+    /// shown as `push`, while `push()` and `vec!` keep their suffixes.
+    pub fn display_text(&self) -> &'a str {
+        match self.text.split_once('@') {
+            Some((prefix, rest))
+                if self.text == self.dest.trim().trim_matches('`')
+                    && !prefix.is_empty()
+                    && prefix.chars().all(|c| c.is_ascii_lowercase()) =>
+            {
+                rest
+            }
+            _ => self.text,
+        }
+    }
+
+    /// Whether the link points to a web page, with an http or https URL.
+    pub fn is_external(&self) -> bool {
+        self.dest.starts_with("http://") || self.dest.starts_with("https://")
+    }
+}
+
+/// What is shown for a link: its text, followed by its URL when it has one,
+/// like "text (url)".
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkResolution {
+    /// The text shown for the link
+    pub text: String,
+    /// URL of the page the link points to
+    pub url: Option<String>,
+    /// The fully qualified path of the item an intra-doc link points to
+    pub item_path: Option<String>,
+}
+
+impl LinkResolution {
+    /// A link shown as `text` alone.
+    pub fn text(text: impl Into<String>) -> Self {
+        Self {
+            text: text.into(),
+            ..Self::default()
+        }
+    }
+
+    /// Show the link with `url` after its text.
+    pub fn with_url(mut self, url: impl Into<String>) -> Self {
+        self.url = Some(url.into());
+        self
+    }
+
+    /// Record `path` as the item the link points to.
+    pub fn with_item_path(mut self, path: impl Into<String>) -> Self {
+        self.item_path = Some(path.into());
+        self
+    }
+}
+
+impl fmt::Display for LinkResolution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.url {
+            Some(url) => write!(f, "{} ({url})", self.text),
+            None => f.write_str(&self.text),
+        }
+    }
 }
 
 /// Default resolver that formats external URLs and returns text as-is for others.
 ///
 /// - External URLs (http/https) are formatted as "text (url)"
+/// - Shorthand intra-doc links that look like item paths, ``[`Vec`]`` or
+///   `[Vec::push]`, are shown as their text; see
+///   [`with_intra_doc_links`](DefaultLinkResolver::with_intra_doc_links) to
+///   resolve them
 /// - All other links return the link text unchanged
+#[derive(Default)]
 pub struct DefaultLinkResolver;
 
+impl DefaultLinkResolver {
+    /// A resolver showing intra-doc links as what `resolve` returns for
+    /// their [target](Link::target), e.g. `Vec::push`.
+    ///
+    /// Shorthand links are only taken as intra-doc links when `resolve`
+    /// knows them, so text like `[1]` keeps its brackets.
+    pub fn with_intra_doc_links<F: Fn(&str) -> Option<String>>(
+        resolve: F,
+    ) -> IntraDocLinkResolver<F> {
+        IntraDocLinkResolver { resolve }
+    }
+}
+
 impl LinkResolver for DefaultLinkResolver {
-    fn resolve_link(&self, link_text: &str, dest_url: &str) -> String {
-        if dest_url.starts_with("http://") || dest_url.starts_with("https://") {
-            format!("{link_text} ({dest_url})")
-        } else {
-            link_text.to_string()
+    fn resolve(&self, link: &Link<'_>) -> LinkResolution {
+        if link.is_external() {
+            return LinkResolution::text(link.text).with_url(link.dest);
+        }
+        LinkResolution::text(link.display_text())
+    }
+
+    fn is_intra_doc_link(&self, reference: &str) -> bool {
+        let link = Link::new("", reference);
+        (reference.starts_with('`') && !link.target.is_empty()) || link.target.contains("::")
+    }
+}
+
+/// [`DefaultLinkResolver`] with a callback resolving intra-doc links, made by
+/// [`DefaultLinkResolver::with_intra_doc_links`].
+pub struct IntraDocLinkResolver<F> {
+    resolve: F,
+}
+
+impl<F: Fn(&str) -> Option<String>> LinkResolver for IntraDocLinkResolver<F> {
+    fn resolve(&self, link: &Link<'_>) -> LinkResolution {
+        if !link.is_external()
+            && let Some(path) = (self.resolve)(link.target)
+        {
+            return LinkResolution::text(path.clone()).with_item_path(path);
         }
+        DefaultLinkResolver.resolve(link)
+    }
+
+    fn is_intra_doc_link(&self, reference: &str) -> bool {
+        (self.resolve)(Link::new("", reference).target).is_some()
     }
 }
 
@@ -63,4 +237,67 @@ mod tests {
         let result = resolver.resolve_link("text", "");
         assert_eq!(result, "text");
     }
+
+    #[test]
+    fn test_shorthand_links() {
+        let resolver = DefaultLinkResolver;
+        assert!(resolver.is_intra_doc_link("`Vec`"));
+        assert!(resolver.is_intra_doc_link("Vec::push"));
+        assert!(!resolver.is_intra_doc_link("1"));
+        assert_eq!(resolver.resolve_link("Vec", "`Vec`"), "Vec");
+    }
+
+    #[test]
+    fn test_intra_doc_callback() {
+        let resolver = DefaultLinkResolver::with_intra_doc_links(|target: &str| {
+            (target == "Vec::push").then(|| "std::vec::Vec::push".to_string())
+        });
+        assert!(resolver.is_intra_doc_link("Vec::push"));
+        assert!(!resolver.is_intra_doc_link("`Missing`"));
+        assert_eq!(
+            resolver.resolve_link("push", "Vec::push"),
+            "std::vec::Vec::push"
+        );
+        assert_eq!(resolver.resolve_link("Missing", "Missing"), "Missing");
+    }
+
+    #[test]
+    fn test_disambiguators() {
+        let link = Link::new("push", "`fn@Vec::push`");
+        assert_eq!(link.disambiguator, Some("fn"));
+        assert_eq!(link.target, "Vec::push");
+        assert_eq!(Link::new("", "Vec::new()").disambiguator, Some("fn"));
+        assert_eq!(Link::new("", "vec!").target, "vec");
+        assert_eq!(Link::new("", "macro@vec").disambiguator, Some("macro"));
+        assert_eq!(Link::new("", "Vec").disambiguator, None);
+        assert_eq!(Link::new("", "mailto:a@b.c").disambiguator, None);
+    }
+
+    #[test]
+    fn test_display_text_drops_disambiguator() {
+        assert_eq!(
+            Link::new("method@len", "`method@len`").display_text(),
+            "len"
+        );
+        assert_eq!(Link::new("fn@push", "fn@push").display_text(), "push");
+        assert_eq!(Link::new("push()", "`push()`").display_text(), "push()");
+        assert_eq!(Link::new("a@b", "struct@B").display_text(), "a@b");
+        let resolver = DefaultLinkResolver;
+        assert_eq!(resolver.resolve_link("macro@vec", "`macro@vec`"), "vec");
+    }
+
+    #[test]
+    fn test_structured_resolution() {
+        let resolver = DefaultLinkResolver::with_intra_doc_links(|target: &str| {
+            (target == "Vec").then(|| "std::vec::Vec".to_string())
+        });
+        assert_eq!(
+            resolver.resolve(&Link::new("Vec", "struct@Vec")),
+            LinkResolution::text("std::vec::Vec").with_item_path("std::vec::Vec")
+        );
+        assert_eq!(
+            resolver.resolve(&Link::new("docs", "https://docs.rs")),
+            LinkResolution::text("docs").with_url("https://docs.rs")
+        );
+    }
 }
@@ -1,9 +1,11 @@
 //! Formats markdown documentation for terminal display with ANSI colors.
 
-use pulldown_cmark::{CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+use std::ops::Range;
 
-use crate::colorizer::Colorizer;
-use crate::link_resolver::LinkResolver;
+use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+use crate::colorizer::{Colorizer, is_rust_language, process_rust_hidden_lines};
+use crate::link_resolver::{DefaultLinkResolver, Link, LinkResolver};
 
 /// Formats markdown documentation for terminal display.
 ///
@@ -15,12 +17,31 @@ use crate::link_resolver::LinkResolver;
 /// - Code blocks are syntax highlighted
 /// - Lists use bullet points
 /// - Block quotes use `│` prefix
+///
+/// Styling uses the global [`Colorizer::get`] instance; see
+/// [`format_markdown_with`] to supply a specific colorizer.
 pub fn format_markdown(docs: &str, resolver: &impl LinkResolver) -> String {
+    format_markdown_with(docs, resolver, Colorizer::get())
+}
+
+/// Formats markdown documentation for terminal display using the given colorizer.
+pub fn format_markdown_with<'a>(
+    docs: &'a str,
+    resolver: &impl LinkResolver,
+    colorizer: &Colorizer,
+) -> String {
     let mut options = Options::empty();
     options.insert(Options::ENABLE_STRIKETHROUGH);
 
-    let parser = Parser::new_ext(docs, options);
-    let mut formatter = MarkdownFormatter::new(resolver);
+    // Intra-doc links like [`Vec`] have no definition in the markdown
+    let mut intra_doc_links = |link: BrokenLink<'a>| {
+        resolver
+            .is_intra_doc_link(&link.reference)
+            .then(|| (link.reference, "".into()))
+    };
+    let parser = Parser::new_with_broken_link_callback(docs, options, Some(&mut intra_doc_links));
+    let mut formatter = MarkdownFormatter::new(resolver, colorizer);
+    formatter.headings = headings(docs);
 
     for event in parser {
         formatter.process_event(event);
@@ -29,9 +50,232 @@ pub fn format_markdown(docs: &str, resolver: &impl LinkResolver) -> String {
     formatter.finish()
 }
 
+/// The headings of markdown documentation, in order, by the anchor rustdoc
+/// gives them and their text.
+fn headings(docs: &str) -> Vec<(String, String)> {
+    let mut headings = Vec::new();
+    let mut current: Option<String> = None;
+    for event in Parser::new(docs) {
+        match event {
+            Event::Start(Tag::Heading { .. }) => current = Some(String::new()),
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(heading) = &mut current {
+                    heading.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Heading(_)) => {
+                if let Some(heading) = current.take() {
+                    headings.push((heading_anchor(&heading), heading));
+                }
+            }
+            _ => {}
+        }
+    }
+    headings
+}
+
+/// The anchor rustdoc gives a heading with `text`, e.g. `examples` for
+/// "Examples".
+fn heading_anchor(text: &str) -> String {
+    text.chars()
+        .filter_map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                Some(c.to_ascii_lowercase())
+            } else if c.is_ascii_whitespace() {
+                Some('-')
+            } else {
+                None
+            }
+        })
+        .collect()
+}
+
+/// The Rust code examples in markdown documentation, in order, without the
+/// lines rustdoc hides, e.g. `# fn main() {`.
+pub fn code_examples(docs: &str) -> Vec<String> {
+    let mut examples = Vec::new();
+    let mut current: Option<String> = None;
+    for event in Parser::new(docs) {
+        match event {
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => lang.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                current = is_rust_language(&lang).then(String::new);
+            }
+            Event::Text(text) => {
+                if let Some(code) = &mut current {
+                    code.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(code) = current.take() {
+                    examples.push(process_rust_hidden_lines(&code));
+                }
+            }
+            _ => {}
+        }
+    }
+    examples
+}
+
+/// A link in markdown documentation, see [`collect_links`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DocLink {
+    /// The visible text of the link
+    pub text: String,
+    /// The destination as written, e.g. a URL or ``[`Vec`]``
+    pub target: String,
+    pub kind: DocLinkKind,
+}
+
+/// What a [`DocLink`] points to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DocLinkKind {
+    /// A web page, e.g. `https://example.com`
+    External,
+    /// An item, e.g. ``[`Vec`]`` or `[push](Vec::push)`
+    IntraDoc,
+    /// A heading of the same docs, e.g. `#examples`
+    Anchor,
+    /// An HTML docs page relative to the page of the docs, e.g.
+    /// `../struct.Foo.html`
+    Relative,
+}
+
+impl DocLinkKind {
+    fn of(target: &str) -> Self {
+        if target.contains("://") || target.starts_with("mailto:") {
+            DocLinkKind::External
+        } else if target.starts_with('#') {
+            DocLinkKind::Anchor
+        } else if target.starts_with("./")
+            || target.starts_with("../")
+            || target
+                .split('#')
+                .next()
+                .is_some_and(|page| page.ends_with(".html"))
+        {
+            DocLinkKind::Relative
+        } else {
+            DocLinkKind::IntraDoc
+        }
+    }
+}
+
+/// The links in markdown documentation, in order, including shorthand
+/// intra-doc links like ``[`Vec`]`` that look like item paths.
+pub fn collect_links(docs: &str) -> Vec<DocLink> {
+    let resolver = DefaultLinkResolver;
+    let mut intra_doc_links = |link: BrokenLink<'_>| {
+        resolver
+            .is_intra_doc_link(&link.reference)
+            .then(|| (link.reference.to_string().into(), "".into()))
+    };
+    let parser = Parser::new_with_broken_link_callback(
+        docs,
+        Options::ENABLE_STRIKETHROUGH,
+        Some(&mut intra_doc_links),
+    );
+
+    let mut links = Vec::new();
+    let mut current: Option<DocLink> = None;
+    for event in parser {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                current = Some(DocLink {
+                    text: String::new(),
+                    kind: DocLinkKind::of(&dest_url),
+                    target: dest_url.to_string(),
+                });
+            }
+            Event::Text(text) | Event::Code(text) => {
+                if let Some(link) = &mut current {
+                    link.text.push_str(&text);
+                }
+            }
+            Event::End(TagEnd::Link) => links.extend(current.take().map(|mut link| {
+                link.text = Link::new(&link.text, &link.target)
+                    .display_text()
+                    .to_string();
+                link
+            })),
+            _ => {}
+        }
+    }
+    links
+}
+
+/// Markdown documentation with the links that `rewrite` returns a
+/// destination for written as `[text](destination)`, e.g. to point intra-doc
+/// links at files. Other links, and everything else, are left as written.
+pub fn rewrite_links(docs: &str, mut rewrite: impl FnMut(&DocLink) -> Option<String>) -> String {
+    let resolver = DefaultLinkResolver;
+    let mut intra_doc_links = |link: BrokenLink<'_>| {
+        resolver
+            .is_intra_doc_link(&link.reference)
+            .then(|| (link.reference.to_string().into(), "".into()))
+    };
+    let parser = Parser::new_with_broken_link_callback(
+        docs,
+        Options::ENABLE_STRIKETHROUGH,
+        Some(&mut intra_doc_links),
+    );
+
+    let mut output = String::with_capacity(docs.len());
+    let mut copied = 0;
+    // The link being read, the span of all of it, and that of its text
+    let mut current: Option<(DocLink, Range<usize>, Option<Range<usize>>)> = None;
+    for (event, span) in parser.into_offset_iter() {
+        match event {
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                let link = DocLink {
+                    text: String::new(),
+                    kind: DocLinkKind::of(&dest_url),
+                    target: dest_url.to_string(),
+                };
+                current = Some((link, span, None));
+            }
+            Event::End(TagEnd::Link) => {
+                let Some((mut link, link_span, text_span)) = current.take() else {
+                    continue;
+                };
+                link.text = Link::new(&link.text, &link.target)
+                    .display_text()
+                    .to_string();
+                if link_span.start < copied {
+                    continue;
+                }
+                if let Some(destination) = rewrite(&link) {
+                    let text = text_span.map_or(link.text.as_str(), |span| &docs[span]);
+                    output.push_str(&docs[copied..link_span.start]);
+                    output.push_str(&format!("[{text}]({destination})"));
+                    copied = link_span.end;
+                }
+            }
+            event => {
+                if let Some((link, _, text_span)) = &mut current {
+                    if let Event::Text(text) | Event::Code(text) = &event {
+                        link.text.push_str(text);
+                    }
+                    *text_span = Some(match text_span.take() {
+                        Some(text_span) => {
+                            text_span.start.min(span.start)..text_span.end.max(span.end)
+                        }
+                        None => span,
+                    });
+                }
+            }
+        }
+    }
+    output.push_str(&docs[copied..]);
+    output
+}
+
 struct MarkdownFormatter<'a, R: LinkResolver> {
     output: String,
-    colorizer: &'static Colorizer,
+    colorizer: &'a Colorizer,
     resolver: &'a R,
 
     // State tracking
@@ -52,13 +296,16 @@ struct MarkdownFormatter<'a, R: LinkResolver> {
     block_quote_text: String,
     list_depth: usize,
     list_stack: Vec<(bool, u64)>, // (is_ordered, current_index)
+    /// Anchors and text of the headings of the docs, for `#section` links
+    headings: Vec<(String, String)>,
+    headings_seen: usize,
 }
 
 impl<'a, R: LinkResolver> MarkdownFormatter<'a, R> {
-    fn new(resolver: &'a R) -> Self {
+    fn new(resolver: &'a R, colorizer: &'a Colorizer) -> Self {
         Self {
             output: String::new(),
-            colorizer: Colorizer::get(),
+            colorizer,
             resolver,
             in_link: false,
             link_text: String::new(),
@@ -77,6 +324,8 @@ impl<'a, R: LinkResolver> MarkdownFormatter<'a, R> {
             block_quote_text: String::new(),
             list_depth: 0,
             list_stack: Vec::new(),
+            headings: Vec::new(),
+            headings_seen: 0,
         }
     }
 
@@ -89,9 +338,13 @@ impl<'a, R: LinkResolver> MarkdownFormatter<'a, R> {
                 self.current_dest_url = dest_url.to_string();
             }
             Event::End(TagEnd::Link) => {
-                let resolved = self
-                    .resolver
-                    .resolve_link(&self.link_text, &self.current_dest_url);
+                let resolved = match self.section_hint() {
+                    Some(hint) => hint,
+                    None => self
+                        .resolver
+                        .resolve(&Link::new(&self.link_text, &self.current_dest_url))
+                        .to_string(),
+                };
                 self.push_text(&resolved);
                 self.in_link = false;
             }
@@ -108,6 +361,7 @@ impl<'a, R: LinkResolver> MarkdownFormatter<'a, R> {
                     .push_str(&self.colorizer.heading(&text, self.heading_level));
                 self.output.push_str("\n\n");
                 self.in_heading = false;
+                self.headings_seen += 1;
             }
 
             // Code blocks
@@ -306,6 +560,28 @@ impl<'a, R: LinkResolver> MarkdownFormatter<'a, R> {
         }
     }
 
+    /// For a `#section` link to a heading of the docs, the link text with
+    /// where to find that section, as the fragment means nothing in a
+    /// terminal: "text (see the Examples section below)".
+    fn section_hint(&self) -> Option<String> {
+        let anchor = self.current_dest_url.strip_prefix('#')?;
+        let position = self.headings.iter().position(|(id, _)| id == anchor)?;
+        let heading = &self.headings[position].1;
+        let direction = if position < self.headings_seen {
+            "above"
+        } else {
+            "below"
+        };
+        if self.link_text.eq_ignore_ascii_case(heading) {
+            Some(format!("the {heading} section {direction}"))
+        } else {
+            Some(format!(
+                "{} (see the {heading} section {direction})",
+                self.link_text
+            ))
+        }
+    }
+
     fn push_text(&mut self, text: &str) {
         if self.in_block_quote {
             self.block_quote_text.push_str(text);
@@ -380,6 +656,12 @@ mod tests {
         assert_eq!(result, "  let x = 1;");
     }
 
+    #[test]
+    fn test_code_examples_skip_other_languages() {
+        let docs = "Setup:\n\n```toml\ntokio = \"1\"\n```\n\n```rust,no_run\n# use tokio::task;\ntask::spawn(work());\n```\n\n```\nlet x = 1;\n```";
+        assert_eq!(code_examples(docs), ["task::spawn(work());", "let x = 1;"]);
+    }
+
     #[test]
     fn test_unordered_list() {
         let result = format_test("- first\n- second");
@@ -415,4 +697,41 @@ mod tests {
         let result = format_test("[docs](https://docs.rs)");
         assert_eq!(result, "docs (https://docs.rs)");
     }
+
+    #[test]
+    fn test_collect_links() {
+        let links = collect_links(
+            "See [`Vec`], [push](Vec::push), [the site](https://example.com),\n\
+             [Examples](#examples), [Foo](../struct.Foo.html), [`method@Vec::len`] and [1].",
+        );
+        let found: Vec<(&str, &str, DocLinkKind)> = links
+            .iter()
+            .map(|link| (link.text.as_str(), link.target.as_str(), link.kind))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("Vec", "`Vec`", DocLinkKind::IntraDoc),
+                ("push", "Vec::push", DocLinkKind::IntraDoc),
+                ("the site", "https://example.com", DocLinkKind::External),
+                ("Examples", "#examples", DocLinkKind::Anchor),
+                ("Foo", "../struct.Foo.html", DocLinkKind::Relative),
+                ("Vec::len", "`method@Vec::len`", DocLinkKind::IntraDoc),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_rewrite_links() {
+        let docs = "See [`Vec`], [*a* push](Vec::push), [the site](https://example.com)\n\
+                    and [`Vec`][vec].\n\n[vec]: std::vec::Vec";
+        let rewritten = rewrite_links(docs, |link| {
+            (link.kind == DocLinkKind::IntraDoc).then(|| format!("{}.md", link.text))
+        });
+        assert_eq!(
+            rewritten,
+            "See [`Vec`](Vec.md), [*a* push](a push.md), [the site](https://example.com)\n\
+             and [`Vec`](Vec.md).\n\n[vec]: std::vec::Vec"
+        );
+    }
 }
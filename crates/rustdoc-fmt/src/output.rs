@@ -1,5 +1,6 @@
 //! Builder-style wrapper for constructing token sequences.
 
+use std::borrow::Cow;
 use std::fmt::Display;
 
 use super::tokens::Token;
@@ -17,19 +18,19 @@ impl Output {
     }
 
     /// Add a symbol token, like `=` or `::<`.
-    pub fn symbol(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn symbol(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Symbol(text.into()));
         self
     }
 
     /// Add a qualifier token, like `pub` or `const`.
-    pub fn qualifier(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn qualifier(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Qualifier(text.into()));
         self
     }
 
     /// Add a kind token, like `function` or `trait`.
-    pub fn kind(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn kind(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Kind(text.into()));
         self
     }
@@ -41,55 +42,55 @@ impl Output {
     }
 
     /// Add an identifier token, like variable names or parts of the path of an item.
-    pub fn identifier(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn identifier(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Identifier(text.into()));
         self
     }
 
     /// Add an annotation token, used e.g. for Rust attributes.
-    pub fn annotation(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn annotation(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Annotation(text.into()));
         self
     }
 
     /// Add a self token, the text can be `self` or `Self`.
-    pub fn self_(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn self_(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Self_(text.into()));
         self
     }
 
     /// Add a function identifier token.
-    pub fn function(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn function(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Function(text.into()));
         self
     }
 
     /// Add a lifetime token including the apostrophe `'`, like `'a`.
-    pub fn lifetime(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn lifetime(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Lifetime(text.into()));
         self
     }
 
     /// Add a keyword token, like `impl`, `where`, or `dyn`.
-    pub fn keyword(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn keyword(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Keyword(text.into()));
         self
     }
 
     /// Add a generic parameter token, like `T`.
-    pub fn generic(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn generic(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Generic(text.into()));
         self
     }
 
     /// Add a primitive type token, like `usize`.
-    pub fn primitive(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn primitive(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Primitive(text.into()));
         self
     }
 
     /// Add a type token, like the name of a struct or a trait.
-    pub fn type_(&mut self, text: impl Into<String>) -> &mut Self {
+    pub fn type_(&mut self, text: impl Into<Cow<'static, str>>) -> &mut Self {
         self.tokens.push(Token::Type(text.into()));
         self
     }
@@ -130,6 +131,13 @@ impl Output {
         self
     }
 
+    /// Add " { .. }", the body of an item left out.
+    pub fn symbol_elided_body(mut self) -> Self {
+        self.whitespace().symbol("{").whitespace().symbol("..");
+        self.whitespace().symbol("}");
+        self
+    }
+
     /// Add ", " (symbol + whitespace).
     pub fn symbol_comma(mut self) -> Self {
         self.symbol(",").whitespace();
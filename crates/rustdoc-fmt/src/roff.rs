@@ -0,0 +1,235 @@
+//! Converts markdown documentation to roff, for man pages.
+
+use pulldown_cmark::{BrokenLink, CodeBlockKind, Event, Options, Parser, Tag, TagEnd};
+
+use crate::colorizer::{is_rust_language, process_rust_hidden_lines};
+use crate::link_resolver::{DefaultLinkResolver, Link, LinkResolver};
+
+/// Converts markdown documentation to the body of a man page section, using
+/// the `man` macros:
+/// - Headings become `.SS` subsections
+/// - `**bold**` and `` `code` `` become bold, `*italic*` italic
+/// - Code blocks are indented and not filled, without the lines rustdoc
+///   hides
+/// - Lists use `.IP` with bullets or numbers
+/// - Links show their text, and the URL after it for web pages
+pub fn markdown_to_roff(docs: &str) -> String {
+    let resolver = DefaultLinkResolver;
+    let mut intra_doc_links = |link: BrokenLink<'_>| {
+        resolver
+            .is_intra_doc_link(&link.reference)
+            .then(|| (link.reference.to_string().into(), "".into()))
+    };
+    let parser = Parser::new_with_broken_link_callback(
+        docs,
+        Options::ENABLE_STRIKETHROUGH,
+        Some(&mut intra_doc_links),
+    );
+
+    let mut roff = RoffWriter::default();
+    // Ordered lists hold the number of their next item
+    let mut lists: Vec<Option<u64>> = Vec::new();
+    let mut code_block: Option<(String, String)> = None;
+    let mut link: Option<(String, String)> = None;
+    // The first paragraph of a list item goes on the `.IP` line's paragraph
+    let mut item_started = false;
+    for event in parser {
+        if let Some((lang, code)) = &mut code_block {
+            match event {
+                Event::Text(text) => code.push_str(&text),
+                Event::End(TagEnd::CodeBlock) => {
+                    let code = if is_rust_language(lang) {
+                        process_rust_hidden_lines(code)
+                    } else {
+                        code.clone()
+                    };
+                    roff.code_block(&code);
+                    code_block = None;
+                }
+                _ => {}
+            }
+            continue;
+        }
+        match event {
+            Event::Start(Tag::Heading { .. }) => roff.macro_line(".SS "),
+            Event::End(TagEnd::Heading(_)) => roff.end_line(),
+            Event::Start(Tag::Paragraph) => {
+                if !item_started {
+                    roff.macro_line(if lists.is_empty() { ".PP" } else { ".IP" });
+                    roff.end_line();
+                }
+                item_started = false;
+            }
+            Event::End(TagEnd::Paragraph) => roff.end_line(),
+            Event::Start(Tag::CodeBlock(kind)) => {
+                let lang = match kind {
+                    CodeBlockKind::Fenced(lang) => lang.to_string(),
+                    CodeBlockKind::Indented => String::new(),
+                };
+                code_block = Some((lang, String::new()));
+            }
+            Event::Start(Tag::List(first)) => {
+                if !lists.is_empty() {
+                    roff.macro_line(".RS");
+                    roff.end_line();
+                }
+                lists.push(first);
+            }
+            Event::End(TagEnd::List(_)) => {
+                lists.pop();
+                if !lists.is_empty() {
+                    roff.macro_line(".RE");
+                    roff.end_line();
+                }
+            }
+            Event::Start(Tag::Item) => {
+                let marker = match lists.last_mut() {
+                    Some(Some(number)) => {
+                        *number += 1;
+                        format!(".IP \"{}.\" 4", *number - 1)
+                    }
+                    _ => ".IP \\(bu 2".to_string(),
+                };
+                roff.macro_line(&marker);
+                roff.end_line();
+                item_started = true;
+            }
+            Event::End(TagEnd::Item) => {
+                roff.end_line();
+                item_started = false;
+            }
+            Event::Start(Tag::BlockQuote(_)) => {
+                roff.macro_line(".RS 4");
+                roff.end_line();
+            }
+            Event::End(TagEnd::BlockQuote(_)) => {
+                roff.macro_line(".RE");
+                roff.end_line();
+            }
+            Event::Start(Tag::Emphasis) => roff.font("\\fI"),
+            Event::Start(Tag::Strong) => roff.font("\\fB"),
+            Event::End(TagEnd::Emphasis | TagEnd::Strong) => roff.font("\\fR"),
+            Event::Start(Tag::Link { dest_url, .. }) => {
+                link = Some((dest_url.to_string(), String::new()));
+            }
+            Event::End(TagEnd::Link) => {
+                if let Some((url, text)) = link.take()
+                    && Link::new(&text, &url).is_external()
+                    && text != url
+                {
+                    roff.text(&format!(" <{url}>"));
+                }
+            }
+            Event::Text(text) => {
+                if let Some((_, link_text)) = &mut link {
+                    link_text.push_str(&text);
+                }
+                roff.text(&text);
+            }
+            Event::Code(code) => {
+                if let Some((_, link_text)) = &mut link {
+                    link_text.push_str(&code);
+                }
+                roff.font("\\fB");
+                roff.text(&code);
+                roff.font("\\fR");
+            }
+            Event::SoftBreak => roff.end_line(),
+            Event::HardBreak => {
+                roff.end_line();
+                roff.macro_line(".br");
+                roff.end_line();
+            }
+            _ => {}
+        }
+    }
+    roff.finish()
+}
+
+/// Text escaped for roff, with macros on lines of their own.
+#[derive(Default)]
+struct RoffWriter {
+    output: String,
+}
+
+impl RoffWriter {
+    fn at_line_start(&self) -> bool {
+        self.output.is_empty() || self.output.ends_with('\n')
+    }
+
+    fn end_line(&mut self) {
+        if !self.at_line_start() {
+            self.output.push('\n');
+        }
+    }
+
+    /// Start a line with a macro, e.g. `.PP`.
+    fn macro_line(&mut self, line: &str) {
+        self.end_line();
+        self.output.push_str(line);
+    }
+
+    fn font(&mut self, font: &str) {
+        self.output.push_str(font);
+    }
+
+    fn text(&mut self, text: &str) {
+        for (i, line) in text.split('\n').enumerate() {
+            if i > 0 {
+                self.end_line();
+            }
+            // A `.` or `'` starting a line would be read as a macro
+            if self.at_line_start() && (line.starts_with('.') || line.starts_with('\'')) {
+                self.output.push_str("\\&");
+            }
+            self.output.push_str(&escape_roff(line));
+        }
+    }
+
+    fn code_block(&mut self, code: &str) {
+        self.macro_line(".PP\n.RS 4\n.nf\n");
+        for line in code.trim_end().lines() {
+            self.text(line);
+            self.output.push('\n');
+        }
+        self.output.push_str(".fi\n.RE\n");
+    }
+
+    fn finish(self) -> String {
+        format!("{}\n", self.output.trim_end())
+    }
+}
+
+/// `text` with the characters roff treats specially escaped.
+pub fn escape_roff(text: &str) -> String {
+    text.replace('\\', "\\e").replace('-', "\\-")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paragraphs_and_fonts() {
+        let roff = markdown_to_roff("Some *emphasis*, **bold** and `code`.\n\nA second -- one.");
+        assert_eq!(
+            roff,
+            ".PP\nSome \\fIemphasis\\fR, \\fBbold\\fR and \\fBcode\\fR.\n.PP\nA second \\-\\- one.\n"
+        );
+    }
+
+    #[test]
+    fn test_code_block_hides_rust_lines() {
+        let roff = markdown_to_roff("```\n# fn main() {\n.lines()\n# }\n```");
+        assert_eq!(roff, ".PP\n.RS 4\n.nf\n\\&.lines()\n.fi\n.RE\n");
+    }
+
+    #[test]
+    fn test_lists_and_links() {
+        let roff = markdown_to_roff("# Usage\n\n- see [`Vec`]\n- the [site](https://example.com)");
+        assert_eq!(
+            roff,
+            ".SS Usage\n.IP \\(bu 2\nsee \\fBVec\\fR\n.IP \\(bu 2\nthe site <https://example.com>\n"
+        );
+    }
+}
@@ -1,34 +1,45 @@
 //! Contains all token handling logic.
 
+use std::borrow::Cow;
+
+use serde::Serialize;
+
 /// A token in a rendered public item, used to apply syntax coloring in downstream applications.
-#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+///
+/// The text is usually a literal like `::` or `where`, so it is kept as a
+/// [`Cow`] to only allocate for names.
+///
+/// Serializes as `{"kind": "type", "text": "String"}`.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize)]
+#[serde(tag = "kind", content = "text", rename_all = "snake_case")]
 pub enum Token {
     /// A symbol, like `=` or `::<`
-    Symbol(String),
+    Symbol(Cow<'static, str>),
     /// A qualifier, like `pub` or `const`
-    Qualifier(String),
+    Qualifier(Cow<'static, str>),
     /// The kind of an item, like `function` or `trait`
-    Kind(String),
+    Kind(Cow<'static, str>),
     /// Whitespace, a single space
     Whitespace,
     /// An identifier, like variable names or parts of the path of an item
-    Identifier(String),
+    Identifier(Cow<'static, str>),
     /// An annotation, used e.g. for Rust attributes.
-    Annotation(String),
+    Annotation(Cow<'static, str>),
     /// The identifier self, the text can be `self` or `Self`
-    Self_(String),
+    #[serde(rename = "self")]
+    Self_(Cow<'static, str>),
     /// The identifier for a function
-    Function(String),
+    Function(Cow<'static, str>),
     /// A lifetime including the apostrophe `'`, like `'a`
-    Lifetime(String),
+    Lifetime(Cow<'static, str>),
     /// A keyword, like `impl`, `where`, or `dyn`
-    Keyword(String),
+    Keyword(Cow<'static, str>),
     /// A generic parameter, like `T`
-    Generic(String),
+    Generic(Cow<'static, str>),
     /// A primitive type, like `usize`
-    Primitive(String),
+    Primitive(Cow<'static, str>),
     /// A non-primitive type, like the name of a struct or a trait
-    Type(String),
+    Type(Cow<'static, str>),
 }
 
 impl Token {
@@ -54,5 +65,28 @@ impl Token {
 
 /// Convert a slice of tokens to a single string.
 pub fn tokens_to_string(tokens: &[Token]) -> String {
-    tokens.iter().map(Token::text).collect()
+    let mut output = String::with_capacity(tokens.iter().map(|token| token.text().len()).sum());
+    for token in tokens {
+        output.push_str(token.text());
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_serialize_tokens() {
+        let tokens = vec![
+            Token::Kind("struct".into()),
+            Token::Whitespace,
+            Token::Self_("Self".into()),
+        ];
+        let json = serde_json::to_string(&tokens).unwrap();
+        assert_eq!(
+            json,
+            r#"[{"kind":"kind","text":"struct"},{"kind":"whitespace"},{"kind":"self","text":"Self"}]"#
+        );
+    }
 }
@@ -0,0 +1,171 @@
+//! Display-width measurement for styled terminal text.
+//!
+//! Output from the [`Colorizer`](crate::Colorizer) contains ANSI escape
+//! sequences, and docs may contain wide characters (CJK, emoji) that occupy
+//! two terminal columns. Byte or char counts misalign columns in both cases,
+//! so anything that pads, truncates or wraps text should use these helpers.
+
+use unicode_width::UnicodeWidthChar;
+
+/// Ellipsis appended to truncated text.
+const ELLIPSIS: char = '\u{2026}';
+
+/// A piece of styled text: either an escape sequence or a visible character.
+enum Segment<'a> {
+    Escape(&'a str),
+    Char(char),
+}
+
+/// Split text into escape sequences and visible characters.
+fn segments(text: &str) -> impl Iterator<Item = Segment<'_>> {
+    let mut rest = text;
+    std::iter::from_fn(move || {
+        let c = rest.chars().next()?;
+        if c == '\x1b' {
+            let len = escape_len(rest);
+            let (escape, tail) = rest.split_at(len);
+            rest = tail;
+            Some(Segment::Escape(escape))
+        } else {
+            rest = &rest[c.len_utf8()..];
+            Some(Segment::Char(c))
+        }
+    })
+}
+
+/// Byte length of the escape sequence at the start of `text`.
+///
+/// Handles CSI sequences (`ESC [ ... final`) and OSC sequences
+/// (`ESC ] ... BEL` or `ESC ] ... ESC \`), and otherwise treats ESC plus the
+/// following character as a two-character sequence.
+fn escape_len(text: &str) -> usize {
+    let bytes = text.as_bytes();
+    match bytes.get(1) {
+        Some(b'[') => bytes[2..]
+            .iter()
+            .position(|b| (0x40..=0x7e).contains(b))
+            .map_or(bytes.len(), |end| end + 3),
+        Some(b']') => {
+            let mut i = 2;
+            while i < bytes.len() {
+                if bytes[i] == 0x07 {
+                    return i + 1;
+                }
+                if bytes[i] == 0x1b && bytes.get(i + 1) == Some(&b'\\') {
+                    return i + 2;
+                }
+                i += 1;
+            }
+            bytes.len()
+        }
+        Some(_) => 1 + text[1..].chars().next().map_or(0, char::len_utf8),
+        None => 1,
+    }
+}
+
+/// Number of terminal columns `text` occupies, ignoring ANSI escapes.
+pub fn display_width(text: &str) -> usize {
+    segments(text)
+        .map(|segment| match segment {
+            Segment::Escape(_) => 0,
+            Segment::Char(c) => c.width().unwrap_or(0),
+        })
+        .sum()
+}
+
+/// Remove all ANSI escape sequences from `text`.
+pub fn strip_ansi(text: &str) -> String {
+    segments(text)
+        .filter_map(|segment| match segment {
+            Segment::Escape(_) => None,
+            Segment::Char(c) => Some(c),
+        })
+        .collect()
+}
+
+/// Truncate `text` to at most `max_width` columns, ending with `…` if cut.
+///
+/// Escape sequences are preserved, and a reset is appended when text that
+/// contained escapes is cut so styles don't leak past the truncation point.
+pub fn truncate_to_width(text: &str, max_width: usize) -> String {
+    if display_width(text) <= max_width {
+        return text.to_string();
+    }
+    if max_width == 0 {
+        return String::new();
+    }
+
+    let budget = max_width - 1; // room for the ellipsis
+    let mut result = String::new();
+    let mut width = 0;
+    let mut saw_escape = false;
+    for segment in segments(text) {
+        match segment {
+            Segment::Escape(escape) => {
+                saw_escape = true;
+                result.push_str(escape);
+            }
+            Segment::Char(c) => {
+                let char_width = c.width().unwrap_or(0);
+                if width + char_width > budget {
+                    break;
+                }
+                width += char_width;
+                result.push(c);
+            }
+        }
+    }
+    result.push(ELLIPSIS);
+    if saw_escape {
+        result.push_str("\x1b[0m");
+    }
+    result
+}
+
+/// Pad `text` with trailing spaces to `width` columns (never truncates).
+pub fn pad_to_width(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{text}{}", " ".repeat(padding))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display_width_ignores_escapes() {
+        assert_eq!(display_width("\x1b[38;2;1;2;3mfn\x1b[0m main"), 7);
+        assert_eq!(display_width("\x1b]8;;https://x\x07link\x1b]8;;\x07"), 4);
+    }
+
+    #[test]
+    fn test_display_width_wide_chars() {
+        assert_eq!(display_width("日本語"), 6);
+        assert_eq!(display_width("ok 🦀"), 5);
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        assert_eq!(strip_ansi("\x1b[1;38;5;196mbold\x1b[0m text"), "bold text");
+    }
+
+    #[test]
+    fn test_truncate_to_width() {
+        assert_eq!(truncate_to_width("short", 10), "short");
+        assert_eq!(truncate_to_width("abcdefgh", 5), "abcd\u{2026}");
+        // Wide characters are not split
+        assert_eq!(truncate_to_width("日本語", 4), "日\u{2026}");
+        assert_eq!(
+            truncate_to_width("\x1b[31mabcdef\x1b[0m", 3),
+            "\x1b[31mab\u{2026}\x1b[0m"
+        );
+        assert_eq!(truncate_to_width("abc", 0), "");
+    }
+
+    #[test]
+    fn test_pad_to_width() {
+        assert_eq!(pad_to_width("日本", 6), "日本  ");
+        assert_eq!(pad_to_width("\x1b[31mab\x1b[0m", 3), "\x1b[31mab\x1b[0m ");
+        assert_eq!(pad_to_width("toolong", 3), "toolong");
+    }
+}
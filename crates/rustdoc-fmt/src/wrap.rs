@@ -0,0 +1,204 @@
+//! Breaking long signatures across multiple lines.
+
+use crate::Token;
+use crate::tokens::tokens_to_string;
+use crate::width::display_width;
+
+/// Break a signature that doesn't fit in `max_width` columns, rustfmt-style:
+/// parameters go one per line and the where clause starts on its own line
+/// with one predicate per line. A body after the where clause, such as
+/// `{ .. }`, goes on a line of its own, and a closing `;` stays on the
+/// line of the last predicate.
+///
+/// `indent` is the column the signature starts at; continuation lines are
+/// indented relative to it. Signatures that fit, or that already span multiple
+/// lines, are returned unchanged. Line breaks are emitted as
+/// [`Token::Symbol`]s containing the newline and indentation.
+pub fn wrap_signature(tokens: &[Token], max_width: usize, indent: usize) -> Vec<Token> {
+    let flat = tokens_to_string(tokens);
+    if flat.contains('\n') || indent + display_width(&flat) <= max_width {
+        return tokens.to_vec();
+    }
+
+    let pad = " ".repeat(indent);
+    let line_break = || Token::Symbol(format!("\n{pad}").into());
+    let nested_break = || Token::Symbol(format!("\n{pad}    ").into());
+
+    let mut output = Vec::with_capacity(tokens.len() + 8);
+    let mut angle_depth = 0usize;
+    let mut paren_depth = 0usize;
+    let mut in_params = false;
+    let mut params_done = false;
+    let mut in_where = false;
+    let mut skip_whitespace = false;
+
+    for (index, token) in tokens.iter().enumerate() {
+        if skip_whitespace {
+            skip_whitespace = false;
+            if matches!(token, Token::Whitespace) {
+                continue;
+            }
+        }
+        let at_top = angle_depth == 0 && paren_depth == 0;
+        match token {
+            Token::Symbol(s) if s == "<" => angle_depth += 1,
+            Token::Symbol(s) if s == ">" => angle_depth = angle_depth.saturating_sub(1),
+            Token::Symbol(s) if s == "(" && at_top && !params_done && !in_where => {
+                paren_depth = 1;
+                output.push(token.clone());
+                let is_empty =
+                    matches!(tokens.get(index + 1), Some(Token::Symbol(next)) if next == ")");
+                if !is_empty {
+                    in_params = true;
+                    output.push(nested_break());
+                }
+                continue;
+            }
+            Token::Symbol(s) if s == "(" => paren_depth += 1,
+            Token::Symbol(s) if s == ")" && in_params && paren_depth == 1 && angle_depth == 0 => {
+                paren_depth = 0;
+                in_params = false;
+                params_done = true;
+                output.push(Token::Symbol(",".into()));
+                output.push(line_break());
+                output.push(token.clone());
+                continue;
+            }
+            Token::Symbol(s) if s == ")" => {
+                paren_depth = paren_depth.saturating_sub(1);
+                if paren_depth == 0 {
+                    params_done = true;
+                }
+            }
+            Token::Symbol(s)
+                if s == ","
+                    && angle_depth == 0
+                    && ((in_params && paren_depth == 1) || (in_where && paren_depth == 0)) =>
+            {
+                output.push(token.clone());
+                output.push(nested_break());
+                skip_whitespace = true;
+                continue;
+            }
+            Token::Symbol(s) if s == "{" && at_top && in_where => {
+                if matches!(output.last(), Some(Token::Whitespace)) {
+                    output.pop();
+                }
+                in_where = false;
+                output.push(Token::Symbol(",".into()));
+                output.push(line_break());
+                output.push(token.clone());
+                continue;
+            }
+            Token::Symbol(s) if s == ";" && at_top && in_where => in_where = false,
+            Token::Keyword(k) if k == "where" && at_top => {
+                if matches!(output.last(), Some(Token::Whitespace)) {
+                    output.pop();
+                }
+                in_where = true;
+                output.push(line_break());
+                output.push(token.clone());
+                output.push(nested_break());
+                skip_whitespace = true;
+                continue;
+            }
+            _ => {}
+        }
+        output.push(token.clone());
+    }
+
+    if in_where {
+        output.push(Token::Symbol(",".into()));
+    }
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Output;
+
+    fn long_fn() -> Vec<Token> {
+        let mut output = Output::new().qualifier_pub();
+        output
+            .kind("fn")
+            .whitespace()
+            .function("spawn_blocking_with_context");
+        output.symbol("<").generic("F");
+        output.extend(Output::new().symbol_comma());
+        output.generic("R").symbol(">");
+        output.symbol("(");
+        output
+            .identifier("handle")
+            .symbol(":")
+            .whitespace()
+            .type_("Handle");
+        output.extend(Output::new().symbol_comma());
+        output.identifier("f").symbol(":").whitespace().generic("F");
+        output.symbol(")");
+        output.extend(Output::new().symbol_arrow());
+        output
+            .type_("JoinHandle")
+            .symbol("<")
+            .generic("R")
+            .symbol(">");
+        output.whitespace().keyword("where").whitespace();
+        output
+            .generic("F")
+            .symbol(":")
+            .whitespace()
+            .type_("FnOnce")
+            .symbol("(")
+            .symbol(")");
+        output.extend(Output::new().symbol_arrow());
+        output.generic("R");
+        output.extend(Output::new().symbol_comma());
+        output.generic("R").symbol(":").whitespace().type_("Send");
+        output.into_tokens()
+    }
+
+    #[test]
+    fn test_fits_unchanged() {
+        let tokens = long_fn();
+        assert_eq!(wrap_signature(&tokens, 500, 0), tokens);
+    }
+
+    #[test]
+    fn test_wraps_params_and_where() {
+        let wrapped = tokens_to_string(&wrap_signature(&long_fn(), 60, 0));
+        assert_eq!(
+            wrapped,
+            "pub fn spawn_blocking_with_context<F, R>(\n    handle: Handle,\n    f: F,\n) -> JoinHandle<R>\nwhere\n    F: FnOnce() -> R,\n    R: Send,"
+        );
+    }
+
+    #[test]
+    fn test_body_after_where_on_its_own_line() {
+        let mut tokens = long_fn();
+        tokens.extend(Output::new().symbol_elided_body().into_tokens());
+        assert!(
+            tokens_to_string(&wrap_signature(&tokens, 60, 0))
+                .ends_with("\nwhere\n    F: FnOnce() -> R,\n    R: Send,\n{ .. }")
+        );
+
+        let mut tokens = long_fn();
+        tokens.push(Token::Symbol(";".into()));
+        assert!(
+            tokens_to_string(&wrap_signature(&tokens, 60, 4))
+                .ends_with("\n    where\n        F: FnOnce() -> R,\n        R: Send;")
+        );
+    }
+
+    #[test]
+    fn test_wraps_with_indent() {
+        let mut output = Output::new();
+        output.kind("fn").whitespace().function("method");
+        output.symbol("(").self_("&self").symbol(")");
+        let tokens = output.into_tokens();
+        // Continuation lines are indented relative to the signature
+        assert_eq!(
+            tokens_to_string(&wrap_signature(&tokens, 10, 4)),
+            "fn method(\n        &self,\n    )"
+        );
+    }
+}
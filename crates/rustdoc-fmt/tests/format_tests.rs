@@ -270,13 +270,59 @@ Also check [`Vec`] and [`String`].
 Use [`Result::unwrap`] for panic on error.
 "#;
     let result = format_test(markdown);
-    insta::assert_snapshot!(result, @r"
+    insta::assert_snapshot!(result, @"
     Intra-doc link styles:
 
-    See [`Option`] for optional values.
-    Also check [`Vec`] and [`String`].
+    See Option for optional values.
+    Also check Vec and String.
+
+    Use Result::unwrap for panic on error.
+    ");
+}
+
+#[test]
+fn link_disambiguator() {
+    let markdown = r#"Disambiguated links:
+
+Call [`method@Vec::len`] or [`fn@std::mem::swap`], and see [`vec!`].
+Also [the struct](struct@Vec).
+"#;
+    let result = format_test(markdown);
+    insta::assert_snapshot!(result, @"
+    Disambiguated links:
+
+    Call Vec::len or std::mem::swap, and see vec!.
+    Also the struct.
+    ");
+}
+
+#[test]
+fn link_section_anchor() {
+    let markdown = r#"Section links:
+
+See [Examples](#examples) and the [error cases](#errors).
+
+# Errors
+
+Fails on [`Overflow`](#errors) and [unknown](#nowhere) input.
 
-    Use [`Result::unwrap`] for panic on error.
+# Examples
+
+Back to the [intro](#section-links).
+"#;
+    let result = format_test(markdown);
+    insta::assert_snapshot!(result, @"
+    Section links:
+
+    See the Examples section below and the error cases (see the Errors section below).
+
+    Errors
+
+    Fails on Overflow (see the Errors section above) and unknown input.
+
+    Examples
+
+    Back to the intro.
     ");
 }
 
@@ -483,7 +529,7 @@ let result = example(42);
 See [`Config`] for more details.
 "#;
     let result = format_test(markdown);
-    insta::assert_snapshot!(result, @r"
+    insta::assert_snapshot!(result, @"
     Getting Started
 
     This is an introduction with bold and italic text.
@@ -500,7 +546,7 @@ See [`Config`] for more details.
         ◦ Also silences warnings
 
 
-    See [`Config`] for more details.
+    See Config for more details.
     ");
 }
 
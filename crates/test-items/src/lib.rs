@@ -0,0 +1,344 @@
+//! Test crate for item signatures in rustdoc JSON
+//!
+//! This crate contains items with various signature shapes to test how the
+//! docsrs tool renders them.
+
+// ============================================================================
+// Long signatures
+// ============================================================================
+
+/// A function whose signature doesn't fit on one line
+pub fn run_with_callback<F, R>(name: &str, retries: usize, callback: F) -> Option<R>
+where
+    F: FnOnce(&str) -> R,
+    R: Clone + Send,
+{
+    let _ = retries;
+    Some(callback(name))
+}
+
+/// Two named values, named after both
+pub struct Pair<A, B>(pub A, pub B);
+
+impl<A, B> Named for Pair<A, B>
+where
+    A: Named + Clone + Send,
+    B: Named + Clone + Send,
+{
+    fn name(&self) -> String {
+        format!("{} and {}", self.0.name(), self.1.name())
+    }
+}
+
+// ============================================================================
+// Items sharing a path
+// ============================================================================
+
+/// A struct with the same name as a macro
+pub struct Shape;
+
+/// A macro with the same name as a struct
+#[macro_export]
+macro_rules! Shape {
+    () => {
+        $crate::Shape
+    };
+}
+
+/// A module with the same name as a function
+pub mod builder {
+    /// Options for the [`builder()`](super::builder()), see [`Builder::finish`]
+    // The link to `Builder::finish` is broken on purpose, for --check-links
+    #[allow(rustdoc::broken_intra_doc_links)]
+    pub struct Options;
+}
+
+/// A function with the same name as a module
+pub fn builder() -> builder::Options {
+    builder::Options
+}
+
+/// A counter whose methods share names with its field and a trait method
+pub struct Counter {
+    /// The current count
+    pub count: usize,
+}
+
+impl Counter {
+    /// The current count, read through a method
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// The inherent name, which shadows `Named::name`
+    pub fn name(&self) -> String {
+        "counter".to_string()
+    }
+}
+
+impl Named for Counter {
+    fn name(&self) -> String {
+        format!("counter at {}", self.count)
+    }
+}
+
+// ============================================================================
+// Deref chains
+// ============================================================================
+
+/// Text measured in columns
+pub struct Text(String);
+
+impl Text {
+    /// Create text from a string
+    pub fn new(text: &str) -> Self {
+        Self(text.to_string())
+    }
+
+    /// Width of the text in columns
+    pub fn width(&self) -> usize {
+        self.0.chars().count()
+    }
+}
+
+impl std::ops::Deref for Text {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// Text with a color, which derefs to its text
+pub struct Styled {
+    text: Text,
+}
+
+impl Styled {
+    /// Name of the color
+    pub fn color(&self) -> &str {
+        "red"
+    }
+}
+
+impl std::ops::Deref for Styled {
+    type Target = Text;
+
+    fn deref(&self) -> &Text {
+        &self.text
+    }
+}
+
+/// Styled text that is highlighted, which derefs to its styled text
+pub struct Highlighted(pub Styled);
+
+impl std::ops::Deref for Highlighted {
+    type Target = Styled;
+
+    fn deref(&self) -> &Styled {
+        &self.0
+    }
+}
+
+// ============================================================================
+// Traits
+// ============================================================================
+
+/// Something that can be drawn
+pub trait Draw {
+    /// Where it is drawn
+    type Canvas;
+
+    /// Draw on the canvas
+    fn draw(&self, canvas: &mut Self::Canvas);
+
+    /// Draw twice, e.g. for a bold look
+    fn draw_twice(&self, canvas: &mut Self::Canvas) {
+        self.draw(canvas);
+        self.draw(canvas);
+    }
+
+    /// Whether drawing shows anything
+    fn is_visible(&self) -> bool {
+        true
+    }
+}
+
+// ============================================================================
+// Generic defaults
+// ============================================================================
+
+/// The default hasher of a `Table`
+#[derive(Default)]
+pub struct DefaultHasher;
+
+/// A table with a default hasher and a default capacity
+pub struct Table<K, V, S = DefaultHasher, const N: usize = 16> {
+    /// The entries, in insertion order
+    pub entries: Vec<(K, V)>,
+    /// Hashes the keys
+    pub hasher: S,
+}
+
+// ============================================================================
+// Blanket impls
+// ============================================================================
+
+/// Something with a name
+pub trait Named {
+    /// The name
+    fn name(&self) -> String;
+}
+
+/// Greets by name, for everything `Named`
+pub trait Greet {
+    /// A greeting
+    fn greet(&self) -> String;
+}
+
+impl<T: Named + ?Sized> Greet for T {
+    fn greet(&self) -> String {
+        format!("Hello, {}!", self.name())
+    }
+}
+
+impl Named for Text {
+    fn name(&self) -> String {
+        self.0.clone()
+    }
+}
+
+/// Introduces itself by name
+pub trait Introduce: Named {
+    /// An introduction
+    fn introduce(&self) -> String {
+        format!("I am {}", self.name())
+    }
+}
+
+/// Introduces itself loudly
+pub trait Shout: Introduce {
+    /// A loud introduction
+    fn shout(&self) -> String {
+        self.introduce().to_uppercase()
+    }
+}
+
+impl Introduce for Text {}
+
+impl Shout for Text {}
+
+/// Makes values from parts, which keeps it from being used as `dyn Make`
+pub trait Make {
+    /// Make a default value
+    fn make() -> Self;
+
+    /// Describe a value made from a part
+    fn describe<P: Into<String>>(&self, part: P) -> String;
+
+    /// Whether the value equals another one
+    fn same_as(&self, other: &Self) -> bool;
+
+    /// A copy of the value, only for sized types
+    fn copy_made(&self) -> Self
+    where
+        Self: Sized;
+}
+
+// ============================================================================
+// Sealed traits
+// ============================================================================
+
+mod private {
+    /// Keeps `Shade` from being implemented outside this crate
+    pub trait Sealed {}
+
+    impl Sealed for super::Text {}
+
+    /// Keeps `Tint` from being implemented outside this crate
+    pub struct Token;
+}
+
+/// A shade of a color, sealed by a private supertrait
+pub trait Shade: private::Sealed {
+    /// How dark the shade is
+    fn darkness(&self) -> u8;
+}
+
+impl Shade for Text {
+    fn darkness(&self) -> u8 {
+        0
+    }
+}
+
+/// A tint of a color, sealed by a required method taking a private type
+pub trait Tint {
+    /// How strong the tint is
+    fn strength(&self) -> u8;
+
+    /// Only callable within this crate
+    fn seal(&self, token: private::Token);
+}
+
+impl Tint for Text {
+    fn strength(&self) -> u8 {
+        1
+    }
+
+    fn seal(&self, _token: private::Token) {}
+}
+
+// ============================================================================
+// Function qualifiers
+// ============================================================================
+
+/// Width of a column of `chars` characters, usable in constants
+pub const fn column_width(chars: usize) -> usize {
+    chars
+}
+
+/// Load text by name, without blocking
+pub async fn load_text(name: &str) -> Text {
+    Text::new(name)
+}
+
+/// Text from a string that is trusted to be valid UTF-8
+///
+/// # Safety
+///
+/// `bytes` must be valid UTF-8.
+pub unsafe fn text_from_utf8_unchecked(bytes: &[u8]) -> Text {
+    Text(unsafe { String::from_utf8_unchecked(bytes.to_vec()) })
+}
+
+/// Width of the column that `chars` points to, usable in constants
+///
+/// # Safety
+///
+/// `chars` must point to a valid `usize`.
+pub const unsafe fn column_width_at(chars: *const usize) -> usize {
+    unsafe { *chars }
+}
+
+// ============================================================================
+// Feature-gated items
+// ============================================================================
+
+/// Number of columns text is laid out in, with the `layout` feature
+#[cfg(feature = "layout")]
+pub fn layout_columns() -> usize {
+    80
+}
+
+/// Text laid out in columns, with the `layout` feature
+#[cfg(feature = "layout")]
+pub mod layout {
+    /// A column of text
+    pub struct Column;
+}
+
+/// Whether text is wider than it looks, without the `emoji` feature
+#[cfg(all(feature = "layout", not(feature = "emoji")))]
+pub fn is_ascii_only() -> bool {
+    true
+}
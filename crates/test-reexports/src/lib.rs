@@ -86,6 +86,11 @@ pub mod reexported {
     pub use crate::inner::*;
 }
 
+/// Module that re-exports the variants of an enum
+pub mod variants {
+    pub use crate::inner::InnerEnum::*;
+}
+
 // ============================================================================
 // Nested re-exports (re-exporting from deep modules)
 // ============================================================================
@@ -103,6 +108,9 @@ pub use std::collections::HashMap;
 /// Re-export from std with rename
 pub use std::vec::Vec as MyVec;
 
+/// Re-export from another crate of the workspace
+pub use test_items::run_with_callback;
+
 // ============================================================================
 // Re-export chains
 // ============================================================================
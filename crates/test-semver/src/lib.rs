@@ -0,0 +1,56 @@
+#![allow(dead_code)]
+//! Test crate for semver checks in rustdoc JSON
+//!
+//! The tests remove items from a copy of this crate's docs to make an older
+//! version, so that each item shows up as added.
+
+/// An enum that code can match exhaustively
+pub enum Exhaustive {
+    First,
+    Second,
+}
+
+/// An enum that code outside the crate must match with a wildcard
+#[non_exhaustive]
+pub enum NonExhaustive {
+    First,
+    Second,
+}
+
+/// A struct that code can construct with a struct expression
+pub struct Fields {
+    pub first: u8,
+    pub second: u8,
+}
+
+/// A struct that code outside the crate can't construct
+#[non_exhaustive]
+pub struct NonExhaustiveFields {
+    pub first: u8,
+    pub second: u8,
+}
+
+/// A struct with a private field, so that it can't be constructed either
+pub struct PrivateFields {
+    pub first: u8,
+    pub second: u8,
+    hidden: u8,
+}
+
+/// A trait with items that implementations must and may provide
+pub trait Implement {
+    /// Required associated type
+    type Output;
+
+    /// Required associated const
+    const REQUIRED: u8;
+
+    /// Associated const with a default
+    const PROVIDED: u8 = 0;
+
+    /// Required method
+    fn required(&self) -> Self::Output;
+
+    /// Provided method
+    fn provided(&self) {}
+}
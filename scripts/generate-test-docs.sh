@@ -8,9 +8,25 @@ echo "  - test-visibility"
 output=$(cargo +nightly rustdoc -p test-visibility -- -Zunstable-options --output-format json 2>&1)
 echo "$output" | grep -v "^warning:" || true
 
+# Generate for test-visibility with private items, into a target dir of its
+# own so it doesn't replace the public docs
+echo "  - test-visibility (private items)"
+output=$(cargo +nightly rustdoc -p test-visibility --target-dir target/private-items -- -Zunstable-options --output-format json --document-private-items 2>&1)
+echo "$output" | grep -v "^warning:" || true
+
 # Generate for test-reexports
 echo "  - test-reexports"
 output=$(cargo +nightly rustdoc -p test-reexports -- -Zunstable-options --output-format json 2>&1)
 echo "$output" | grep -v "^warning:" || true
 
+# Generate for test-items
+echo "  - test-items"
+output=$(cargo +nightly rustdoc -p test-items -- -Zunstable-options --output-format json 2>&1)
+echo "$output" | grep -v "^warning:" || true
+
+# Generate for test-semver
+echo "  - test-semver"
+output=$(cargo +nightly rustdoc -p test-semver -- -Zunstable-options --output-format json 2>&1)
+echo "$output" | grep -v "^warning:" || true
+
 echo "Rustdoc JSON generation complete"